@@ -0,0 +1,593 @@
+//! A disk-backed cold tier for the [`ChunkStore`].
+//!
+//! See [`crate::ChunkStoreConfig::cold_storage_dir`].
+
+use std::{
+    collections::BTreeMap,
+    fs::File,
+    io::{Read as _, Seek as _, SeekFrom, Write as _},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use ahash::{HashMap, HashSet};
+use itertools::Itertools as _;
+use nohash_hasher::IntMap;
+use parking_lot::Mutex;
+
+use re_chunk::{Chunk, ChunkId, LatestAtQuery, RangeQuery, TimelineName};
+use re_log_types::{AbsoluteTimeRange, EntityPath, StoreId, TimeInt};
+use re_types_core::ComponentDescriptor;
+
+use crate::store::ChunkIdSetPerTime;
+
+#[expect(unused_imports)] // used in docstrings
+use crate::ChunkStore;
+
+// ---
+
+/// How many bytes worth of reloaded chunks are kept around in memory, so that consecutive
+/// queries on the same evicted time range (e.g. scrubbing) don't all have to go to disk.
+const RELOADED_CHUNKS_CACHE_BYTES: u64 = 256 * 1024 * 1024;
+
+/// Past this size, evicted chunks are written to a new file.
+///
+/// A file is deleted as soon as none of the chunks it contains can be reached anymore, which is
+/// how the disk space of forgotten chunks gets reclaimed.
+const MAX_SEGMENT_BYTES: u64 = 64 * 1024 * 1024;
+
+type ColdChunkIdsPerComponentPerTimelinePerEntity =
+    IntMap<EntityPath, IntMap<TimelineName, IntMap<ComponentDescriptor, ChunkIdSetPerTime>>>;
+
+/// One of the files that make up the cold tier.
+///
+/// It is append-only, and deleted once no [`ColdChunk`] refers to it anymore.
+#[derive(Debug)]
+struct ColdSegment {
+    path: PathBuf,
+    file: Mutex<File>,
+}
+
+impl Drop for ColdSegment {
+    fn drop(&mut self) {
+        if let Err(err) = std::fs::remove_file(&self.path) {
+            re_log::debug!(path = ?self.path, %err, "failed to remove cold storage file");
+        }
+    }
+}
+
+impl ColdSegment {
+    fn create(dir: &Path, file_prefix: &str) -> std::io::Result<Self> {
+        // NOTE: Several stores (and several viewers) can share the same directory.
+        let path = dir.join(format!("{file_prefix}-{}.cold", ChunkId::new()));
+
+        let file = File::options()
+            .read(true)
+            .write(true)
+            .create_new(true)
+            .open(&path)?;
+
+        re_log::debug!(?path, "created cold storage file");
+
+        Ok(Self {
+            path,
+            file: Mutex::new(file),
+        })
+    }
+
+    fn read(&self, offset: u64, len: u64) -> std::io::Result<Vec<u8>> {
+        let mut bytes = vec![0; len as usize];
+
+        let mut file = self.file.lock();
+        file.seek(SeekFrom::Start(offset))?;
+        file.read_exact(&mut bytes)?;
+
+        Ok(bytes)
+    }
+}
+
+/// The files of a [`ColdTier`], shared with all of its clones.
+#[derive(Debug)]
+struct ColdSegments {
+    dir: PathBuf,
+    file_prefix: String,
+
+    /// The segment that new evictions get appended to, and its size.
+    current: Mutex<Option<(Arc<ColdSegment>, u64)>>,
+}
+
+impl ColdSegments {
+    /// Appends `bytes` to the current segment, rotating it first if it's grown too large.
+    ///
+    /// Returns the segment and the offset the bytes were written at.
+    fn append(&self, bytes: &[u8]) -> std::io::Result<(Arc<ColdSegment>, u64)> {
+        let mut current = self.current.lock();
+
+        let segment = match current.take() {
+            Some((segment, num_bytes)) if num_bytes < MAX_SEGMENT_BYTES => segment,
+            _ => Arc::new(ColdSegment::create(&self.dir, &self.file_prefix)?),
+        };
+
+        let offset = {
+            let mut file = segment.file.lock();
+            let offset = file.seek(SeekFrom::End(0))?;
+            file.write_all(bytes)?;
+            offset
+        };
+        *current = Some((Arc::clone(&segment), offset + bytes.len() as u64));
+
+        Ok((segment, offset))
+    }
+}
+
+/// Where an evicted chunk lives, and what it covers.
+#[derive(Debug, Clone)]
+struct ColdChunk {
+    entity_path: EntityPath,
+
+    /// The time range covered by the chunk on each of its timelines.
+    time_ranges: IntMap<TimelineName, AbsoluteTimeRange>,
+
+    /// The file the encoded chunk lives in.
+    segment: Arc<ColdSegment>,
+
+    /// Byte offset of the encoded chunk in [`Self::segment`].
+    offset: u64,
+
+    /// Size of the encoded chunk in [`Self::segment`].
+    len: u64,
+}
+
+#[derive(Debug, Default, Clone)]
+struct ColdIndex {
+    chunks: HashMap<ChunkId, ColdChunk>,
+
+    /// The evicted chunks, organized by the time range they cover for each of their components.
+    chunk_ids_per_entity: ColdChunkIdsPerComponentPerTimelinePerEntity,
+
+    /// Total size of all the chunks still referenced, in the cold storage files.
+    num_bytes: u64,
+}
+
+/// Least-recently-used cache of the chunks that were reloaded from disk.
+///
+/// See [`RELOADED_CHUNKS_CACHE_BYTES`].
+#[derive(Debug, Default, Clone)]
+struct ReloadedChunks {
+    /// Each chunk along with the tick it was last accessed at.
+    chunks: HashMap<ChunkId, (Arc<Chunk>, u64)>,
+
+    /// The chunks, from least to most recently accessed.
+    chunk_ids_per_tick: BTreeMap<u64, ChunkId>,
+
+    next_tick: u64,
+
+    num_bytes: u64,
+}
+
+impl ReloadedChunks {
+    fn get(&mut self, chunk_id: ChunkId) -> Option<Arc<Chunk>> {
+        let (chunk, tick) = self.chunks.get_mut(&chunk_id)?;
+
+        self.chunk_ids_per_tick.remove(tick);
+        *tick = self.next_tick;
+        self.chunk_ids_per_tick.insert(*tick, chunk_id);
+        self.next_tick += 1;
+
+        Some(Arc::clone(chunk))
+    }
+
+    fn insert(&mut self, chunk: Arc<Chunk>) {
+        let num_bytes = <Chunk as re_byte_size::SizeBytes>::total_size_bytes(&chunk);
+
+        while self.num_bytes + num_bytes > RELOADED_CHUNKS_CACHE_BYTES
+            && let Some((_, chunk_id)) = self.chunk_ids_per_tick.pop_first()
+        {
+            if let Some((chunk, _)) = self.chunks.remove(&chunk_id) {
+                self.num_bytes -= <Chunk as re_byte_size::SizeBytes>::total_size_bytes(&chunk);
+            }
+        }
+
+        let tick = self.next_tick;
+        self.next_tick += 1;
+
+        self.chunk_ids_per_tick.insert(tick, chunk.id());
+        if let Some((previous, previous_tick)) = self.chunks.insert(chunk.id(), (chunk, tick)) {
+            self.chunk_ids_per_tick.remove(&previous_tick);
+            self.num_bytes -= <Chunk as re_byte_size::SizeBytes>::total_size_bytes(&previous);
+        }
+        self.num_bytes += num_bytes;
+    }
+
+    fn remove(&mut self, chunk_ids: &HashSet<ChunkId>) {
+        for chunk_id in chunk_ids {
+            if let Some((chunk, tick)) = self.chunks.remove(chunk_id) {
+                self.chunk_ids_per_tick.remove(&tick);
+                self.num_bytes -= <Chunk as re_byte_size::SizeBytes>::total_size_bytes(&chunk);
+            }
+        }
+    }
+}
+
+/// Chunks that were evicted from the [`ChunkStore`] and written to local files, from where they
+/// can be transparently reloaded at query time.
+///
+/// Clones share the same files but each have their own index: forgetting chunks in one of them
+/// doesn't affect the others, and a file is only deleted once none of them can reach it anymore.
+#[derive(Debug)]
+pub(crate) struct ColdTier {
+    segments: Arc<ColdSegments>,
+    index: ColdIndex,
+    reloaded: Mutex<ReloadedChunks>,
+}
+
+impl Clone for ColdTier {
+    fn clone(&self) -> Self {
+        Self {
+            segments: Arc::clone(&self.segments),
+            index: self.index.clone(),
+            reloaded: Mutex::new(self.reloaded.lock().clone()),
+        }
+    }
+}
+
+impl ColdTier {
+    /// Creates a new, empty cold tier for the given store in `dir`.
+    pub fn new(dir: &Path, store_id: &StoreId) -> std::io::Result<Self> {
+        std::fs::create_dir_all(dir)?;
+
+        Ok(Self {
+            segments: Arc::new(ColdSegments {
+                dir: dir.to_owned(),
+                file_prefix: sanitize_file_name(store_id.recording_id().as_str()),
+                current: Mutex::new(None),
+            }),
+            index: ColdIndex::default(),
+            reloaded: Mutex::new(ReloadedChunks::default()),
+        })
+    }
+
+    /// The number of chunks currently living in the cold tier.
+    pub fn num_chunks(&self) -> usize {
+        self.index.chunks.len()
+    }
+
+    /// The total size of the chunks currently living in the cold tier, in their encoded form.
+    pub fn num_bytes(&self) -> u64 {
+        self.index.num_bytes
+    }
+
+    /// Writes a _temporal_ chunk to disk, and indexes it so that it can be found again by queries.
+    pub fn evict(&mut self, chunk: &Chunk) -> anyhow::Result<()> {
+        re_tracing::profile_function!();
+
+        debug_assert!(!chunk.is_static(), "static data is never garbage collected");
+
+        let mut bytes = Vec::new();
+        {
+            let batch = chunk.to_record_batch()?;
+            let mut writer =
+                arrow::ipc::writer::StreamWriter::try_new(&mut bytes, &batch.schema())?;
+            writer.write(&batch)?;
+            writer.finish()?;
+        }
+
+        let (segment, offset) = self.segments.append(&bytes)?;
+
+        let ColdIndex {
+            chunks,
+            chunk_ids_per_entity,
+            num_bytes,
+        } = &mut self.index;
+
+        let per_timeline = chunk_ids_per_entity
+            .entry(chunk.entity_path().clone())
+            .or_default();
+        for (timeline, time_range_per_component) in chunk.time_range_per_component() {
+            let per_component = per_timeline.entry(timeline).or_default();
+            for (component_descr, time_range) in time_range_per_component {
+                let chunk_ids_per_time = per_component.entry(component_descr).or_default();
+
+                // See `ChunkIdSetPerTime::max_interval_length`'s documentation.
+                chunk_ids_per_time.max_interval_length = u64::max(
+                    chunk_ids_per_time.max_interval_length,
+                    time_range.abs_length(),
+                );
+
+                chunk_ids_per_time
+                    .per_start_time
+                    .entry(time_range.min())
+                    .or_default()
+                    .insert(chunk.id());
+                chunk_ids_per_time
+                    .per_end_time
+                    .entry(time_range.max())
+                    .or_default()
+                    .insert(chunk.id());
+            }
+        }
+
+        *num_bytes += bytes.len() as u64;
+        chunks.insert(
+            chunk.id(),
+            ColdChunk {
+                entity_path: chunk.entity_path().clone(),
+                time_ranges: chunk
+                    .timelines()
+                    .iter()
+                    .map(|(timeline, time_column)| (*timeline, time_column.time_range()))
+                    .collect(),
+                segment,
+                offset,
+                len: bytes.len() as u64,
+            },
+        );
+
+        Ok(())
+    }
+
+    /// Forgets about all the evicted chunks that match the given predicate, which is passed the
+    /// entity path of each chunk as well as the time range it covers on each of its timelines.
+    ///
+    /// Their data stays on disk until nothing refers to the file they live in anymore.
+    pub fn forget(
+        &mut self,
+        mut predicate: impl FnMut(&EntityPath, &IntMap<TimelineName, AbsoluteTimeRange>) -> bool,
+    ) {
+        let forgotten: HashSet<ChunkId> = self
+            .index
+            .chunks
+            .iter()
+            .filter(|(_, cold_chunk)| predicate(&cold_chunk.entity_path, &cold_chunk.time_ranges))
+            .map(|(chunk_id, _)| *chunk_id)
+            .collect();
+
+        self.forget_chunks(&forgotten);
+    }
+
+    /// Forgets about the given evicted chunks, if they are present.
+    pub fn forget_chunks(&mut self, forgotten: &HashSet<ChunkId>) {
+        if forgotten.is_empty() {
+            return;
+        }
+
+        re_tracing::profile_function!();
+
+        let ColdIndex {
+            chunks,
+            chunk_ids_per_entity,
+            num_bytes,
+        } = &mut self.index;
+
+        for chunk_id in forgotten {
+            if let Some(cold_chunk) = chunks.remove(chunk_id) {
+                *num_bytes -= cold_chunk.len;
+            }
+        }
+
+        chunk_ids_per_entity.retain(|_entity_path, per_timeline| {
+            per_timeline.retain(|_timeline, per_component| {
+                per_component.retain(|_component_descr, chunk_ids_per_time| {
+                    let ChunkIdSetPerTime {
+                        max_interval_length: _,
+                        per_start_time,
+                        per_end_time,
+                    } = chunk_ids_per_time;
+
+                    for per_time in [per_start_time, per_end_time] {
+                        per_time.retain(|_time, chunk_ids| {
+                            chunk_ids.retain(|chunk_id| !forgotten.contains(chunk_id));
+                            !chunk_ids.is_empty()
+                        });
+                    }
+
+                    !chunk_ids_per_time.per_start_time.is_empty()
+                });
+                !per_component.is_empty()
+            });
+            !per_timeline.is_empty()
+        });
+
+        self.reloaded.get_mut().remove(forgotten);
+    }
+
+    /// Returns the evicted chunk(s) that might contain the latest value for the given query.
+    ///
+    /// Only the components for which `component_filter` returns `true` are considered.
+    ///
+    /// `hot_start_time` returns, for a given component, the most recent time at or before the
+    /// query at which the hot store has data, if any: the latest value is at least that recent,
+    /// so evicted chunks that end before it are never reloaded.
+    pub fn latest_at_relevant_chunks(
+        &self,
+        query: &LatestAtQuery,
+        entity_path: &EntityPath,
+        mut component_filter: impl FnMut(&ComponentDescriptor) -> bool,
+        mut hot_start_time: impl FnMut(&ComponentDescriptor) -> Option<TimeInt>,
+    ) -> Vec<Arc<Chunk>> {
+        let Some(per_component) = self
+            .index
+            .chunk_ids_per_entity
+            .get(entity_path)
+            .and_then(|per_timeline| per_timeline.get(&query.timeline()))
+        else {
+            return Vec::new();
+        };
+
+        let mut chunk_ids = Vec::new();
+        for (component_descr, chunk_ids_per_time) in per_component {
+            if !component_filter(component_descr) {
+                continue;
+            }
+
+            self.latest_at_candidates(
+                query,
+                chunk_ids_per_time,
+                hot_start_time(component_descr),
+                &mut chunk_ids,
+            );
+        }
+
+        self.load_all(chunk_ids.into_iter().unique())
+    }
+
+    /// Returns the evicted chunk(s) that have data within the range of the given query.
+    ///
+    /// Only the components for which `component_filter` returns `true` are considered.
+    pub fn range_relevant_chunks(
+        &self,
+        query: &RangeQuery,
+        entity_path: &EntityPath,
+        mut component_filter: impl FnMut(&ComponentDescriptor) -> bool,
+    ) -> Vec<Arc<Chunk>> {
+        let Some(per_component) = self
+            .index
+            .chunk_ids_per_entity
+            .get(entity_path)
+            .and_then(|per_timeline| per_timeline.get(query.timeline()))
+        else {
+            return Vec::new();
+        };
+
+        let range = query.range();
+
+        let mut chunk_ids = Vec::new();
+        for (component_descr, chunk_ids_per_time) in per_component {
+            if !component_filter(component_descr) || range.max() < range.min() {
+                continue;
+            }
+
+            // Same bounded backwards walk as the hot store, see `ChunkStore::latest_at`.
+            let min_start_time = TimeInt::new_temporal(
+                range
+                    .min()
+                    .as_i64()
+                    .saturating_sub(chunk_ids_per_time.max_interval_length as _),
+            );
+
+            chunk_ids.extend(
+                chunk_ids_per_time
+                    .per_start_time
+                    .range(min_start_time..=range.max())
+                    .flat_map(|(_time, chunk_ids)| chunk_ids.iter().copied())
+                    .filter(|chunk_id| {
+                        self.time_range(chunk_id, *query.timeline())
+                            .is_some_and(|time_range| time_range.intersects(range))
+                    }),
+            );
+        }
+
+        self.load_all(chunk_ids.into_iter().unique())
+    }
+
+    /// Appends to `chunk_ids` the chunks that can possibly hold the latest value at `query.at()`.
+    ///
+    /// The latest value is at least as recent as the most recent start time before `query.at()`,
+    /// whether in the cold tier or in the hot store: any chunk that ends before that cannot
+    /// contain it.
+    fn latest_at_candidates(
+        &self,
+        query: &LatestAtQuery,
+        chunk_ids_per_time: &ChunkIdSetPerTime,
+        hot_start_time: Option<TimeInt>,
+        chunk_ids: &mut Vec<ChunkId>,
+    ) {
+        let at = query.at();
+
+        let Some(cold_start_time) = chunk_ids_per_time
+            .per_start_time
+            .range(..=at)
+            .next_back()
+            .map(|(time, _)| *time)
+        else {
+            return;
+        };
+
+        let lower_bound = hot_start_time.map_or(cold_start_time, |hot_start_time| {
+            TimeInt::max(hot_start_time, cold_start_time)
+        });
+
+        let min_start_time = TimeInt::new_temporal(
+            lower_bound
+                .as_i64()
+                .saturating_sub(chunk_ids_per_time.max_interval_length as _),
+        );
+
+        chunk_ids.extend(
+            chunk_ids_per_time
+                .per_start_time
+                .range(min_start_time..=at)
+                .flat_map(|(_time, chunk_ids)| chunk_ids.iter().copied())
+                .filter(|chunk_id| {
+                    self.time_range(chunk_id, query.timeline())
+                        .is_some_and(|time_range| time_range.max() >= lower_bound)
+                }),
+        );
+    }
+
+    fn time_range(&self, chunk_id: &ChunkId, timeline: TimelineName) -> Option<AbsoluteTimeRange> {
+        self.index
+            .chunks
+            .get(chunk_id)
+            .and_then(|cold_chunk| cold_chunk.time_ranges.get(&timeline))
+            .copied()
+    }
+
+    fn load_all(&self, chunk_ids: impl Iterator<Item = ChunkId>) -> Vec<Arc<Chunk>> {
+        chunk_ids
+            .filter_map(|chunk_id| match self.load(chunk_id) {
+                Ok(chunk) => chunk,
+                Err(err) => {
+                    re_log::warn_once!(
+                        "Failed to reload chunk from cold storage in {:?}: {err}",
+                        self.segments.dir
+                    );
+                    None
+                }
+            })
+            .collect()
+    }
+
+    fn load(&self, chunk_id: ChunkId) -> anyhow::Result<Option<Arc<Chunk>>> {
+        if let Some(chunk) = self.reloaded.lock().get(chunk_id) {
+            return Ok(Some(chunk));
+        }
+
+        let Some(ColdChunk {
+            entity_path: _,
+            time_ranges: _,
+            segment,
+            offset,
+            len,
+        }) = self.index.chunks.get(&chunk_id)
+        else {
+            return Ok(None);
+        };
+
+        re_tracing::profile_function!();
+
+        let bytes = segment.read(*offset, *len)?;
+
+        let mut reader = arrow::ipc::reader::StreamReader::try_new(bytes.as_slice(), None)?;
+        let batch = reader
+            .next()
+            .ok_or_else(|| anyhow::anyhow!("missing record batch"))??;
+        let chunk = Arc::new(Chunk::from_record_batch(&batch)?);
+
+        self.reloaded.lock().insert(Arc::clone(&chunk));
+
+        Ok(Some(chunk))
+    }
+}
+
+fn sanitize_file_name(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
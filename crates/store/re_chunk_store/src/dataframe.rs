@@ -34,8 +34,13 @@ pub enum SparseFillStrategy {
     /// The latest-at semantics are applied on the entire dataset as opposed to just the current
     /// view contents: it is possible to end up with values from outside the view!
     LatestAtGlobal,
-    //
-    // TODO(cmc): `LatestAtView`?
+
+    /// Fill null values using view-scoped latest-at semantics.
+    ///
+    /// Like [`Self::LatestAtGlobal`], except that only values that are themselves part of the
+    /// view are used, i.e. values that were logged within the
+    /// [`QueryExpression::filtered_index_range`], if any.
+    LatestAtView,
 }
 
 impl std::fmt::Display for SparseFillStrategy {
@@ -43,8 +48,95 @@ impl std::fmt::Display for SparseFillStrategy {
         match self {
             Self::None => f.write_str("none"),
             Self::LatestAtGlobal => f.write_str("latest-at (global)"),
+            Self::LatestAtView => f.write_str("latest-at (view)"),
+        }
+    }
+}
+
+/// Specifies how values are resampled at the index values of [`QueryExpression::using_index_values`].
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum InterpolationStrategy {
+    /// No interpolation: values only show up at the exact index values they were logged at.
+    ///
+    /// Use [`QueryExpression::sparse_fill_strategy`] to get step (latest-at) semantics.
+    #[default]
+    None,
+
+    /// Interpolate in between the values that surround each sampled index value:
+    /// * linearly for scalars and translations,
+    /// * spherically (slerp) for rotation quaternions,
+    /// * using the nearest value for everything else.
+    ///
+    /// Index values that aren't surrounded by data on both sides are left as-is.
+    Interpolate,
+}
+
+impl std::fmt::Display for InterpolationStrategy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::None => f.write_str("none"),
+            Self::Interpolate => f.write_str("interpolate"),
+        }
+    }
+}
+
+/// How the columns of an [`IsNotNullFilter`] are combined.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum IsNotNullOperator {
+    /// Keep the rows where _all_ of the columns contain non-null data.
+    #[default]
+    And,
+
+    /// Keep the rows where _any_ of the columns contains non-null data.
+    Or,
+}
+
+impl std::fmt::Display for IsNotNullOperator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::And => f.write_str("and"),
+            Self::Or => f.write_str("or"),
+        }
+    }
+}
+
+/// Filters out _rows_ based on whether some component columns contain data.
+///
+/// See [`QueryExpression::filtered_is_not_null`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct IsNotNullFilter {
+    /// The columns that must contain non-null data.
+    ///
+    /// An empty list of columns filters out nothing.
+    pub columns: Vec<ComponentColumnSelector>,
+
+    /// How the columns are combined.
+    pub operator: IsNotNullOperator,
+}
+
+impl IsNotNullFilter {
+    /// Keep the rows where _all_ of the columns contain non-null data.
+    pub fn all(columns: impl IntoIterator<Item = ComponentColumnSelector>) -> Self {
+        Self {
+            columns: columns.into_iter().collect(),
+            operator: IsNotNullOperator::And,
         }
     }
+
+    /// Keep the rows where _any_ of the columns contains non-null data.
+    pub fn any(columns: impl IntoIterator<Item = ComponentColumnSelector>) -> Self {
+        Self {
+            columns: columns.into_iter().collect(),
+            operator: IsNotNullOperator::Or,
+        }
+    }
+}
+
+impl From<ComponentColumnSelector> for IsNotNullFilter {
+    #[inline]
+    fn from(column: ComponentColumnSelector) -> Self {
+        Self::all([column])
+    }
 }
 
 /// The view contents specify which subset of the database (i.e., which columns) the query runs on.
@@ -223,14 +315,23 @@ pub struct QueryExpression {
     /// Example: `[TimeInt(12), TimeInt(14)]`.
     pub using_index_values: Option<BTreeSet<IndexValue>>,
 
-    /// The component column used to filter out _rows_ from the view contents.
+    /// Specifies how values are resampled at the index values of [`QueryExpression::using_index_values`].
     ///
-    /// Only rows where this column contains non-null data be kept in the final dataset.
+    /// Interpolation happens before sparse filling, see [`QueryExpression::sparse_fill_strategy`].
     ///
-    /// Example: `ComponentColumnSelector("Points3D:positions")`.
-    //
-    // TODO(cmc): multi-pov support
-    pub filtered_is_not_null: Option<ComponentColumnSelector>,
+    /// * This has no effect if [`QueryExpression::using_index_values`] isn't set.
+    ///
+    /// Defaults to [`InterpolationStrategy::None`].
+    pub interpolation_strategy: InterpolationStrategy,
+
+    /// The component columns used to filter out _rows_ from the view contents.
+    ///
+    /// Only rows where these columns contain non-null data will be kept in the final dataset,
+    /// see [`IsNotNullFilter`].
+    ///
+    /// Example: `IsNotNullFilter::all([ComponentColumnSelector("/camera:EncodedImage:blob"),
+    /// ComponentColumnSelector("/lidar:Points3D:positions")])`.
+    pub filtered_is_not_null: Option<IsNotNullFilter>,
 
     /// Specifies how null values should be filled in the returned dataframe.
     ///
@@ -477,6 +578,7 @@ impl ChunkStore {
             filtered_index_range: _,
             filtered_index_values: _,
             using_index_values: _,
+            interpolation_strategy: _,
             filtered_is_not_null: _,
             sparse_fill_strategy: _,
             selection: _,
@@ -22,6 +22,15 @@ impl ChunkStore {
             return Default::default();
         }
 
+        // Evicted chunks are dropped as a whole: they cannot be split without reloading them.
+        if let Some(cold_tier) = &mut self.cold_tier {
+            cold_tier.forget(|_entity_path, time_ranges| {
+                time_ranges
+                    .get(timeline)
+                    .is_some_and(|time_range| drop_range.intersects(*time_range))
+            });
+        }
+
         // Prepare the changes:
 
         let mut chunk_ids_to_drop = vec![];
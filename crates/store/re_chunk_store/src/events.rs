@@ -145,6 +145,18 @@ pub struct ChunkStoreDiff {
     /// A corollary to that is that the destination [`Chunk`] must have never been seen before,
     /// i.e. it's [`ChunkId`] must have never been seen before.
     pub compacted: Option<ChunkCompactionReport>,
+
+    /// Is this deletion an eviction to the cold tier?
+    ///
+    /// Evicted chunks are not in memory anymore, but can still be returned by queries, which
+    /// will transparently reload them from disk (see [`crate::ChunkStoreConfig::cold_storage_dir`]).
+    ///
+    /// Subscribers that hold on to chunk data (e.g. caches) should handle evictions like any
+    /// other deletion, while subscribers that keep track of the logical contents of the recording
+    /// (e.g. time histograms) should ignore them.
+    ///
+    /// Always `false` for additions.
+    pub evicted: bool,
 }
 
 impl PartialEq for ChunkStoreDiff {
@@ -154,8 +166,12 @@ impl PartialEq for ChunkStoreDiff {
             kind,
             chunk,
             compacted,
+            evicted,
         } = self;
-        *kind == rhs.kind && chunk.id() == rhs.chunk.id() && compacted == &rhs.compacted
+        *kind == rhs.kind
+            && chunk.id() == rhs.chunk.id()
+            && compacted == &rhs.compacted
+            && *evicted == rhs.evicted
     }
 }
 
@@ -168,6 +184,7 @@ impl ChunkStoreDiff {
             kind: ChunkStoreDiffKind::Addition,
             chunk,
             compacted,
+            evicted: false,
         }
     }
 
@@ -177,6 +194,16 @@ impl ChunkStoreDiff {
             kind: ChunkStoreDiffKind::Deletion,
             chunk,
             compacted: None,
+            evicted: false,
+        }
+    }
+
+    /// A deletion that only moved the chunk to the cold tier, see [`Self::evicted`].
+    #[inline]
+    pub fn eviction(chunk: Arc<Chunk>) -> Self {
+        Self {
+            evicted: true,
+            ..Self::deletion(chunk)
         }
     }
 
@@ -14,7 +14,7 @@ use re_types_core::ComponentDescriptor;
 
 use crate::{
     ChunkStore, ChunkStoreChunkStats, ChunkStoreDiff, ChunkStoreDiffKind, ChunkStoreEvent,
-    ChunkStoreStats, store::ChunkIdSetPerTime,
+    ChunkStoreStats, cold::ColdTier, store::ChunkIdSetPerTime,
 };
 
 // Used all over in docstrings.
@@ -32,6 +32,83 @@ pub enum GarbageCollectionTarget {
 
     /// GC Everything that isn't protected.
     Everything,
+
+    /// Drop all chunks whose data on `timeline` falls behind the latest time on that timeline by
+    /// more than `duration`, i.e. keep a sliding window of data.
+    ///
+    /// `duration` is expressed in the native unit of the timeline: nanoseconds for temporal
+    /// timelines, ticks for sequence timelines.
+    ///
+    /// Chunks that don't have any data on `timeline` are left untouched.
+    OlderThan {
+        timeline: TimelineName,
+        duration: i64,
+    },
+}
+
+/// A sliding window of data to keep in a [`ChunkStore`], see [`GarbageCollectionTarget::OlderThan`].
+///
+/// Can be parsed from a `TIMELINE=DURATION` string, e.g. `log_time=30s` or `frame_nr=1000`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TimeToLive {
+    /// The timeline the window slides on.
+    pub timeline: TimelineName,
+
+    /// The length of the window, in the native unit of the timeline.
+    pub duration: i64,
+}
+
+impl From<TimeToLive> for GarbageCollectionTarget {
+    #[inline]
+    fn from(ttl: TimeToLive) -> Self {
+        let TimeToLive { timeline, duration } = ttl;
+        Self::OlderThan { timeline, duration }
+    }
+}
+
+impl std::fmt::Display for TimeToLive {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let Self { timeline, duration } = self;
+        write!(f, "{timeline}={duration}")
+    }
+}
+
+impl std::str::FromStr for TimeToLive {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (timeline, duration) = s
+            .split_once('=')
+            .ok_or_else(|| format!("expected TIMELINE=DURATION, got {s:?}"))?;
+
+        let timeline = timeline.trim();
+        if timeline.is_empty() {
+            return Err(format!("missing timeline name in {s:?}"));
+        }
+
+        // Plain integers are taken as-is (ticks or nanoseconds), anything else has to be a
+        // human-readable duration (e.g. `30s`, `5m`).
+        let duration = duration.trim();
+        let duration = duration
+            .parse::<i64>()
+            .or_else(|_| {
+                duration
+                    .parse::<re_log_types::Duration>()
+                    .map(|duration| duration.as_nanos())
+            })
+            .map_err(|err| format!("invalid duration {duration:?}: {err}"))?;
+
+        if duration <= 0 {
+            return Err(format!(
+                "duration must be strictly positive, got {duration}"
+            ));
+        }
+
+        Ok(Self {
+            timeline: TimelineName::new(timeline),
+            duration,
+        })
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -78,6 +155,20 @@ impl GarbageCollectionOptions {
         }
         false
     }
+
+    /// If true, we cannot remove a chunk covering these time ranges.
+    fn is_time_ranges_protected(
+        &self,
+        time_ranges: &IntMap<TimelineName, AbsoluteTimeRange>,
+    ) -> bool {
+        self.protected_time_ranges
+            .iter()
+            .any(|(timeline, protected_time_range)| {
+                time_ranges
+                    .get(timeline)
+                    .is_some_and(|time_range| time_range.intersects(*protected_time_range))
+            })
+    }
 }
 
 impl std::fmt::Display for GarbageCollectionTarget {
@@ -87,6 +178,9 @@ impl std::fmt::Display for GarbageCollectionTarget {
                 write!(f, "DropAtLeast({:.3}%)", *p * 100.0)
             }
             Self::Everything => write!(f, "Everything"),
+            Self::OlderThan { timeline, duration } => {
+                write!(f, "OlderThan({timeline}, {duration})")
+            }
         }
     }
 }
@@ -96,6 +190,39 @@ pub type RemovableChunkIdPerTimePerComponentPerTimelinePerEntity = IntMap<
     IntMap<TimelineName, IntMap<ComponentDescriptor, HashMap<TimeInt, Vec<ChunkId>>>>,
 >;
 
+/// Registers all the index entries of `chunk` in `chunk_ids_to_be_removed`.
+fn mark_chunk_for_removal(
+    chunk_ids_to_be_removed: &mut RemovableChunkIdPerTimePerComponentPerTimelinePerEntity,
+    chunk: &Chunk,
+) {
+    // NOTE: We cannot blindly `retain` across all temporal tables, it's way too costly
+    // and slow. Rather we need to surgically remove the superfluous chunks.
+    let entity_path = chunk.entity_path();
+    let per_timeline = chunk_ids_to_be_removed
+        .entry(entity_path.clone())
+        .or_default();
+    for (&timeline, time_column) in chunk.timelines() {
+        let per_component = per_timeline.entry(timeline).or_default();
+        for component_descr in chunk.component_descriptors() {
+            let per_time = per_component.entry(component_descr).or_default();
+
+            // NOTE: As usual, these are vectors of `ChunkId`s, as it is legal to
+            // have perfectly overlapping chunks.
+            let time_range = time_column.time_range();
+            per_time
+                .entry(time_range.min())
+                .or_default()
+                .push(chunk.id());
+            if time_range.min() != time_range.max() {
+                per_time
+                    .entry(time_range.max())
+                    .or_default()
+                    .push(chunk.id());
+            }
+        }
+    }
+}
+
 impl ChunkStore {
     /// Triggers a garbage collection according to the desired `target`.
     ///
@@ -155,7 +282,16 @@ impl ChunkStore {
                     "starting GC"
                 );
 
-                self.gc_drop_at_least_num_bytes(options, num_bytes_to_drop, &protected_chunk_ids)
+                // This is the only GC target that is driven by memory pressure: spill to the
+                // cold tier, if any, rather than losing that data forever.
+                let evict_to_cold_tier = self.init_cold_tier();
+
+                self.gc_drop_at_least_num_bytes(
+                    options,
+                    num_bytes_to_drop,
+                    &protected_chunk_ids,
+                    evict_to_cold_tier,
+                )
             }
             GarbageCollectionTarget::Everything => {
                 re_log::trace!(
@@ -167,7 +303,45 @@ impl ChunkStore {
                     "starting GC"
                 );
 
-                self.gc_drop_at_least_num_bytes(options, f64::INFINITY, &protected_chunk_ids)
+                if let Some(cold_tier) = &mut self.cold_tier {
+                    cold_tier.forget(|_entity_path, time_ranges| {
+                        !options.is_time_ranges_protected(time_ranges)
+                    });
+                }
+
+                self.gc_drop_at_least_num_bytes(options, f64::INFINITY, &protected_chunk_ids, false)
+            }
+            GarbageCollectionTarget::OlderThan { timeline, duration } => {
+                let cutoff = self.time_range(&timeline).map(|time_range| {
+                    TimeInt::saturated_temporal_i64(
+                        time_range.max().as_i64().saturating_sub(duration),
+                    )
+                });
+
+                re_log::trace!(
+                    kind = "gc",
+                    id = self.gc_id,
+                    %options.target,
+                    total_num_rows_before = re_format::format_uint(total_num_rows_before),
+                    total_size_bytes_before = re_format::format_bytes(total_size_bytes_before),
+                    ?cutoff,
+                    "starting GC"
+                );
+
+                if let (Some(cold_tier), Some(cutoff)) = (&mut self.cold_tier, cutoff) {
+                    cold_tier.forget(|_entity_path, time_ranges| {
+                        time_ranges
+                            .get(&timeline)
+                            .is_some_and(|time_range| time_range.max() < cutoff)
+                            && !options.is_time_ranges_protected(time_ranges)
+                    });
+                }
+
+                if let Some(cutoff) = cutoff {
+                    self.gc_drop_older_than(options, timeline, cutoff, &protected_chunk_ids)
+                } else {
+                    Vec::new()
+                }
             }
         };
 
@@ -269,12 +443,14 @@ impl ChunkStore {
         options: &GarbageCollectionOptions,
         mut num_bytes_to_drop: f64,
         protected_chunk_ids: &BTreeSet<ChunkId>,
+        evict_to_cold_tier: bool,
     ) -> Vec<ChunkStoreDiff> {
         re_tracing::profile_function!(re_format::format_bytes(num_bytes_to_drop));
 
         let mut chunk_ids_to_be_removed =
             RemovableChunkIdPerTimePerComponentPerTimelinePerEntity::default();
         let mut chunk_ids_dangling = HashSet::default();
+        let mut chunk_ids_evicted = HashSet::default();
 
         let start_time = Instant::now();
 
@@ -291,36 +467,27 @@ impl ChunkStore {
                         continue;
                     }
 
+                    // Evicting means writing to disk, which must count against the time budget
+                    // just like everything else: do it as part of marking.
+                    if evict_to_cold_tier
+                        && !chunk.is_static()
+                        && let Some(cold_tier) = &mut self.cold_tier
+                    {
+                        match cold_tier.evict(chunk) {
+                            Ok(()) => {
+                                chunk_ids_evicted.insert(*chunk_id);
+                            }
+                            Err(err) => {
+                                re_log::warn_once!("Failed to evict chunk to cold storage: {err}");
+                            }
+                        }
+                    }
+
                     // NOTE: Do _NOT_ use `chunk.total_size_bytes` as it is sitting behind an Arc
                     // and would count as amortized (i.e. 0 bytes).
                     num_bytes_to_drop -= <Chunk as SizeBytes>::total_size_bytes(chunk) as f64;
 
-                    // NOTE: We cannot blindly `retain` across all temporal tables, it's way too costly
-                    // and slow. Rather we need to surgically remove the superfluous chunks.
-                    let entity_path = chunk.entity_path();
-                    let per_timeline = chunk_ids_to_be_removed
-                        .entry(entity_path.clone())
-                        .or_default();
-                    for (&timeline, time_column) in chunk.timelines() {
-                        let per_component = per_timeline.entry(timeline).or_default();
-                        for component_descr in chunk.component_descriptors() {
-                            let per_time = per_component.entry(component_descr).or_default();
-
-                            // NOTE: As usual, these are vectors of `ChunkId`s, as it is legal to
-                            // have perfectly overlapping chunks.
-                            let time_range = time_column.time_range();
-                            per_time
-                                .entry(time_range.min())
-                                .or_default()
-                                .push(chunk.id());
-                            if time_range.min() != time_range.max() {
-                                per_time
-                                    .entry(time_range.max())
-                                    .or_default()
-                                    .push(chunk.id());
-                            }
-                        }
-                    }
+                    mark_chunk_for_removal(&mut chunk_ids_to_be_removed, chunk);
                 } else {
                     chunk_ids_dangling.insert(*chunk_id);
                 }
@@ -354,6 +521,7 @@ impl ChunkStore {
                 insert_id: _,
                 gc_id: _,
                 event_id: _,
+                cold_tier: _,
             } = self;
 
             let mut diffs = Vec::new();
@@ -447,10 +615,127 @@ impl ChunkStore {
                 ));
             }
 
+            if !chunk_ids_evicted.is_empty() {
+                diffs = diffs
+                    .into_iter()
+                    .map(|diff| {
+                        if diff.kind == ChunkStoreDiffKind::Deletion
+                            && chunk_ids_evicted.remove(&diff.chunk.id())
+                        {
+                            ChunkStoreDiff::eviction(diff.chunk)
+                        } else {
+                            diff
+                        }
+                    })
+                    .collect();
+
+                // Whatever the sweep didn't get to in time is still in the hot store, and will be
+                // evicted again next time.
+                if let Some(cold_tier) = &mut self.cold_tier {
+                    cold_tier.forget_chunks(&chunk_ids_evicted);
+                }
+            }
+
             diffs
         }
     }
 
+    /// Creates the cold tier if it's enabled and doesn't exist yet.
+    ///
+    /// Returns whether there is a cold tier to evict to.
+    ///
+    /// See [`crate::ChunkStoreConfig::cold_storage_dir`].
+    fn init_cold_tier(&mut self) -> bool {
+        if self.cold_tier.is_some() {
+            return true;
+        }
+
+        let Some(cold_storage_dir) = self.config.cold_storage_dir.as_deref() else {
+            return false;
+        };
+
+        match ColdTier::new(cold_storage_dir, &self.id) {
+            Ok(cold_tier) => {
+                self.cold_tier = Some(cold_tier);
+                true
+            }
+            Err(err) => {
+                re_log::warn_once!(
+                    "Failed to create cold storage in {cold_storage_dir:?}, evicted data will be lost: {err}"
+                );
+                false
+            }
+        }
+    }
+
+    /// Drops all the chunks whose data on `timeline` ends strictly before `cutoff`.
+    ///
+    /// See [`GarbageCollectionTarget::OlderThan`].
+    fn gc_drop_older_than(
+        &mut self,
+        options: &GarbageCollectionOptions,
+        timeline: TimelineName,
+        cutoff: TimeInt,
+        protected_chunk_ids: &BTreeSet<ChunkId>,
+    ) -> Vec<ChunkStoreDiff> {
+        re_tracing::profile_function!();
+
+        let mut chunk_ids_to_be_removed =
+            RemovableChunkIdPerTimePerComponentPerTimelinePerEntity::default();
+
+        let start_time = Instant::now();
+
+        {
+            re_tracing::profile_scope!("mark");
+
+            // NOTE: The component-less index is keyed by end time, so this only ever visits the
+            // chunks that are actually expired, no matter how large the store is.
+            let expired_chunk_ids = self
+                .temporal_chunk_ids_per_entity
+                .values()
+                .filter_map(|temporal_chunk_ids_per_timeline| {
+                    temporal_chunk_ids_per_timeline.get(&timeline)
+                })
+                .flat_map(|temporal_chunk_ids_per_time| {
+                    temporal_chunk_ids_per_time
+                        .per_end_time
+                        .range(..cutoff)
+                        .flat_map(|(_time, chunk_ids)| chunk_ids)
+                });
+
+            for chunk_id in expired_chunk_ids {
+                if protected_chunk_ids.contains(chunk_id) {
+                    continue;
+                }
+
+                let Some(chunk) = self.chunks_per_chunk_id.get(chunk_id) else {
+                    continue;
+                };
+
+                if options.is_chunk_protected(chunk) {
+                    continue;
+                }
+
+                mark_chunk_for_removal(&mut chunk_ids_to_be_removed, chunk);
+
+                // NOTE: Same as above: keep some of the time budget for the sweep phase.
+                if start_time.elapsed() >= options.time_budget / 4 {
+                    break;
+                }
+            }
+        }
+
+        if chunk_ids_to_be_removed.is_empty() {
+            return Vec::new();
+        }
+
+        re_tracing::profile_scope!("sweep");
+        self.remove_chunks(
+            chunk_ids_to_be_removed,
+            Some((start_time, options.time_budget)),
+        )
+    }
+
     /// Surgically removes a _temporal_ [`ChunkId`] from all indices.
     ///
     /// This is orders of magnitude faster than trying to `retain()` on all our internal indices.
@@ -14,6 +14,7 @@
 #![doc = document_features::document_features!()]
 //!
 
+mod cold;
 mod dataframe;
 mod drop_time_range;
 mod events;
@@ -26,11 +27,11 @@ mod writes;
 
 pub use self::{
     dataframe::{
-        Index, IndexRange, IndexValue, QueryExpression, SparseFillStrategy, StaticColumnSelection,
-        ViewContentsSelector,
+        Index, IndexRange, IndexValue, InterpolationStrategy, IsNotNullFilter, IsNotNullOperator,
+        QueryExpression, SparseFillStrategy, StaticColumnSelection, ViewContentsSelector,
     },
     events::{ChunkCompactionReport, ChunkStoreDiff, ChunkStoreDiffKind, ChunkStoreEvent},
-    gc::{GarbageCollectionOptions, GarbageCollectionTarget},
+    gc::{GarbageCollectionOptions, GarbageCollectionTarget, TimeToLive},
     stats::{ChunkStoreChunkStats, ChunkStoreStats},
     store::{ChunkStore, ChunkStoreConfig, ChunkStoreGeneration, ChunkStoreHandle, ColumnMetadata},
     subscribers::{ChunkStoreSubscriber, ChunkStoreSubscriberHandle, PerStoreChunkSubscriber},
@@ -15,7 +15,7 @@ use re_types_core::{
     ComponentDescriptor, ComponentDescriptorSet, ComponentType, UnorderedComponentDescriptorSet,
 };
 
-use crate::{ChunkStore, store::ChunkIdSetPerTime};
+use crate::{ChunkStore, cold::ColdTier, store::ChunkIdSetPerTime};
 
 // Used all over in docstrings.
 #[allow(unused_imports)]
@@ -621,6 +621,51 @@ impl ChunkStore {
     }
 }
 
+// Cold tier
+impl ChunkStore {
+    /// Appends the chunks returned by `cold_chunks` to `chunks`, if this store has evicted
+    /// anything to its cold tier.
+    ///
+    /// See [`crate::ChunkStoreConfig::cold_storage_dir`].
+    fn with_cold_chunks(
+        &self,
+        mut chunks: Vec<Arc<Chunk>>,
+        cold_chunks: impl FnOnce(&ColdTier) -> Vec<Arc<Chunk>>,
+    ) -> Vec<Arc<Chunk>> {
+        let Some(cold_tier) = self.cold_tier.as_ref() else {
+            return chunks;
+        };
+
+        let cold_chunks = cold_chunks(cold_tier);
+        if cold_chunks.is_empty() {
+            return chunks;
+        }
+
+        chunks.extend(cold_chunks);
+        chunks.into_iter().unique_by(|chunk| chunk.id()).collect()
+    }
+
+    /// The most recent time at or before the query time at which the hot store has data for the
+    /// given component, if any.
+    ///
+    /// See [`ColdTier::latest_at_relevant_chunks`].
+    fn hot_start_time(
+        &self,
+        query: &LatestAtQuery,
+        entity_path: &EntityPath,
+        component_descr: &ComponentDescriptor,
+    ) -> Option<TimeInt> {
+        self.temporal_chunk_ids_per_entity_per_component
+            .get(entity_path)?
+            .get(&query.timeline())?
+            .get(component_descr)?
+            .per_start_time
+            .range(..=query.at())
+            .next_back()
+            .map(|(time, _)| *time)
+    }
+}
+
 // LatestAt
 impl ChunkStore {
     /// Returns the most-relevant chunk(s) for the given [`LatestAtQuery`] and [`ComponentDescriptor`].
@@ -674,6 +719,15 @@ impl ChunkStore {
             })
             .unwrap_or_default();
 
+        let chunks = self.with_cold_chunks(chunks, |cold_tier| {
+            cold_tier.latest_at_relevant_chunks(
+                query,
+                entity_path,
+                |descr| descr == component_descr,
+                |descr| self.hot_start_time(query, entity_path, descr),
+            )
+        });
+
         debug_assert!(
             chunks.iter().map(|chunk| chunk.id()).all_unique(),
             "{entity_path}:{component_descr} @ {query:?}",
@@ -739,16 +793,26 @@ impl ChunkStore {
                 })
                 .flatten();
 
-            static_chunks
+            let chunks = static_chunks
                 .chain(temporal_chunks)
                 // Deduplicate before passing it along.
                 // Both temporal and static chunk "sets" here may have duplicates in them,
                 // so we de-duplicate them together to reduce the number of allocations.
                 .unique_by(|chunk| chunk.id())
-                .collect_vec()
+                .collect_vec();
+
+            self.with_cold_chunks(chunks, |cold_tier| {
+                cold_tier.latest_at_relevant_chunks(
+                    query,
+                    entity_path,
+                    |descr| !static_chunks_per_component.contains_key(descr),
+                    |descr| self.hot_start_time(query, entity_path, descr),
+                )
+            })
         } else {
             // This cannot yield duplicates by definition.
-            self.temporal_chunk_ids_per_entity
+            let chunks = self
+                .temporal_chunk_ids_per_entity
                 .get(entity_path)
                 .and_then(|temporal_chunk_ids_per_timeline| {
                     temporal_chunk_ids_per_timeline.get(&query.timeline())
@@ -756,7 +820,16 @@ impl ChunkStore {
                 .and_then(|temporal_chunk_ids_per_time| {
                     self.latest_at(query, temporal_chunk_ids_per_time)
                 })
-                .unwrap_or_default()
+                .unwrap_or_default();
+
+            self.with_cold_chunks(chunks, |cold_tier| {
+                cold_tier.latest_at_relevant_chunks(
+                    query,
+                    entity_path,
+                    |_| true,
+                    |descr| self.hot_start_time(query, entity_path, descr),
+                )
+            })
         };
 
         debug_assert!(chunks.iter().map(|chunk| chunk.id()).all_unique());
@@ -878,6 +951,10 @@ impl ChunkStore {
             })
             .collect_vec();
 
+        let chunks = self.with_cold_chunks(chunks, |cold_tier| {
+            cold_tier.range_relevant_chunks(query, entity_path, |descr| descr == component_descr)
+        });
+
         debug_assert!(chunks.iter().map(|chunk| chunk.id()).all_unique());
 
         chunks
@@ -972,6 +1049,18 @@ impl ChunkStore {
             })
             .collect_vec();
 
+        let chunks = self.with_cold_chunks(chunks, |cold_tier| {
+            let static_chunks_per_component = if include_static {
+                self.static_chunk_ids_per_entity.get(entity_path)
+            } else {
+                None
+            };
+            cold_tier.range_relevant_chunks(query, entity_path, |descr| {
+                static_chunks_per_component
+                    .is_none_or(|static_chunks| !static_chunks.contains_key(descr))
+            })
+        });
+
         debug_assert!(chunks.iter().map(|chunk| chunk.id()).all_unique());
 
         chunks
@@ -1,4 +1,5 @@
 use std::collections::{BTreeMap, BTreeSet};
+use std::path::PathBuf;
 use std::sync::Arc;
 use std::sync::atomic::AtomicU64;
 
@@ -9,7 +10,7 @@ use re_chunk::{Chunk, ChunkId, ComponentIdentifier, RowId, TimelineName};
 use re_log_types::{EntityPath, StoreId, StoreInfo, TimeInt, TimeType};
 use re_types_core::{ComponentDescriptor, ComponentType};
 
-use crate::{ChunkStoreChunkStats, ChunkStoreError, ChunkStoreResult};
+use crate::{ChunkStoreChunkStats, ChunkStoreError, ChunkStoreResult, TimeToLive, cold::ColdTier};
 
 // ---
 
@@ -72,6 +73,28 @@ pub struct ChunkStoreConfig {
     /// The default byte threshold is set to 8MiB, which is a reasonable unit of work when e.g.
     /// sending chunks over the network.
     pub chunk_max_rows_if_unsorted: u64,
+
+    /// If set, only keep a sliding window of data on a given timeline: whenever new data comes
+    /// in, everything that falls behind the latest time by more than the configured duration gets
+    /// garbage collected.
+    ///
+    /// This is applied by the owner of the store (e.g. the viewer's `EntityDb`) after each
+    /// insertion, see [`crate::GarbageCollectionTarget::OlderThan`].
+    ///
+    /// Disabled by default.
+    pub time_to_live: Option<TimeToLive>,
+
+    /// If set, chunks that get garbage collected because of memory pressure are spilled to a
+    /// cache file in this directory rather than being dropped for good.
+    ///
+    /// Evicted chunks are transparently reloaded by [`ChunkStore::latest_at_relevant_chunks`],
+    /// [`ChunkStore::range_relevant_chunks`] and friends whenever a query touches their time range.
+    /// The corresponding store events are flagged with [`crate::ChunkStoreDiff::evicted`].
+    ///
+    /// The cache file is deleted when the store is dropped.
+    ///
+    /// Disabled by default.
+    pub cold_storage_dir: Option<PathBuf>,
     //
     // TODO(cmc): It could make sense to have time-range-based thresholds in here, since the time
     // range covered by a chunk has direct effects on A) the complexity of backward walks and
@@ -109,6 +132,9 @@ impl ChunkStoreConfig {
         chunk_max_rows: 4096,
 
         chunk_max_rows_if_unsorted: 1024,
+
+        time_to_live: None,
+        cold_storage_dir: None,
     };
 
     /// [`Self::DEFAULT`], but with compaction entirely disabled.
@@ -131,6 +157,8 @@ impl ChunkStoreConfig {
         chunk_max_bytes: 0,
         chunk_max_rows: 0,
         chunk_max_rows_if_unsorted: 0,
+        time_to_live: None,
+        cold_storage_dir: None,
     };
 
     /// Environment variable to configure [`Self::enable_changelog`].
@@ -147,6 +175,14 @@ impl ChunkStoreConfig {
     // NOTE: Shared with the same env-var on the batcher side, for consistency.
     pub const ENV_CHUNK_MAX_ROWS_IF_UNSORTED: &'static str = "RERUN_CHUNK_MAX_ROWS_IF_UNSORTED";
 
+    /// Environment variable to configure [`Self::time_to_live`].
+    ///
+    /// Expects a `TIMELINE=DURATION` string, e.g. `log_time=30s` or `frame_nr=1000`.
+    pub const ENV_STORE_TIME_TO_LIVE: &'static str = "RERUN_STORE_TIME_TO_LIVE";
+
+    /// Environment variable to configure [`Self::cold_storage_dir`].
+    pub const ENV_STORE_COLD_STORAGE_DIR: &'static str = "RERUN_STORE_COLD_STORAGE_DIR";
+
     /// Creates a new `ChunkStoreConfig` using the default values, optionally overridden
     /// through the environment.
     ///
@@ -159,8 +195,9 @@ impl ChunkStoreConfig {
     /// Returns a copy of `self`, overriding existing fields with values from the environment if
     /// they are present.
     ///
-    /// See [`Self::ENV_STORE_ENABLE_CHANGELOG`], [`Self::ENV_CHUNK_MAX_BYTES`], [`Self::ENV_CHUNK_MAX_ROWS`]
-    /// and [`Self::ENV_CHUNK_MAX_ROWS_IF_UNSORTED`].
+    /// See [`Self::ENV_STORE_ENABLE_CHANGELOG`], [`Self::ENV_CHUNK_MAX_BYTES`], [`Self::ENV_CHUNK_MAX_ROWS`],
+    /// [`Self::ENV_CHUNK_MAX_ROWS_IF_UNSORTED`], [`Self::ENV_STORE_TIME_TO_LIVE`] and
+    /// [`Self::ENV_STORE_COLD_STORAGE_DIR`].
     pub fn apply_env(&self) -> ChunkStoreResult<Self> {
         let mut new = self.clone();
 
@@ -197,6 +234,21 @@ impl ChunkStoreConfig {
                 })?;
         }
 
+        if let Ok(s) = std::env::var(Self::ENV_STORE_TIME_TO_LIVE) {
+            let time_to_live = s
+                .parse()
+                .map_err(|err: String| ChunkStoreError::ParseConfig {
+                    name: Self::ENV_STORE_TIME_TO_LIVE,
+                    value: s.clone(),
+                    err: err.into(),
+                })?;
+            new.time_to_live = Some(time_to_live);
+        }
+
+        if let Ok(s) = std::env::var(Self::ENV_STORE_COLD_STORAGE_DIR) {
+            new.cold_storage_dir = (!s.is_empty()).then(|| PathBuf::from(s));
+        }
+
         Ok(new)
     }
 }
@@ -212,6 +264,8 @@ fn chunk_store_config() {
         std::env::set_var("RERUN_CHUNK_MAX_BYTES", "42");
         std::env::set_var("RERUN_CHUNK_MAX_ROWS", "666");
         std::env::set_var("RERUN_CHUNK_MAX_ROWS_IF_UNSORTED", "999");
+        std::env::set_var("RERUN_STORE_TIME_TO_LIVE", "log_time=30s");
+        std::env::set_var("RERUN_STORE_COLD_STORAGE_DIR", "/tmp/rerun");
     };
 
     let config = ChunkStoreConfig::from_env().unwrap();
@@ -221,6 +275,11 @@ fn chunk_store_config() {
         chunk_max_bytes: 42,
         chunk_max_rows: 666,
         chunk_max_rows_if_unsorted: 999,
+        time_to_live: Some(TimeToLive {
+            timeline: TimelineName::log_time(),
+            duration: 30_000_000_000,
+        }),
+        cold_storage_dir: Some(PathBuf::from("/tmp/rerun")),
     };
 
     assert_eq!(expected, config);
@@ -469,6 +528,13 @@ pub struct ChunkStore {
 
     /// Monotonically increasing ID for store events.
     pub(crate) event_id: AtomicU64,
+
+    /// Chunks that were evicted to disk because of memory pressure.
+    ///
+    /// Created lazily, on the first eviction. See [`ChunkStoreConfig::cold_storage_dir`].
+    ///
+    /// Clones of the store get their own copy of the index, but share the files on disk.
+    pub(crate) cold_tier: Option<ColdTier>,
 }
 
 impl Drop for ChunkStore {
@@ -508,6 +574,7 @@ impl Clone for ChunkStore {
             insert_id: Default::default(),
             gc_id: Default::default(),
             event_id: Default::default(),
+            cold_tier: self.cold_tier.clone(),
         }
     }
 }
@@ -531,6 +598,7 @@ impl std::fmt::Display for ChunkStore {
             insert_id: _,
             gc_id: _,
             event_id: _,
+            cold_tier,
         } = self;
 
         f.write_str("ChunkStore {\n")?;
@@ -545,6 +613,17 @@ impl std::fmt::Display for ChunkStore {
         ))?;
         f.write_str(&indent::indent_all_by(4, "}\n"))?;
 
+        if let Some(cold_tier) = cold_tier {
+            f.write_str(&indent::indent_all_by(
+                4,
+                format!(
+                    "cold tier: {} chunks ({})\n",
+                    re_format::format_uint(cold_tier.num_chunks()),
+                    re_format::format_bytes(cold_tier.num_bytes() as _),
+                ),
+            ))?;
+        }
+
         f.write_str(&indent::indent_all_by(4, "chunks: [\n"))?;
         for chunk_id in chunk_id_per_min_row_id.values() {
             if let Some(chunk) = chunks_per_chunk_id.get(chunk_id) {
@@ -593,6 +672,7 @@ impl ChunkStore {
             insert_id: 0,
             gc_id: 0,
             event_id: AtomicU64::new(0),
+            cold_tier: None,
         }
     }
 
@@ -709,28 +789,46 @@ impl ChunkStore {
     ///
     /// See also:
     /// * [`ChunkStore::new`]
+    /// * [`ChunkStore::from_rrd_filepath_with_query`]
     #[cfg(not(target_arch = "wasm32"))]
     pub fn from_rrd_filepath(
         store_config: &ChunkStoreConfig,
         path_to_rrd: impl AsRef<std::path::Path>,
+    ) -> anyhow::Result<BTreeMap<StoreId, Self>> {
+        Self::from_rrd_filepath_with_query(
+            store_config,
+            path_to_rrd,
+            &re_log_encoding::footer::RrdFooterQuery::default(),
+        )
+    }
+
+    /// Instantiate a new `ChunkStore` with the given [`ChunkStoreConfig`].
+    ///
+    /// The stores will be prefilled with the chunks at the specified path that match the given
+    /// `query`.
+    ///
+    /// If the file was written with a footer, only the matching chunks are read from disk.
+    /// Otherwise, the whole file is decoded and filtered on the fly.
+    ///
+    /// See also:
+    /// * [`ChunkStore::new`]
+    /// * [`ChunkStore::from_rrd_filepath`]
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn from_rrd_filepath_with_query(
+        store_config: &ChunkStoreConfig,
+        path_to_rrd: impl AsRef<std::path::Path>,
+        query: &re_log_encoding::footer::RrdFooterQuery,
     ) -> anyhow::Result<BTreeMap<StoreId, Self>> {
         let path_to_rrd = path_to_rrd.as_ref();
 
         re_tracing::profile_function!(path_to_rrd.to_string_lossy());
 
         use anyhow::Context as _;
+        use std::io::Seek as _;
 
         let mut stores = BTreeMap::new();
 
-        let rrd_file = std::fs::File::open(path_to_rrd)
-            .with_context(|| format!("couldn't open {path_to_rrd:?}"))?;
-
-        let mut decoder = re_log_encoding::decoder::Decoder::new(rrd_file)
-            .with_context(|| format!("couldn't decode {path_to_rrd:?}"))?;
-
-        // TODO(cmc): offload the decoding to a background thread.
-        for res in &mut decoder {
-            let msg = res.with_context(|| format!("couldn't decode message {path_to_rrd:?}"))?;
+        let mut insert_msg = |msg: re_log_types::LogMsg| -> anyhow::Result<()> {
             match msg {
                 re_log_types::LogMsg::SetStoreInfo(info) => {
                     let store = stores.entry(info.info.store_id.clone()).or_insert_with(|| {
@@ -748,6 +846,12 @@ impl ChunkStore {
                     let chunk = Chunk::from_arrow_msg(&msg)
                         .with_context(|| format!("couldn't decode chunk {path_to_rrd:?}"))?;
 
+                    // Only relevant when reading files without a footer.
+                    let footer_chunk = re_log_encoding::footer::RrdFooterChunk::from_chunk(&chunk);
+                    if !query.matches(&store_id, &footer_chunk) {
+                        return Ok(());
+                    }
+
                     store
                         .insert_chunk(&Arc::new(chunk))
                         .with_context(|| format!("couldn't insert chunk {path_to_rrd:?}"))?;
@@ -755,6 +859,38 @@ impl ChunkStore {
 
                 re_log_types::LogMsg::BlueprintActivationCommand(_) => {}
             }
+
+            Ok(())
+        };
+
+        let mut rrd_file = std::fs::File::open(path_to_rrd)
+            .with_context(|| format!("couldn't open {path_to_rrd:?}"))?;
+
+        if !query.is_everything() {
+            let decoder = re_log_encoding::decoder::seekable::SeekableDecoder::new(&rrd_file)
+                .with_context(|| format!("couldn't decode {path_to_rrd:?}"))?;
+
+            if let Some(mut decoder) = decoder {
+                for res in decoder.read_query(query) {
+                    insert_msg(
+                        res.with_context(|| format!("couldn't decode message {path_to_rrd:?}"))?,
+                    )?;
+                }
+
+                return Ok(stores);
+            }
+
+            rrd_file
+                .rewind()
+                .with_context(|| format!("couldn't rewind {path_to_rrd:?}"))?;
+        }
+
+        let mut decoder = re_log_encoding::decoder::Decoder::new(rrd_file)
+            .with_context(|| format!("couldn't decode {path_to_rrd:?}"))?;
+
+        // TODO(cmc): offload the decoding to a background thread.
+        for res in &mut decoder {
+            insert_msg(res.with_context(|| format!("couldn't decode message {path_to_rrd:?}"))?)?;
         }
 
         Ok(stores)
@@ -464,6 +464,8 @@ impl ChunkStore {
                 chunk_max_bytes,
                 chunk_max_rows,
                 chunk_max_rows_if_unsorted,
+                time_to_live: _,
+                cold_storage_dir: _,
             } = self.config;
 
             let total_bytes = <Chunk as SizeBytes>::total_size_bytes(chunk);
@@ -489,6 +491,8 @@ impl ChunkStore {
                     chunk_max_bytes,
                     chunk_max_rows,
                     chunk_max_rows_if_unsorted,
+                    time_to_live: _,
+                    cold_storage_dir: _,
                 } = store.config;
 
                 *candidates_below_threshold
@@ -623,10 +627,15 @@ impl ChunkStore {
             insert_id: _,
             gc_id: _,
             event_id,
+            cold_tier,
         } = self;
 
         per_column_metadata.remove(entity_path);
 
+        if let Some(cold_tier) = cold_tier {
+            cold_tier.forget(|cold_entity_path, _time_ranges| cold_entity_path == entity_path);
+        }
+
         let dropped_static_chunks = {
             let dropped_static_chunk_ids: BTreeSet<_> = static_chunk_ids_per_entity
                 .remove(entity_path)
@@ -1177,6 +1186,8 @@ mod tests {
                     chunk_max_bytes: u64::MAX,
                     chunk_max_rows: u64::MAX,
                     chunk_max_rows_if_unsorted: u64::MAX,
+                    time_to_live: None,
+                    cold_storage_dir: None,
                 },
             );
 
@@ -1206,6 +1217,8 @@ mod tests {
                     chunk_max_bytes: u64::MAX,
                     chunk_max_rows: u64::MAX,
                     chunk_max_rows_if_unsorted: u64::MAX,
+                    time_to_live: None,
+                    cold_storage_dir: None,
                 },
             );
 
@@ -120,6 +120,7 @@ fn schema_for_query() -> anyhow::Result<()> {
         filtered_index_range: None,
         filtered_index_values: None,
         using_index_values: None,
+        interpolation_strategy: re_chunk_store::InterpolationStrategy::None,
         filtered_is_not_null: None,
         sparse_fill_strategy: re_chunk_store::SparseFillStrategy::None,
         selection: None,
@@ -185,6 +186,7 @@ fn schema_static_columns() -> anyhow::Result<()> {
         filtered_index_range: None,
         filtered_index_values: None,
         using_index_values: None,
+        interpolation_strategy: re_chunk_store::InterpolationStrategy::None,
         filtered_is_not_null: None,
         sparse_fill_strategy: re_chunk_store::SparseFillStrategy::None,
         selection: None,
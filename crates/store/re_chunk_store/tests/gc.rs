@@ -3,10 +3,12 @@ use std::sync::Arc;
 use arrow::array::ArrayRef;
 use rand::Rng as _;
 
-use re_chunk::{Chunk, ChunkId, LatestAtQuery, RowId, TimeInt, TimePoint, TimelineName};
+use re_chunk::{
+    Chunk, ChunkId, LatestAtQuery, RangeQuery, RowId, TimeInt, TimePoint, TimelineName,
+};
 use re_chunk_store::{
     ChunkStore, ChunkStoreConfig, ChunkStoreDiffKind, GarbageCollectionOptions,
-    GarbageCollectionTarget,
+    GarbageCollectionTarget, TimeToLive,
 };
 use re_log_types::{
     AbsoluteTimeRange, EntityPath, Timestamp, build_frame_nr, build_log_time,
@@ -447,6 +449,187 @@ fn protected_time_ranges() -> anyhow::Result<()> {
 
 // ---
 
+#[test]
+fn older_than() -> anyhow::Result<()> {
+    re_log::setup_logging();
+
+    let mut store = ChunkStore::new(
+        re_log_types::StoreId::random(re_log_types::StoreKind::Recording, "test_app"),
+        ChunkStoreConfig::COMPACTION_DISABLED,
+    );
+
+    let entity_path = EntityPath::from("this/that");
+    let other_entity_path = EntityPath::from("other");
+
+    for frame_nr in 1..=10 {
+        let chunk = Chunk::builder(entity_path.clone())
+            .with_component_batches(
+                RowId::new(),
+                [build_frame_nr(TimeInt::new_temporal(frame_nr))],
+                [(
+                    MyPoints::descriptor_points(),
+                    &MyPoint::from_iter(0..3) as _,
+                )],
+            )
+            .build()?;
+        store.insert_chunk(&Arc::new(chunk))?;
+    }
+
+    // Not on the `frame_nr` timeline at all: must be left untouched.
+    let other_chunk = Chunk::builder(other_entity_path.clone())
+        .with_component_batches(
+            RowId::new(),
+            [build_log_time(Timestamp::now())],
+            [(
+                MyPoints::descriptor_colors(),
+                &MyColor::from_iter(0..3) as _,
+            )],
+        )
+        .build()?;
+    store.insert_chunk(&Arc::new(other_chunk))?;
+
+    let (events, _stats_diff) = store.gc(&GarbageCollectionOptions {
+        target: GarbageCollectionTarget::OlderThan {
+            timeline: TimelineName::new("frame_nr"),
+            duration: 3,
+        },
+        ..GarbageCollectionOptions::gc_everything()
+    });
+
+    eprintln!("{store}");
+
+    // Latest time is 10, so everything that ends before 7 goes away.
+    assert_eq!(6, events.len());
+    assert!(
+        events
+            .iter()
+            .all(|event| event.kind == ChunkStoreDiffKind::Deletion)
+    );
+
+    assert_eq!(
+        Some(AbsoluteTimeRange::new(
+            TimeInt::new_temporal(7),
+            TimeInt::new_temporal(10)
+        )),
+        store.entity_time_range(&TimelineName::new("frame_nr"), &entity_path),
+    );
+    assert!(
+        store
+            .entity_time_range(&TimelineName::log_time(), &other_entity_path)
+            .is_some()
+    );
+
+    // Nothing left to collect.
+    let (events, _stats_diff) = store.gc(&GarbageCollectionOptions {
+        target: GarbageCollectionTarget::OlderThan {
+            timeline: TimelineName::new("frame_nr"),
+            duration: 3,
+        },
+        ..GarbageCollectionOptions::gc_everything()
+    });
+    assert!(events.is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn cold_storage() -> anyhow::Result<()> {
+    re_log::setup_logging();
+
+    let cold_storage_dir = tempfile::tempdir()?;
+
+    let mut store = ChunkStore::new(
+        re_log_types::StoreId::random(re_log_types::StoreKind::Recording, "test_app"),
+        ChunkStoreConfig {
+            cold_storage_dir: Some(cold_storage_dir.path().to_owned()),
+            ..ChunkStoreConfig::COMPACTION_DISABLED
+        },
+    );
+
+    let entity_path = EntityPath::from("this/that");
+    let timeline_frame_nr = TimelineName::new("frame_nr");
+
+    let mut row_ids = Vec::new();
+    for frame_nr in 1..=10 {
+        let row_id = RowId::new();
+        row_ids.push(row_id);
+        let chunk = Chunk::builder(entity_path.clone())
+            .with_component_batches(
+                row_id,
+                [build_frame_nr(TimeInt::new_temporal(frame_nr))],
+                [(
+                    MyPoints::descriptor_points(),
+                    &MyPoint::from_iter(0..frame_nr as u32) as _,
+                )],
+            )
+            .build()?;
+        store.insert_chunk(&Arc::new(chunk))?;
+    }
+
+    let (events, _stats_diff) = store.gc(&GarbageCollectionOptions {
+        target: GarbageCollectionTarget::DropAtLeastFraction(1.0),
+        ..GarbageCollectionOptions::gc_everything()
+    });
+
+    eprintln!("{store}");
+
+    // Everything was spilled to disk…
+    assert_eq!(10, events.len());
+    assert!(
+        events
+            .iter()
+            .all(|event| event.kind == ChunkStoreDiffKind::Deletion && event.evicted)
+    );
+    assert_eq!(0, store.num_chunks());
+
+    // …but can still be queried.
+    let query = LatestAtQuery::new(timeline_frame_nr, 5);
+    let (data_time, row_id, array) =
+        query_latest_array(&store, &entity_path, &MyPoints::descriptor_points(), &query)
+            .expect("evicted data should be reloaded from disk");
+    assert_eq!(TimeInt::new_temporal(5), data_time);
+    assert_eq!(row_ids[4], row_id);
+    assert_eq!(5, array.len());
+
+    let query = RangeQuery::new(
+        timeline_frame_nr,
+        AbsoluteTimeRange::new(TimeInt::new_temporal(3), TimeInt::new_temporal(6)),
+    );
+    let chunks = store.range_relevant_chunks(&query, &entity_path, &MyPoints::descriptor_points());
+    assert_eq!(4, chunks.len());
+    let chunks = store.range_relevant_chunks_for_all_components(&query, &entity_path, true);
+    assert_eq!(4, chunks.len());
+
+    // Collecting everything gets rid of the cold data too.
+    store.gc(&GarbageCollectionOptions::gc_everything());
+    let query = LatestAtQuery::new(timeline_frame_nr, 5);
+    assert!(
+        query_latest_array(&store, &entity_path, &MyPoints::descriptor_points(), &query).is_none()
+    );
+
+    // The cold storage file goes away with the store.
+    drop(store);
+    assert_eq!(0, std::fs::read_dir(cold_storage_dir.path())?.count());
+
+    Ok(())
+}
+
+#[test]
+fn parse_time_to_live() {
+    let ttl: TimeToLive = "frame_nr=100".parse().unwrap();
+    assert_eq!(TimelineName::new("frame_nr"), ttl.timeline);
+    assert_eq!(100, ttl.duration);
+
+    let ttl: TimeToLive = "log_time=30s".parse().unwrap();
+    assert_eq!(TimelineName::log_time(), ttl.timeline);
+    assert_eq!(30_000_000_000, ttl.duration);
+
+    assert!("frame_nr".parse::<TimeToLive>().is_err());
+    assert!("=100".parse::<TimeToLive>().is_err());
+    assert!("frame_nr=0".parse::<TimeToLive>().is_err());
+    assert!("frame_nr=-1".parse::<TimeToLive>().is_err());
+}
+
 #[test]
 fn manual_drop_entity_path() -> anyhow::Result<()> {
     re_log::setup_logging();
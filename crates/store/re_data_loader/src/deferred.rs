@@ -0,0 +1,227 @@
+//! Deferring the loading of a store until it is actually needed.
+//!
+//! Some data sources contain many independent recordings, e.g. every episode of a `LeRobot`
+//! dataset. Loading all of them upfront is slow and memory hungry, so loaders can instead announce
+//! a store and register how to load it with [`DeferredStores::defer_store`]. The consumer then
+//! calls [`DeferredStores::load`] once the store is needed, e.g. when the viewer first shows it.
+//!
+//! Deferring is opt-in: loaders only defer stores if [`crate::DataLoaderSettings::deferred_stores`]
+//! is set, which only consumers that call [`DeferredStores::load`] should do. Otherwise deferred
+//! stores would never be loaded.
+//!
+//! A deferred store is loaded into a channel of its own, independent of the channel of the
+//! loader that deferred it. That way, the loader's channel is closed once everything else is
+//! loaded, and stores that are never requested don't keep it open.
+
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use ahash::HashMap;
+use parking_lot::Mutex;
+
+use re_log_types::{FileSource, LogMsg, StoreId};
+use re_smart_channel::{SmartChannelSource, SmartMessageSource};
+
+use crate::{DataLoaderSettings, LoadedData};
+
+type LoadFn = Box<dyn FnOnce(std::sync::mpsc::Sender<LoadedData>) + Send>;
+
+struct DeferredStore {
+    /// The file the store is loaded from.
+    path: PathBuf,
+
+    /// The settings of the load that deferred the store.
+    settings: DataLoaderSettings,
+
+    /// Where the file that the store is loaded from comes from.
+    file_source: FileSource,
+
+    load: LoadFn,
+}
+
+/// The stores whose loading was deferred, see the [module docs](self).
+///
+/// Cloning this is cheap, and the clones share the same stores.
+#[derive(Clone)]
+pub struct DeferredStores {
+    stores: Arc<Mutex<HashMap<StoreId, DeferredStore>>>,
+
+    /// Where the files of the stores deferred through this handle come from.
+    ///
+    /// Set for each load, see [`Self::with_file_source`].
+    file_source: FileSource,
+}
+
+impl Default for DeferredStores {
+    fn default() -> Self {
+        Self {
+            stores: Default::default(),
+            file_source: FileSource::Sdk,
+        }
+    }
+}
+
+impl std::fmt::Debug for DeferredStores {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DeferredStores")
+            .field("num_stores", &self.stores.lock().len())
+            .field("file_source", &self.file_source)
+            .finish()
+    }
+}
+
+impl DeferredStores {
+    /// The same stores, for deferring the stores of a file that comes from `file_source`.
+    pub(crate) fn with_file_source(&self, file_source: FileSource) -> Self {
+        Self {
+            stores: Arc::clone(&self.stores),
+            file_source,
+        }
+    }
+
+    /// Registers `load` to be called the first time the store is requested with [`Self::load`].
+    ///
+    /// `load` is called on the thread requesting the store, so it must not block: it should only
+    /// kick off the loading, e.g. on a dedicated thread, and send the store's data to the given
+    /// sender. The store must have been announced already, as no `SetStoreInfo` is sent for it.
+    ///
+    /// The data is processed with the `settings` the store was deferred with.
+    pub fn defer_store(
+        &self,
+        store_id: StoreId,
+        path: PathBuf,
+        settings: &DataLoaderSettings,
+        load: impl FnOnce(std::sync::mpsc::Sender<LoadedData>) + Send + 'static,
+    ) {
+        self.stores.lock().insert(
+            store_id,
+            DeferredStore {
+                path,
+                settings: DataLoaderSettings {
+                    // Deferred stores are loaded in full, and this would keep the stores alive.
+                    deferred_stores: None,
+                    ..settings.clone()
+                },
+                file_source: self.file_source.clone(),
+                load: Box::new(load),
+            },
+        );
+    }
+
+    /// Starts loading the given store, if its loading was deferred and hasn't started yet.
+    ///
+    /// Returns the channel the store is loaded into, if loading was started.
+    pub fn load(&self, store_id: &StoreId) -> Option<re_smart_channel::Receiver<LogMsg>> {
+        // Don't hold the lock while loading, `load` may defer other stores.
+        let DeferredStore {
+            path,
+            settings,
+            file_source,
+            load,
+        } = self.stores.lock().remove(store_id)?;
+
+        re_log::debug!("Loading deferred store {store_id:?}");
+
+        let (tx_loader, rx_loader) = std::sync::mpsc::channel();
+        load(tx_loader);
+
+        let (tx, rx) = re_smart_channel::smart_channel(
+            SmartMessageSource::File(path.clone()),
+            SmartChannelSource::File(path),
+        );
+
+        // The store was announced when it got deferred.
+        let settings = DataLoaderSettings {
+            opened_store_id: Some(store_id.clone()),
+            force_store_info: false,
+            ..settings
+        };
+        crate::load_file::send(settings, file_source, rx_loader, &tx);
+
+        Some(rx)
+    }
+
+    /// Drops the deferred loading of a store, e.g. once it is closed without ever being loaded.
+    pub fn forget_store(&self, store_id: &StoreId) {
+        self.stores.lock().remove(store_id);
+    }
+
+    /// Drops the deferred loading of all stores, see [`Self::forget_store`].
+    pub fn forget_all_stores(&self) {
+        self.stores.lock().clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use re_chunk::{Chunk, RowId, TimePoint};
+    use re_log_types::StoreKind;
+    use re_smart_channel::SmartMessagePayload;
+    use re_types::archetypes::TextDocument;
+
+    use super::*;
+    use crate::{DataLoader as _, LeRobotDatasetLoader};
+
+    #[test]
+    fn deferred_store_is_loaded_into_its_own_channel() -> anyhow::Result<()> {
+        let store_id = StoreId::random(StoreKind::Recording, "test");
+        let chunk = Chunk::builder("text")
+            .with_archetype(RowId::new(), TimePoint::STATIC, &TextDocument::new("hello"))
+            .build()?;
+
+        let deferred_stores = DeferredStores::default();
+        let settings = DataLoaderSettings::recommended("test");
+        deferred_stores.defer_store(store_id.clone(), PathBuf::from("dataset"), &settings, {
+            let store_id = store_id.clone();
+            move |tx| {
+                let data = LoadedData::Chunk(LeRobotDatasetLoader.name(), store_id, chunk);
+                tx.send(data).ok();
+            }
+        });
+
+        let rx = deferred_stores
+            .load(&store_id)
+            .expect("the store was deferred");
+        assert!(
+            deferred_stores.load(&store_id).is_none(),
+            "loaded only once"
+        );
+
+        // The channel ends once `load` is done with its sender.
+        let mut num_chunks = 0;
+        loop {
+            match rx.recv()?.payload {
+                SmartMessagePayload::Msg(LogMsg::ArrowMsg(msg_store_id, _)) => {
+                    assert_eq!(msg_store_id, store_id);
+                    num_chunks += 1;
+                }
+                SmartMessagePayload::Msg(msg) => panic!("unexpected message {msg:?}"),
+                SmartMessagePayload::Flush { .. } => {}
+                SmartMessagePayload::Quit(err) => {
+                    assert!(err.is_none());
+                    break;
+                }
+            }
+        }
+        assert_eq!(num_chunks, 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn forgotten_store_is_not_loaded() {
+        let store_id = StoreId::random(StoreKind::Recording, "test");
+        let deferred_stores = DeferredStores::default();
+        deferred_stores.defer_store(
+            store_id.clone(),
+            PathBuf::from("dataset"),
+            &DataLoaderSettings::recommended("test"),
+            |_tx| {
+                panic!("forgotten stores must not be loaded");
+            },
+        );
+
+        deferred_stores.forget_store(&store_id);
+        assert!(deferred_stores.load(&store_id).is_none());
+    }
+}
@@ -0,0 +1,1201 @@
+//! Conversion of Rerun recordings into `LeRobot` datasets, see [`LeRobotDatasetWriter`].
+
+use std::{
+    collections::BTreeMap,
+    io::{Cursor, Write as _},
+    path::{Path, PathBuf},
+    process::{ChildStdin, Command, Stdio},
+    sync::Arc,
+};
+
+use ahash::HashMap;
+use arrow::{
+    array::{
+        Array as _, ArrayRef, AsArray as _, BinaryArray, FixedSizeListArray, Float32Array,
+        Int64Array, RecordBatch, StringArray, StructArray,
+    },
+    compute::cast,
+    datatypes::{DataType, Field, Float64Type, Schema},
+};
+use re_chunk::{Chunk, EntityPath, TimelineName};
+use re_log_types::TimeType;
+use re_types::{
+    Loggable as _, archetypes, components,
+    datatypes::{ChannelDatatype, ColorModel, ImageFormat},
+};
+
+use super::{
+    DType, EpisodeIndex, Feature, LeRobotDatasetEpisode, LeRobotDatasetInfo, LeRobotDatasetTask,
+    LeRobotError, Names, TaskIndex,
+};
+
+/// The version of the `LeRobot` dataset format that we write.
+const CODEBASE_VERSION: &str = "v2.1";
+
+/// How many episodes are stored in each chunk directory.
+const CHUNKS_SIZE: usize = 1000;
+
+const DATA_PATH: &str = "data/chunk-{episode_chunk:03d}/episode_{episode_index:06d}.parquet";
+const VIDEO_PATH: &str =
+    "videos/chunk-{episode_chunk:03d}/{video_key}/episode_{episode_index:06d}.mp4";
+
+/// Where the values of a `LeRobot` feature come from.
+///
+/// Parsed from `ENTITY_PATH:COMPONENT=FEATURE[:DTYPE]`, e.g.
+/// `/robot/joints:Scalars:scalars=observation.state:float32`.
+///
+/// If the dtype is omitted, it is [`DType::Video`] for images and [`DType::Float32`] for
+/// everything else.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FeatureMapping {
+    /// The entity holding the values.
+    pub entity_path: EntityPath,
+
+    /// The component holding the values, e.g. `Scalars:scalars`.
+    pub component: String,
+
+    /// The name of the feature in the dataset, e.g. `observation.state`.
+    pub feature: String,
+
+    /// How the feature is stored in the dataset.
+    ///
+    /// [`DType::Image`] and [`DType::Video`] features can be read from either
+    /// `EncodedImage:blob` or `Image:buffer` (8-bit grayscale, RGB or RGBA) components.
+    /// All other dtypes are cast from the Arrow data of the component.
+    pub dtype: DType,
+}
+
+impl FeatureMapping {
+    fn is_image_component(component: &str) -> bool {
+        component
+            == archetypes::EncodedImage::descriptor_blob()
+                .component
+                .as_str()
+            || component == archetypes::Image::descriptor_buffer().component.as_str()
+    }
+}
+
+impl std::str::FromStr for FeatureMapping {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (source, target) = s
+            .rsplit_once('=')
+            .ok_or_else(|| format!("expected ENTITY_PATH:COMPONENT=FEATURE[:DTYPE], got {s:?}"))?;
+
+        let (entity_path, component) = source
+            .split_once(':')
+            .filter(|(entity_path, component)| !entity_path.is_empty() && !component.is_empty())
+            .ok_or_else(|| format!("expected ENTITY_PATH:COMPONENT, got {source:?}"))?;
+
+        let (feature, dtype) = if let Some((feature, dtype)) = target.split_once(':') {
+            let dtype = serde_json::from_value(serde_json::Value::String(dtype.to_owned()))
+                .map_err(|_err| format!("unknown dtype {dtype:?}"))?;
+            (feature, dtype)
+        } else if Self::is_image_component(component) {
+            (target, DType::Video)
+        } else {
+            (target, DType::Float32)
+        };
+
+        if feature.is_empty() {
+            return Err(format!("missing feature name in {s:?}"));
+        }
+
+        Ok(Self {
+            entity_path: EntityPath::parse_forgiving(entity_path),
+            component: component.to_owned(),
+            feature: feature.to_owned(),
+            dtype,
+        })
+    }
+}
+
+/// Writes Rerun recordings as the episodes of a v2.1 `LeRobot` dataset.
+///
+/// Each episode is resampled at a fixed frame rate on the given timeline: the value of a
+/// feature at a given frame is the latest value logged at or before that frame (static values
+/// apply to all frames). An episode starts as soon as all of its features have a value, and ends
+/// with the last logged value.
+///
+/// Sequence timelines are sampled at every integer, temporal timelines every `1/fps` seconds.
+///
+/// Video features are encoded to H.264 MP4 files using the `ffmpeg` executable, which must be
+/// available on the `PATH`.
+///
+/// The statistics of every feature are computed per episode, and written to
+/// `meta/episodes_stats.jsonl`. Like `LeRobot` does, the statistics of image and video features
+/// are only computed on a subset of their frames.
+///
+/// See [`super::LeRobotDataset`] for the layout of the dataset on disk.
+pub struct LeRobotDatasetWriter {
+    path: PathBuf,
+    timeline: TimelineName,
+    fps: usize,
+    mappings: Vec<FeatureMapping>,
+    robot_type: Option<String>,
+
+    /// The features that were written so far, including the standard ones.
+    features: BTreeMap<String, Feature>,
+    episodes: Vec<LeRobotDatasetEpisode>,
+    episodes_stats: Vec<EpisodeStats>,
+    tasks: Vec<LeRobotDatasetTask>,
+    total_frames: usize,
+}
+
+/// A line of `meta/episodes_stats.jsonl`.
+#[derive(serde::Serialize)]
+struct EpisodeStats {
+    episode_index: EpisodeIndex,
+    stats: BTreeMap<String, serde_json::Value>,
+}
+
+/// A single value of a feature.
+struct Sample {
+    /// `None` for static values.
+    time: Option<i64>,
+    value: ArrayRef,
+
+    /// The format of `Image:buffer` values.
+    image_format: Option<ImageFormat>,
+}
+
+impl LeRobotDatasetWriter {
+    /// Prepares a new dataset in the `path` directory.
+    ///
+    /// Fails if `fps` is zero.
+    pub fn new(
+        path: impl Into<PathBuf>,
+        timeline: TimelineName,
+        fps: usize,
+        mappings: Vec<FeatureMapping>,
+    ) -> Result<Self, LeRobotError> {
+        if fps == 0 {
+            return Err(LeRobotError::InvalidFps(fps));
+        }
+
+        let path = path.into();
+        std::fs::create_dir_all(&path).map_err(|err| LeRobotError::IO(err, path.clone()))?;
+
+        let scalar = |dtype| Feature {
+            dtype,
+            shape: vec![1],
+            names: None,
+        };
+        let features = [
+            ("timestamp", scalar(DType::Float32)),
+            ("frame_index", scalar(DType::Int64)),
+            ("episode_index", scalar(DType::Int64)),
+            ("index", scalar(DType::Int64)),
+            ("task_index", scalar(DType::Int64)),
+        ]
+        .into_iter()
+        .map(|(key, feature)| (key.to_owned(), feature))
+        .collect();
+
+        Ok(Self {
+            path,
+            timeline,
+            fps,
+            mappings,
+            robot_type: None,
+            features,
+            episodes: Vec::new(),
+            episodes_stats: Vec::new(),
+            tasks: Vec::new(),
+            total_frames: 0,
+        })
+    }
+
+    /// The type of robot, as recorded in `meta/info.json`.
+    pub fn with_robot_type(mut self, robot_type: impl Into<String>) -> Self {
+        self.robot_type = Some(robot_type.into());
+        self
+    }
+
+    /// Writes all the frames of a single recording as the next episode of the dataset.
+    ///
+    /// Returns the number of frames of the episode.
+    pub fn write_episode(
+        &mut self,
+        chunks: &[Arc<Chunk>],
+        task: &str,
+    ) -> Result<usize, LeRobotError> {
+        re_tracing::profile_function!();
+
+        let episode = EpisodeIndex(self.episodes.len());
+
+        let mut time_type = None;
+        let samples = self
+            .mappings
+            .iter()
+            .map(|mapping| {
+                let samples = self.collect_samples(chunks, mapping, &mut time_type)?;
+                if samples.is_empty() {
+                    Err(LeRobotError::MissingFeatureData {
+                        feature: mapping.feature.clone(),
+                        episode,
+                    })
+                } else {
+                    Ok(samples)
+                }
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // Every feature must have a value at every frame.
+        let start = samples
+            .iter()
+            .filter_map(|samples| samples.iter().find_map(|sample| sample.time))
+            .max();
+        let end = samples
+            .iter()
+            .filter_map(|samples| samples.iter().rev().find_map(|sample| sample.time))
+            .max();
+        let (Some(start), Some(end)) = (start, end) else {
+            return Err(LeRobotError::EmptyEpisode(episode));
+        };
+
+        let step = match time_type {
+            Some(TimeType::Sequence) | None => 1,
+            Some(TimeType::DurationNs | TimeType::TimestampNs) => {
+                (1_000_000_000 / self.fps as i64).max(1)
+            }
+        };
+        let num_frames = ((end - start).max(0) / step) as usize + 1;
+
+        let task_index = self.task_index(task);
+        let is_new_task = task_index.0 == self.tasks.len();
+
+        let mut columns: Vec<(String, ArrayRef)> = vec![
+            (
+                "timestamp".to_owned(),
+                Arc::new(Float32Array::from_iter_values(
+                    (0..num_frames).map(|frame| frame as f32 / self.fps as f32),
+                )),
+            ),
+            (
+                "frame_index".to_owned(),
+                Arc::new(Int64Array::from_iter_values(0..num_frames as i64)),
+            ),
+            (
+                "episode_index".to_owned(),
+                Arc::new(Int64Array::from_value(episode.0 as i64, num_frames)),
+            ),
+            (
+                "index".to_owned(),
+                Arc::new(Int64Array::from_iter_values(
+                    (0..num_frames).map(|frame| (self.total_frames + frame) as i64),
+                )),
+            ),
+            (
+                "task_index".to_owned(),
+                Arc::new(Int64Array::from_value(task_index.0 as i64, num_frames)),
+            ),
+        ];
+        let mut features = Vec::new();
+        let mut videos = Vec::new();
+        let mut stats = BTreeMap::new();
+
+        for (mapping, samples) in self.mappings.iter().zip(&samples) {
+            // The latest sample at or before each frame.
+            let frames = (0..num_frames)
+                .map(|frame| {
+                    let time = start + frame as i64 * step;
+                    let index = samples.partition_point(|sample| {
+                        sample.time.is_none_or(|sample_time| sample_time <= time)
+                    });
+                    &samples[index.saturating_sub(1)]
+                })
+                .collect::<Vec<_>>();
+
+            let feature = match mapping.dtype {
+                DType::Video => {
+                    // The frames are only encoded once the video is written.
+                    let first_image = encode_image(mapping, frames[0])?;
+                    let feature = image_feature(mapping, &first_image)?;
+                    videos.push((mapping.clone(), frames));
+                    feature
+                }
+
+                DType::Image => {
+                    let images = encode_images(mapping, &frames)?;
+                    let feature = image_feature(mapping, images[0].as_slice())?;
+
+                    let mut image_stats = FeatureStats::new(3);
+                    for index in sample_indices(images.len()) {
+                        image_stats.add_image(mapping, images[index].as_slice())?;
+                    }
+                    stats.insert(mapping.feature.clone(), image_stats.to_json(true));
+
+                    columns.push((mapping.feature.clone(), image_column(&images)?));
+                    feature
+                }
+
+                DType::Float32 | DType::Float64 => {
+                    let (column, len) = vector_column(mapping, &frames)?;
+                    columns.push((mapping.feature.clone(), column));
+                    Feature {
+                        dtype: mapping.dtype,
+                        shape: vec![len],
+                        names: None,
+                    }
+                }
+
+                DType::Bool | DType::Int16 | DType::Int64 | DType::String => {
+                    columns.push((mapping.feature.clone(), scalar_column(mapping, &frames)?));
+                    Feature {
+                        dtype: mapping.dtype,
+                        shape: vec![1],
+                        names: None,
+                    }
+                }
+            };
+
+            features.push((mapping.feature.clone(), feature));
+        }
+
+        for (key, feature) in features {
+            self.add_feature(&key, feature)?;
+        }
+
+        let info = self.info(episode.0 + 1);
+
+        for (mapping, frames) in videos {
+            let video_path = self.path.join(info.video_path(&mapping.feature, episode)?);
+            create_parent_dir(&video_path)?;
+            let video_stats = encode_video(&video_path, self.fps, &mapping, &frames)?;
+            stats.insert(mapping.feature.clone(), video_stats.to_json(true));
+        }
+
+        for (key, column) in &columns {
+            if let Some(column_stats) = FeatureStats::from_column(column)? {
+                stats.insert(key.clone(), column_stats.to_json(false));
+            }
+        }
+
+        let data_path = self.path.join(info.episode_data_path(episode)?);
+        create_parent_dir(&data_path)?;
+        write_parquet(&data_path, columns)?;
+
+        if is_new_task {
+            self.tasks.push(LeRobotDatasetTask {
+                index: task_index,
+                task: task.to_owned(),
+            });
+        }
+        self.episodes.push(LeRobotDatasetEpisode {
+            index: episode,
+            tasks: vec![task.to_owned()],
+            length: num_frames as u32,
+            files: None,
+        });
+        self.episodes_stats.push(EpisodeStats {
+            episode_index: episode,
+            stats,
+        });
+        self.total_frames += num_frames;
+
+        Ok(num_frames)
+    }
+
+    /// Writes the metadata files of the dataset, and returns its info.
+    pub fn finish(self) -> Result<LeRobotDatasetInfo, LeRobotError> {
+        let info = self.info(self.episodes.len());
+
+        let metadir = self.path.join("meta");
+        std::fs::create_dir_all(&metadir).map_err(|err| LeRobotError::IO(err, metadir.clone()))?;
+
+        let info_path = metadir.join("info.json");
+        let json = serde_json::to_vec_pretty(&info)?;
+        std::fs::write(&info_path, json).map_err(|err| LeRobotError::IO(err, info_path))?;
+
+        write_jsonl_file(&metadir.join("episodes.jsonl"), &self.episodes)?;
+        write_jsonl_file(&metadir.join("episodes_stats.jsonl"), &self.episodes_stats)?;
+        write_jsonl_file(&metadir.join("tasks.jsonl"), &self.tasks)?;
+
+        Ok(info)
+    }
+
+    /// The dataset info, as if it contained `total_episodes` episodes.
+    fn info(&self, total_episodes: usize) -> LeRobotDatasetInfo {
+        let num_videos = self
+            .features
+            .values()
+            .filter(|feature| feature.dtype == DType::Video)
+            .count();
+
+        LeRobotDatasetInfo {
+            robot_type: self.robot_type.clone(),
+            codebase_version: CODEBASE_VERSION.to_owned(),
+            total_episodes,
+            total_frames: self.total_frames,
+            total_tasks: self.tasks.len(),
+            total_videos: num_videos * total_episodes,
+            total_chunks: total_episodes.div_ceil(CHUNKS_SIZE),
+            chunks_size: CHUNKS_SIZE,
+            data_path: DATA_PATH.to_owned(),
+            video_path: (num_videos > 0).then(|| VIDEO_PATH.to_owned()),
+            image_path: None,
+            fps: self.fps,
+            features: self
+                .features
+                .iter()
+                .map(|(key, feature)| (key.clone(), feature.clone()))
+                .collect::<HashMap<_, _>>(),
+        }
+    }
+
+    /// The index of the given task, which is the next available one for new tasks.
+    fn task_index(&self, task: &str) -> TaskIndex {
+        self.tasks
+            .iter()
+            .find(|existing| existing.task == task)
+            .map_or(TaskIndex(self.tasks.len()), |existing| existing.index)
+    }
+
+    /// Registers the shape of a feature, which must be the same in all episodes.
+    fn add_feature(&mut self, key: &str, feature: Feature) -> Result<(), LeRobotError> {
+        if let Some(existing) = self.features.get(key) {
+            if existing.shape != feature.shape {
+                return Err(LeRobotError::InvalidFeatureShape {
+                    feature: key.to_owned(),
+                    expected: existing.shape.clone(),
+                    actual: feature.shape,
+                });
+            }
+        } else {
+            self.features.insert(key.to_owned(), feature);
+        }
+
+        Ok(())
+    }
+
+    /// All the values of a feature in the given chunks, sorted by time.
+    fn collect_samples(
+        &self,
+        chunks: &[Arc<Chunk>],
+        mapping: &FeatureMapping,
+        time_type: &mut Option<TimeType>,
+    ) -> Result<Vec<Sample>, LeRobotError> {
+        let image_format_descr = archetypes::Image::descriptor_format();
+        let is_image_buffer =
+            mapping.component == archetypes::Image::descriptor_buffer().component.as_str();
+
+        let mut samples = Vec::new();
+
+        for chunk in chunks {
+            if chunk.entity_path() != &mapping.entity_path {
+                continue;
+            }
+
+            let Some(descr) = chunk
+                .component_descriptors()
+                .find(|descr| descr.component.as_str() == mapping.component)
+            else {
+                continue;
+            };
+
+            let times: Vec<Option<i64>> = if chunk.is_static() {
+                vec![None; chunk.num_rows()]
+            } else if let Some(time_column) = chunk.timelines().get(&self.timeline) {
+                time_type.get_or_insert(time_column.timeline().typ());
+                time_column.times_raw().iter().copied().map(Some).collect()
+            } else {
+                re_log::warn_once!(
+                    "Skipping data on {:?} that is not on the {:?} timeline",
+                    chunk.entity_path(),
+                    self.timeline
+                );
+                continue;
+            };
+
+            for (row, time) in times.into_iter().enumerate() {
+                let Some(value) = chunk.component_batch_raw(&descr, row).transpose()? else {
+                    continue;
+                };
+
+                let image_format = if is_image_buffer {
+                    chunk
+                        .component_batch::<components::ImageFormat>(&image_format_descr, row)
+                        .transpose()?
+                        .and_then(|formats| formats.into_iter().next())
+                        .map(|format| format.0)
+                } else {
+                    None
+                };
+
+                samples.push(Sample {
+                    time,
+                    value,
+                    image_format,
+                });
+            }
+        }
+
+        // Static values first. The sort is stable, so that later rows win at equal times.
+        samples.sort_by_key(|sample| sample.time.unwrap_or(i64::MIN));
+
+        Ok(samples)
+    }
+}
+
+// ---
+
+fn create_parent_dir(path: &Path) -> Result<(), LeRobotError> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|err| LeRobotError::IO(err, parent.to_owned()))?;
+    }
+    Ok(())
+}
+
+fn write_jsonl_file<T: serde::Serialize>(path: &Path, entries: &[T]) -> Result<(), LeRobotError> {
+    let mut contents = Vec::new();
+    for entry in entries {
+        serde_json::to_writer(&mut contents, entry)?;
+        contents.push(b'\n');
+    }
+    std::fs::write(path, contents).map_err(|err| LeRobotError::IO(err, path.to_owned()))
+}
+
+pub(super) fn write_parquet(
+    path: &Path,
+    columns: Vec<(String, ArrayRef)>,
+) -> Result<(), LeRobotError> {
+    let fields = columns
+        .iter()
+        .map(|(name, column)| Field::new(name, column.data_type().clone(), true))
+        .collect::<Vec<_>>();
+    let schema = Arc::new(Schema::new_with_metadata(fields, Default::default()));
+    let batch = RecordBatch::try_new(
+        schema.clone(),
+        columns.into_iter().map(|(_, column)| column).collect(),
+    )?;
+
+    let file = std::fs::File::create(path).map_err(|err| LeRobotError::IO(err, path.to_owned()))?;
+    let mut writer = parquet::arrow::ArrowWriter::try_new(file, schema, None)?;
+    writer.write(&batch)?;
+    writer.close()?;
+
+    Ok(())
+}
+
+fn unsupported(mapping: &FeatureMapping, reason: impl Into<String>) -> LeRobotError {
+    LeRobotError::UnsupportedFeature {
+        feature: mapping.feature.clone(),
+        dtype: mapping.dtype,
+        reason: reason.into(),
+    }
+}
+
+/// A fixed-size list of floats per frame, e.g. a joint state.
+///
+/// Returns the column and the length of the lists.
+fn vector_column(
+    mapping: &FeatureMapping,
+    frames: &[&Sample],
+) -> Result<(ArrayRef, usize), LeRobotError> {
+    let datatype = if mapping.dtype == DType::Float64 {
+        DataType::Float64
+    } else {
+        DataType::Float32
+    };
+
+    let len = frames.first().map_or(0, |sample| sample.value.len());
+    let values = frames
+        .iter()
+        .map(|sample| {
+            if sample.value.len() == len {
+                Ok(cast(&sample.value, &datatype)?)
+            } else {
+                Err(LeRobotError::InvalidFeatureShape {
+                    feature: mapping.feature.clone(),
+                    expected: vec![len],
+                    actual: vec![sample.value.len()],
+                })
+            }
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let values = re_arrow_util::concat_arrays(
+        &values
+            .iter()
+            .map(|values| values.as_ref())
+            .collect::<Vec<_>>(),
+    )?;
+    let column = FixedSizeListArray::try_new(
+        Arc::new(Field::new("item", datatype, true)),
+        len as i32,
+        values,
+        None,
+    )?;
+
+    Ok((Arc::new(column), len))
+}
+
+/// A single value per frame, e.g. a gripper state or a reward.
+fn scalar_column(mapping: &FeatureMapping, frames: &[&Sample]) -> Result<ArrayRef, LeRobotError> {
+    let datatype = match mapping.dtype {
+        DType::Bool => DataType::Boolean,
+        DType::Int16 => DataType::Int16,
+        DType::Int64 => DataType::Int64,
+        DType::String => DataType::Utf8,
+        DType::Float32 | DType::Float64 | DType::Image | DType::Video => {
+            return Err(unsupported(mapping, "not a scalar dtype"));
+        }
+    };
+
+    let values = frames
+        .iter()
+        .map(|sample| {
+            if sample.value.is_empty() {
+                Err(LeRobotError::InvalidFeatureShape {
+                    feature: mapping.feature.clone(),
+                    expected: vec![1],
+                    actual: vec![0],
+                })
+            } else {
+                Ok(cast(&sample.value.slice(0, 1), &datatype)?)
+            }
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(re_arrow_util::concat_arrays(
+        &values
+            .iter()
+            .map(|values| values.as_ref())
+            .collect::<Vec<_>>(),
+    )?)
+}
+
+/// An encoded image (PNG, JPEG, …) per frame.
+fn encode_images(
+    mapping: &FeatureMapping,
+    frames: &[&Sample],
+) -> Result<Vec<Arc<Vec<u8>>>, LeRobotError> {
+    // Consecutive frames often share the same sample: only encode each of them once.
+    let mut encoded: HashMap<*const Sample, Arc<Vec<u8>>> = HashMap::default();
+
+    frames
+        .iter()
+        .map(|&sample| {
+            if let Some(image) = encoded.get(&std::ptr::from_ref(sample)) {
+                return Ok(image.clone());
+            }
+
+            let image = Arc::new(encode_image(mapping, sample)?);
+            encoded.insert(std::ptr::from_ref(sample), image.clone());
+            Ok(image)
+        })
+        .collect()
+}
+
+fn encode_image(mapping: &FeatureMapping, sample: &Sample) -> Result<Vec<u8>, LeRobotError> {
+    if let Some(format) = &sample.image_format {
+        let buffer = components::ImageBuffer::from_arrow(&sample.value)?
+            .into_iter()
+            .next()
+            .ok_or_else(|| unsupported(mapping, "empty image buffer"))?;
+        encode_raw_image(buffer.0.0.to_vec(), format).ok_or_else(|| {
+            unsupported(
+                mapping,
+                format!("only 8-bit L, RGB and RGBA images are supported, got {format:?}"),
+            )
+        })
+    } else if mapping.component
+        == archetypes::EncodedImage::descriptor_blob()
+            .component
+            .as_str()
+    {
+        let blob = components::Blob::from_arrow(&sample.value)?
+            .into_iter()
+            .next()
+            .ok_or_else(|| unsupported(mapping, "empty image blob"))?;
+        Ok(blob.0.0.to_vec())
+    } else {
+        Err(unsupported(
+            mapping,
+            format!(
+                "images must come from `EncodedImage:blob` or `Image:buffer`, not {:?}",
+                mapping.component
+            ),
+        ))
+    }
+}
+
+/// Encodes a raw image as PNG.
+fn encode_raw_image(buffer: Vec<u8>, format: &ImageFormat) -> Option<Vec<u8>> {
+    let ImageFormat { width, height, .. } = *format;
+
+    let image: image::DynamicImage =
+        match (format.pixel_format, format.color_model(), format.datatype()) {
+            (None, ColorModel::L, ChannelDatatype::U8) => {
+                image::GrayImage::from_raw(width, height, buffer)?.into()
+            }
+            (None, ColorModel::RGB, ChannelDatatype::U8) => {
+                image::RgbImage::from_raw(width, height, buffer)?.into()
+            }
+            (None, ColorModel::RGBA, ChannelDatatype::U8) => {
+                image::RgbaImage::from_raw(width, height, buffer)?.into()
+            }
+            _ => return None,
+        };
+
+    let mut png = Vec::new();
+    image
+        .write_to(&mut Cursor::new(&mut png), image::ImageFormat::Png)
+        .ok()?;
+    Some(png)
+}
+
+/// The feature description of an image or video feature, based on its first frame.
+fn image_feature(mapping: &FeatureMapping, first_image: &[u8]) -> Result<Feature, LeRobotError> {
+    let (width, height) = image::ImageReader::new(Cursor::new(first_image))
+        .with_guessed_format()
+        .ok()
+        .and_then(|reader| reader.into_dimensions().ok())
+        .ok_or_else(|| unsupported(mapping, "couldn't read the dimensions of the images"))?;
+
+    Ok(Feature {
+        dtype: mapping.dtype,
+        shape: vec![height as usize, width as usize, 3],
+        names: Some(Names(vec![
+            "height".to_owned(),
+            "width".to_owned(),
+            "channels".to_owned(),
+        ])),
+    })
+}
+
+/// Images are stored the same way as Hugging Face `datasets` do: a struct of bytes and path.
+fn image_column(images: &[Arc<Vec<u8>>]) -> Result<ArrayRef, LeRobotError> {
+    let bytes = BinaryArray::from_iter_values(images.iter().map(|image| image.as_slice()));
+    let paths = StringArray::new_null(images.len());
+
+    Ok(Arc::new(StructArray::try_new(
+        vec![
+            Field::new("bytes", DataType::Binary, true),
+            Field::new("path", DataType::Utf8, true),
+        ]
+        .into(),
+        vec![Arc::new(bytes), Arc::new(paths)],
+        None,
+    )?))
+}
+
+/// Encodes the frames of a video feature into an H.264 MP4 file, using `ffmpeg`.
+///
+/// The frames are encoded and written to `ffmpeg` one at a time. Returns the statistics of the
+/// video, see [`sample_indices`].
+fn encode_video(
+    path: &Path,
+    fps: usize,
+    mapping: &FeatureMapping,
+    frames: &[&Sample],
+) -> Result<FeatureStats, LeRobotError> {
+    re_tracing::profile_function!();
+
+    let mut child = Command::new("ffmpeg")
+        .args(["-hide_banner", "-loglevel", "error", "-y"])
+        .args([
+            "-f",
+            "image2pipe",
+            "-framerate",
+            &fps.to_string(),
+            "-i",
+            "-",
+        ])
+        // H.264 with 4:2:0 chroma subsampling requires even dimensions.
+        .args(["-vf", "pad=ceil(iw/2)*2:ceil(ih/2)*2"])
+        .args(["-c:v", "libx264", "-pix_fmt", "yuv420p"])
+        .arg(path)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|err| LeRobotError::IO(err, PathBuf::from("ffmpeg")))?;
+
+    // Closing stdin when done, or on error, lets ffmpeg finish.
+    let stats = write_video_frames(child.stdin.take(), mapping, frames);
+
+    let output = child
+        .wait_with_output()
+        .map_err(|err| LeRobotError::IO(err, PathBuf::from("ffmpeg")))?;
+    let stats = stats?;
+
+    if output.status.success() {
+        Ok(stats)
+    } else {
+        Err(LeRobotError::VideoEncoding(
+            path.to_owned(),
+            String::from_utf8_lossy(&output.stderr).trim().to_owned(),
+        ))
+    }
+}
+
+fn write_video_frames(
+    stdin: Option<ChildStdin>,
+    mapping: &FeatureMapping,
+    frames: &[&Sample],
+) -> Result<FeatureStats, LeRobotError> {
+    let mut stats = FeatureStats::new(3);
+    let Some(mut stdin) = stdin else {
+        return Ok(stats);
+    };
+
+    let sampled = sample_indices(frames.len());
+    let mut sampled = sampled.iter().peekable();
+
+    // Consecutive frames often share the same sample: only encode each of them once.
+    let mut previous: Option<(*const Sample, Vec<u8>)> = None;
+
+    for (index, &sample) in frames.iter().enumerate() {
+        let sample_ptr = std::ptr::from_ref(sample);
+        let image = match previous.take() {
+            Some((ptr, image)) if ptr == sample_ptr => image,
+            _ => encode_image(mapping, sample)?,
+        };
+
+        if sampled.next_if_eq(&&index).is_some() {
+            stats.add_image(mapping, &image)?;
+        }
+
+        // If ffmpeg exits early, the error is reported by the caller.
+        if stdin.write_all(&image).is_err() {
+            break;
+        }
+
+        previous = Some((sample_ptr, image));
+    }
+
+    Ok(stats)
+}
+
+/// The indices of the frames that the statistics of image and video features are computed on.
+///
+/// Evenly spaced, and as many as `LeRobot` uses: all frames of short episodes, and up to 10 000
+/// of long ones.
+fn sample_indices(num_frames: usize) -> Vec<usize> {
+    const MIN_SAMPLES: usize = 100;
+    const MAX_SAMPLES: usize = 10_000;
+
+    let num_samples = ((num_frames as f64).powf(0.75) as usize)
+        .min(MAX_SAMPLES)
+        .max(num_frames.min(MIN_SAMPLES));
+
+    match num_samples {
+        0 => Vec::new(),
+        1 => vec![0],
+        _ => {
+            let last = (num_frames - 1) as f64;
+            let mut indices = (0..num_samples)
+                .map(|i| (i as f64 * last / (num_samples - 1) as f64).round() as usize)
+                .collect::<Vec<_>>();
+            indices.dedup();
+            indices
+        }
+    }
+}
+
+/// The statistics of a feature in an episode, per dimension.
+///
+/// The statistics of images are per color channel, over all of their pixels, with values
+/// normalized to `[0, 1]`.
+struct FeatureStats {
+    min: Vec<f64>,
+    max: Vec<f64>,
+    sum: Vec<f64>,
+    sum_squares: Vec<f64>,
+
+    /// The number of values of each dimension.
+    num_values: usize,
+
+    /// The number of frames.
+    count: usize,
+}
+
+impl FeatureStats {
+    fn new(num_dims: usize) -> Self {
+        Self {
+            min: vec![f64::INFINITY; num_dims],
+            max: vec![f64::NEG_INFINITY; num_dims],
+            sum: vec![0.0; num_dims],
+            sum_squares: vec![0.0; num_dims],
+            num_values: 0,
+            count: 0,
+        }
+    }
+
+    /// The statistics of a numeric column, or `None` for other columns.
+    fn from_column(column: &ArrayRef) -> Result<Option<Self>, LeRobotError> {
+        let (values, num_dims) = match column.data_type() {
+            DataType::FixedSizeList(_, len) => {
+                (column.as_fixed_size_list().values().clone(), *len as usize)
+            }
+            DataType::Boolean
+            | DataType::Int16
+            | DataType::Int64
+            | DataType::Float32
+            | DataType::Float64 => (column.clone(), 1),
+            _ => return Ok(None),
+        };
+        if num_dims == 0 {
+            return Ok(None);
+        }
+
+        let values = cast(&values, &DataType::Float64)?;
+        let mut stats = Self::new(num_dims);
+        for frame in values
+            .as_primitive::<Float64Type>()
+            .values()
+            .chunks(num_dims)
+        {
+            stats.add(frame);
+            stats.count += 1;
+        }
+
+        Ok(Some(stats))
+    }
+
+    /// Adds a value for each dimension.
+    fn add(&mut self, values: &[f64]) {
+        for (dim, &value) in values.iter().enumerate() {
+            self.min[dim] = self.min[dim].min(value);
+            self.max[dim] = self.max[dim].max(value);
+            self.sum[dim] += value;
+            self.sum_squares[dim] += value * value;
+        }
+        self.num_values += 1;
+    }
+
+    /// Adds all pixels of an encoded image.
+    fn add_image(&mut self, mapping: &FeatureMapping, image: &[u8]) -> Result<(), LeRobotError> {
+        let image = image::load_from_memory(image)
+            .map_err(|err| unsupported(mapping, format!("couldn't decode image: {err}")))?
+            .into_rgb8();
+
+        for pixel in image.pixels() {
+            self.add(&pixel.0.map(|value| f64::from(value) / 255.0));
+        }
+        self.count += 1;
+
+        Ok(())
+    }
+
+    /// The statistics as stored in `meta/episodes_stats.jsonl`.
+    ///
+    /// Image statistics have a `[channels, 1, 1]` shape.
+    fn to_json(&self, is_image: bool) -> serde_json::Value {
+        let n = self.num_values.max(1) as f64;
+        let mean = self.sum.iter().map(|sum| sum / n).collect::<Vec<_>>();
+        let std = self
+            .sum_squares
+            .iter()
+            .zip(&mean)
+            .map(|(sum_squares, mean)| (sum_squares / n - mean * mean).max(0.0).sqrt())
+            .collect::<Vec<_>>();
+
+        let values = |values: &[f64]| {
+            if is_image {
+                serde_json::json!(values.iter().map(|value| [[value]]).collect::<Vec<_>>())
+            } else {
+                serde_json::json!(values)
+            }
+        };
+
+        serde_json::json!({
+            "min": values(&self.min),
+            "max": values(&self.max),
+            "mean": values(&mean),
+            "std": values(&std),
+            "count": [self.count],
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use re_chunk::{RowId, TimePoint, Timeline};
+
+    use super::*;
+    use crate::lerobot::LeRobotDataset;
+
+    #[test]
+    fn parse_feature_mapping() {
+        let mapping: FeatureMapping = "/robot/joints:Scalars:scalars=observation.state:float64"
+            .parse()
+            .unwrap();
+        assert_eq!(
+            mapping,
+            FeatureMapping {
+                entity_path: "/robot/joints".into(),
+                component: "Scalars:scalars".to_owned(),
+                feature: "observation.state".to_owned(),
+                dtype: DType::Float64,
+            }
+        );
+
+        let mapping: FeatureMapping = "/camera:EncodedImage:blob=observation.images.front"
+            .parse()
+            .unwrap();
+        assert_eq!(mapping.dtype, DType::Video);
+
+        let mapping: FeatureMapping = "/action:Scalars:scalars=action".parse().unwrap();
+        assert_eq!(mapping.dtype, DType::Float32);
+
+        assert!("/action=action".parse::<FeatureMapping>().is_err());
+        assert!("/action:Scalars:scalars".parse::<FeatureMapping>().is_err());
+        assert!(
+            "/action:Scalars:scalars=action:float128"
+                .parse::<FeatureMapping>()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn write_and_read_back() -> anyhow::Result<()> {
+        let timeline = Timeline::new_sequence("frame");
+        let frame = |frame: i64| TimePoint::from([(timeline, frame)]);
+
+        // The joints are logged at every frame, the camera only every other frame.
+        let mut joints = Chunk::builder("/robot/joints");
+        for i in 0..4 {
+            joints = joints.with_archetype(
+                RowId::new(),
+                frame(i),
+                &archetypes::Scalars::new([i as f64, -i as f64]),
+            );
+        }
+        let mut camera = Chunk::builder("/robot/camera");
+        for i in [1, 3] {
+            camera = camera.with_archetype(
+                RowId::new(),
+                frame(i),
+                &archetypes::Image::from_elements(&[i as u8; 4 * 2 * 3], [4, 2], ColorModel::RGB),
+            );
+        }
+        let chunks = vec![Arc::new(joints.build()?), Arc::new(camera.build()?)];
+
+        let dir = tempfile::tempdir()?;
+        let mut writer = LeRobotDatasetWriter::new(
+            dir.path(),
+            *timeline.name(),
+            10,
+            vec![
+                "/robot/joints:Scalars:scalars=observation.state"
+                    .parse()
+                    .map_err(anyhow::Error::msg)?,
+                "/robot/camera:Image:buffer=observation.image:image"
+                    .parse()
+                    .map_err(anyhow::Error::msg)?,
+            ],
+        )?
+        .with_robot_type("test_arm");
+
+        // The first frame without an image is dropped.
+        assert_eq!(writer.write_episode(&chunks, "pick")?, 3);
+        assert_eq!(writer.write_episode(&chunks, "place")?, 3);
+        let info = writer.finish()?;
+        assert_eq!(info.total_episodes, 2);
+        assert_eq!(info.total_frames, 6);
+        assert_eq!(info.total_tasks, 2);
+
+        let dataset = LeRobotDataset::load_from_directory(dir.path())?;
+        assert_eq!(dataset.metadata.episodes.len(), 2);
+        assert_eq!(dataset.metadata.episodes[1].tasks, ["place"]);
+        assert_eq!(
+            dataset
+                .metadata
+                .info
+                .feature("observation.image")
+                .unwrap()
+                .shape,
+            [2, 4, 3]
+        );
+
+        let episodes_stats = std::fs::read_to_string(dir.path().join("meta/episodes_stats.jsonl"))?
+            .lines()
+            .map(serde_json::from_str::<serde_json::Value>)
+            .collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(episodes_stats.len(), 2);
+        let stats = &episodes_stats[1]["stats"];
+        assert_eq!(episodes_stats[1]["episode_index"], 1);
+
+        let values = |value: &serde_json::Value| -> Vec<f64> {
+            serde_json::from_value::<Vec<f64>>(value.clone())
+                .or_else(|_| {
+                    // Image statistics have a `[channels, 1, 1]` shape.
+                    serde_json::from_value::<Vec<[[f64; 1]; 1]>>(value.clone())
+                        .map(|values| values.into_iter().map(|value| value[0][0]).collect())
+                })
+                .unwrap()
+        };
+        let assert_close = |actual: Vec<f64>, expected: &[f64]| {
+            assert_eq!(actual.len(), expected.len());
+            for (actual, expected) in actual.iter().zip(expected) {
+                assert!((actual - expected).abs() < 1e-6, "{actual} != {expected}");
+            }
+        };
+
+        let state = &stats["observation.state"];
+        assert_close(values(&state["min"]), &[1.0, -3.0]);
+        assert_close(values(&state["max"]), &[3.0, -1.0]);
+        assert_close(values(&state["mean"]), &[2.0, -2.0]);
+        let std = (2.0_f64 / 3.0).sqrt();
+        assert_close(values(&state["std"]), &[std, std]);
+        assert_eq!(state["count"], serde_json::json!([3]));
+
+        assert_close(values(&stats["index"]["min"]), &[3.0]);
+
+        // The image of frame 1 is used for frames 1 and 2.
+        let image = &stats["observation.image"];
+        let mean = (1.0 + 1.0 + 3.0) / 3.0 / 255.0;
+        assert_close(values(&image["mean"]), &[mean; 3]);
+        assert_close(values(&image["max"]), &[3.0 / 255.0; 3]);
+        assert_eq!(image["count"], serde_json::json!([3]));
+
+        let data = dataset.read_episode_data(EpisodeIndex(1))?;
+        assert_eq!(data.num_rows(), 3);
+
+        let index = data
+            .column_by_name("index")
+            .and_then(|column| column.as_any().downcast_ref::<Int64Array>())
+            .unwrap();
+        assert_eq!(index.values(), &[3, 4, 5]);
+
+        let state = data
+            .column_by_name("observation.state")
+            .and_then(|column| column.as_any().downcast_ref::<FixedSizeListArray>())
+            .unwrap();
+        let first_state = state.value(0);
+        let first_state = first_state.as_any().downcast_ref::<Float32Array>().unwrap();
+        assert_eq!(first_state.values(), &[1.0, -1.0]);
+
+        let chunks = crate::loader_lerobot::load_episode(&dataset, EpisodeIndex(0))?;
+        assert!(
+            chunks
+                .iter()
+                .any(|chunk| chunk.entity_path() == &"observation.image".into())
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn zero_fps_is_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let result = LeRobotDatasetWriter::new(dir.path(), "frame_nr".into(), 0, Vec::new());
+        assert!(matches!(result, Err(LeRobotError::InvalidFps(0))));
+    }
+
+    #[test]
+    fn sampled_frames() {
+        assert!(sample_indices(0).is_empty());
+        assert_eq!(sample_indices(1), [0]);
+        assert_eq!(sample_indices(5), [0, 1, 2, 3, 4]);
+
+        let indices = sample_indices(100_000);
+        assert_eq!(indices.len(), 5623); // 100 000 ^ 0.75
+        assert_eq!(indices.first(), Some(&0));
+        assert_eq!(indices.last(), Some(&99_999));
+    }
+}
@@ -0,0 +1,1439 @@
+//! A module for loading and working with `LeRobot` datasets.
+//!
+//! This module provides functionality to identify and parse `LeRobot` datasets,
+//! which consist of metadata and episode data stored in a structured format.
+//!
+//! # Important
+//!
+//! Currently this only supports v2 and v3 `LeRobot` datasets!
+//!
+//! See [`LeRobotDataset`] for more information on the dataset format, and [`export`] for
+//! writing Rerun recordings as `LeRobot` datasets.
+
+pub mod export;
+
+use std::collections::hash_map::Entry;
+use std::fmt;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use ahash::HashMap;
+use arrow::array::{
+    ArrayRef, BooleanArray, Float64Array, Int64Array, ListArray, RecordBatch,
+    RecordBatchReader as _, StringArray,
+};
+use arrow::buffer::ScalarBuffer;
+use arrow::compute::cast;
+use arrow::datatypes::DataType;
+use parking_lot::Mutex;
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use parquet::file::metadata::ParquetMetaData;
+use parquet::file::statistics::Statistics;
+use re_arrow_util::ArrowArrayDowncastRef as _;
+use serde::de::{DeserializeOwned, MapAccess, SeqAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize};
+
+/// Check whether the provided path contains a `LeRobot` dataset.
+pub fn is_lerobot_dataset(path: impl AsRef<Path>) -> bool {
+    is_v1_lerobot_dataset(path.as_ref()) || is_v2_lerobot_dataset(path.as_ref())
+}
+
+/// Check whether the provided path contains a v2 `LeRobot` dataset.
+pub fn is_v2_lerobot_dataset(path: impl AsRef<Path>) -> bool {
+    let path = path.as_ref();
+
+    if !path.is_dir() {
+        return false;
+    }
+
+    // v2 `LeRobot` datasets store the metadata in a `meta` directory,
+    // instead of the `meta_data` directory used in v1 datasets.
+    has_sub_directories(&["meta", "data"], path)
+}
+
+/// Check whether the provided path contains a v1 `LeRobot` dataset.
+pub fn is_v1_lerobot_dataset(path: impl AsRef<Path>) -> bool {
+    let path = path.as_ref();
+
+    if !path.is_dir() {
+        return false;
+    }
+
+    // v1 `LeRobot` datasets stored the metadata in a `meta_data` directory,
+    // instead of the `meta` directory used in v2 datasets.
+    has_sub_directories(&["meta_data", "data"], path)
+}
+
+fn has_sub_directories(directories: &[&str], path: impl AsRef<Path>) -> bool {
+    directories.iter().all(|subdir| {
+        let subpath = path.as_ref().join(subdir);
+
+        // check that the sub directory exists and is not empty
+        subpath.is_dir()
+            && subpath
+                .read_dir()
+                .is_ok_and(|mut contents| contents.next().is_some())
+    })
+}
+
+/// Errors that might happen when loading data through a [`crate::loader_lerobot::LeRobotDatasetLoader`].
+#[derive(thiserror::Error, Debug)]
+pub enum LeRobotError {
+    #[error("IO error occurred on path: {1}")]
+    IO(#[source] std::io::Error, std::path::PathBuf),
+
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+
+    #[error(transparent)]
+    Parquet(#[from] parquet::errors::ParquetError),
+
+    #[error(transparent)]
+    Arrow(#[from] arrow::error::ArrowError),
+
+    #[error("Invalid feature key: {0}")]
+    InvalidFeatureKey(String),
+
+    #[error("Missing dataset info: {0}")]
+    MissingDatasetInfo(String),
+
+    #[error("Invalid feature dtype, expected {key} to be of type {expected:?}, but got {actual:?}")]
+    InvalidFeatureDtype {
+        key: String,
+        expected: DType,
+        actual: DType,
+    },
+
+    #[error("Invalid path template {0:?}")]
+    InvalidPathTemplate(String),
+
+    #[error("Invalid chunk index: {0}")]
+    InvalidChunkIndex(usize),
+
+    #[error("Invalid frame rate: {0}")]
+    InvalidFps(usize),
+
+    #[error("Invalid episode index: {0:?}")]
+    InvalidEpisodeIndex(EpisodeIndex),
+
+    #[error("Episode {0:?} data file does not contain any records")]
+    EmptyEpisode(EpisodeIndex),
+
+    #[error(transparent)]
+    Chunk(#[from] re_chunk::ChunkError),
+
+    #[error(transparent)]
+    Deserialization(#[from] re_types::DeserializationError),
+
+    #[error("Feature {feature:?} has no data in episode {episode:?}")]
+    MissingFeatureData {
+        feature: String,
+        episode: EpisodeIndex,
+    },
+
+    #[error("Invalid shape for feature {feature:?}, expected {expected:?}, but got {actual:?}")]
+    InvalidFeatureShape {
+        feature: String,
+        expected: Vec<usize>,
+        actual: Vec<usize>,
+    },
+
+    #[error("Unsupported {dtype:?} feature {feature:?}: {reason}")]
+    UnsupportedFeature {
+        feature: String,
+        dtype: DType,
+        reason: String,
+    },
+
+    #[error("Failed to encode video {0:?}: {1}")]
+    VideoEncoding(PathBuf, String),
+}
+
+/// A `LeRobot` dataset consists of structured metadata and recorded episode data stored in
+/// Parquet files.
+///
+/// # `LeRobot` Dataset Format
+///
+/// The dataset follows a standardized directory layout, typically organized as follows:
+///
+/// ```text
+/// .
+/// ├── README.md
+/// ├── data
+/// │  └── chunk-000
+/// │      ├── episode_000000.parquet
+/// │      ├── episode_000001.parquet
+/// │      ├── …
+/// ├── meta
+/// │  ├── episodes.jsonl
+/// │  ├── info.json
+/// │  ├── stats.json
+/// │  └── tasks.jsonl
+/// └── videos
+///     └── chunk-000
+///         └── observation.image
+///             ├── episode_000000.mp4
+///             ├── episode_000001.mp4
+///             ├── …
+/// ```
+///
+/// ## File layout
+///
+/// - `data/`: Stores episode data in Parquet format, organized in chunks.
+/// - `meta/`: Contains metadata files:
+///   - `info.json`: General dataset metadata (robot type, number of episodes, etc.).
+///   - `episodes.jsonl`: Episode-specific metadata (tasks, number of frames, etc.).
+///   - `tasks.jsonl`: Task definitions for episodes.
+///   - `stats.json`: Summary statistics of dataset features.
+/// - `videos/`: Optional directory storing video observations for episodes, organized similarly to `data/`.
+///
+/// Each episode is identified by a unique index and mapped to its corresponding chunk, based on the number of episodes
+/// per chunk (which can be found in `meta/info.json`).
+///
+/// ## v3 layout
+///
+/// v3 datasets store several episodes per file, and their metadata in Parquet instead of JSONL:
+///
+/// ```text
+/// .
+/// ├── data
+/// │  └── chunk-000
+/// │      ├── file-000.parquet
+/// │      ├── …
+/// ├── meta
+/// │  ├── episodes
+/// │  │  └── chunk-000
+/// │  │      ├── file-000.parquet
+/// │  │      ├── …
+/// │  ├── info.json
+/// │  ├── stats.json
+/// │  └── tasks.parquet
+/// └── videos
+///     └── observation.image
+///         └── chunk-000
+///             ├── file-000.mp4
+///             ├── …
+/// ```
+///
+/// The episode metadata records which data and video files each episode is stored in, see [`EpisodeFiles`].
+///
+/// In both layouts, the actual paths are given by the `data_path` and `video_path` templates in `meta/info.json`.
+#[derive(Debug, Clone)]
+pub struct LeRobotDataset {
+    pub path: PathBuf,
+    pub metadata: LeRobotDatasetMetadata,
+
+    /// The contents of the v3 video files that still have episodes left to read, see
+    /// [`Self::read_episode_video_contents`].
+    shared_videos: Arc<Mutex<HashMap<PathBuf, SharedVideoFile>>>,
+}
+
+/// A video file shared by several v3 episodes.
+#[derive(Debug)]
+struct SharedVideoFile {
+    contents: ScalarBuffer<u8>,
+
+    /// The number of episodes stored in this file whose video hasn't been read yet.
+    remaining_episodes: usize,
+}
+
+impl LeRobotDataset {
+    /// Loads a `LeRobotDataset` from a directory.
+    ///
+    /// This method initializes a dataset by reading its metadata from the `meta/` directory.
+    ///
+    /// # Important
+    ///
+    /// Currently, this only supports v2 and v3 `LeRobot` datasets.
+    pub fn load_from_directory(path: impl AsRef<Path>) -> Result<Self, LeRobotError> {
+        let path = path.as_ref();
+        let metadatapath = path.join("meta");
+        let metadata = LeRobotDatasetMetadata::load_from_directory(&metadatapath)?;
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            metadata,
+            shared_videos: Default::default(),
+        })
+    }
+
+    /// Retrieve the metadata of the provided episode.
+    pub fn episode(&self, episode: EpisodeIndex) -> Option<&LeRobotDatasetEpisode> {
+        let episodes = &self.metadata.episodes;
+        episodes
+            .binary_search_by_key(&episode, |e| e.index)
+            .ok()
+            .map(|idx| &episodes[idx])
+    }
+
+    /// Read the Parquet data of the provided episode.
+    ///
+    /// For v3 datasets, only the rows of this episode are returned from its (shared) data file,
+    /// and only the row groups that may contain them are read, see [`episode_row_groups`].
+    pub fn read_episode_data(&self, episode: EpisodeIndex) -> Result<RecordBatch, LeRobotError> {
+        let Some(metadata) = self.episode(episode) else {
+            return Err(LeRobotError::InvalidEpisodeIndex(episode));
+        };
+
+        let episode_data_path = match &metadata.files {
+            Some(files) => self.metadata.info.data_file_path(files.data)?,
+            None => self.metadata.info.episode_data_path(episode)?,
+        };
+        let episode_parquet_file = self.path.join(episode_data_path);
+
+        let file = File::open(&episode_parquet_file)
+            .map_err(|err| LeRobotError::IO(err, episode_parquet_file))?;
+        let builder = ParquetRecordBatchReaderBuilder::try_new(file)?;
+        let builder = if metadata.files.is_some() {
+            let row_groups = episode_row_groups(builder.metadata(), episode);
+            builder.with_row_groups(row_groups)
+        } else {
+            builder
+        };
+        let reader = builder.build()?;
+        let schema = reader.schema();
+
+        let mut batches = Vec::new();
+        for batch in reader {
+            let batch = batch?;
+            if metadata.files.is_some() {
+                batches.push(filter_episode_rows(&batch, episode)?);
+            } else {
+                batches.push(batch);
+            }
+        }
+
+        let data = arrow::compute::concat_batches(&schema, &batches)?;
+        if data.num_rows() == 0 {
+            return Err(LeRobotError::EmptyEpisode(episode));
+        }
+
+        Ok(data)
+    }
+
+    /// Read video feature for the provided episode.
+    ///
+    /// For v3 datasets, this is the video file shared by several episodes, see
+    /// [`Self::episode_video_segment`]. That file is only read once for all of its episodes, which
+    /// then share the returned buffer.
+    pub fn read_episode_video_contents(
+        &self,
+        observation_key: &str,
+        episode: EpisodeIndex,
+    ) -> Result<ScalarBuffer<u8>, LeRobotError> {
+        let Some(segment) = self.episode_video_segment(observation_key, episode) else {
+            let video_file = self.metadata.info.video_path(observation_key, episode)?;
+            return read_video_file(&self.path.join(video_file));
+        };
+
+        let video_file = self
+            .metadata
+            .info
+            .video_file_path(observation_key, segment.file)?;
+        let videopath = self.path.join(video_file);
+
+        let mut shared_videos = self.shared_videos.lock();
+        let shared = match shared_videos.entry(videopath.clone()) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => {
+                let contents = read_video_file(&videopath)?;
+                let remaining_episodes = self
+                    .metadata
+                    .episodes
+                    .iter()
+                    .filter(|episode| {
+                        episode
+                            .files
+                            .as_ref()
+                            .and_then(|files| files.videos.get(observation_key))
+                            .is_some_and(|other| other.file == segment.file)
+                    })
+                    .count();
+
+                entry.insert(SharedVideoFile {
+                    contents,
+                    remaining_episodes,
+                })
+            }
+        };
+
+        let contents = shared.contents.clone();
+
+        // Don't keep the file around once all of its episodes have been read.
+        shared.remaining_episodes = shared.remaining_episodes.saturating_sub(1);
+        if shared.remaining_episodes == 0 {
+            shared_videos.remove(&videopath);
+        }
+
+        Ok(contents)
+    }
+
+    /// The segment of its video file that a v3 episode occupies, for the provided video feature.
+    ///
+    /// Returns `None` for v2 datasets, where every episode has its own video file.
+    pub fn episode_video_segment(
+        &self,
+        observation_key: &str,
+        episode: EpisodeIndex,
+    ) -> Option<VideoSegment> {
+        self.episode(episode)?
+            .files
+            .as_ref()?
+            .videos
+            .get(observation_key)
+            .copied()
+    }
+
+    /// Retrieve the task using the provided task index.
+    pub fn task_by_index(&self, task: TaskIndex) -> Option<&LeRobotDatasetTask> {
+        self.metadata.tasks.get(task.0)
+    }
+}
+
+/// Metadata for a `LeRobot` dataset.
+///
+/// This is a wrapper struct for the metadata files in the `meta` directory of a
+/// `LeRobot` dataset. For more see [`LeRobotDataset`].
+#[derive(Debug, Clone)]
+pub struct LeRobotDatasetMetadata {
+    pub info: LeRobotDatasetInfo,
+    pub episodes: Vec<LeRobotDatasetEpisode>,
+    pub tasks: Vec<LeRobotDatasetTask>,
+}
+
+impl LeRobotDatasetMetadata {
+    /// Loads all metadata files from the provided directory.
+    ///
+    /// This method reads dataset metadata from the files stored in the `meta/` directory: JSON and JSONL
+    /// files for v2 datasets, JSON and Parquet files for v3 datasets.
+    /// It retrieves general dataset information, a list of recorded episodes, and defined tasks.
+    pub fn load_from_directory(metadir: impl AsRef<Path>) -> Result<Self, LeRobotError> {
+        let metadir = metadir.as_ref();
+
+        let info = LeRobotDatasetInfo::load_from_json_file(metadir.join("info.json"))?;
+        let (mut episodes, mut tasks) = if info.is_v3() {
+            (
+                load_v3_episodes(&info, &metadir.join("episodes"))?,
+                load_v3_tasks(&metadir.join("tasks.parquet"))?,
+            )
+        } else {
+            (
+                load_jsonl_file(metadir.join("episodes.jsonl"))?,
+                load_jsonl_file(metadir.join("tasks.jsonl"))?,
+            )
+        };
+
+        episodes.sort_by_key(|e: &LeRobotDatasetEpisode| e.index);
+        tasks.sort_by_key(|e: &LeRobotDatasetTask| e.index);
+
+        Ok(Self {
+            info,
+            episodes,
+            tasks,
+        })
+    }
+}
+
+/// `LeRobot` dataset metadata.
+///
+/// This struct contains the metadata for a `LeRobot` dataset, and is loaded from the `meta/info.json` file
+/// of the dataset.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LeRobotDatasetInfo {
+    /// The type of the robot.
+    pub robot_type: Option<String>,
+
+    /// The version of the `LeRobot` codebase the dataset was created for.
+    pub codebase_version: String,
+
+    /// The total number of unique episodes in the dataset.
+    pub total_episodes: usize,
+
+    /// The total number of unique frames in the dataset.
+    pub total_frames: usize,
+
+    /// The total number of unique tasks in the dataset.
+    pub total_tasks: usize,
+
+    /// The total amount of videos in the dataset.
+    ///
+    /// Not recorded by v3 datasets.
+    #[serde(default)]
+    pub total_videos: usize,
+
+    /// The total number of unique chunks in the dataset.
+    ///
+    /// Not recorded by v3 datasets.
+    #[serde(default)]
+    pub total_chunks: usize,
+
+    /// The amount of episodes per chunk for v2 datasets, or of files per chunk for v3 datasets.
+    ///
+    /// This is used to determine the path to video and data files.
+    pub chunks_size: usize,
+
+    /// The path template for accessing episode data files.
+    ///
+    /// v2 datasets use the `episode_chunk` and `episode_index` variables, e.g.
+    /// `data/chunk-{episode_chunk:03d}/episode_{episode_index:06d}.parquet`, while v3 datasets
+    /// use `chunk_index` and `file_index`.
+    pub data_path: String,
+
+    /// The path template for accessing video files for an episode.
+    pub video_path: Option<String>,
+
+    /// The path template for accessing image files for an episode.
+    pub image_path: Option<String>,
+
+    /// The frame rate of the recorded episode data.
+    pub fps: usize,
+
+    /// A mapping of feature names to their respective [`Feature`] definitions.
+    pub features: HashMap<String, Feature>,
+}
+
+impl LeRobotDatasetInfo {
+    /// Loads `LeRobotDatasetInfo` from a JSON file.
+    ///
+    /// The `LeRobot` dataset info file is typically stored under `meta/info.json`.
+    pub fn load_from_json_file(filepath: impl AsRef<Path>) -> Result<Self, LeRobotError> {
+        let info_file = File::open(filepath.as_ref())
+            .map_err(|err| LeRobotError::IO(err, filepath.as_ref().to_owned()))?;
+        let reader = BufReader::new(info_file);
+
+        serde_json::from_reader(reader).map_err(|err| err.into())
+    }
+
+    /// Whether the dataset uses the v3 layout, which stores several episodes per file.
+    pub fn is_v3(&self) -> bool {
+        self.codebase_version.starts_with("v3.")
+    }
+
+    /// Retrieve the metadata for a specific feature.
+    pub fn feature(&self, feature_key: &str) -> Option<&Feature> {
+        self.features.get(feature_key)
+    }
+
+    /// Computes the storage chunk index for a given episode.
+    ///
+    /// Episodes are organized into chunks to optimize storage and retrieval. This method determines
+    /// which chunk a specific episode belongs to based on the dataset's chunk size.
+    pub fn chunk_index(&self, episode: EpisodeIndex) -> Result<usize, LeRobotError> {
+        if episode.0 > self.total_episodes {
+            return Err(LeRobotError::InvalidEpisodeIndex(episode));
+        }
+
+        // chunk indices start at 0
+        let chunk_idx = episode.0 / self.chunks_size;
+        if chunk_idx < self.total_chunks {
+            Ok(chunk_idx)
+        } else {
+            Err(LeRobotError::InvalidChunkIndex(chunk_idx))
+        }
+    }
+
+    /// Generates the file path for a given episode's Parquet data, for v2 datasets.
+    pub fn episode_data_path(&self, episode: EpisodeIndex) -> Result<PathBuf, LeRobotError> {
+        let chunk = self.chunk_index(episode)?;
+
+        format_path_template(
+            &self.data_path,
+            &[("episode_chunk", &chunk), ("episode_index", &episode.0)],
+        )
+    }
+
+    /// Generates the path of a Parquet data file, for v3 datasets.
+    pub fn data_file_path(&self, file: FileLocation) -> Result<PathBuf, LeRobotError> {
+        format_path_template(
+            &self.data_path,
+            &[
+                ("chunk_index", &file.chunk_index),
+                ("file_index", &file.file_index),
+            ],
+        )
+    }
+
+    /// Generates the file path for a video observation of a given episode, for v2 datasets.
+    pub fn video_path(
+        &self,
+        feature_key: &str,
+        episode: EpisodeIndex,
+    ) -> Result<PathBuf, LeRobotError> {
+        let chunk = self.chunk_index(episode)?;
+
+        format_path_template(
+            self.video_path_template(feature_key)?,
+            &[
+                ("episode_chunk", &chunk),
+                ("episode_index", &episode.0),
+                ("video_key", &feature_key),
+            ],
+        )
+    }
+
+    /// Generates the path of a video file for a video observation, for v3 datasets.
+    pub fn video_file_path(
+        &self,
+        feature_key: &str,
+        file: FileLocation,
+    ) -> Result<PathBuf, LeRobotError> {
+        format_path_template(
+            self.video_path_template(feature_key)?,
+            &[
+                ("chunk_index", &file.chunk_index),
+                ("file_index", &file.file_index),
+                ("video_key", &feature_key),
+            ],
+        )
+    }
+
+    fn video_path_template(&self, feature_key: &str) -> Result<&str, LeRobotError> {
+        let feature = self
+            .feature(feature_key)
+            .ok_or(LeRobotError::InvalidFeatureKey(feature_key.to_owned()))?;
+
+        if feature.dtype != DType::Video {
+            return Err(LeRobotError::InvalidFeatureDtype {
+                key: feature_key.to_owned(),
+                expected: DType::Video,
+                actual: feature.dtype,
+            });
+        }
+
+        self.video_path
+            .as_deref()
+            .ok_or_else(|| LeRobotError::MissingDatasetInfo("video_path".to_owned()))
+    }
+}
+
+/// Renders a path template from `info.json`, such as `data/chunk-{episode_chunk:03d}/episode_{episode_index:06d}.parquet`.
+///
+/// Placeholders are either `{name}` or `{name:0Nd}`, the latter padding the value with zeros to `N` characters
+/// (Python's format specification, which is what `LeRobot` uses).
+fn format_path_template(
+    template: &str,
+    values: &[(&str, &dyn fmt::Display)],
+) -> Result<PathBuf, LeRobotError> {
+    let invalid = || LeRobotError::InvalidPathTemplate(template.to_owned());
+
+    let mut path = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find('{') {
+        path.push_str(&rest[..start]);
+
+        let end = rest[start..].find('}').ok_or_else(invalid)? + start;
+        let placeholder = &rest[start + 1..end];
+        let (name, spec) = placeholder.split_once(':').unwrap_or((placeholder, ""));
+
+        let value = values
+            .iter()
+            .find(|(key, _)| *key == name)
+            .map(|(_, value)| value.to_string())
+            .ok_or_else(invalid)?;
+
+        if spec.is_empty() {
+            path.push_str(&value);
+        } else {
+            let width = spec
+                .strip_suffix('d')
+                .and_then(|width| width.parse::<usize>().ok())
+                .ok_or_else(invalid)?;
+            if spec.starts_with('0') {
+                path.push_str(&format!("{value:0>width$}"));
+            } else {
+                path.push_str(&format!("{value:>width$}"));
+            }
+        }
+
+        rest = &rest[end + 1..];
+    }
+    path.push_str(rest);
+
+    Ok(path.into())
+}
+
+/// Feature definition for a `LeRobot` dataset.
+///
+/// Each feature represents a data stream recorded during an episode, of a specific data type (`dtype`)
+/// and dimensionality (`shape`).
+///
+/// For example, a shape of `[3, 224, 224]` for a [`DType::Image`] feature denotes a 3-channel (e.g. RGB)
+/// image with a height and width of 224 pixels each.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Feature {
+    pub dtype: DType,
+    pub shape: Vec<usize>,
+    pub names: Option<Names>,
+}
+
+impl Feature {
+    /// Get the channel dimension for this [`Feature`].
+    ///
+    /// Returns the number of channels in the feature's data representation.
+    ///
+    /// # Note
+    ///
+    /// This is primarily intended for [`DType::Image`] and [`DType::Video`] features,
+    /// where it represents color channels (e.g., 3 for RGB, 4 for RGBA).
+    /// For other feature types, this function returns the size of the last dimension
+    /// from the feature's shape.
+    pub fn channel_dim(&self) -> usize {
+        // first check if there's a "channels" name, if there is we can use that index.
+        if let Some(names) = &self.names
+            && let Some(channel_idx) = names.0.iter().position(|name| name == "channels")
+        {
+            // If channel_idx is within bounds of shape, return that dimension
+            if channel_idx < self.shape.len() {
+                return self.shape[channel_idx];
+            }
+        }
+
+        // Default to the last dimension if no channels name is found
+        // or if the found index is out of bounds
+        self.shape.last().copied().unwrap_or(0)
+    }
+}
+
+/// Data types supported for features in a `LeRobot` dataset.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DType {
+    Video,
+    Image,
+    Bool,
+    Float32,
+    Float64,
+    Int16,
+    Int64,
+    String,
+}
+
+/// Name metadata for a feature in the `LeRobot` dataset.
+///
+/// The name metadata can consist of
+/// - A flat list of names for each dimension of a feature (e.g., `["height", "width", "channel"]`).
+/// - A nested list of names for each dimension of a feature (e.g., `[[""kLeftShoulderPitch", "kLeftShoulderRoll"]]`)
+/// - A map with a string array value (e.g., `{ "motors": ["motor_0", "motor_1", ...] }` or `{ "axes": ["x", "y", "z"] }`).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Names(Vec<String>);
+
+impl Names {
+    /// Retrieves the name corresponding to a specific index.
+    ///
+    /// Returns `None` if the index is out of bounds.
+    pub fn name_for_index(&self, index: usize) -> Option<&String> {
+        self.0.get(index)
+    }
+}
+
+/// Visitor implementation for deserializing the [`Names`] type.
+///
+/// Handles multiple representation formats:
+/// - Flat string arrays: `["x", "y", "z"]`
+/// - Nested string arrays: `[["motor_1", "motor_2"]]`
+/// - Single-entry objects: `{"motors": ["motor_1", "motor_2"]}` or `{"axes": null}`
+///
+/// See the `Names` type documentation for more details on the supported formats.
+struct NamesVisitor;
+
+impl<'de> Visitor<'de> for NamesVisitor {
+    type Value = Names;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(
+            "a flat string array, a nested string array, or a single-entry object with a string array or null value",
+        )
+    }
+
+    /// Handle sequences:
+    /// - Flat string arrays: `["x", "y", "z"]`
+    /// - Nested string arrays: `[["motor_1", "motor_2"]]`
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        // Helper enum to deserialize sequence elements
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum ListItem {
+            Str(String),
+            List(Vec<String>),
+        }
+
+        /// Enum to track the list type
+        #[derive(PartialEq)]
+        enum ListType {
+            Undetermined,
+            Flat,
+            Nested,
+        }
+
+        let mut names = Vec::new();
+        let mut determined_type = ListType::Undetermined;
+
+        while let Some(item) = seq.next_element::<ListItem>()? {
+            match item {
+                ListItem::Str(s) => {
+                    if determined_type == ListType::Nested {
+                        return Err(serde::de::Error::custom(
+                            "Cannot mix nested lists with flat strings within names array",
+                        ));
+                    }
+                    determined_type = ListType::Flat;
+                    names.push(s);
+                }
+                ListItem::List(list) => {
+                    if determined_type == ListType::Flat {
+                        return Err(serde::de::Error::custom(
+                            "Cannot mix flat strings and nested lists within names array",
+                        ));
+                    }
+                    determined_type = ListType::Nested;
+
+                    // Flatten the nested list
+                    names.extend(list);
+                }
+            }
+        }
+
+        Ok(Names(names))
+    }
+
+    /// Handle single-entry objects: `{"motors": ["motor_1", "motor_2"]}` or `{"axes": null}`
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut names_vec: Option<Vec<String>> = None;
+        let mut entry_count = 0;
+
+        // We expect exactly one entry.
+        while let Some((_key, value)) = map.next_entry::<String, Option<Vec<String>>>()? {
+            entry_count += 1;
+            if entry_count > 1 {
+                // Consume remaining entries to be a good citizen before erroring
+                while map
+                    .next_entry::<serde::de::IgnoredAny, serde::de::IgnoredAny>()?
+                    .is_some()
+                {}
+
+                return Err(serde::de::Error::invalid_length(
+                    entry_count,
+                    &"a Names object with exactly one entry.",
+                ));
+            }
+
+            names_vec = Some(value.unwrap_or_default());
+        }
+
+        Ok(Names(names_vec.unwrap_or_default()))
+    }
+}
+
+impl<'de> Deserialize<'de> for Names {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(NamesVisitor)
+    }
+}
+
+// TODO(gijsd): Do we want to stream in episodes or tasks?
+#[cfg(not(target_arch = "wasm32"))]
+fn load_jsonl_file<D>(filepath: impl AsRef<Path>) -> Result<Vec<D>, LeRobotError>
+where
+    D: DeserializeOwned,
+{
+    let entries = std::fs::read_to_string(filepath.as_ref())
+        .map_err(|err| LeRobotError::IO(err, filepath.as_ref().to_owned()))?
+        .lines()
+        .map(|line| serde_json::from_str(line))
+        .collect::<Result<Vec<D>, _>>()?;
+
+    Ok(entries)
+}
+
+/// Loads the episode metadata of a v3 dataset, from the Parquet files in `meta/episodes`.
+fn load_v3_episodes(
+    info: &LeRobotDatasetInfo,
+    episodes_dir: &Path,
+) -> Result<Vec<LeRobotDatasetEpisode>, LeRobotError> {
+    let video_keys = info
+        .features
+        .iter()
+        .filter(|(_, feature)| feature.dtype == DType::Video)
+        .map(|(key, _)| key.as_str())
+        .collect::<Vec<_>>();
+
+    let mut episodes = Vec::new();
+
+    for path in parquet_files_in(episodes_dir)? {
+        let file = File::open(&path).map_err(|err| LeRobotError::IO(err, path.clone()))?;
+
+        for batch in ParquetRecordBatchReaderBuilder::try_new(file)?.build()? {
+            let batch = batch?;
+
+            let episode_indices = int_column(&batch, "episode_index")?;
+            let lengths = int_column(&batch, "length")?;
+            let tasks = batch
+                .column_by_name("tasks")
+                .and_then(|column| column.downcast_array_ref::<ListArray>())
+                .ok_or_else(|| missing_column("tasks"))?;
+            let data_chunks = int_column(&batch, "data/chunk_index")?;
+            let data_files = int_column(&batch, "data/file_index")?;
+
+            let videos = video_keys
+                .iter()
+                .map(|key| {
+                    Ok((
+                        *key,
+                        int_column(&batch, &format!("videos/{key}/chunk_index"))?,
+                        int_column(&batch, &format!("videos/{key}/file_index"))?,
+                        float_column(&batch, &format!("videos/{key}/from_timestamp"))?,
+                        float_column(&batch, &format!("videos/{key}/to_timestamp"))?,
+                    ))
+                })
+                .collect::<Result<Vec<_>, LeRobotError>>()?;
+
+            for row in 0..batch.num_rows() {
+                let episode_tasks = cast(&tasks.value(row), &DataType::Utf8)?;
+                let episode_tasks = episode_tasks
+                    .downcast_array_ref::<StringArray>()
+                    .ok_or_else(|| missing_column("tasks"))?
+                    .iter()
+                    .flatten()
+                    .map(ToOwned::to_owned)
+                    .collect();
+
+                let videos = videos
+                    .iter()
+                    .map(|(key, chunks, files, from, to)| {
+                        (
+                            (*key).to_owned(),
+                            VideoSegment {
+                                file: FileLocation {
+                                    chunk_index: chunks.value(row) as usize,
+                                    file_index: files.value(row) as usize,
+                                },
+                                from_timestamp: from.value(row),
+                                to_timestamp: to.value(row),
+                            },
+                        )
+                    })
+                    .collect();
+
+                episodes.push(LeRobotDatasetEpisode {
+                    index: EpisodeIndex(episode_indices.value(row) as usize),
+                    tasks: episode_tasks,
+                    length: lengths.value(row) as u32,
+                    files: Some(EpisodeFiles {
+                        data: FileLocation {
+                            chunk_index: data_chunks.value(row) as usize,
+                            file_index: data_files.value(row) as usize,
+                        },
+                        videos,
+                    }),
+                });
+            }
+        }
+    }
+
+    Ok(episodes)
+}
+
+/// Loads the tasks of a v3 dataset from `meta/tasks.parquet`.
+///
+/// The task descriptions are stored in the (`pandas`) index of the table, which is either called
+/// `task` or `__index_level_0__`.
+fn load_v3_tasks(filepath: &Path) -> Result<Vec<LeRobotDatasetTask>, LeRobotError> {
+    let file = File::open(filepath).map_err(|err| LeRobotError::IO(err, filepath.to_owned()))?;
+
+    let mut tasks = Vec::new();
+
+    for batch in ParquetRecordBatchReaderBuilder::try_new(file)?.build()? {
+        let batch = batch?;
+
+        let task_indices = int_column(&batch, "task_index")?;
+        let descriptions = ["task", "__index_level_0__"]
+            .iter()
+            .find_map(|name| batch.column_by_name(name))
+            .ok_or_else(|| missing_column("task"))?;
+        let descriptions = cast(descriptions, &DataType::Utf8)?;
+        let descriptions = descriptions
+            .downcast_array_ref::<StringArray>()
+            .ok_or_else(|| missing_column("task"))?;
+
+        for (index, task) in task_indices.iter().zip(descriptions) {
+            if let (Some(index), Some(task)) = (index, task) {
+                tasks.push(LeRobotDatasetTask {
+                    index: TaskIndex(index as usize),
+                    task: task.to_owned(),
+                });
+            }
+        }
+    }
+
+    Ok(tasks)
+}
+
+/// All Parquet files in `dir` and its sub-directories, in lexical order.
+fn parquet_files_in(dir: &Path) -> Result<Vec<PathBuf>, LeRobotError> {
+    let mut files = Vec::new();
+
+    for entry in std::fs::read_dir(dir).map_err(|err| LeRobotError::IO(err, dir.to_owned()))? {
+        let path = entry
+            .map_err(|err| LeRobotError::IO(err, dir.to_owned()))?
+            .path();
+
+        if path.is_dir() {
+            files.extend(parquet_files_in(&path)?);
+        } else if path.extension().is_some_and(|ext| ext == "parquet") {
+            files.push(path);
+        }
+    }
+
+    files.sort();
+    Ok(files)
+}
+
+/// Reads a whole video file into memory.
+fn read_video_file(videopath: &Path) -> Result<ScalarBuffer<u8>, LeRobotError> {
+    re_tracing::profile_function!();
+
+    std::fs::read(videopath)
+        .map(Into::into)
+        .map_err(|err| LeRobotError::IO(err, videopath.to_owned()))
+}
+
+/// The row groups of a v3 data file that may contain rows of the given episode, according to the
+/// statistics of their `episode_index` column.
+///
+/// Row groups without statistics are always read.
+fn episode_row_groups(metadata: &ParquetMetaData, episode: EpisodeIndex) -> Vec<usize> {
+    let episode = episode.0 as i64;
+    let column = metadata
+        .file_metadata()
+        .schema_descr()
+        .columns()
+        .iter()
+        .position(|column| column.path().string() == "episode_index");
+
+    (0..metadata.num_row_groups())
+        .filter(|&row_group| {
+            let Some(column) = column else {
+                return true;
+            };
+
+            let min_max = match metadata.row_group(row_group).column(column).statistics() {
+                Some(Statistics::Int64(stats)) => stats
+                    .min_opt()
+                    .zip(stats.max_opt())
+                    .map(|(&min, &max)| (min, max)),
+                Some(Statistics::Int32(stats)) => stats
+                    .min_opt()
+                    .zip(stats.max_opt())
+                    .map(|(&min, &max)| (min as i64, max as i64)),
+                _ => None,
+            };
+
+            min_max.is_none_or(|(min, max)| (min..=max).contains(&episode))
+        })
+        .collect()
+}
+
+/// Only keeps the rows of `batch` that belong to `episode`, for data files that store several episodes.
+fn filter_episode_rows(
+    batch: &RecordBatch,
+    episode: EpisodeIndex,
+) -> Result<RecordBatch, LeRobotError> {
+    let episode_indices = int_column(batch, "episode_index")?;
+    let mask: BooleanArray = episode_indices
+        .iter()
+        .map(|index| Some(index == Some(episode.0 as i64)))
+        .collect();
+
+    Ok(arrow::compute::filter_record_batch(batch, &mask)?)
+}
+
+fn int_column(batch: &RecordBatch, name: &str) -> Result<Int64Array, LeRobotError> {
+    let column = batch
+        .column_by_name(name)
+        .ok_or_else(|| missing_column(name))?;
+    let column: ArrayRef = cast(column, &DataType::Int64)?;
+
+    column
+        .downcast_array_ref::<Int64Array>()
+        .cloned()
+        .ok_or_else(|| missing_column(name))
+}
+
+fn float_column(batch: &RecordBatch, name: &str) -> Result<Float64Array, LeRobotError> {
+    let column = batch
+        .column_by_name(name)
+        .ok_or_else(|| missing_column(name))?;
+    let column: ArrayRef = cast(column, &DataType::Float64)?;
+
+    column
+        .downcast_array_ref::<Float64Array>()
+        .cloned()
+        .ok_or_else(|| missing_column(name))
+}
+
+fn missing_column(name: &str) -> LeRobotError {
+    LeRobotError::MissingDatasetInfo(format!("{name:?} column"))
+}
+
+/// Newtype wrapper for episode indices.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(transparent)]
+pub struct EpisodeIndex(pub usize);
+
+/// An episode in a `LeRobot` dataset.
+///
+/// Each episode contains its index, a list of associated tasks, and its total length in frames.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct LeRobotDatasetEpisode {
+    #[serde(rename = "episode_index")]
+    pub index: EpisodeIndex,
+    pub tasks: Vec<String>,
+    pub length: u32,
+
+    /// Where the episode is stored, for v3 datasets.
+    ///
+    /// v2 datasets store every episode in its own files, derived from its index.
+    #[serde(skip)]
+    pub files: Option<EpisodeFiles>,
+}
+
+/// The files a v3 episode is stored in, alongside other episodes.
+#[derive(Clone, Debug, Default)]
+pub struct EpisodeFiles {
+    /// The Parquet file containing the data of the episode.
+    pub data: FileLocation,
+
+    /// The video file and segment of the episode, for every video feature.
+    pub videos: HashMap<String, VideoSegment>,
+}
+
+/// The location of a file in a v3 dataset, used to render the `data_path` and `video_path` templates.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FileLocation {
+    pub chunk_index: usize,
+    pub file_index: usize,
+}
+
+/// The segment of a video file that belongs to a single v3 episode.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct VideoSegment {
+    pub file: FileLocation,
+
+    /// The start of the episode in the video, in seconds.
+    pub from_timestamp: f64,
+
+    /// The end of the episode in the video, in seconds.
+    pub to_timestamp: f64,
+}
+
+/// Newtype wrapper for task indices.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(transparent)]
+pub struct TaskIndex(pub usize);
+
+/// A task in a `LeRobot` dataset.
+///
+/// Each task consists of its index and a task description.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct LeRobotDatasetTask {
+    #[serde(rename = "task_index")]
+    pub index: TaskIndex,
+    pub task: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use arrow::array::{Float32Array, ListBuilder, StringBuilder};
+
+    use super::*;
+    use serde_json;
+
+    #[test]
+    #[expect(clippy::literal_string_with_formatting_args)] // LeRobot path templates
+    fn test_format_path_template() {
+        let path = format_path_template(
+            "videos/chunk-{episode_chunk:03d}/{video_key}/episode_{episode_index:06d}.mp4",
+            &[
+                ("episode_chunk", &1),
+                ("episode_index", &42),
+                ("video_key", &"observation.image"),
+            ],
+        )
+        .unwrap();
+        assert_eq!(
+            path,
+            PathBuf::from("videos/chunk-001/observation.image/episode_000042.mp4")
+        );
+
+        let path = format_path_template(
+            "data/{chunk_index}/file-{file_index:3d}.parquet",
+            &[("chunk_index", &7), ("file_index", &12)],
+        )
+        .unwrap();
+        assert_eq!(path, PathBuf::from("data/7/file- 12.parquet"));
+
+        for invalid in [
+            "data/{unknown}.parquet",
+            "data/{chunk_index",
+            "{chunk_index:x}",
+        ] {
+            assert!(matches!(
+                format_path_template(invalid, &[("chunk_index", &0)]),
+                Err(LeRobotError::InvalidPathTemplate(_))
+            ));
+        }
+    }
+
+    #[test]
+    #[expect(clippy::literal_string_with_formatting_args)] // LeRobot path templates
+    fn test_load_v3_dataset() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path();
+        for subdir in ["meta/episodes/chunk-000", "data/chunk-000"] {
+            std::fs::create_dir_all(path.join(subdir))?;
+        }
+
+        std::fs::write(
+            path.join("meta/info.json"),
+            r#"{
+                "codebase_version": "v3.0",
+                "robot_type": null,
+                "total_episodes": 2,
+                "total_frames": 5,
+                "total_tasks": 2,
+                "chunks_size": 1000,
+                "fps": 10,
+                "data_path": "data/chunk-{chunk_index:03d}/file-{file_index:03d}.parquet",
+                "video_path": null,
+                "features": {
+                    "observation.state": { "dtype": "float32", "shape": [1], "names": null }
+                }
+            }"#,
+        )?;
+
+        let mut tasks = ListBuilder::new(StringBuilder::new());
+        tasks.append_value([Some("pick")]);
+        tasks.append_value([Some("pick"), Some("place")]);
+        export::write_parquet(
+            &path.join("meta/episodes/chunk-000/file-000.parquet"),
+            vec![
+                (
+                    "episode_index".to_owned(),
+                    Arc::new(Int64Array::from(vec![0, 1])),
+                ),
+                ("tasks".to_owned(), Arc::new(tasks.finish())),
+                ("length".to_owned(), Arc::new(Int64Array::from(vec![2, 3]))),
+                (
+                    "data/chunk_index".to_owned(),
+                    Arc::new(Int64Array::from(vec![0, 0])),
+                ),
+                (
+                    "data/file_index".to_owned(),
+                    Arc::new(Int64Array::from(vec![0, 0])),
+                ),
+            ],
+        )?;
+
+        export::write_parquet(
+            &path.join("meta/tasks.parquet"),
+            vec![
+                (
+                    "task_index".to_owned(),
+                    Arc::new(Int64Array::from(vec![1, 0])),
+                ),
+                (
+                    "__index_level_0__".to_owned(),
+                    Arc::new(StringArray::from(vec!["place", "pick"])),
+                ),
+            ],
+        )?;
+
+        export::write_parquet(
+            &path.join("data/chunk-000/file-000.parquet"),
+            vec![
+                (
+                    "episode_index".to_owned(),
+                    Arc::new(Int64Array::from(vec![0, 0, 1, 1, 1])),
+                ),
+                (
+                    "frame_index".to_owned(),
+                    Arc::new(Int64Array::from(vec![0, 1, 0, 1, 2])),
+                ),
+                (
+                    "observation.state".to_owned(),
+                    Arc::new(Float32Array::from(vec![0.0, 1.0, 2.0, 3.0, 4.0])),
+                ),
+            ],
+        )?;
+
+        let dataset = LeRobotDataset::load_from_directory(path)?;
+        assert!(dataset.metadata.info.is_v3());
+
+        let tasks: Vec<_> = dataset
+            .metadata
+            .tasks
+            .iter()
+            .map(|t| t.task.as_str())
+            .collect();
+        assert_eq!(tasks, ["pick", "place"]);
+
+        let episode = dataset.episode(EpisodeIndex(1)).unwrap();
+        assert_eq!(episode.tasks, ["pick", "place"]);
+        assert_eq!(episode.length, 3);
+        assert_eq!(
+            episode.files.as_ref().unwrap().data,
+            FileLocation::default()
+        );
+
+        let data = dataset.read_episode_data(EpisodeIndex(1))?;
+        assert_eq!(data.num_rows(), 3);
+        let states = data
+            .column_by_name("observation.state")
+            .and_then(|column| column.downcast_array_ref::<Float32Array>())
+            .unwrap();
+        assert_eq!(states.values().as_ref(), [2.0, 3.0, 4.0]);
+
+        assert!(matches!(
+            dataset.read_episode_data(EpisodeIndex(2)),
+            Err(LeRobotError::InvalidEpisodeIndex(EpisodeIndex(2)))
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_episode_row_groups() -> anyhow::Result<()> {
+        let batch = RecordBatch::try_from_iter([(
+            "episode_index",
+            Arc::new(Int64Array::from(vec![0, 0, 1, 1, 1, 3])) as ArrayRef,
+        )])?;
+
+        let file = tempfile::tempfile()?;
+        let properties = parquet::file::properties::WriterProperties::builder()
+            .set_max_row_group_size(2)
+            .build();
+        let mut writer = parquet::arrow::ArrowWriter::try_new(
+            file.try_clone()?,
+            batch.schema(),
+            Some(properties),
+        )?;
+        writer.write(&batch)?;
+        writer.close()?;
+
+        let builder = ParquetRecordBatchReaderBuilder::try_new(file)?;
+        let metadata = builder.metadata();
+        assert_eq!(metadata.num_row_groups(), 3);
+
+        assert_eq!(episode_row_groups(metadata, EpisodeIndex(0)), [0]);
+        assert_eq!(episode_row_groups(metadata, EpisodeIndex(1)), [1, 2]);
+        assert_eq!(episode_row_groups(metadata, EpisodeIndex(2)), [2]);
+        assert!(episode_row_groups(metadata, EpisodeIndex(4)).is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_deserialize_flat_list() {
+        let json = r#"["a", "b", "c"]"#;
+        let expected = Names(vec!["a".to_owned(), "b".to_owned(), "c".to_owned()]);
+        let names: Names = serde_json::from_str(json).unwrap();
+        assert_eq!(names, expected);
+    }
+
+    #[test]
+    fn test_deserialize_nested_list() {
+        let json = r#"[["a", "b"], ["c"]]"#;
+        let expected = Names(vec!["a".to_owned(), "b".to_owned(), "c".to_owned()]);
+        let names: Names = serde_json::from_str(json).unwrap();
+        assert_eq!(names, expected);
+    }
+
+    #[test]
+    fn test_deserialize_empty_nested_list() {
+        let json = r#"[[], []]"#;
+        let expected = Names(vec![]);
+        let names: Names = serde_json::from_str(json).unwrap();
+        assert_eq!(names, expected);
+    }
+
+    #[test]
+    fn test_deserialize_empty_list() {
+        let json = r#"[]"#;
+        let expected = Names(vec![]);
+        let names: Names = serde_json::from_str(json).unwrap();
+        assert_eq!(names, expected);
+    }
+
+    #[test]
+    fn test_deserialize_object_with_list() {
+        let json = r#"{ "axes": ["x", "y", "z"] }"#;
+        let expected = Names(vec!["x".to_owned(), "y".to_owned(), "z".to_owned()]);
+        let names: Names = serde_json::from_str(json).unwrap();
+        assert_eq!(names, expected);
+    }
+
+    #[test]
+    fn test_deserialize_object_with_empty_list() {
+        let json = r#"{ "motors": [] }"#;
+        let expected = Names(vec![]);
+        let names: Names = serde_json::from_str(json).unwrap();
+        assert_eq!(names, expected);
+    }
+
+    #[test]
+    fn test_deserialize_object_with_null() {
+        let json = r#"{ "axes": null }"#;
+        let expected = Names(vec![]); // Null results in an empty list
+        let names: Names = serde_json::from_str(json).unwrap();
+        assert_eq!(names, expected);
+    }
+
+    #[test]
+    fn test_deserialize_empty_object() {
+        // Empty object results in empty list.
+        let json = r#"{}"#;
+        let expected = Names(vec![]);
+        let names: Names = serde_json::from_str(json).unwrap();
+        assert_eq!(names, expected);
+    }
+
+    #[test]
+    fn test_deserialize_error_mixed_list() {
+        let json = r#"["a", ["b"]]"#; // Mixed flat and nested
+        let result: Result<Names, _> = serde_json::from_str(json);
+        assert!(result.is_err());
+        assert!(
+            result
+                .unwrap_err()
+                .to_string()
+                .contains("Cannot mix flat strings and nested lists")
+        );
+    }
+
+    #[test]
+    fn test_deserialize_error_object_multiple_entries() {
+        let json = r#"{ "axes": ["x"], "motors": ["m"] }"#;
+        let result: Result<Names, _> = serde_json::from_str(json);
+        assert!(result.is_err());
+        assert!(
+            result
+                .unwrap_err()
+                .to_string()
+                .contains("a Names object with exactly one entry")
+        );
+    }
+}
@@ -7,6 +7,9 @@ use re_log_types::{ArrowMsg, EntityPath, LogMsg, RecordingId, StoreId, TimePoint
 
 // ----------------------------------------------------------------------------
 
+pub mod deferred;
+pub mod robots;
+
 mod load_file;
 mod loader_archetype;
 mod loader_directory;
@@ -28,6 +31,10 @@ pub mod loader_mcap;
 #[cfg(not(target_arch = "wasm32"))]
 mod loader_external;
 
+// SQLite needs to read from an actual file.
+#[cfg(not(target_arch = "wasm32"))]
+pub mod loader_rosbag2;
+
 pub use self::loader_mcap::McapLoader;
 
 pub use self::{
@@ -44,9 +51,11 @@ pub use self::{
         iter_external_loaders,
     },
     loader_lerobot::LeRobotDatasetLoader,
+    loader_rosbag2::Rosbag2Loader,
 };
 
 pub mod external {
+    pub use ::mcap;
     pub use urdf_rs;
 }
 
@@ -92,6 +101,24 @@ pub struct DataLoaderSettings {
 
     /// At what time(s) should the data be logged to?
     pub timepoint: Option<TimePoint>,
+
+    /// Only load the chunks of `.rrd` files that match this query.
+    ///
+    /// Files that have a footer are only partially read from disk, see
+    /// [`re_log_encoding::footer`]. Loads everything by default.
+    pub rrd_query: re_log_encoding::footer::RrdFooterQuery,
+
+    /// The robots that joint positions are applied to, see [`robots`].
+    ///
+    /// Only the loads that share the same [`robots::Robots`] see each other's robots, e.g. all
+    /// the files opened in the same viewer. Each call to [`Self::recommended`] starts a new one.
+    pub robots: robots::Robots,
+
+    /// Where loaders register the stores whose loading they defer, see [`deferred`].
+    ///
+    /// Loaders only defer stores if this is set, in which case it is up to the consumer to load
+    /// them with [`deferred::DeferredStores::load`]. Unset by default.
+    pub deferred_stores: Option<deferred::DeferredStores>,
 }
 
 impl DataLoaderSettings {
@@ -104,6 +131,9 @@ impl DataLoaderSettings {
             force_store_info: false,
             entity_path_prefix: Default::default(),
             timepoint: Default::default(),
+            rrd_query: Default::default(),
+            robots: Default::default(),
+            deferred_stores: None,
         }
     }
 
@@ -134,6 +164,9 @@ impl DataLoaderSettings {
             force_store_info: _,
             entity_path_prefix,
             timepoint,
+            rrd_query: _,
+            robots: _,
+            deferred_stores: _,
         } = self;
 
         let mut args = Vec::new();
@@ -426,6 +459,8 @@ static BUILTIN_LOADERS: LazyLock<Vec<Arc<dyn DataLoader>>> = LazyLock::new(|| {
         #[cfg(not(target_arch = "wasm32"))]
         Arc::new(LeRobotDatasetLoader),
         #[cfg(not(target_arch = "wasm32"))]
+        Arc::new(Rosbag2Loader),
+        #[cfg(not(target_arch = "wasm32"))]
         Arc::new(ExternalLoader),
         Arc::new(UrdfDataLoader),
     ]
@@ -475,7 +510,10 @@ pub const SUPPORTED_IMAGE_EXTENSIONS: &[&str] = &[
     "pbm", "pgm", "png", "ppm", "tga", "tif", "tiff", "webp",
 ];
 
-pub const SUPPORTED_VIDEO_EXTENSIONS: &[&str] = &["mp4"];
+/// `.ts` is missing on purpose: it is far more common for `TypeScript` sources than for MPEG
+/// transport streams. Those are still loaded when their contents look like one, see
+/// [`ArchetypeLoader`].
+pub const SUPPORTED_VIDEO_EXTENSIONS: &[&str] = &["mp4", "mkv", "webm", "m2ts", "mts"];
 
 pub const SUPPORTED_MESH_EXTENSIONS: &[&str] = &["glb", "gltf", "obj", "stl"];
 
@@ -485,6 +523,13 @@ pub const SUPPORTED_POINT_CLOUD_EXTENSIONS: &[&str] = &["ply"];
 pub const SUPPORTED_RERUN_EXTENSIONS: &[&str] = &["rbl", "rrd"];
 
 /// 3rd party formats with built-in support.
+#[cfg(not(target_arch = "wasm32"))]
+pub const SUPPORTED_THIRD_PARTY_FORMATS: &[&str] = &["db3", "mcap"];
+
+/// 3rd party formats with built-in support.
+///
+/// ROS 2 bags (`.db3`) need `SQLite`, which is native-only.
+#[cfg(target_arch = "wasm32")]
 pub const SUPPORTED_THIRD_PARTY_FORMATS: &[&str] = &["mcap"];
 
 // TODO(#4555): Add catch-all builtin `DataLoader` for text files
@@ -517,5 +562,7 @@ pub fn is_supported_file_extension(extension: &str) -> bool {
 fn test_supported_extensions() {
     assert!(is_supported_file_extension("rrd"));
     assert!(is_supported_file_extension("mcap"));
+    assert!(is_supported_file_extension("db3"));
     assert!(is_supported_file_extension("png"));
+    assert!(!is_supported_file_extension("ts"));
 }
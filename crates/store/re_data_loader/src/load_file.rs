@@ -45,6 +45,10 @@ pub fn load_from_path(
         // When loading a LeRobot dataset, avoid sending a `SetStoreInfo` message since the LeRobot loader handles this automatically.
         force_store_info: !crate::lerobot::is_lerobot_dataset(path),
         application_id,
+        deferred_stores: settings
+            .deferred_stores
+            .as_ref()
+            .map(|deferred_stores| deferred_stores.with_file_source(file_source.clone())),
         ..settings.clone()
     };
 
@@ -75,6 +79,14 @@ pub fn load_from_file_contents(
 
     re_log::info!("Loading {filepath:?}…");
 
+    let settings = &crate::DataLoaderSettings {
+        deferred_stores: settings
+            .deferred_stores
+            .as_ref()
+            .map(|deferred_stores| deferred_stores.with_file_source(file_source.clone())),
+        ..settings.clone()
+    };
+
     let data = load(settings, filepath, Some(contents))?;
 
     send(settings.clone(), file_source, data, tx);
@@ -295,6 +307,9 @@ pub(crate) fn send(
             // poll the channel in any case so as to make sure that the data producer
             // doesn't get stuck.
             for data in rx_loader {
+                // Robots are animated by the joint positions of any file of their recording.
+                let joint_transforms = settings.robots.joint_transforms(&data);
+
                 let data_loader_name = data.data_loader_name().clone();
                 let msg = match data.into_log_msg() {
                     Ok(msg) => {
@@ -323,6 +338,17 @@ pub(crate) fn send(
                     }
                 };
                 tx.send(msg).ok();
+
+                for data in joint_transforms {
+                    match data.into_log_msg() {
+                        Ok(msg) => {
+                            tx.send(msg).ok();
+                        }
+                        Err(err) => {
+                            re_log::error!(%err, "Couldn't serialize joint transforms");
+                        }
+                    }
+                }
             }
 
             for (store_id, tracked) in store_info_tracker {
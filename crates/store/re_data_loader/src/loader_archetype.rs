@@ -4,7 +4,7 @@ use re_chunk::{Chunk, RowId};
 use re_log_types::{ApplicationId, EntityPath, TimePoint};
 use re_types::ComponentBatch;
 use re_types::archetypes::{AssetVideo, VideoFrameReference};
-use re_types::components::VideoTimestamp;
+use re_types::components::{MediaType, VideoTimestamp};
 
 use crate::{DataLoader, DataLoaderError, LoadedData};
 
@@ -54,7 +54,8 @@ impl DataLoader for ArchetypeLoader {
         tx: std::sync::mpsc::Sender<LoadedData>,
     ) -> Result<(), crate::DataLoaderError> {
         let extension = crate::extension(&filepath);
-        if !crate::is_supported_file_extension(&extension) {
+        let is_mpeg_ts = is_mpeg_ts(&extension, &contents);
+        if !crate::is_supported_file_extension(&extension) && !is_mpeg_ts {
             return Err(crate::DataLoaderError::Incompatible(filepath.clone()));
         }
 
@@ -102,7 +103,7 @@ impl DataLoader for ArchetypeLoader {
                 entity_path,
                 contents.into_owned(),
             )?);
-        } else if crate::SUPPORTED_VIDEO_EXTENSIONS.contains(&extension.as_str()) {
+        } else if crate::SUPPORTED_VIDEO_EXTENSIONS.contains(&extension.as_str()) || is_mpeg_ts {
             re_log::debug!(?filepath, loader = self.name(), "Loading video…",);
             rows.extend(load_video(
                 &filepath,
@@ -179,6 +180,12 @@ fn load_image(
     Ok(rows.into_iter())
 }
 
+/// Whether this is a `.ts` file that contains an MPEG transport stream rather than `TypeScript`,
+/// according to its sync bytes.
+fn is_mpeg_ts(extension: &str, contents: &[u8]) -> bool {
+    extension == "ts" && MediaType::guess_from_data(contents) == Some(MediaType::mpeg_ts())
+}
+
 fn load_video(
     filepath: &std::path::Path,
     mut timepoint: TimePoint,
@@ -321,3 +328,33 @@ fn load_text_document(
 
     Ok(rows.into_iter())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ts_files_are_sniffed() {
+        let mut mpeg_ts = vec![0; 5 * 188];
+        for packet in mpeg_ts.chunks_mut(188) {
+            packet[0] = 0x47;
+        }
+        assert!(is_mpeg_ts("ts", &mpeg_ts));
+        assert!(!is_mpeg_ts("mp4", &mpeg_ts));
+
+        let typescript = b"export const answer: number = 42;\n".repeat(10);
+        assert!(!is_mpeg_ts("ts", &typescript));
+
+        let (tx, _rx) = std::sync::mpsc::channel();
+        let settings = crate::DataLoaderSettings::recommended(re_log_types::RecordingId::random());
+        assert!(matches!(
+            ArchetypeLoader.load_from_file_contents(
+                &settings,
+                "answer.ts".into(),
+                std::borrow::Cow::Owned(typescript),
+                tx,
+            ),
+            Err(DataLoaderError::Incompatible(_))
+        ));
+    }
+}
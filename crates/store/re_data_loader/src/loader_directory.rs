@@ -29,6 +29,11 @@ impl crate::DataLoader for DirectoryLoader {
             return Err(crate::DataLoaderError::Incompatible(dirpath.clone()));
         }
 
+        if crate::loader_rosbag2::is_rosbag2_sqlite_directory(&dirpath) {
+            // ROS 2 bags are loaded by Rosbag2Loader
+            return Err(crate::DataLoaderError::Incompatible(dirpath.clone()));
+        }
+
         re_tracing::profile_function!(dirpath.display().to_string());
 
         re_log::debug!(?dirpath, loader = self.name(), "Loading directory…",);
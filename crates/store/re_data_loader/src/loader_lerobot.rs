@@ -6,9 +6,10 @@ use std::{
 use anyhow::{Context as _, anyhow};
 use arrow::{
     array::{
-        ArrayRef, BinaryArray, FixedSizeListArray, Int64Array, RecordBatch, StringArray,
-        StructArray,
+        ArrayRef, BinaryArray, FixedSizeListArray, Int64Array, ListArray, RecordBatch, StringArray,
+        StructArray, UInt8Array, UInt32Array, UInt64Array,
     },
+    buffer::{OffsetBuffer, ScalarBuffer},
     compute::cast,
     datatypes::{DataType, Field},
 };
@@ -21,15 +22,16 @@ use re_chunk::{
 };
 use re_log_types::{ApplicationId, StoreId};
 use re_types::{
+    AnyValues, SerializedComponentBatch,
     archetypes::{
         self, AssetVideo, DepthImage, EncodedImage, Scalars, TextDocument, VideoFrameReference,
     },
-    components::VideoTimestamp,
+    components::{self, VideoTimestamp},
 };
 
 use crate::lerobot::{
-    DType, EpisodeIndex, Feature, LeRobotDataset, TaskIndex, is_lerobot_dataset,
-    is_v1_lerobot_dataset,
+    DType, EpisodeIndex, Feature, LeRobotDataset, LeRobotDatasetEpisode, TaskIndex,
+    is_lerobot_dataset, is_v1_lerobot_dataset,
 };
 use crate::load_file::prepare_store_info;
 use crate::{DataLoader, DataLoaderError, LoadedData};
@@ -40,10 +42,13 @@ const LEROBOT_DATASET_IGNORED_COLUMNS: &[&str] =
 
 /// Only supports `LeRobot` datasets that are in a supported version format.
 /// Datasets from unsupported versions won't load.
-const LEROBOT_DATASET_SUPPORTED_VERSIONS: &[&str] = &["v2.0", "v2.1"];
+const LEROBOT_DATASET_SUPPORTED_VERSIONS: &[&str] = &["v2.0", "v2.1", "v3.0"];
 
 /// A [`DataLoader`] for `LeRobot` datasets.
 ///
+/// Every episode is loaded into its own recording. If [deferred loading](crate::deferred) is
+/// enabled, an episode is only loaded once it is requested, e.g. when it is first opened in the viewer.
+///
 /// An example dataset which can be loaded can be found on Hugging Face: [lerobot/pusht_image](https://huggingface.co/datasets/lerobot/pusht_image)
 pub struct LeRobotDatasetLoader;
 
@@ -68,6 +73,7 @@ impl DataLoader for LeRobotDatasetLoader {
         }
 
         let dataset = LeRobotDataset::load_from_directory(&filepath)
+            .map(Arc::new)
             .map_err(|err| anyhow!("Loading LeRobot dataset failed: {err}"))?;
 
         if !LEROBOT_DATASET_SUPPORTED_VERSIONS
@@ -93,13 +99,15 @@ impl DataLoader for LeRobotDatasetLoader {
         thread::Builder::new()
             .name(format!("load_and_stream({filepath:?}"))
             .spawn({
+                let filepath = filepath.clone();
+                let settings = settings.clone();
                 move || {
                     re_log::info!(
                         "Loading LeRobot dataset from {:?}, with {} episode(s)",
                         dataset.path,
                         dataset.metadata.episodes.len(),
                     );
-                    load_and_stream(&dataset, &application_id, &tx);
+                    load_and_stream(&dataset, &filepath, &settings, &application_id, &tx);
                 }
             })
             .with_context(|| {
@@ -121,55 +129,76 @@ impl DataLoader for LeRobotDatasetLoader {
 }
 
 fn load_and_stream(
-    dataset: &LeRobotDataset,
+    dataset: &Arc<LeRobotDataset>,
+    filepath: &std::path::Path,
+    settings: &crate::DataLoaderSettings,
     application_id: &ApplicationId,
     tx: &Sender<crate::LoadedData>,
 ) {
     // set up all recordings
     let episodes = prepare_episode_chunks(dataset, application_id, tx);
 
-    for (episode, store_id) in &episodes {
-        // log episode data to its respective recording
-        match load_episode(dataset, *episode) {
-            Ok(chunks) => {
-                let recording_info = re_types::archetypes::RecordingInfo::new()
-                    .with_name(format!("Episode {}", episode.0));
-
-                let Ok(initial) = Chunk::builder(EntityPath::properties())
-                    .with_archetype(RowId::new(), TimePoint::STATIC, &recording_info)
-                    .build()
-                else {
+    for (episode, store_id) in episodes {
+        if let Some(deferred_stores) = &settings.deferred_stores {
+            // Deferred episodes are sent on a channel of their own, so that `tx` isn't kept open
+            // by episodes that are never loaded.
+            let dataset = Arc::clone(dataset);
+            let path = filepath.to_owned();
+            deferred_stores.defer_store(store_id.clone(), path, settings, move |tx| {
+                let spawned = thread::Builder::new()
+                    .name(format!("load_episode({})", episode.0))
+                    .spawn(move || stream_episode(&dataset, episode, &store_id, &tx));
+                if let Err(err) = spawned {
                     re_log::error!(
-                        "Failed to build recording properties chunk for episode {}",
+                        "Failed to spawn thread to load episode {}: {err}",
                         episode.0
                     );
-                    return;
-                };
-
-                for chunk in std::iter::once(initial).chain(chunks.into_iter()) {
-                    let data = LoadedData::Chunk(
-                        LeRobotDatasetLoader::name(&LeRobotDatasetLoader),
-                        store_id.clone(),
-                        chunk,
-                    );
-
-                    if tx.send(data).is_err() {
-                        break; // The other end has decided to hang up, not our problem.
-                    }
                 }
-            }
-            Err(err) => {
-                re_log::warn!(
-                    "Failed to load episode {} from LeRobot dataset: {err}",
-                    episode.0
+            });
+        } else if !stream_episode(dataset, episode, &store_id, tx) {
+            break; // The other end has decided to hang up, not our problem.
+        }
+    }
+}
+
+/// Loads an episode and sends it to its recording.
+///
+/// Returns `false` if the receiving end hung up.
+fn stream_episode(
+    dataset: &LeRobotDataset,
+    episode: EpisodeIndex,
+    store_id: &StoreId,
+    tx: &Sender<crate::LoadedData>,
+) -> bool {
+    match load_episode(dataset, episode) {
+        Ok(chunks) => {
+            for chunk in chunks {
+                let data = LoadedData::Chunk(
+                    LeRobotDatasetLoader::name(&LeRobotDatasetLoader),
+                    store_id.clone(),
+                    chunk,
                 );
+
+                if tx.send(data).is_err() {
+                    return false;
+                }
             }
         }
+        Err(err) => {
+            re_log::warn!(
+                "Failed to load episode {} from LeRobot dataset: {err}",
+                episode.0
+            );
+        }
     }
+
+    true
 }
 
 /// Prepare the viewer for all episodes, by sending out a [`SetStoreInfo`](`re_log_types::SetStoreInfo`)
-/// [`LogMsg`](`re_log_types::LogMsg`) for each episode.
+/// [`LogMsg`](`re_log_types::LogMsg`) and the recording properties for each episode.
+///
+/// The properties contain the name of the episode, its length and its tasks.
 fn prepare_episode_chunks(
     dataset: &LeRobotDataset,
     application_id: &ApplicationId,
@@ -178,9 +207,10 @@ fn prepare_episode_chunks(
     let mut store_ids = vec![];
 
     for episode in &dataset.metadata.episodes {
-        let episode = episode.index;
-
-        let store_id = StoreId::recording(application_id.clone(), format!("episode_{}", episode.0));
+        let store_id = StoreId::recording(
+            application_id.clone(),
+            format!("episode_{}", episode.index.0),
+        );
         let set_store_info = LoadedData::LogMsg(
             LeRobotDatasetLoader::name(&LeRobotDatasetLoader),
             prepare_store_info(&store_id, re_log_types::FileSource::Sdk),
@@ -190,12 +220,53 @@ fn prepare_episode_chunks(
             break;
         }
 
-        store_ids.push((episode, store_id));
+        match episode_properties_chunk(episode) {
+            Ok(chunk) => {
+                let properties = LoadedData::Chunk(
+                    LeRobotDatasetLoader::name(&LeRobotDatasetLoader),
+                    store_id.clone(),
+                    chunk,
+                );
+
+                if tx.send(properties).is_err() {
+                    break;
+                }
+            }
+            Err(err) => {
+                re_log::error!(
+                    "Failed to build recording properties chunk for episode {}: {err}",
+                    episode.index.0
+                );
+            }
+        }
+
+        store_ids.push((episode.index, store_id));
     }
 
     store_ids
 }
 
+fn episode_properties_chunk(episode: &LeRobotDatasetEpisode) -> Result<Chunk, DataLoaderError> {
+    let recording_info =
+        archetypes::RecordingInfo::new().with_name(format!("Episode {}", episode.index.0));
+
+    let episode_info = AnyValues::new("rerun.lerobot.Episode")
+        .with_field(
+            "episode_index",
+            Arc::new(UInt64Array::from_value(episode.index.0 as u64, 1)),
+        )
+        .with_field(
+            "length",
+            Arc::new(UInt32Array::from_value(episode.length, 1)),
+        )
+        .with_component::<components::Text>("tasks", episode.tasks.clone());
+
+    Ok(Chunk::builder(EntityPath::properties())
+        .with_archetype(RowId::new(), TimePoint::STATIC, &recording_info)
+        .with_archetype(RowId::new(), TimePoint::STATIC, &episode_info)
+        .build()?)
+}
+
 /// Loads a single episode from a `LeRobot` dataset and converts it into a collection of Rerun chunks.
 ///
 /// This function processes an episode from the dataset by extracting the relevant data columns and
@@ -381,9 +452,24 @@ fn load_episode_video(
         .read_episode_video_contents(observation, episode)
         .with_context(|| format!("Reading video contents for episode {episode:?} failed!"))?;
 
-    let video_asset = AssetVideo::new(contents.into_owned());
+    // v3 episodes share their video file, so share its contents rather than copying them
+    // into every episode, as `AssetVideo::new` would.
+    let video_asset = AssetVideo {
+        blob: Some(shared_video_blob(contents)?),
+        media_type: None,
+    };
     let entity_path = observation;
 
+    // v3 episodes only occupy a segment of their video file.
+    let segment_start_nanos =
+        dataset
+            .episode_video_segment(observation, episode)
+            .map_or(i64::MIN, |segment| {
+                // Allow for rounding errors, by accepting frames up to half a frame early.
+                let half_frame_secs = 0.5 / dataset.metadata.info.fps.max(1) as f64;
+                ((segment.from_timestamp - half_frame_secs) * 1e9) as i64
+            });
+
     let video_frame_reference_chunk = match video_asset.read_frame_timestamps_nanos() {
         Ok(frame_timestamps_nanos) => {
             let frame_timestamps_nanos: arrow::buffer::ScalarBuffer<i64> =
@@ -391,6 +477,7 @@ fn load_episode_video(
 
             let video_timestamps = frame_timestamps_nanos
                 .iter()
+                .skip_while(|&&timestamp| timestamp < segment_start_nanos)
                 .take(time_column.num_rows())
                 .copied()
                 .map(VideoTimestamp::from_nanos)
@@ -435,6 +522,23 @@ fn load_episode_video(
     }
 }
 
+/// Serializes the blob of an [`AssetVideo`] without copying `contents`.
+fn shared_video_blob(
+    contents: ScalarBuffer<u8>,
+) -> Result<SerializedComponentBatch, DataLoaderError> {
+    let blob = ListArray::try_new(
+        Arc::new(Field::new("item", DataType::UInt8, false)),
+        OffsetBuffer::from_lengths([contents.len()]),
+        Arc::new(UInt8Array::new(contents, None)),
+        None,
+    )?;
+
+    Ok(SerializedComponentBatch::new(
+        Arc::new(blob),
+        AssetVideo::descriptor_blob(),
+    ))
+}
+
 /// Helper type similar to [`Either`], but with 3 variants.
 enum ScalarChunkIterator {
     Empty(std::iter::Empty<Chunk>),
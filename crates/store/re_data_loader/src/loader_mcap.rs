@@ -1,12 +1,16 @@
 //! Rerun dataloader for MCAP files.
 
-use std::{io::Cursor, sync::mpsc::Sender};
+use std::sync::mpsc::Sender;
 
 use anyhow::Context as _;
 use re_chunk::RowId;
 use re_log_types::{SetStoreInfo, StoreId, StoreInfo};
 
-use crate::mcap::layers::{LayerRegistry, SelectedLayers};
+use crate::mcap::{
+    filter::McapFilter,
+    layers::{LayerRegistry, SelectedLayers},
+    util::McapReader,
+};
 use crate::{DataLoader, DataLoaderError, DataLoaderSettings, LoadedData};
 
 const MCAP_LOADER_NAME: &str = "McapLoader";
@@ -25,12 +29,14 @@ const MCAP_LOADER_NAME: &str = "McapLoader";
 /// - [`crate::mcap::layers::McapRawLayer`]
 pub struct McapLoader {
     selected_layers: SelectedLayers,
+    filter: McapFilter,
 }
 
 impl Default for McapLoader {
     fn default() -> Self {
         Self {
             selected_layers: SelectedLayers::All,
+            filter: McapFilter::default(),
         }
     }
 }
@@ -38,7 +44,16 @@ impl Default for McapLoader {
 impl McapLoader {
     /// Creates a new [`McapLoader`] that only extracts the specified `layers`.
     pub fn new(selected_layers: SelectedLayers) -> Self {
-        Self { selected_layers }
+        Self {
+            selected_layers,
+            filter: McapFilter::default(),
+        }
+    }
+
+    /// Only loads the messages selected by `filter`.
+    pub fn with_filter(mut self, filter: McapFilter) -> Self {
+        self.filter = filter;
+        self
     }
 }
 
@@ -71,10 +86,11 @@ impl DataLoader for McapLoader {
         // common rayon thread pool.
         let settings = settings.clone();
         let selected_layers = self.selected_layers.clone();
+        let filter = self.filter.clone();
         std::thread::Builder::new()
             .name(format!("load_mcap({path:?}"))
             .spawn(
-                move || match load_mcap_mmap(&path, &settings, &tx, selected_layers) {
+                move || match load_mcap_file(&path, &settings, &tx, selected_layers, &filter) {
                     Ok(_) => {}
                     Err(err) => {
                         re_log::error!("Failed to load MCAP file: {err}");
@@ -102,6 +118,7 @@ impl DataLoader for McapLoader {
 
         let settings = settings.clone();
         let selected_layers = self.selected_layers.clone();
+        let filter = self.filter.clone();
 
         // NOTE(1): `spawn` is fine, this whole function is native-only.
         // NOTE(2): this must spawned on a dedicated thread to avoid a deadlock!
@@ -110,14 +127,14 @@ impl DataLoader for McapLoader {
         // common rayon thread pool.
         std::thread::Builder::new()
             .name(format!("load_mcap({filepath:?}"))
-            .spawn(
-                move || match load_mcap_mmap(&filepath, &settings, &tx, selected_layers) {
+            .spawn(move || {
+                match load_mcap_file(&filepath, &settings, &tx, selected_layers, &filter) {
                     Ok(_) => {}
                     Err(err) => {
                         re_log::error!("Failed to load MCAP file: {err}");
                     }
-                },
-            )
+                }
+            })
             .map_err(|err| DataLoaderError::Other(err.into()))?;
 
         Ok(())
@@ -131,34 +148,39 @@ impl DataLoader for McapLoader {
         contents: std::borrow::Cow<'_, [u8]>,
         tx: Sender<crate::LoadedData>,
     ) -> std::result::Result<(), DataLoaderError> {
-        let contents = contents.into_owned();
-
-        load_mcap(&contents, settings, &tx, self.selected_layers.clone())
+        let mut reader = std::io::Cursor::new(contents.as_ref());
+
+        load_mcap(
+            &mut reader,
+            settings,
+            &tx,
+            self.selected_layers.clone(),
+            &self.filter,
+        )
     }
 }
 
 #[cfg(not(target_arch = "wasm32"))]
-fn load_mcap_mmap(
+fn load_mcap_file(
     filepath: &std::path::PathBuf,
     settings: &DataLoaderSettings,
     tx: &Sender<LoadedData>,
     selected_layers: SelectedLayers,
+    filter: &McapFilter,
 ) -> std::result::Result<(), DataLoaderError> {
-    use std::fs::File;
-    let file = File::open(filepath)?;
+    // The file is read one MCAP chunk at a time, so that arbitrarily large files can be
+    // loaded with bounded memory usage.
+    let mut reader = std::io::BufReader::new(std::fs::File::open(filepath)?);
 
-    // SAFETY: file-backed memory maps are marked unsafe because of potential UB when using the map and the underlying file is modified.
-    #[allow(unsafe_code)]
-    let mmap = unsafe { memmap2::Mmap::map(&file)? };
-
-    load_mcap(&mmap, settings, tx, selected_layers)
+    load_mcap(&mut reader, settings, tx, selected_layers, filter)
 }
 
 fn load_mcap(
-    mcap: &[u8],
+    reader: &mut dyn McapReader,
     settings: &DataLoaderSettings,
     tx: &Sender<LoadedData>,
     selected_layers: SelectedLayers,
+    filter: &McapFilter,
 ) -> Result<(), DataLoaderError> {
     re_tracing::profile_function!();
 
@@ -194,9 +216,7 @@ fn load_mcap(
         }
     };
 
-    let reader = Cursor::new(&mcap);
-
-    let summary = crate::mcap::util::read_summary(reader)?
+    let summary = crate::mcap::util::read_summary(&mut *reader)?
         .ok_or_else(|| anyhow::anyhow!("MCAP file does not contain a summary"))?;
 
     let registry = LayerRegistry::all();
@@ -208,7 +228,7 @@ fn load_mcap(
         re_tracing::profile_scope!("process-layer");
         empty = false;
         layer
-            .process(mcap, &summary, &mut send_chunk)
+            .process(reader, &summary, filter, &mut send_chunk)
             .with_context(|| "processing layers")?;
     }
     if empty {
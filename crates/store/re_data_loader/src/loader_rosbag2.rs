@@ -0,0 +1,589 @@
+//! A [`DataLoader`] for ROS 2 bags recorded with the `sqlite3` storage plugin.
+//!
+//! A bag is a directory holding a `metadata.yaml` file and one or more `.db3` `SQLite` databases.
+//! See <https://github.com/ros2/rosbag2/tree/rolling/rosbag2_storage_sqlite3>.
+
+use std::{
+    borrow::Cow,
+    path::{Path, PathBuf},
+    sync::{Arc, mpsc::Sender},
+};
+
+use anyhow::Context as _;
+use re_chunk::{RowId, external::nohash_hasher::IntMap};
+use re_log_types::{SetStoreInfo, StoreId, StoreInfo};
+use rusqlite::{Connection, OpenFlags};
+
+use crate::{
+    DataLoader, DataLoaderError, DataLoaderSettings, LoadedData,
+    mcap::{
+        MessageLayer as _,
+        decode::{ChannelId, McapChunkDecoder, ParserContext},
+        layers::McapRos2Layer,
+    },
+};
+
+const ROSBAG2_LOADER_NAME: &str = "Rosbag2Loader";
+
+/// The name of the file describing a bag.
+const METADATA_FILE_NAME: &str = "metadata.yaml";
+
+/// The number of messages that are decoded into chunks at once.
+///
+/// This plays the same role as MCAP chunks, and bounds memory usage for large bags.
+const MESSAGES_PER_BATCH: usize = 16 * 1024;
+
+/// The subset of `metadata.yaml` that we need.
+#[derive(Debug, serde::Deserialize)]
+struct Metadata {
+    rosbag2_bagfile_information: BagfileInformation,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct BagfileInformation {
+    storage_identifier: String,
+    relative_file_paths: Vec<PathBuf>,
+
+    #[serde(default)]
+    compression_format: String,
+}
+
+impl Metadata {
+    fn from_directory(dirpath: &Path) -> anyhow::Result<Self> {
+        let path = dirpath.join(METADATA_FILE_NAME);
+        let file = std::fs::File::open(&path).with_context(|| format!("opening {path:?}"))?;
+        serde_yaml::from_reader(file).with_context(|| format!("parsing {path:?}"))
+    }
+}
+
+/// Checks whether `path` is a ROS 2 bag that uses the `sqlite3` storage plugin.
+pub fn is_rosbag2_sqlite_directory(path: &Path) -> bool {
+    path.is_dir()
+        && Metadata::from_directory(path).is_ok_and(|metadata| {
+            metadata.rosbag2_bagfile_information.storage_identifier == "sqlite3"
+        })
+}
+
+/// A [`DataLoader`] for ROS 2 bags recorded with the `sqlite3` storage plugin.
+///
+/// Accepts either a bag directory, or a single `.db3` file of a bag. Messages are decoded with
+/// the same parsers as [`McapRos2Layer`], so the result is the same as loading the bag after
+/// converting it to MCAP.
+pub struct Rosbag2Loader;
+
+impl DataLoader for Rosbag2Loader {
+    fn name(&self) -> crate::DataLoaderName {
+        ROSBAG2_LOADER_NAME.into()
+    }
+
+    fn load_from_path(
+        &self,
+        settings: &DataLoaderSettings,
+        path: PathBuf,
+        tx: Sender<LoadedData>,
+    ) -> Result<(), DataLoaderError> {
+        let db_paths = if is_rosbag2_sqlite_directory(&path) {
+            let BagfileInformation {
+                storage_identifier: _,
+                relative_file_paths,
+                compression_format,
+            } = Metadata::from_directory(&path)?.rosbag2_bagfile_information;
+
+            if !compression_format.is_empty() {
+                return Err(anyhow::anyhow!(
+                    "Compressed ROS 2 bags ({compression_format}) are not supported yet"
+                )
+                .into());
+            }
+
+            relative_file_paths
+                .into_iter()
+                .map(|file_path| path.join(file_path))
+                .collect()
+        } else if path.is_file() && crate::extension(&path) == "db3" {
+            vec![path.clone()]
+        } else {
+            return Err(DataLoaderError::Incompatible(path)); // simply not interested
+        };
+
+        spawn_load_rosbag2(path, db_paths, settings, tx, None)
+    }
+
+    fn load_from_file_contents(
+        &self,
+        settings: &DataLoaderSettings,
+        filepath: PathBuf,
+        contents: Cow<'_, [u8]>,
+        tx: Sender<LoadedData>,
+    ) -> Result<(), DataLoaderError> {
+        if crate::extension(&filepath) != "db3" {
+            return Err(DataLoaderError::Incompatible(filepath)); // simply not interested
+        }
+
+        // SQLite needs an actual file to work with.
+        let mut db_file = tempfile::Builder::new().suffix(".db3").tempfile()?;
+        std::io::Write::write_all(&mut db_file, &contents)?;
+
+        let db_paths = vec![db_file.path().to_owned()];
+        spawn_load_rosbag2(filepath, db_paths, settings, tx, Some(db_file))
+    }
+}
+
+/// Loads the given databases of a bag on a dedicated thread.
+///
+/// `temp_file` is only deleted once loading is done.
+fn spawn_load_rosbag2(
+    path: PathBuf,
+    db_paths: Vec<PathBuf>,
+    settings: &DataLoaderSettings,
+    tx: Sender<LoadedData>,
+    temp_file: Option<tempfile::NamedTempFile>,
+) -> Result<(), DataLoaderError> {
+    re_tracing::profile_function!();
+
+    // NOTE(1): `spawn` is fine, this whole module is native-only.
+    // NOTE(2): this must spawned on a dedicated thread to avoid a deadlock!
+    // `load` will spawn a bunch of loaders on the common rayon thread pool and wait for
+    // their response via channels: we cannot be waiting for these responses on the
+    // common rayon thread pool.
+    let settings = settings.clone();
+    std::thread::Builder::new()
+        .name(format!("load_rosbag2({path:?}"))
+        .spawn(move || {
+            if let Err(err) = load_rosbag2(&db_paths, &settings, &tx) {
+                re_log::error!("Failed to load ROS 2 bag {path:?}: {err}");
+            }
+            drop(temp_file);
+        })
+        .map_err(|err| DataLoaderError::Other(err.into()))?;
+
+    Ok(())
+}
+
+fn load_rosbag2(
+    db_paths: &[PathBuf],
+    settings: &DataLoaderSettings,
+    tx: &Sender<LoadedData>,
+) -> anyhow::Result<()> {
+    re_tracing::profile_function!();
+
+    let store_id = settings.recommended_store_id();
+
+    if tx
+        .send(LoadedData::LogMsg(
+            ROSBAG2_LOADER_NAME.to_owned(),
+            re_log_types::LogMsg::SetStoreInfo(store_info(store_id.clone())),
+        ))
+        .is_err()
+    {
+        re_log::debug_once!(
+            "Failed to send `SetStoreInfo` because smart channel closed unexpectedly."
+        );
+        // If the other side decided to hang up this is not our problem.
+        return Ok(());
+    }
+
+    let mut send_chunk = |chunk| {
+        if tx
+            .send(LoadedData::Chunk(
+                ROSBAG2_LOADER_NAME.to_owned(),
+                store_id.clone(),
+                chunk,
+            ))
+            .is_err()
+        {
+            // If the other side decided to hang up this is not our problem.
+            re_log::debug_once!(
+                "Failed to send chunk because the smart channel has been closed unexpectedly."
+            );
+        }
+    };
+
+    // The layer is shared by all files, as the split files of a bag form a single recording.
+    let layer = McapRos2Layer::default();
+
+    // Transforms are logged on the path of their frame in the tree, which must be known in
+    // full before the first batch is decoded, see `MessageLayer::prepare`.
+    for db_path in db_paths {
+        read_transform_frames(db_path, &layer)
+            .with_context(|| format!("reading transforms of {db_path:?}"))?;
+    }
+
+    for db_path in db_paths {
+        load_db3(db_path, &layer, &mut send_chunk)
+            .with_context(|| format!("loading {db_path:?}"))?;
+    }
+
+    Ok(())
+}
+
+/// Reads the `topics` table of a bag database into MCAP channels.
+fn read_channels(
+    connection: &Connection,
+) -> anyhow::Result<IntMap<i64, Arc<mcap::Channel<'static>>>> {
+    let mut statement =
+        connection.prepare("SELECT id, name, type, serialization_format FROM topics")?;
+
+    let mut channels = IntMap::default();
+    let mut rows = statement.query([])?;
+    while let Some(row) = rows.next()? {
+        let topic_id: i64 = row.get(0)?;
+        let id = u16::try_from(channels.len()).context("too many topics")?;
+
+        let schema = mcap::Schema {
+            id,
+            name: row.get(2)?,
+            encoding: "ros2msg".to_owned(),
+            data: Cow::Borrowed(&[]),
+        };
+
+        channels.insert(
+            topic_id,
+            Arc::new(mcap::Channel {
+                id,
+                topic: row.get(1)?,
+                schema: Some(Arc::new(schema)),
+                message_encoding: row.get(3)?,
+                metadata: Default::default(),
+            }),
+        );
+    }
+
+    Ok(channels)
+}
+
+fn open_db3(db_path: &Path) -> rusqlite::Result<Connection> {
+    Connection::open_with_flags(
+        db_path,
+        OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+    )
+}
+
+/// Adds the frames of all transform messages of a bag database to the transform tree of `layer`.
+fn read_transform_frames(db_path: &Path, layer: &McapRos2Layer) -> anyhow::Result<()> {
+    re_tracing::profile_function!(db_path.display().to_string());
+
+    let connection = open_db3(db_path)?;
+    let channels = read_channels(&connection)?;
+
+    let tf_topic_ids = channels
+        .iter()
+        .filter(|(_, channel)| McapRos2Layer::is_transform_channel(channel))
+        .map(|(topic_id, _)| topic_id.to_string())
+        .collect::<Vec<_>>();
+    if tf_topic_ids.is_empty() {
+        return Ok(());
+    }
+
+    let mut statement = connection.prepare(&format!(
+        "SELECT topic_id, data FROM messages WHERE topic_id IN ({})",
+        tf_topic_ids.join(", ")
+    ))?;
+    let mut rows = statement.query([])?;
+    while let Some(row) = rows.next()? {
+        let topic_id: i64 = row.get(0)?;
+        layer.add_transform_frames(&mcap::Message {
+            channel: Arc::clone(&channels[&topic_id]),
+            sequence: 0,
+            log_time: 0,
+            publish_time: 0,
+            data: Cow::Owned(row.get(1)?),
+        });
+    }
+
+    Ok(())
+}
+
+fn load_db3(
+    db_path: &Path,
+    layer: &McapRos2Layer,
+    emit: &mut dyn FnMut(re_chunk::Chunk),
+) -> anyhow::Result<()> {
+    re_tracing::profile_function!(db_path.display().to_string());
+
+    let connection = open_db3(db_path)?;
+
+    let channels = read_channels(&connection)?;
+
+    let mut statement =
+        connection.prepare("SELECT topic_id, timestamp, data FROM messages ORDER BY timestamp")?;
+    let mut rows = statement.query([])?;
+
+    let mut batch = Vec::with_capacity(MESSAGES_PER_BATCH);
+    loop {
+        let row = rows.next()?;
+
+        if let Some(row) = row {
+            let topic_id: i64 = row.get(0)?;
+            let Some(channel) = channels.get(&topic_id) else {
+                re_log::warn_once!("Found message for unknown topic id {topic_id}");
+                continue;
+            };
+
+            let timestamp: i64 = row.get(1)?;
+            batch.push(mcap::Message {
+                channel: Arc::clone(channel),
+                sequence: 0,
+                log_time: timestamp as u64,
+                publish_time: timestamp as u64,
+                data: Cow::Owned(row.get(2)?),
+            });
+        }
+
+        if batch.len() >= MESSAGES_PER_BATCH || (row.is_none() && !batch.is_empty()) {
+            decode_batch(layer, &batch, emit);
+            batch.clear();
+        }
+
+        if row.is_none() {
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+/// Decodes a batch of messages the same way as a chunk of an MCAP file.
+fn decode_batch(
+    layer: &McapRos2Layer,
+    batch: &[mcap::Message<'static>],
+    emit: &mut dyn FnMut(re_chunk::Chunk),
+) {
+    re_tracing::profile_function!();
+
+    let mut num_rows_per_channel = IntMap::<ChannelId, usize>::default();
+    for msg in batch {
+        *num_rows_per_channel
+            .entry(ChannelId::from(msg.channel.id))
+            .or_default() += 1;
+    }
+
+    let mut parsers = IntMap::default();
+    for msg in batch {
+        let channel_id = ChannelId::from(msg.channel.id);
+        if parsers.contains_key(&channel_id) {
+            continue;
+        }
+
+        if let Some(parser) = layer.message_parser(&msg.channel, num_rows_per_channel[&channel_id])
+        {
+            let ctx = ParserContext::new(msg.channel.topic.as_str().into());
+            parsers.insert(channel_id, (ctx, parser));
+        }
+    }
+
+    let mut decoder = McapChunkDecoder::new(parsers);
+
+    for msg in batch {
+        if let Err(err) = decoder.decode_next(msg) {
+            re_log::error!(
+                "Failed to decode message from ROS 2 bag: {err} on topic: {}",
+                msg.channel.topic
+            );
+        }
+    }
+
+    for chunk in decoder.finish() {
+        match chunk {
+            Ok(chunk) => emit(chunk),
+            Err(err) => re_log::error!("Failed to decode chunk from ROS 2 bag: {err}"),
+        }
+    }
+}
+
+fn store_info(store_id: StoreId) -> SetStoreInfo {
+    SetStoreInfo {
+        row_id: *RowId::new(),
+        info: StoreInfo {
+            store_id,
+            cloned_from: None,
+            store_source: re_log_types::StoreSource::Other(ROSBAG2_LOADER_NAME.to_owned()),
+            store_version: Some(re_build_info::CrateVersion::LOCAL),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::mpsc;
+
+    use re_chunk::EntityPath;
+
+    use super::*;
+
+    /// Encodes a `std_msgs/msg/String` in CDR.
+    fn string_message(text: &str) -> Vec<u8> {
+        let mut data = vec![0x00, 0x01, 0x00, 0x00]; // CDR, little-endian
+        data.extend_from_slice(&(text.len() as u32 + 1).to_le_bytes());
+        data.extend_from_slice(text.as_bytes());
+        data.push(0);
+        data
+    }
+
+    /// Creates a bag database holding the given `(topic, type, timestamp, data)` messages.
+    fn write_db3(path: &Path, messages: &[(&str, &str, i64, Vec<u8>)]) -> anyhow::Result<()> {
+        let connection = Connection::open(path)?;
+        connection.execute_batch(
+            "CREATE TABLE topics(id INTEGER PRIMARY KEY, name TEXT NOT NULL, type TEXT NOT NULL, \
+                serialization_format TEXT NOT NULL, offered_qos_profiles TEXT NOT NULL);
+             CREATE TABLE messages(id INTEGER PRIMARY KEY, topic_id INTEGER NOT NULL, \
+                timestamp INTEGER NOT NULL, data BLOB NOT NULL);",
+        )?;
+        for (topic, ty, timestamp, data) in messages {
+            connection.execute(
+                "INSERT OR IGNORE INTO topics(name, type, serialization_format, offered_qos_profiles) \
+                    SELECT ?1, ?2, 'cdr', '' WHERE NOT EXISTS (SELECT 1 FROM topics WHERE name = ?1)",
+                rusqlite::params![topic, ty],
+            )?;
+            connection.execute(
+                "INSERT INTO messages(topic_id, timestamp, data) \
+                    SELECT id, ?2, ?3 FROM topics WHERE name = ?1",
+                rusqlite::params![topic, timestamp, data],
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Writes the `metadata.yaml` of a bag made of the given database files.
+    fn write_metadata(bag: &Path, db_file_names: &[&str]) -> anyhow::Result<()> {
+        let relative_file_paths = db_file_names
+            .iter()
+            .map(|name| format!("    - {name}\n"))
+            .collect::<String>();
+        std::fs::write(
+            bag.join(METADATA_FILE_NAME),
+            format!(
+                "\
+rosbag2_bagfile_information:
+  version: 5
+  storage_identifier: sqlite3
+  relative_file_paths:
+{relative_file_paths}  compression_format: \"\"
+  compression_mode: \"\"
+"
+            ),
+        )?;
+        Ok(())
+    }
+
+    fn received_chunks(rx: &mpsc::Receiver<LoadedData>) -> Vec<re_chunk::Chunk> {
+        rx.iter()
+            .filter_map(|data| match data {
+                LoadedData::Chunk(_, _, chunk) => Some(chunk),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn load_bag_directory() -> anyhow::Result<()> {
+        let bag = tempfile::tempdir()?;
+        write_metadata(bag.path(), &["bag_0.db3"])?;
+        write_db3(
+            &bag.path().join("bag_0.db3"),
+            &[
+                (
+                    "/chatter",
+                    "std_msgs/msg/String",
+                    2,
+                    string_message("world"),
+                ),
+                (
+                    "/chatter",
+                    "std_msgs/msg/String",
+                    1,
+                    string_message("hello"),
+                ),
+            ],
+        )?;
+
+        assert!(is_rosbag2_sqlite_directory(bag.path()));
+
+        let (tx, rx) = mpsc::channel();
+        Rosbag2Loader.load_from_path(
+            &DataLoaderSettings::recommended("rosbag2"),
+            bag.path().to_owned(),
+            tx,
+        )?;
+
+        let chunks = received_chunks(&rx);
+        assert_eq!(chunks.len(), 1);
+        assert_eq!(chunks[0].entity_path(), &EntityPath::from("/chatter"));
+        assert_eq!(chunks[0].num_rows(), 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn load_db3_contents() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let db_path = dir.path().join("bag_0.db3");
+        write_db3(
+            &db_path,
+            &[(
+                "/chatter",
+                "std_msgs/msg/String",
+                1,
+                string_message("hello"),
+            )],
+        )?;
+
+        let (tx, rx) = mpsc::channel();
+        Rosbag2Loader.load_from_file_contents(
+            &DataLoaderSettings::recommended("rosbag2"),
+            "bag_0.db3".into(),
+            std::fs::read(&db_path)?.into(),
+            tx,
+        )?;
+
+        let chunks = received_chunks(&rx);
+        assert_eq!(chunks.len(), 1);
+        assert_eq!(chunks[0].entity_path(), &EntityPath::from("/chatter"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn tf_tree_is_resolved_across_files() -> anyhow::Result<()> {
+        use crate::mcap::layers::ros2_tf_message as tf_message;
+
+        const TF: &str = "tf2_msgs/msg/TFMessage";
+
+        // The parent of `odom` is only known from the second file of the bag onwards.
+        let bag = tempfile::tempdir()?;
+        write_metadata(bag.path(), &["bag_0.db3", "bag_1.db3"])?;
+        write_db3(
+            &bag.path().join("bag_0.db3"),
+            &[("/tf", TF, 1, tf_message("odom", "base_link"))],
+        )?;
+        write_db3(
+            &bag.path().join("bag_1.db3"),
+            &[
+                ("/tf", TF, 2, tf_message("map", "odom")),
+                ("/tf", TF, 3, tf_message("odom", "base_link")),
+            ],
+        )?;
+
+        let (tx, rx) = mpsc::channel();
+        Rosbag2Loader.load_from_path(
+            &DataLoaderSettings::recommended("rosbag2"),
+            bag.path().to_owned(),
+            tx,
+        )?;
+
+        let chunks = received_chunks(&rx);
+        let base_link_rows = chunks
+            .iter()
+            .filter(|chunk| chunk.entity_path() == &EntityPath::from("/tf/map/odom/base_link"))
+            .map(|chunk| chunk.num_rows())
+            .sum::<usize>();
+        assert_eq!(base_link_rows, 2);
+        assert!(
+            chunks
+                .iter()
+                .all(|chunk| chunk.entity_path() != &EntityPath::from("/tf/odom/base_link"))
+        );
+
+        Ok(())
+    }
+}
@@ -1,4 +1,7 @@
-use re_log_encoding::decoder::Decoder;
+use re_log_encoding::decoder::{DecodeError, Decoder};
+
+#[cfg(not(target_arch = "wasm32"))]
+use re_log_encoding::decoder::seekable::SeekableDecoder;
 
 #[cfg(not(target_arch = "wasm32"))]
 use crossbeam::channel::Receiver;
@@ -76,6 +79,39 @@ impl crate::DataLoader for RrdLoader {
             }
 
             "rrd" => {
+                // Files that were properly finished with a footer only need to be partially read
+                // when we're only interested in some of their chunks.
+                if !settings.rrd_query.is_everything() {
+                    let file = std::fs::File::open(&filepath)
+                        .with_context(|| format!("Failed to open file {filepath:?}"))?;
+                    let decoder = SeekableDecoder::new(std::io::BufReader::new(file))?;
+
+                    if let Some(mut decoder) = decoder {
+                        // NOTE: This is IO bound, it must run on a dedicated thread, not the shared rayon thread pool.
+                        std::thread::Builder::new()
+                            .name(format!("decode_and_stream({filepath:?})"))
+                            .spawn({
+                                let filepath = filepath.clone();
+                                let query = settings.rrd_query.clone();
+                                move || {
+                                    decode_and_stream(
+                                        &filepath,
+                                        &tx,
+                                        decoder.read_query(&query),
+                                        // Never use import semantics for .rrd files
+                                        None,
+                                        None,
+                                    );
+                                }
+                            })
+                            .with_context(|| {
+                                format!("Failed to spawn IO thread for {filepath:?}")
+                            })?;
+
+                        return Ok(());
+                    }
+                }
+
                 // For .rrd files we retry reading despite reaching EOF to support live (writer) streaming.
                 // Decoder will give up when it sees end of file marker (i.e. end-of-stream message header)
                 let retryable_reader = RetryableFileReader::new(&filepath).with_context(|| {
@@ -88,11 +124,18 @@ impl crate::DataLoader for RrdLoader {
                     .name(format!("decode_and_stream({filepath:?})"))
                     .spawn({
                         let filepath = filepath.clone();
+                        let query = settings.rrd_query.clone();
                         move || {
                             decode_and_stream(
-                                &filepath, &tx, decoder,
+                                &filepath,
+                                &tx,
+                                decoder.filter(|res| match res {
+                                    Ok(msg) => query.matches_msg(msg),
+                                    Err(_) => true, // errors are reported downstream
+                                }),
                                 // Never use import semantics for .rrd files
-                                None, None,
+                                None,
+                                None,
                             );
                         }
                     })
@@ -154,10 +197,10 @@ impl crate::DataLoader for RrdLoader {
     }
 }
 
-fn decode_and_stream<R: std::io::Read>(
+fn decode_and_stream(
     filepath: &std::path::Path,
     tx: &std::sync::mpsc::Sender<crate::LoadedData>,
-    decoder: Decoder<R>,
+    decoder: impl Iterator<Item = Result<re_log_types::LogMsg, DecodeError>>,
     forced_application_id: Option<&ApplicationId>,
     forced_recording_id: Option<&String>,
 ) {
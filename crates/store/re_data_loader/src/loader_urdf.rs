@@ -6,13 +6,13 @@ use std::{
 use ahash::{HashMap, HashMapExt as _, HashSet, HashSetExt as _};
 use anyhow::{Context as _, bail};
 use itertools::Itertools as _;
-use urdf_rs::{Geometry, Joint, Link, Material, Robot, Vec3, Vec4};
+use urdf_rs::{Geometry, Joint, JointType, Link, Material, Robot, Vec3, Vec4};
 
-use re_chunk::{ChunkBuilder, ChunkId, EntityPath, RowId, TimePoint};
+use re_chunk::{Chunk, ChunkBuilder, ChunkId, EntityPath, RowId, TimePoint};
 use re_log_types::{EntityPathPart, StoreId};
 use re_types::{
     AsComponents, Component as _, ComponentDescriptor, SerializedComponentBatch,
-    archetypes::{Asset3D, Transform3D},
+    archetypes::{Asset3D, Scalars, SeriesLines, Transform3D},
     datatypes::Vec3D,
     external::glam,
 };
@@ -80,8 +80,7 @@ impl DataLoader for UrdfDataLoader {
         let robot = urdf_rs::read_file(&filepath)
             .with_context(|| format!("Path: {}", filepath.display()))?;
 
-        log_robot(robot, &filepath, &tx, &settings.recommended_store_id())
-            .with_context(|| "Failed to load URDF file!")?;
+        log_robot(robot, &filepath, &tx, settings).with_context(|| "Failed to load URDF file!")?;
 
         Ok(())
     }
@@ -102,8 +101,7 @@ impl DataLoader for UrdfDataLoader {
         let robot = urdf_rs::read_from_string(&String::from_utf8_lossy(&contents))
             .with_context(|| format!("Path: {}", filepath.display()))?;
 
-        log_robot(robot, &filepath, &tx, &settings.recommended_store_id())
-            .with_context(|| "Failed to load URDF file!")?;
+        log_robot(robot, &filepath, &tx, settings).with_context(|| "Failed to load URDF file!")?;
 
         Ok(())
     }
@@ -244,14 +242,157 @@ impl UrdfTree {
     pub fn get_joint_child(&self, joint: &Joint) -> &Link {
         &self.links[&joint.child.link] // Safe because we checked that the joint's child link exists in `new()`
     }
+
+    /// The motion of the child link of `joint` for the given joint position, relative to the joint frame.
+    ///
+    /// The position is in radians for revolute and continuous joints, and in meters for prismatic
+    /// joints. It is clamped to the limits of the joint, if any.
+    ///
+    /// Returns `None` for joints that can't move, or that can't be driven by a single value.
+    pub fn joint_transform(joint: &Joint, position: f64) -> Option<Transform3D> {
+        let urdf_rs::Vec3([x, y, z]) = joint.axis.xyz;
+        let axis = glam::DVec3::new(x, y, z).try_normalize()?;
+
+        let clamped = || {
+            let urdf_rs::JointLimit { lower, upper, .. } = joint.limit;
+            if lower < upper {
+                position.clamp(lower, upper)
+            } else {
+                position
+            }
+        };
+
+        let rotation = |angle: f64| {
+            Transform3D::update_fields().with_quaternion(
+                glam::Quat::from_axis_angle(axis.as_vec3(), angle as f32).to_array(),
+            )
+        };
+
+        match joint.joint_type {
+            JointType::Revolute => Some(rotation(clamped())),
+            JointType::Continuous => Some(rotation(position)),
+            JointType::Prismatic => Some(
+                Transform3D::update_fields()
+                    .with_translation((axis * clamped()).as_vec3().to_array()),
+            ),
+            JointType::Fixed => None,
+            JointType::Floating | JointType::Planar | JointType::Spherical => {
+                re_log::warn_once!(
+                    "Joint {:?} is {:?}, which can't be driven by a single value",
+                    joint.name,
+                    joint.joint_type
+                );
+                None
+            }
+        }
+    }
+
+    /// Converts recorded joint positions into [`Transform3D`] updates, one chunk per moving link.
+    ///
+    /// Two kinds of chunks are recognized:
+    /// * [`Scalars`] on an entity called `position`, with one [`SeriesLines`] name per joint,
+    ///   as produced by the MCAP loader for `sensor_msgs/JointState` messages.
+    /// * [`Scalars`] on an entity named after a joint, e.g. `/joints/shoulder_pan_joint`.
+    ///
+    /// The transforms are logged on the child link of each joint, at the same time points as the
+    /// positions, so that the static joint origin logged by the [`UrdfDataLoader`] stays untouched.
+    /// Joints that mimic a recorded joint are moved along with it. Any other chunk is ignored.
+    pub fn joint_transform_chunks(&self, chunk: &Chunk) -> anyhow::Result<Vec<Chunk>> {
+        let Some(entity_name) = chunk.entity_path().last() else {
+            return Ok(Vec::new());
+        };
+
+        let scalars = Scalars::descriptor_scalars();
+        let names = SeriesLines::descriptor_names();
+
+        let mut builders = HashMap::<EntityPath, ChunkBuilder>::new();
+        let mut add_position = |joint_name: &str, position: f64, timepoint: &TimePoint| {
+            for (joint, position) in self.driven_joints(joint_name, position) {
+                let Some(transform) = Self::joint_transform(joint, position) else {
+                    continue;
+                };
+                let entity_path = self.get_link_path_by_name(&joint.child.link);
+                let builder = builders
+                    .remove(&entity_path)
+                    .unwrap_or_else(|| ChunkBuilder::new(ChunkId::new(), entity_path.clone()));
+                builders.insert(
+                    entity_path,
+                    builder.with_archetype(RowId::new(), timepoint.clone(), &transform),
+                );
+            }
+        };
+
+        if Self::is_joint_state(chunk) {
+            for (timepoint, positions, names) in itertools::izip!(
+                chunk.iter_component_timepoints(&scalars),
+                chunk.iter_slices::<f64>(scalars.clone()),
+                chunk.iter_slices::<String>(names),
+            ) {
+                for (name, position) in itertools::izip!(names, positions) {
+                    add_position(name.as_str(), *position, &timepoint);
+                }
+            }
+        } else if self
+            .get_joint_by_name(entity_name.unescaped_str())
+            .is_some()
+        {
+            for (timepoint, positions) in itertools::izip!(
+                chunk.iter_component_timepoints(&scalars),
+                chunk.iter_slices::<f64>(scalars.clone()),
+            ) {
+                if let Some(position) = positions.first() {
+                    add_position(entity_name.unescaped_str(), *position, &timepoint);
+                }
+            }
+        }
+
+        builders
+            .into_values()
+            .map(|builder| builder.build().map_err(Into::into))
+            .collect()
+    }
+
+    /// Whether the chunk holds joint positions as produced for `sensor_msgs/JointState` messages,
+    /// see [`Self::joint_transform_chunks`].
+    pub(crate) fn is_joint_state(chunk: &Chunk) -> bool {
+        chunk
+            .entity_path()
+            .last()
+            .is_some_and(|name| name.unescaped_str() == "position")
+            && chunk
+                .components()
+                .contains_component(&SeriesLines::descriptor_names())
+    }
+
+    /// The joint called `joint_name` and all the joints that mimic it, each with its own position.
+    fn driven_joints<'a>(
+        &'a self,
+        joint_name: &'a str,
+        position: f64,
+    ) -> impl Iterator<Item = (&'a Joint, f64)> + 'a {
+        self.joints.iter().filter_map(move |joint| {
+            if joint.name == joint_name {
+                Some((joint, position))
+            } else {
+                let mimic = joint.mimic.as_ref()?;
+                (mimic.joint == joint_name).then(|| {
+                    (
+                        joint,
+                        position * mimic.multiplier.unwrap_or(1.0) + mimic.offset.unwrap_or(0.0),
+                    )
+                })
+            }
+        })
+    }
 }
 
 fn log_robot(
     robot: urdf_rs::Robot,
     filepath: &Path,
     tx: &Sender<LoadedData>,
-    store_id: &StoreId,
+    settings: &crate::DataLoaderSettings,
 ) -> anyhow::Result<()> {
+    let store_id = &settings.recommended_store_id();
     let urdf_dir = filepath.parent().map(|path| path.to_path_buf());
 
     let urdf_tree = UrdfTree::new(robot, urdf_dir).with_context(|| "Failed to build URDF tree!")?;
@@ -264,6 +405,15 @@ fn log_robot(
         &urdf_tree.root.name,
     )?;
 
+    // Joint positions that were loaded before the robot, e.g. from an MCAP file opened along with it.
+    for chunk in settings.robots.add_robot(store_id, urdf_tree) {
+        tx.send(LoadedData::Chunk(
+            UrdfDataLoader.name(),
+            store_id.clone(),
+            chunk,
+        ))?;
+    }
+
     Ok(())
 }
 
@@ -693,3 +843,170 @@ fn resolve_package_uri(uri: &str) -> anyhow::Result<PathBuf> {
         "Failed to resolve package URI: {uri}, tried `ROS_PACKAGE_PATH` and `AMENT_PREFIX_PATH`, but no matching package found"
     );
 }
+
+#[cfg(test)]
+mod tests {
+    use re_chunk::TimelineName;
+    use re_log_types::TimeInt;
+
+    use super::*;
+
+    const URDF: &str = r#"
+        <robot name="arm">
+            <link name="base"/>
+            <link name="upper_arm"/>
+            <link name="slider"/>
+            <link name="finger"/>
+            <joint name="shoulder" type="revolute">
+                <parent link="base"/>
+                <child link="upper_arm"/>
+                <origin xyz="0 0 1"/>
+                <axis xyz="0 0 1"/>
+                <limit lower="-1.0" upper="1.0" effort="1" velocity="1"/>
+            </joint>
+            <joint name="rail" type="prismatic">
+                <parent link="upper_arm"/>
+                <child link="slider"/>
+                <axis xyz="2 0 0"/>
+                <limit lower="0" upper="0.5" effort="1" velocity="1"/>
+            </joint>
+            <joint name="finger_joint" type="prismatic">
+                <parent link="slider"/>
+                <child link="finger"/>
+                <axis xyz="0 1 0"/>
+                <mimic joint="rail" multiplier="-1" offset="0.1"/>
+            </joint>
+        </robot>
+    "#;
+
+    fn tree() -> UrdfTree {
+        UrdfTree::new(urdf_rs::read_from_string(URDF).unwrap(), None).unwrap()
+    }
+
+    fn translation(xyz: [f32; 3]) -> Transform3D {
+        Transform3D::update_fields().with_translation(xyz)
+    }
+
+    fn joint_states(entity_path: &str, timeline: re_chunk::Timeline) -> Chunk {
+        ChunkBuilder::new(ChunkId::new(), entity_path.into())
+            .with_serialized_batches(
+                RowId::new(),
+                TimePoint::from([(timeline, TimeInt::new_temporal(1))]),
+                vec![
+                    SerializedComponentBatch {
+                        descriptor: Scalars::descriptor_scalars(),
+                        array: Arc::new(arrow::array::Float64Array::from(vec![0.5, 0.2, 3.0])),
+                    },
+                    SerializedComponentBatch {
+                        descriptor: SeriesLines::descriptor_names(),
+                        array: Arc::new(arrow::array::StringArray::from(vec![
+                            "shoulder", "rail", "unknown",
+                        ])),
+                    },
+                ],
+            )
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn joint_transforms() {
+        let tree = tree();
+
+        let rail = tree.get_joint_by_name("rail").unwrap();
+        assert_eq!(
+            UrdfTree::joint_transform(rail, 0.25),
+            Some(translation([0.25, 0.0, 0.0]))
+        );
+        // Clamped to the limits.
+        assert_eq!(
+            UrdfTree::joint_transform(rail, 2.0),
+            Some(translation([0.5, 0.0, 0.0]))
+        );
+
+        let shoulder = tree.get_joint_by_name("shoulder").unwrap();
+        assert_eq!(
+            UrdfTree::joint_transform(shoulder, 3.0),
+            UrdfTree::joint_transform(shoulder, 1.0),
+        );
+        assert_eq!(
+            UrdfTree::joint_transform(shoulder, 0.5),
+            Some(
+                Transform3D::update_fields()
+                    .with_quaternion(glam::Quat::from_rotation_z(0.5).to_array())
+            )
+        );
+    }
+
+    #[test]
+    fn joint_state_chunks() {
+        let tree = tree();
+
+        let timeline = re_chunk::Timeline::new_sequence("frame");
+        let positions = joint_states("/joint_states/position", timeline);
+
+        let mut chunks = tree.joint_transform_chunks(&positions).unwrap();
+        chunks.sort_by_key(|chunk| chunk.entity_path().to_string());
+
+        let entity_paths = chunks
+            .iter()
+            .map(|chunk| chunk.entity_path().to_string())
+            .collect_vec();
+        assert_eq!(
+            entity_paths,
+            [
+                "/arm/base/shoulder/upper_arm",
+                "/arm/base/shoulder/upper_arm/rail/slider",
+                "/arm/base/shoulder/upper_arm/rail/slider/finger_joint/finger",
+            ]
+        );
+        for chunk in &chunks {
+            assert_eq!(chunk.num_rows(), 1);
+            assert!(chunk.timelines().contains_key(&TimelineName::new("frame")));
+        }
+
+        // A single joint logged as a plain scalar.
+        let shoulder = ChunkBuilder::new(ChunkId::new(), "/joints/shoulder".into())
+            .with_archetype(
+                RowId::new(),
+                TimePoint::from([(timeline, TimeInt::new_temporal(1))]),
+                &Scalars::single(0.5),
+            )
+            .build()
+            .unwrap();
+        let chunks = tree.joint_transform_chunks(&shoulder).unwrap();
+        assert_eq!(chunks.len(), 1);
+        assert_eq!(
+            chunks[0].entity_path().to_string(),
+            "/arm/base/shoulder/upper_arm"
+        );
+
+        // Velocities and unrelated scalars are ignored.
+        let velocities = joint_states("/joint_states/velocity", timeline);
+        assert!(tree.joint_transform_chunks(&velocities).unwrap().is_empty());
+    }
+
+    #[test]
+    fn joint_states_loaded_separately() {
+        let store_id = StoreId::random(re_log_types::StoreKind::Recording, "test");
+        let timeline = re_chunk::Timeline::new_sequence("frame");
+        let loaded = |chunk| LoadedData::Chunk("test".to_owned(), store_id.clone(), chunk);
+
+        let robots = crate::robots::Robots::default();
+
+        // Joint states loaded before the robot are kept until it gets registered.
+        let positions = loaded(joint_states("/joint_states/position", timeline));
+        assert!(robots.joint_transforms(&positions).is_empty());
+        assert_eq!(robots.add_robot(&store_id, tree()).len(), 3);
+
+        let positions = loaded(joint_states("/joint_states/position", timeline));
+        assert_eq!(robots.joint_transforms(&positions).len(), 3);
+
+        // Other sessions don't see the robot.
+        let other_robots = crate::robots::Robots::default();
+        assert!(other_robots.joint_transforms(&positions).is_empty());
+
+        robots.forget_store(&store_id);
+        assert!(robots.joint_transforms(&positions).is_empty());
+    }
+}
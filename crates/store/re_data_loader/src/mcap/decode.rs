@@ -1,12 +1,15 @@
 //! Utilities for decoding MCAP messages into Rerun chunks.
 
+use std::collections::BTreeMap;
+
 use anyhow::Context as _;
 
 use re_chunk::{
-    Chunk, EntityPath, TimeColumn, TimeColumnBuilder, TimePoint, Timeline, TimelineName,
+    Chunk, EntityPath, RowId, TimeColumn, TimeColumnBuilder, TimePoint, Timeline, TimelineName,
     external::nohash_hasher::{IntMap, IsEnabled},
 };
 use re_log_types::TimeCell;
+use re_types::SerializedComponentBatch;
 use thiserror::Error;
 
 pub type SchemaName = String;
@@ -28,6 +31,9 @@ pub enum PluginError {
     #[error(transparent)]
     Mcap(#[from] ::mcap::McapError),
 
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+
     #[error(transparent)]
     Arrow(#[from] arrow::error::ArrowError),
 
@@ -102,7 +108,6 @@ impl McapChunkDecoder {
 
         let channel = msg.channel.as_ref();
         let channel_id = ChannelId(channel.id);
-        let entity_path = EntityPath::from(channel.topic.as_str());
         let timepoint = TimePoint::from([
             (
                 "log_time",
@@ -114,14 +119,11 @@ impl McapChunkDecoder {
             ),
         ]);
 
-        let schema = channel.schema.as_ref();
-
-        let Some(schema) = schema else {
-            // TODO(harold): in the future, we might want to try sensible decodings with heuristics like the name.
-            // Schemaless messages are not supported yet.
-            re_log::warn_once!("Found schemaless message for {entity_path}");
-            return Err(PluginError::NoSchema(channel.topic.clone()));
-        };
+        // Schemaless messages are only decoded by layers that explicitly support them, e.g. JSON.
+        let schema_name = channel
+            .schema
+            .as_ref()
+            .map_or("<schemaless>", |schema| schema.name.as_str());
 
         if let Some((ctx, parser)) = self.parsers.get_mut(&channel_id) {
             ctx.add_timepoint(timepoint.clone());
@@ -131,7 +133,7 @@ impl McapChunkDecoder {
                 .with_context(|| {
                     format!(
                         "Failed to append message for topic: {} of type: {}",
-                        channel.topic, schema.name
+                        channel.topic, schema_name
                     )
                 })
                 .map_err(PluginError::Other)?;
@@ -220,3 +222,77 @@ impl ParserContext {
         &self.entity_path
     }
 }
+
+/// Accumulates rows of arbitrary components, for any number of entities.
+///
+/// This is meant for parsers whose messages address several entities at once, or carry several
+/// timestamps, and therefore cannot rely on the per-message timelines of [`ParserContext`].
+#[derive(Default)]
+pub struct RowsPerEntity {
+    rows: BTreeMap<EntityPath, Vec<(TimePoint, Vec<SerializedComponentBatch>)>>,
+}
+
+impl RowsPerEntity {
+    /// The timepoint of data recorded at `timestamp_nanos` that was received in `msg`.
+    ///
+    /// This mirrors what [`McapChunkDecoder`] and [`ParserContext`] produce for other parsers.
+    pub fn timepoint(msg: &mcap::Message<'_>, timestamp_nanos: i64) -> TimePoint {
+        TimePoint::from([
+            (
+                "log_time",
+                TimeCell::from_timestamp_nanos_since_epoch(msg.log_time as i64),
+            ),
+            (
+                "publish_time",
+                TimeCell::from_timestamp_nanos_since_epoch(msg.publish_time as i64),
+            ),
+            (
+                "timestamp",
+                TimeCell::from_timestamp_nanos_since_epoch(timestamp_nanos),
+            ),
+        ])
+    }
+
+    /// Adds a row to the given entity.
+    pub fn push(
+        &mut self,
+        entity_path: EntityPath,
+        timepoint: TimePoint,
+        batches: impl IntoIterator<Item = SerializedComponentBatch>,
+    ) {
+        self.rows
+            .entry(entity_path)
+            .or_default()
+            .push((timepoint, batches.into_iter().collect()));
+    }
+
+    /// Replaces all rows of the given entity with a single static row.
+    pub fn set_static(
+        &mut self,
+        entity_path: EntityPath,
+        batches: impl IntoIterator<Item = SerializedComponentBatch>,
+    ) {
+        self.rows.insert(
+            entity_path,
+            vec![(TimePoint::default(), batches.into_iter().collect())],
+        );
+    }
+
+    /// Builds one chunk per entity.
+    pub fn into_chunks(self) -> anyhow::Result<Vec<Chunk>> {
+        self.rows
+            .into_iter()
+            .map(|(entity_path, rows)| {
+                rows.into_iter()
+                    .fold(
+                        Chunk::builder(entity_path),
+                        |builder, (timepoint, batches)| {
+                            builder.with_serialized_batches(RowId::new(), timepoint, batches)
+                        },
+                    )
+                    .build()
+                    .map_err(|err| PluginError::Other(anyhow::anyhow!(err)).into())
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,361 @@
+//! Protobuf definitions of the subset of the [Foxglove schemas](https://github.com/foxglove/foxglove-sdk/tree/main/schemas)
+//! that we export to.
+//!
+//! The descriptors are built by hand (rather than from `.proto` files) so that we don't need
+//! `protoc` at build time. Field numbers must match the upstream definitions exactly.
+
+use prost_reflect::{
+    DescriptorPool, DynamicMessage, MessageDescriptor, ReflectMessage as _, Value,
+    prost::Message as _,
+    prost_types::{
+        DescriptorProto, EnumDescriptorProto, EnumValueDescriptorProto, FieldDescriptorProto,
+        FileDescriptorProto, FileDescriptorSet,
+        field_descriptor_proto::{Label, Type},
+    },
+};
+
+/// Foxglove's `NumericType` enum, used to describe the fields of a `foxglove.PointCloud`.
+#[derive(Debug, Clone, Copy)]
+#[repr(i32)]
+pub enum NumericType {
+    UInt8 = 1,
+    Float32 = 7,
+}
+
+/// Foxglove's `LogLevel` enum, used by `foxglove.Log`.
+#[derive(Debug, Clone, Copy)]
+#[repr(i32)]
+pub enum LogLevel {
+    Unknown = 0,
+    Debug = 1,
+    Info = 2,
+    Warning = 3,
+    Error = 4,
+    Fatal = 5,
+}
+
+impl LogLevel {
+    /// Maps the level of a Rerun `TextLog` onto a Foxglove log level.
+    pub fn from_rerun(level: &str) -> Self {
+        match level {
+            "TRACE" | "DEBUG" => Self::Debug,
+            "INFO" => Self::Info,
+            "WARN" => Self::Warning,
+            "ERROR" => Self::Error,
+            "CRITICAL" => Self::Fatal,
+            _ => Self::Unknown,
+        }
+    }
+}
+
+/// All the Foxglove message types that we know how to write.
+pub struct FoxgloveSchemas {
+    pool: DescriptorPool,
+}
+
+impl FoxgloveSchemas {
+    pub fn new() -> Self {
+        let file_descriptor_set = FileDescriptorSet {
+            file: vec![timestamp_file(), foxglove_file()],
+        };
+
+        #[allow(clippy::unwrap_used)] // The descriptors are static, see tests.
+        let pool = DescriptorPool::from_file_descriptor_set(file_descriptor_set).unwrap();
+
+        Self { pool }
+    }
+
+    /// The serialized `FileDescriptorSet`, to be used as the data of an MCAP schema.
+    pub fn encoded_descriptors(&self) -> Vec<u8> {
+        self.pool.encode_to_vec()
+    }
+
+    /// Starts a new message of the given type, e.g. `foxglove.CompressedImage`.
+    pub fn message(&self, name: &str) -> MessageBuilder {
+        #[allow(clippy::unwrap_used)] // Only ever called with the names defined below.
+        let descriptor = self.pool.get_message_by_name(name).unwrap();
+        MessageBuilder::new(descriptor)
+    }
+}
+
+/// Thin wrapper around [`DynamicMessage`] to set fields in a builder-like fashion.
+pub struct MessageBuilder(DynamicMessage);
+
+impl MessageBuilder {
+    fn new(descriptor: MessageDescriptor) -> Self {
+        Self(DynamicMessage::new(descriptor))
+    }
+
+    pub fn with(mut self, name: &str, value: Value) -> Self {
+        self.0.set_field_by_name(name, value);
+        self
+    }
+
+    pub fn with_timestamp(self, timestamp_nanos: u64) -> Self {
+        self.with_message("timestamp", |timestamp| {
+            timestamp
+                .with(
+                    "seconds",
+                    Value::I64((timestamp_nanos / 1_000_000_000) as i64),
+                )
+                .with(
+                    "nanos",
+                    Value::I32((timestamp_nanos % 1_000_000_000) as i32),
+                )
+        })
+    }
+
+    /// The message type of the given (message-typed) field.
+    fn nested(&self, field: &str) -> MessageDescriptor {
+        let field = self
+            .0
+            .descriptor()
+            .get_field_by_name(field)
+            .unwrap_or_else(|| panic!("unknown field {field:?}"));
+        match field.kind() {
+            prost_reflect::Kind::Message(descriptor) => descriptor,
+            kind => panic!("field {:?} is a {kind:?}, not a message", field.name()),
+        }
+    }
+
+    pub fn with_message(self, field: &str, f: impl FnOnce(Self) -> Self) -> Self {
+        let nested = f(Self::new(self.nested(field)));
+        self.with(field, nested.into_value())
+    }
+
+    /// The fully qualified name of the message type, e.g. `foxglove.CompressedImage`.
+    pub fn schema_name(&self) -> String {
+        self.0.descriptor().full_name().to_owned()
+    }
+
+    pub fn into_value(self) -> Value {
+        Value::Message(self.0)
+    }
+
+    pub fn encode(&self) -> Vec<u8> {
+        self.0.encode_to_vec()
+    }
+}
+
+// ---
+
+fn field(name: &str, number: i32, ty: Type) -> FieldDescriptorProto {
+    FieldDescriptorProto {
+        name: Some(name.to_owned()),
+        number: Some(number),
+        label: Some(Label::Optional as i32),
+        r#type: Some(ty as i32),
+        json_name: Some(name.to_owned()),
+        ..Default::default()
+    }
+}
+
+/// A field that refers to another message or enum, e.g. `.foxglove.Vector3`.
+fn typed_field(name: &str, number: i32, ty: Type, type_name: &str) -> FieldDescriptorProto {
+    FieldDescriptorProto {
+        type_name: Some(format!(".{type_name}")),
+        ..field(name, number, ty)
+    }
+}
+
+fn repeated(field: FieldDescriptorProto) -> FieldDescriptorProto {
+    FieldDescriptorProto {
+        label: Some(Label::Repeated as i32),
+        ..field
+    }
+}
+
+fn message(name: &str, fields: Vec<FieldDescriptorProto>) -> DescriptorProto {
+    DescriptorProto {
+        name: Some(name.to_owned()),
+        field: fields,
+        ..Default::default()
+    }
+}
+
+fn enumeration(name: &str, values: &[(&str, i32)]) -> EnumDescriptorProto {
+    EnumDescriptorProto {
+        name: Some(name.to_owned()),
+        value: values
+            .iter()
+            .map(|(name, number)| EnumValueDescriptorProto {
+                name: Some((*name).to_owned()),
+                number: Some(*number),
+                options: None,
+            })
+            .collect(),
+        ..Default::default()
+    }
+}
+
+fn timestamp_file() -> FileDescriptorProto {
+    FileDescriptorProto {
+        name: Some("google/protobuf/timestamp.proto".to_owned()),
+        package: Some("google.protobuf".to_owned()),
+        message_type: vec![message(
+            "Timestamp",
+            vec![
+                field("seconds", 1, Type::Int64),
+                field("nanos", 2, Type::Int32),
+            ],
+        )],
+        syntax: Some("proto3".to_owned()),
+        ..Default::default()
+    }
+}
+
+fn foxglove_file() -> FileDescriptorProto {
+    const TIMESTAMP: &str = "google.protobuf.Timestamp";
+
+    let vector3 = message(
+        "Vector3",
+        vec![
+            field("x", 1, Type::Double),
+            field("y", 2, Type::Double),
+            field("z", 3, Type::Double),
+        ],
+    );
+
+    let quaternion = message(
+        "Quaternion",
+        vec![
+            field("x", 1, Type::Double),
+            field("y", 2, Type::Double),
+            field("z", 3, Type::Double),
+            field("w", 4, Type::Double),
+        ],
+    );
+
+    let pose = message(
+        "Pose",
+        vec![
+            typed_field("position", 1, Type::Message, "foxglove.Vector3"),
+            typed_field("orientation", 2, Type::Message, "foxglove.Quaternion"),
+        ],
+    );
+
+    let packed_element_field = DescriptorProto {
+        enum_type: vec![enumeration(
+            "NumericType",
+            &[
+                ("UNKNOWN", 0),
+                ("UINT8", 1),
+                ("INT8", 2),
+                ("UINT16", 3),
+                ("INT16", 4),
+                ("UINT32", 5),
+                ("INT32", 6),
+                ("FLOAT32", 7),
+                ("FLOAT64", 8),
+            ],
+        )],
+        ..message(
+            "PackedElementField",
+            vec![
+                field("name", 1, Type::String),
+                field("offset", 2, Type::Fixed32),
+                typed_field(
+                    "type",
+                    3,
+                    Type::Enum,
+                    "foxglove.PackedElementField.NumericType",
+                ),
+            ],
+        )
+    };
+
+    let compressed_image = message(
+        "CompressedImage",
+        vec![
+            typed_field("timestamp", 1, Type::Message, TIMESTAMP),
+            field("frame_id", 4, Type::String),
+            field("data", 2, Type::Bytes),
+            field("format", 3, Type::String),
+        ],
+    );
+
+    let raw_image = message(
+        "RawImage",
+        vec![
+            typed_field("timestamp", 1, Type::Message, TIMESTAMP),
+            field("frame_id", 7, Type::String),
+            field("width", 2, Type::Fixed32),
+            field("height", 3, Type::Fixed32),
+            field("encoding", 4, Type::String),
+            field("step", 5, Type::Fixed32),
+            field("data", 6, Type::Bytes),
+        ],
+    );
+
+    let point_cloud = message(
+        "PointCloud",
+        vec![
+            typed_field("timestamp", 1, Type::Message, TIMESTAMP),
+            field("frame_id", 2, Type::String),
+            typed_field("pose", 3, Type::Message, "foxglove.Pose"),
+            field("point_stride", 4, Type::Fixed32),
+            repeated(typed_field(
+                "fields",
+                5,
+                Type::Message,
+                "foxglove.PackedElementField",
+            )),
+            field("data", 6, Type::Bytes),
+        ],
+    );
+
+    let frame_transform = message(
+        "FrameTransform",
+        vec![
+            typed_field("timestamp", 1, Type::Message, TIMESTAMP),
+            field("parent_frame_id", 2, Type::String),
+            field("child_frame_id", 3, Type::String),
+            typed_field("translation", 4, Type::Message, "foxglove.Vector3"),
+            typed_field("rotation", 5, Type::Message, "foxglove.Quaternion"),
+        ],
+    );
+
+    let log = DescriptorProto {
+        enum_type: vec![enumeration(
+            "Level",
+            &[
+                ("UNKNOWN", 0),
+                ("DEBUG", 1),
+                ("INFO", 2),
+                ("WARNING", 3),
+                ("ERROR", 4),
+                ("FATAL", 5),
+            ],
+        )],
+        ..message(
+            "Log",
+            vec![
+                typed_field("timestamp", 1, Type::Message, TIMESTAMP),
+                typed_field("level", 2, Type::Enum, "foxglove.Log.Level"),
+                field("message", 3, Type::String),
+                field("name", 4, Type::String),
+                field("file", 5, Type::String),
+                field("line", 6, Type::Fixed32),
+            ],
+        )
+    };
+
+    FileDescriptorProto {
+        name: Some("foxglove/schemas.proto".to_owned()),
+        package: Some("foxglove".to_owned()),
+        dependency: vec!["google/protobuf/timestamp.proto".to_owned()],
+        message_type: vec![
+            vector3,
+            quaternion,
+            pose,
+            packed_element_field,
+            compressed_image,
+            raw_image,
+            point_cloud,
+            frame_transform,
+            log,
+        ],
+        syntax: Some("proto3".to_owned()),
+        ..Default::default()
+    }
+}
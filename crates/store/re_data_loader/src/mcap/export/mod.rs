@@ -0,0 +1,884 @@
+//! Conversion of Rerun recordings into MCAP files, see [`McapExporter`].
+
+mod foxglove;
+
+use std::{
+    collections::BTreeMap,
+    io::{Seek, Write},
+    sync::Arc,
+};
+
+use arrow::{
+    array::RecordBatch,
+    datatypes::{Field, Schema},
+    error::ArrowError,
+    ipc::writer::StreamWriter,
+};
+use prost_reflect::Value;
+use re_chunk::{Chunk, ChunkError, EntityPath, TimelineName};
+use re_types::{
+    Component, ComponentDescriptor, archetypes,
+    components::{self, MediaType},
+    datatypes::{ChannelDatatype, ColorModel, PixelFormat},
+    external::glam,
+};
+
+use self::foxglove::{FoxgloveSchemas, LogLevel, MessageBuilder, NumericType};
+
+/// The message encoding of the channels that hold raw Rerun components.
+///
+/// Every message is an Arrow IPC record batch with a single column, with one row per instance
+/// of the component. Appending it to the data of the channel's schema results in a complete
+/// Arrow IPC stream.
+pub const ARROW_IPC_MESSAGE_ENCODING: &str = "arrow.ipc";
+
+/// The schema encoding of the channels that hold raw Rerun components.
+///
+/// The schema data is the beginning of an Arrow IPC stream, made up of its schema message.
+pub const ARROW_IPC_SCHEMA_ENCODING: &str = "arrow.ipc.schema";
+
+#[derive(thiserror::Error, Debug)]
+pub enum McapExportError {
+    #[error(transparent)]
+    Mcap(#[from] ::mcap::McapError),
+
+    #[error(transparent)]
+    Arrow(#[from] ArrowError),
+
+    #[error(transparent)]
+    Chunk(#[from] ChunkError),
+}
+
+/// Writes Rerun [`Chunk`]s to an MCAP file.
+///
+/// Every component column of every entity becomes its own channel, named
+/// `<entity_path>:<component>` and holding Arrow IPC-encoded messages (see
+/// [`ARROW_IPC_MESSAGE_ENCODING`] and [`ARROW_IPC_SCHEMA_ENCODING`]).
+///
+/// The exceptions are the archetypes that have an equivalent Foxglove schema, which are written
+/// as protobuf messages on a channel named after their entity instead, so that they can be
+/// visualized by the wider MCAP ecosystem:
+/// * [`archetypes::EncodedImage`] as `foxglove.CompressedImage`
+/// * [`archetypes::Image`] and [`archetypes::DepthImage`] as `foxglove.RawImage`
+/// * [`archetypes::Points3D`] as `foxglove.PointCloud`
+/// * [`archetypes::Transform3D`] as `foxglove.FrameTransform`, unless it has a scale or a matrix
+/// * [`archetypes::TextLog`] as `foxglove.Log`
+///
+/// Entity paths are used as frame ids, and transforms are expressed relative to the parent entity.
+///
+/// MCAP only has a single notion of time: the values of the selected timeline are used as
+/// `log_time` (in nanoseconds), static data is written at time zero, and chunks that aren't
+/// on the selected timeline are skipped.
+pub struct McapExporter<W: Write + Seek> {
+    writer: ::mcap::Writer<W>,
+    timeline: TimelineName,
+
+    foxglove: FoxgloveSchemas,
+
+    /// Keyed by topic and schema name.
+    channels: BTreeMap<ChannelKey, Channel>,
+}
+
+/// Topic and schema name of a channel.
+type ChannelKey = (String, Option<String>);
+
+struct Channel {
+    id: u16,
+    sequence: u32,
+
+    /// Encodes the messages of channels that hold raw Rerun components.
+    arrow: Option<ArrowChannel>,
+}
+
+struct ArrowChannel {
+    schema: Arc<Schema>,
+
+    /// Only the record batches written to this go into messages, the schema that precedes them
+    /// is the data of the MCAP schema.
+    writer: StreamWriter<Vec<u8>>,
+}
+
+impl<W: Write + Seek> McapExporter<W> {
+    /// Starts a new MCAP file, using `timeline` as the source of the message timestamps.
+    pub fn new(writer: W, timeline: TimelineName) -> Result<Self, McapExportError> {
+        let writer = ::mcap::WriteOptions::new()
+            .library(format!("rerun {}", re_build_info::CrateVersion::LOCAL))
+            .create(writer)?;
+
+        Ok(Self {
+            writer,
+            timeline,
+            foxglove: FoxgloveSchemas::new(),
+            channels: Default::default(),
+        })
+    }
+
+    /// Writes all the rows of a chunk.
+    pub fn add_chunk(&mut self, chunk: &Chunk) -> Result<(), McapExportError> {
+        re_tracing::profile_function!();
+
+        let log_times: Vec<u64> = if chunk.is_static() {
+            vec![0; chunk.num_rows()]
+        } else if let Some(time_column) = chunk.timelines().get(&self.timeline) {
+            time_column
+                .times_raw()
+                .iter()
+                .map(|&time| time.max(0) as u64)
+                .collect()
+        } else {
+            re_log::warn_once!(
+                "Skipping data on {:?} that is not on the {:?} timeline",
+                chunk.entity_path(),
+                self.timeline
+            );
+            return Ok(());
+        };
+
+        // `foxglove.FrameTransform` can neither represent a scale nor an arbitrary matrix, so such
+        // transforms are kept as raw components.
+        let has_raw_transform = has_scale_or_matrix(chunk)?;
+
+        let mut remaining: Vec<ComponentDescriptor> = chunk.component_descriptors().collect();
+        let mut take = |descr: &ComponentDescriptor| {
+            let len = remaining.len();
+            remaining.retain(|other| other != descr);
+            remaining.len() != len
+        };
+
+        if take(&archetypes::EncodedImage::descriptor_blob()) {
+            take(&archetypes::EncodedImage::descriptor_media_type());
+            self.write_encoded_images(chunk, &log_times)?;
+        }
+
+        if take(&archetypes::Image::descriptor_buffer()) {
+            take(&archetypes::Image::descriptor_format());
+            self.write_raw_images(
+                chunk,
+                &log_times,
+                &archetypes::Image::descriptor_buffer(),
+                &archetypes::Image::descriptor_format(),
+            )?;
+        }
+
+        if take(&archetypes::DepthImage::descriptor_buffer()) {
+            take(&archetypes::DepthImage::descriptor_format());
+            self.write_raw_images(
+                chunk,
+                &log_times,
+                &archetypes::DepthImage::descriptor_buffer(),
+                &archetypes::DepthImage::descriptor_format(),
+            )?;
+        }
+
+        if take(&archetypes::Points3D::descriptor_positions()) {
+            take(&archetypes::Points3D::descriptor_colors());
+            self.write_point_clouds(chunk, &log_times)?;
+        }
+
+        if has_raw_transform {
+            re_log::warn_once!(
+                "Keeping transforms with a scale or a matrix on {:?} as raw components",
+                chunk.entity_path()
+            );
+        } else {
+            let has_translation = take(&archetypes::Transform3D::descriptor_translation());
+            let has_axis_angle = take(&archetypes::Transform3D::descriptor_rotation_axis_angle());
+            let has_quaternion = take(&archetypes::Transform3D::descriptor_quaternion());
+            if has_translation || has_axis_angle || has_quaternion {
+                // Only identities, which are implied by the Foxglove message.
+                take(&archetypes::Transform3D::descriptor_scale());
+                take(&archetypes::Transform3D::descriptor_mat3x3());
+                self.write_frame_transforms(chunk, &log_times)?;
+            }
+        }
+
+        if take(&archetypes::TextLog::descriptor_text()) {
+            take(&archetypes::TextLog::descriptor_level());
+            self.write_logs(chunk, &log_times)?;
+        }
+
+        for descr in &remaining {
+            for (row, &log_time) in log_times.iter().enumerate() {
+                self.write_arrow_row(chunk, descr, row, log_time)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Writes the summary section and flushes the underlying writer.
+    pub fn finish(mut self) -> Result<W, McapExportError> {
+        self.writer.finish()?;
+        Ok(self.writer.into_inner())
+    }
+
+    fn write_encoded_images(
+        &mut self,
+        chunk: &Chunk,
+        log_times: &[u64],
+    ) -> Result<(), McapExportError> {
+        let blob_descr = archetypes::EncodedImage::descriptor_blob();
+        let media_type_descr = archetypes::EncodedImage::descriptor_media_type();
+
+        for (row, &log_time) in log_times.iter().enumerate() {
+            let Some(blob) = mono::<components::Blob>(chunk, &blob_descr, row)? else {
+                continue;
+            };
+
+            let bytes: &[u8] = &blob.0.0;
+            let media_type = mono::<MediaType>(chunk, &media_type_descr, row)?
+                .or_else(|| MediaType::guess_from_data(bytes));
+            let format = match media_type.as_ref().map(|media_type| media_type.as_str()) {
+                Some(MediaType::JPEG) => "jpeg",
+                Some(MediaType::PNG) => "png",
+                Some("image/webp") => "webp",
+                Some("image/avif") => "avif",
+                _ => {
+                    re_log::warn_once!(
+                        "Unsupported media type {media_type:?} for image on {:?}",
+                        chunk.entity_path()
+                    );
+                    self.write_arrow_row(chunk, &blob_descr, row, log_time)?;
+                    self.write_arrow_row(chunk, &media_type_descr, row, log_time)?;
+                    continue;
+                }
+            };
+
+            let message = self
+                .foxglove
+                .message("foxglove.CompressedImage")
+                .with_timestamp(log_time)
+                .with("frame_id", frame_id(chunk.entity_path()))
+                .with("data", Value::Bytes(bytes.to_vec().into()))
+                .with("format", Value::String(format.to_owned()));
+            self.write_foxglove(chunk.entity_path(), log_time, &message)?;
+        }
+
+        Ok(())
+    }
+
+    fn write_raw_images(
+        &mut self,
+        chunk: &Chunk,
+        log_times: &[u64],
+        buffer_descr: &ComponentDescriptor,
+        format_descr: &ComponentDescriptor,
+    ) -> Result<(), McapExportError> {
+        for (row, &log_time) in log_times.iter().enumerate() {
+            let Some(buffer) = mono::<components::ImageBuffer>(chunk, buffer_descr, row)? else {
+                continue;
+            };
+
+            let format = mono::<components::ImageFormat>(chunk, format_descr, row)?;
+            let Some((format, encoding)) = format.and_then(|format| {
+                let encoding = image_encoding(&format.0)?;
+                Some((format.0, encoding))
+            }) else {
+                re_log::warn_once!(
+                    "Unsupported or missing image format for image on {:?}",
+                    chunk.entity_path()
+                );
+                self.write_arrow_row(chunk, buffer_descr, row, log_time)?;
+                self.write_arrow_row(chunk, format_descr, row, log_time)?;
+                continue;
+            };
+
+            let step = format.num_bytes() / (format.height.max(1) as usize);
+
+            let message = self
+                .foxglove
+                .message("foxglove.RawImage")
+                .with_timestamp(log_time)
+                .with("frame_id", frame_id(chunk.entity_path()))
+                .with("width", Value::U32(format.width))
+                .with("height", Value::U32(format.height))
+                .with("encoding", Value::String(encoding.to_owned()))
+                .with("step", Value::U32(step as u32))
+                .with("data", Value::Bytes(buffer.0.0.to_vec().into()));
+            self.write_foxglove(chunk.entity_path(), log_time, &message)?;
+        }
+
+        Ok(())
+    }
+
+    fn write_point_clouds(
+        &mut self,
+        chunk: &Chunk,
+        log_times: &[u64],
+    ) -> Result<(), McapExportError> {
+        let positions_descr = archetypes::Points3D::descriptor_positions();
+        let colors_descr = archetypes::Points3D::descriptor_colors();
+
+        for (row, &log_time) in log_times.iter().enumerate() {
+            let Some(positions) = batch::<components::Position3D>(chunk, &positions_descr, row)?
+            else {
+                continue;
+            };
+            let colors = batch::<components::Color>(chunk, &colors_descr, row)?.unwrap_or_default();
+
+            let color_at = |i: usize| match colors.len() {
+                0 => None,
+                1 => Some(colors[0].0.to_array()),
+                _ => colors.get(i).map(|color| color.0.to_array()),
+            };
+
+            let mut fields = vec![
+                ("x", 0, NumericType::Float32),
+                ("y", 4, NumericType::Float32),
+                ("z", 8, NumericType::Float32),
+            ];
+            let mut point_stride = 12;
+            if !colors.is_empty() {
+                for (i, name) in ["red", "green", "blue", "alpha"].into_iter().enumerate() {
+                    fields.push((name, 12 + i as u32, NumericType::UInt8));
+                }
+                point_stride += 4;
+            }
+
+            let mut data = Vec::with_capacity(positions.len() * point_stride);
+            for (i, position) in positions.iter().enumerate() {
+                for value in position.0.0 {
+                    data.extend_from_slice(&value.to_le_bytes());
+                }
+                if !colors.is_empty() {
+                    data.extend_from_slice(&color_at(i).unwrap_or([255; 4]));
+                }
+            }
+
+            let fields = fields
+                .into_iter()
+                .map(|(name, offset, numeric_type)| {
+                    self.foxglove
+                        .message("foxglove.PackedElementField")
+                        .with("name", Value::String(name.to_owned()))
+                        .with("offset", Value::U32(offset))
+                        .with("type", Value::EnumNumber(numeric_type as i32))
+                        .into_value()
+                })
+                .collect();
+
+            let message = self
+                .foxglove
+                .message("foxglove.PointCloud")
+                .with_timestamp(log_time)
+                .with("frame_id", frame_id(chunk.entity_path()))
+                .with_message("pose", |pose| {
+                    pose.with_message("position", |position| position)
+                        .with_message("orientation", |orientation| {
+                            orientation.with("w", Value::F64(1.0))
+                        })
+                })
+                .with("point_stride", Value::U32(point_stride as u32))
+                .with("fields", Value::List(fields))
+                .with("data", Value::Bytes(data.into()));
+            self.write_foxglove(chunk.entity_path(), log_time, &message)?;
+        }
+
+        Ok(())
+    }
+
+    fn write_frame_transforms(
+        &mut self,
+        chunk: &Chunk,
+        log_times: &[u64],
+    ) -> Result<(), McapExportError> {
+        let translation_descr = archetypes::Transform3D::descriptor_translation();
+        let axis_angle_descr = archetypes::Transform3D::descriptor_rotation_axis_angle();
+        let quaternion_descr = archetypes::Transform3D::descriptor_quaternion();
+
+        let entity_path = chunk.entity_path();
+        let parent_frame_id = entity_path
+            .parent()
+            .map_or_else(|| frame_id(&EntityPath::root()), |parent| frame_id(&parent));
+
+        for (row, &log_time) in log_times.iter().enumerate() {
+            let translation = mono::<components::Translation3D>(chunk, &translation_descr, row)?;
+            let axis_angle = mono::<components::RotationAxisAngle>(chunk, &axis_angle_descr, row)?;
+            let quaternion = mono::<components::RotationQuat>(chunk, &quaternion_descr, row)?;
+            if translation.is_none() && axis_angle.is_none() && quaternion.is_none() {
+                continue;
+            }
+
+            let [x, y, z] = translation.map_or([0.0; 3], |translation| translation.0.0);
+
+            // Like in Rerun, the axis-angle rotation is applied after the quaternion.
+            let axis_angle = axis_angle
+                .and_then(|axis_angle| glam::Quat::try_from(axis_angle.0).ok())
+                .unwrap_or(glam::Quat::IDENTITY);
+            let quaternion = quaternion
+                .and_then(|quaternion| glam::Quat::try_from(quaternion.0).ok())
+                .unwrap_or(glam::Quat::IDENTITY);
+            let [qx, qy, qz, qw] = (axis_angle * quaternion).to_array();
+
+            let message = self
+                .foxglove
+                .message("foxglove.FrameTransform")
+                .with_timestamp(log_time)
+                .with("parent_frame_id", parent_frame_id.clone())
+                .with("child_frame_id", frame_id(entity_path))
+                .with_message("translation", |translation| {
+                    translation
+                        .with("x", Value::F64(x.into()))
+                        .with("y", Value::F64(y.into()))
+                        .with("z", Value::F64(z.into()))
+                })
+                .with_message("rotation", |rotation| {
+                    rotation
+                        .with("x", Value::F64(qx.into()))
+                        .with("y", Value::F64(qy.into()))
+                        .with("z", Value::F64(qz.into()))
+                        .with("w", Value::F64(qw.into()))
+                });
+            self.write_foxglove(entity_path, log_time, &message)?;
+        }
+
+        Ok(())
+    }
+
+    fn write_logs(&mut self, chunk: &Chunk, log_times: &[u64]) -> Result<(), McapExportError> {
+        let text_descr = archetypes::TextLog::descriptor_text();
+        let level_descr = archetypes::TextLog::descriptor_level();
+
+        for (row, &log_time) in log_times.iter().enumerate() {
+            let Some(text) = mono::<components::Text>(chunk, &text_descr, row)? else {
+                continue;
+            };
+            let level = mono::<components::TextLogLevel>(chunk, &level_descr, row)?
+                .map_or(LogLevel::Unknown, |level| {
+                    LogLevel::from_rerun(level.as_str())
+                });
+
+            let message = self
+                .foxglove
+                .message("foxglove.Log")
+                .with_timestamp(log_time)
+                .with("level", Value::EnumNumber(level as i32))
+                .with("message", Value::String(text.as_str().to_owned()))
+                .with("name", frame_id(chunk.entity_path()));
+            self.write_foxglove(chunk.entity_path(), log_time, &message)?;
+        }
+
+        Ok(())
+    }
+
+    fn write_foxglove(
+        &mut self,
+        entity_path: &EntityPath,
+        log_time: u64,
+        message: &MessageBuilder,
+    ) -> Result<(), McapExportError> {
+        let schema_name = message.schema_name();
+        let key = (entity_path.to_string(), Some(schema_name.clone()));
+
+        if !self.channels.contains_key(&key) {
+            let schema_id = self.writer.add_schema(
+                &schema_name,
+                "protobuf",
+                &self.foxglove.encoded_descriptors(),
+            )?;
+            let id = self
+                .writer
+                .add_channel(schema_id, &key.0, "protobuf", &Default::default())?;
+            self.channels.insert(
+                key.clone(),
+                Channel {
+                    id,
+                    sequence: 0,
+                    arrow: None,
+                },
+            );
+        }
+
+        self.write_message(&key, log_time, &message.encode())
+    }
+
+    fn write_arrow_row(
+        &mut self,
+        chunk: &Chunk,
+        descr: &ComponentDescriptor,
+        row: usize,
+        log_time: u64,
+    ) -> Result<(), McapExportError> {
+        let Some(array) = chunk.component_batch_raw(descr, row).transpose()? else {
+            return Ok(()); // nothing logged for this component on this row
+        };
+
+        let entity_path = chunk.entity_path();
+        let key = (format!("{entity_path}:{}", descr.component), None);
+
+        let mut metadata = BTreeMap::from([
+            ("rerun:entity_path".to_owned(), entity_path.to_string()),
+            ("rerun:component".to_owned(), descr.component.to_string()),
+        ]);
+        if let Some(archetype) = descr.archetype {
+            metadata.insert("rerun:archetype".to_owned(), archetype.to_string());
+        }
+        if let Some(component_type) = descr.component_type {
+            metadata.insert(
+                "rerun:component_type".to_owned(),
+                component_type.to_string(),
+            );
+        }
+
+        let schema = Arc::new(Schema::new_with_metadata(
+            vec![Field::new(
+                descr.component.as_str(),
+                array.data_type().clone(),
+                true,
+            )],
+            metadata.clone().into_iter().collect(),
+        ));
+
+        // A channel has a single schema, so a component that changes its datatype continues on a
+        // new channel with the same topic.
+        let has_channel = self
+            .channels
+            .get(&key)
+            .and_then(|channel| channel.arrow.as_ref())
+            .is_some_and(|arrow| arrow.schema == schema);
+        if !has_channel {
+            let mut writer = StreamWriter::try_new(Vec::new(), &schema)?;
+            let schema_data = std::mem::take(writer.get_mut());
+
+            let schema_name = descr
+                .component_type
+                .map_or_else(|| descr.component.to_string(), |ty| ty.to_string());
+            let schema_id =
+                self.writer
+                    .add_schema(&schema_name, ARROW_IPC_SCHEMA_ENCODING, &schema_data)?;
+            let id = self.writer.add_channel(
+                schema_id,
+                &key.0,
+                ARROW_IPC_MESSAGE_ENCODING,
+                &metadata,
+            )?;
+            self.channels.insert(
+                key.clone(),
+                Channel {
+                    id,
+                    sequence: 0,
+                    arrow: Some(ArrowChannel {
+                        schema: Arc::clone(&schema),
+                        writer,
+                    }),
+                },
+            );
+        }
+
+        let Some(arrow) = self
+            .channels
+            .get_mut(&key)
+            .and_then(|channel| channel.arrow.as_mut())
+        else {
+            return Ok(());
+        };
+
+        let batch = RecordBatch::try_new(schema, vec![array])?;
+        arrow.writer.write(&batch)?;
+        let data = std::mem::take(arrow.writer.get_mut());
+
+        self.write_message(&key, log_time, &data)
+    }
+
+    fn write_message(
+        &mut self,
+        key: &ChannelKey,
+        log_time: u64,
+        data: &[u8],
+    ) -> Result<(), McapExportError> {
+        let Some(channel) = self.channels.get_mut(key) else {
+            return Ok(());
+        };
+
+        let header = ::mcap::records::MessageHeader {
+            channel_id: channel.id,
+            sequence: channel.sequence,
+            log_time,
+            publish_time: log_time,
+        };
+        channel.sequence += 1;
+
+        self.writer.write_to_known_channel(&header, data)?;
+
+        Ok(())
+    }
+}
+
+/// Writes all `chunks` to `writer` as an MCAP file, see [`McapExporter`].
+pub fn export_chunks<W: Write + Seek>(
+    writer: W,
+    timeline: TimelineName,
+    chunks: impl IntoIterator<Item = Arc<Chunk>>,
+) -> Result<W, McapExportError> {
+    let mut exporter = McapExporter::new(writer, timeline)?;
+    for chunk in chunks {
+        exporter.add_chunk(&chunk)?;
+    }
+    exporter.finish()
+}
+
+// ---
+
+/// Whether any transform of the chunk has a scale or a 3x3 matrix other than the identity.
+fn has_scale_or_matrix(chunk: &Chunk) -> Result<bool, ChunkError> {
+    let scale_descr = archetypes::Transform3D::descriptor_scale();
+    let mat3x3_descr = archetypes::Transform3D::descriptor_mat3x3();
+
+    for row in 0..chunk.num_rows() {
+        let scales = batch::<components::Scale3D>(chunk, &scale_descr, row)?.unwrap_or_default();
+        let matrices =
+            batch::<components::TransformMat3x3>(chunk, &mat3x3_descr, row)?.unwrap_or_default();
+        if scales.iter().any(|scale| scale.0.0 != [1.0; 3])
+            || matrices
+                .iter()
+                .any(|matrix| matrix.0 != re_types::datatypes::Mat3x3::IDENTITY)
+        {
+            return Ok(true);
+        }
+    }
+
+    Ok(false)
+}
+
+fn frame_id(entity_path: &EntityPath) -> Value {
+    Value::String(entity_path.to_string())
+}
+
+fn batch<C: Component>(
+    chunk: &Chunk,
+    descr: &ComponentDescriptor,
+    row: usize,
+) -> Result<Option<Vec<C>>, ChunkError> {
+    chunk.component_batch::<C>(descr, row).transpose()
+}
+
+fn mono<C: Component>(
+    chunk: &Chunk,
+    descr: &ComponentDescriptor,
+    row: usize,
+) -> Result<Option<C>, ChunkError> {
+    Ok(batch::<C>(chunk, descr, row)?.and_then(|values| values.into_iter().next()))
+}
+
+/// The ROS-style `encoding` of a `foxglove.RawImage`, if there is one for this format.
+fn image_encoding(format: &re_types::datatypes::ImageFormat) -> Option<&'static str> {
+    if let Some(pixel_format) = format.pixel_format {
+        return match pixel_format {
+            PixelFormat::YUY2 => Some("yuyv"),
+            _ => None,
+        };
+    }
+
+    Some(match (format.color_model(), format.datatype()) {
+        (ColorModel::L, ChannelDatatype::U8) => "mono8",
+        (ColorModel::L, ChannelDatatype::U16) => "16UC1",
+        (ColorModel::L, ChannelDatatype::F32) => "32FC1",
+        (ColorModel::RGB, ChannelDatatype::U8) => "rgb8",
+        (ColorModel::RGBA, ChannelDatatype::U8) => "rgba8",
+        (ColorModel::BGR, ChannelDatatype::U8) => "bgr8",
+        (ColorModel::BGRA, ChannelDatatype::U8) => "bgra8",
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use arrow::ipc::reader::StreamReader;
+    use re_chunk::{RowId, TimePoint, Timeline};
+    use re_types::Loggable as _;
+
+    use crate::mcap::{
+        Layer as _, filter::McapFilter, layers::McapFoxgloveLayer, util::read_summary,
+    };
+
+    use super::*;
+
+    #[test]
+    fn points_and_scalars() -> anyhow::Result<()> {
+        let timeline = Timeline::new_timestamp("log_time");
+        let timepoint = |nanos: i64| TimePoint::from([(timeline, nanos)]);
+
+        let points = Chunk::builder("/world/points")
+            .with_archetype(
+                RowId::new(),
+                timepoint(1_000),
+                &archetypes::Points3D::new([[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]])
+                    .with_colors([0xFF0000FF]),
+            )
+            .build()?;
+        let scalars = Chunk::builder("/scalar")
+            .with_archetype(
+                RowId::new(),
+                timepoint(1_000),
+                &archetypes::Scalars::new([1.0]),
+            )
+            .with_archetype(
+                RowId::new(),
+                timepoint(2_000),
+                &archetypes::Scalars::new([2.0]),
+            )
+            .build()?;
+        let other_timeline = Chunk::builder("/ignored")
+            .with_archetype(
+                RowId::new(),
+                TimePoint::from([(Timeline::new_sequence("frame"), 1)]),
+                &archetypes::Scalars::new([3.0]),
+            )
+            .build()?;
+
+        let mut mcap = export_chunks(
+            Cursor::new(Vec::new()),
+            *timeline.name(),
+            [points, scalars, other_timeline].map(Arc::new),
+        )?;
+
+        let summary = read_summary(&mut mcap)?.expect("summary");
+        let channels = summary
+            .channels
+            .values()
+            .map(|channel| {
+                (
+                    channel.topic.clone(),
+                    (
+                        channel.message_encoding.clone(),
+                        channel.schema.as_ref().map(|schema| schema.name.clone()),
+                    ),
+                )
+            })
+            .collect::<BTreeMap<_, _>>();
+        assert_eq!(
+            channels,
+            BTreeMap::from([
+                (
+                    "/scalar:Scalars:scalars".to_owned(),
+                    (
+                        ARROW_IPC_MESSAGE_ENCODING.to_owned(),
+                        Some("rerun.components.Scalar".to_owned())
+                    )
+                ),
+                (
+                    "/world/points".to_owned(),
+                    (
+                        "protobuf".to_owned(),
+                        Some("foxglove.PointCloud".to_owned())
+                    )
+                ),
+            ])
+        );
+
+        // The raw components can be read back as Arrow, the schema is only written once.
+        let mcap_bytes = mcap.into_inner();
+        let scalars = ::mcap::MessageStream::new(&mcap_bytes)?
+            .filter_map(|message| message.ok())
+            .filter(|message| message.channel.topic == "/scalar:Scalars:scalars")
+            .map(|message| {
+                let schema = message.channel.schema.as_ref().expect("schema");
+                assert_eq!(schema.encoding, ARROW_IPC_SCHEMA_ENCODING);
+                let stream = [schema.data.as_ref(), message.data.as_ref()].concat();
+                let batch = StreamReader::try_new(Cursor::new(stream), None)?
+                    .next()
+                    .expect("batch")?;
+                let scalars = components::Scalar::from_arrow(batch.column(0))?;
+                anyhow::Ok((message.log_time, scalars[0].0.0))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        assert_eq!(scalars, vec![(1_000, 1.0), (2_000, 2.0)]);
+
+        // The point cloud is understood by our own Foxglove layer.
+        let mut chunks = Vec::new();
+        McapFoxgloveLayer::default().process(
+            &mut Cursor::new(&mcap_bytes),
+            &summary,
+            &McapFilter::default(),
+            &mut |chunk| chunks.push(chunk),
+        )?;
+        let positions = chunks
+            .iter()
+            .filter(|chunk| chunk.entity_path() == &EntityPath::from("/world/points"))
+            .flat_map(|chunk| {
+                chunk
+                    .iter_component::<components::Position3D>(
+                        &archetypes::Points3D::descriptor_positions(),
+                    )
+                    .flat_map(|positions| positions.to_vec())
+                    .collect::<Vec<_>>()
+            })
+            .map(|position| position.0.0)
+            .collect::<Vec<_>>();
+        assert_eq!(positions, vec![[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn transforms() -> anyhow::Result<()> {
+        let timeline = Timeline::new_timestamp("log_time");
+        let timepoint = TimePoint::from([(timeline, 1_000)]);
+
+        let rotated = Chunk::builder("/rotated")
+            .with_archetype(
+                RowId::new(),
+                timepoint.clone(),
+                &archetypes::Transform3D::from_rotation(
+                    re_types::datatypes::RotationAxisAngle::new(
+                        [0.0, 0.0, 1.0],
+                        re_types::datatypes::Angle::from_degrees(90.0),
+                    ),
+                ),
+            )
+            .build()?;
+        let scaled = Chunk::builder("/scaled")
+            .with_archetype(
+                RowId::new(),
+                timepoint,
+                &archetypes::Transform3D::from_translation([1.0, 2.0, 3.0]).with_scale(2.0),
+            )
+            .build()?;
+
+        let mcap = export_chunks(
+            Cursor::new(Vec::new()),
+            *timeline.name(),
+            [rotated, scaled].map(Arc::new),
+        )?;
+        let mcap_bytes = mcap.into_inner();
+
+        let topics = ::mcap::MessageStream::new(&mcap_bytes)?
+            .map(|message| anyhow::Ok(message?.channel.topic.clone()))
+            .collect::<anyhow::Result<std::collections::BTreeSet<_>>>()?;
+        assert!(topics.contains("/rotated"));
+        assert!(topics.contains("/scaled:Transform3D:translation"));
+        assert!(topics.contains("/scaled:Transform3D:scale"));
+        assert!(!topics.contains("/scaled"));
+
+        // The axis-angle rotation is converted to a quaternion.
+        let mut mcap = Cursor::new(&mcap_bytes);
+        let summary = read_summary(&mut mcap)?.expect("summary");
+        let mut chunks = Vec::new();
+        McapFoxgloveLayer::default().process(
+            &mut mcap,
+            &summary,
+            &McapFilter::default(),
+            &mut |chunk| chunks.push(chunk),
+        )?;
+        let quaternions = chunks
+            .iter()
+            .flat_map(|chunk| {
+                chunk
+                    .iter_component::<components::RotationQuat>(
+                        &archetypes::Transform3D::descriptor_quaternion(),
+                    )
+                    .flat_map(|quaternions| quaternions.to_vec())
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(quaternions.len(), 1);
+        let [x, y, z, w] = quaternions[0].0.xyzw();
+        let half_sqrt2 = std::f32::consts::FRAC_1_SQRT_2;
+        assert!(x.abs() < 1e-6 && y.abs() < 1e-6);
+        assert!((z - half_sqrt2).abs() < 1e-6 && (w - half_sqrt2).abs() < 1e-6);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,202 @@
+//! Restricting the processing of an MCAP file to a subset of its messages.
+
+use mcap::{Channel, records::ChunkIndex};
+use re_chunk::external::nohash_hasher::IntSet;
+
+/// Selects a subset of the messages of an MCAP file, by topic, schema and `log_time`.
+///
+/// The filter is applied on the chunk index first, so that MCAP chunks that don't contain any
+/// selected message are never read nor decompressed. Chunks that are only partially selected
+/// are then filtered message by message.
+///
+/// The default filter selects everything.
+#[derive(Clone, Debug, Default)]
+pub struct McapFilter {
+    /// Only topics that match at least one of these patterns are selected, all topics if empty.
+    pub include_topics: Vec<glob::Pattern>,
+
+    /// Topics that match any of these patterns are never selected, even if included.
+    pub exclude_topics: Vec<glob::Pattern>,
+
+    /// Only channels whose schema name matches one of these patterns are selected, all
+    /// channels (including schemaless ones) if empty.
+    pub schemas: Vec<glob::Pattern>,
+
+    /// Only messages with a `log_time` at or after this value (in nanoseconds) are selected.
+    pub start: Option<u64>,
+
+    /// Only messages with a `log_time` at or before this value (in nanoseconds) are selected.
+    pub end: Option<u64>,
+}
+
+impl McapFilter {
+    /// Whether this filter selects everything.
+    pub fn is_all(&self) -> bool {
+        let Self {
+            include_topics,
+            exclude_topics,
+            schemas,
+            start,
+            end,
+        } = self;
+
+        include_topics.is_empty()
+            && exclude_topics.is_empty()
+            && schemas.is_empty()
+            && start.is_none()
+            && end.is_none()
+    }
+
+    /// Whether messages on this channel can be selected at all.
+    pub fn matches_channel(&self, channel: &Channel<'_>) -> bool {
+        let matches_any = |patterns: &[glob::Pattern], value: &str| {
+            patterns.iter().any(|pattern| pattern.matches(value))
+        };
+
+        if !self.include_topics.is_empty() && !matches_any(&self.include_topics, &channel.topic) {
+            return false;
+        }
+
+        if matches_any(&self.exclude_topics, &channel.topic) {
+            return false;
+        }
+
+        if !self.schemas.is_empty() {
+            let Some(schema) = channel.schema.as_ref() else {
+                return false;
+            };
+            if !matches_any(&self.schemas, &schema.name) {
+                return false;
+            }
+        }
+
+        true
+    }
+
+    /// Whether a message logged at `log_time` is within the time bounds.
+    pub fn matches_log_time(&self, log_time: u64) -> bool {
+        self.start.is_none_or(|start| start <= log_time)
+            && self.end.is_none_or(|end| log_time <= end)
+    }
+
+    /// Whether the given MCAP chunk might contain selected messages, according to its index.
+    ///
+    /// `channels` are the ids of the channels for which [`Self::matches_channel`] holds.
+    pub fn matches_chunk(&self, chunk: &ChunkIndex, channels: &IntSet<u16>) -> bool {
+        let overlaps_time = self
+            .start
+            .is_none_or(|start| start <= chunk.message_end_time)
+            && self.end.is_none_or(|end| chunk.message_start_time <= end);
+
+        // Without message indexes, we can't know which channels are in the chunk.
+        let has_channel = chunk.message_index_offsets.is_empty()
+            || chunk
+                .message_index_offsets
+                .keys()
+                .any(|channel_id| channels.contains(channel_id));
+
+        overlaps_time && has_channel
+    }
+
+    /// The ids of all the channels of `summary` that match [`Self::matches_channel`].
+    pub fn selected_channels(&self, summary: &mcap::Summary) -> IntSet<u16> {
+        summary
+            .channels
+            .values()
+            .filter(|channel| self.matches_channel(channel))
+            .map(|channel| channel.id)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{borrow::Cow, collections::BTreeMap, sync::Arc};
+
+    use super::*;
+
+    fn channel(id: u16, topic: &str, schema: Option<&str>) -> Channel<'static> {
+        Channel {
+            id,
+            topic: topic.to_owned(),
+            schema: schema.map(|name| {
+                Arc::new(mcap::Schema {
+                    id: 1,
+                    name: name.to_owned(),
+                    encoding: "ros2msg".to_owned(),
+                    data: Cow::Borrowed(&[]),
+                })
+            }),
+            message_encoding: "cdr".to_owned(),
+            metadata: BTreeMap::default(),
+        }
+    }
+
+    fn patterns(patterns: &[&str]) -> Vec<glob::Pattern> {
+        patterns
+            .iter()
+            .map(|pattern| glob::Pattern::new(pattern).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn channels() {
+        let image = channel(1, "/camera/image", Some("sensor_msgs/msg/Image"));
+        let info = channel(2, "/camera/camera_info", Some("sensor_msgs/msg/CameraInfo"));
+        let json = channel(3, "/debug", None);
+
+        assert!(McapFilter::default().is_all());
+        for channel in [&image, &info, &json] {
+            assert!(McapFilter::default().matches_channel(channel));
+        }
+
+        let filter = McapFilter {
+            include_topics: patterns(&["/camera/*"]),
+            exclude_topics: patterns(&["*_info"]),
+            ..Default::default()
+        };
+        assert!(filter.matches_channel(&image));
+        assert!(!filter.matches_channel(&info));
+        assert!(!filter.matches_channel(&json));
+
+        let filter = McapFilter {
+            schemas: patterns(&["sensor_msgs/msg/Camera*"]),
+            ..Default::default()
+        };
+        assert!(!filter.matches_channel(&image));
+        assert!(filter.matches_channel(&info));
+        assert!(!filter.matches_channel(&json));
+    }
+
+    #[test]
+    fn chunks() {
+        let chunk = ChunkIndex {
+            message_start_time: 100,
+            message_end_time: 200,
+            chunk_start_offset: 0,
+            chunk_length: 0,
+            message_index_offsets: BTreeMap::from([(1, 0), (2, 0)]),
+            message_index_length: 0,
+            compression: String::new(),
+            compressed_size: 0,
+            uncompressed_size: 0,
+        };
+        let all_channels = IntSet::from_iter([1, 2, 3]);
+
+        let filter = |start, end| McapFilter {
+            start,
+            end,
+            ..Default::default()
+        };
+        assert!(filter(None, None).matches_chunk(&chunk, &all_channels));
+        assert!(filter(Some(200), None).matches_chunk(&chunk, &all_channels));
+        assert!(!filter(Some(201), None).matches_chunk(&chunk, &all_channels));
+        assert!(filter(None, Some(100)).matches_chunk(&chunk, &all_channels));
+        assert!(!filter(None, Some(99)).matches_chunk(&chunk, &all_channels));
+
+        assert!(!filter(None, None).matches_chunk(&chunk, &IntSet::from_iter([3])));
+
+        assert!(filter(Some(150), Some(150)).matches_log_time(150));
+        assert!(!filter(Some(150), Some(150)).matches_log_time(151));
+    }
+}
@@ -0,0 +1,130 @@
+use std::sync::Arc;
+
+use parking_lot::Mutex;
+use prost_reflect::DescriptorPool;
+use re_chunk::external::nohash_hasher::IntSet;
+
+use crate::mcap::{
+    decode::{McapMessageParser, PluginError},
+    layers::{LayerIdentifier, MessageLayer},
+    schema::{
+        foxglove::{FoxgloveMessageParser, FoxgloveSchema, MessageEncoding},
+        tf2_msgs::TfTree,
+    },
+    util::{McapReader, for_each_message},
+};
+
+/// Provides semantic conversions of the Foxglove well-known schemas.
+///
+/// Supports both protobuf and JSON encoded messages, and outputs Rerun archetypes for
+/// visualization in the viewer, as opposed to [`super::McapProtobufLayer`].
+#[derive(Debug, Default)]
+pub struct McapFoxgloveLayer {
+    schemas_per_topic: ahash::HashMap<String, (FoxgloveSchema, MessageEncoding)>,
+
+    /// Shared by all transform parsers, see [`TfTree`].
+    tf_tree: Arc<Mutex<TfTree>>,
+}
+
+impl MessageLayer for McapFoxgloveLayer {
+    fn identifier() -> LayerIdentifier {
+        "foxglove".into()
+    }
+
+    fn init(&mut self, summary: &mcap::Summary) -> Result<(), PluginError> {
+        for channel in summary.channels.values() {
+            let Some(schema) = channel.schema.as_ref() else {
+                continue;
+            };
+
+            let Some(foxglove_schema) = FoxgloveSchema::from_name(&schema.name) else {
+                continue;
+            };
+
+            let encoding = match channel.message_encoding.as_str() {
+                "protobuf" => {
+                    let pool = DescriptorPool::decode(schema.data.as_ref()).map_err(|err| {
+                        PluginError::InvalidSchema {
+                            schema: schema.name.clone(),
+                            source: err.into(),
+                        }
+                    })?;
+
+                    let message_descriptor = pool
+                        .get_message_by_name(schema.name.as_str())
+                        .ok_or_else(|| PluginError::NoSchema(schema.name.clone()))?;
+
+                    MessageEncoding::Protobuf(message_descriptor)
+                }
+                "json" => MessageEncoding::Json,
+                encoding => {
+                    re_log::warn_once!(
+                        "Schema {} is not supported with message encoding {encoding:?}",
+                        schema.name
+                    );
+                    continue;
+                }
+            };
+
+            self.schemas_per_topic
+                .insert(channel.topic.clone(), (foxglove_schema, encoding));
+        }
+
+        Ok(())
+    }
+
+    fn prepare(
+        &mut self,
+        mcap_reader: &mut dyn McapReader,
+        summary: &mcap::Summary,
+        selected_channels: &IntSet<u16>,
+    ) -> Result<(), PluginError> {
+        re_tracing::profile_function!();
+
+        // Transforms are logged on the path of their frame in the tree, which must be known in
+        // full before the first chunk is parsed.
+        let transform_channels = summary
+            .channels
+            .values()
+            .filter(|channel| {
+                selected_channels.contains(&channel.id)
+                    && self
+                        .schemas_per_topic
+                        .get(&channel.topic)
+                        .is_some_and(|(schema, _)| {
+                            matches!(
+                                schema,
+                                FoxgloveSchema::FrameTransform | FoxgloveSchema::FrameTransforms
+                            )
+                        })
+            })
+            .map(|channel| channel.id)
+            .collect();
+
+        let mut tf_tree = self.tf_tree.lock();
+        for_each_message(mcap_reader, summary, &transform_channels, |msg| {
+            let Some((schema, encoding)) = self.schemas_per_topic.get(&msg.channel.topic) else {
+                return;
+            };
+            if let Err(err) = schema.add_to_tf_tree(encoding, &msg.data, &mut tf_tree) {
+                re_log::warn_once!(
+                    "Failed to read transform frames on topic {:?}: {err}",
+                    msg.channel.topic
+                );
+            }
+        })
+    }
+
+    fn message_parser(
+        &self,
+        channel: &mcap::Channel<'_>,
+        _num_rows: usize,
+    ) -> Option<Box<dyn McapMessageParser>> {
+        let (schema, encoding) = self.schemas_per_topic.get(&channel.topic)?;
+        Some(Box::new(FoxgloveMessageParser::new(
+            *schema,
+            encoding.clone(),
+            Arc::clone(&self.tf_tree),
+        )))
+    }
+}
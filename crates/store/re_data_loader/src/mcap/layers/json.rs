@@ -0,0 +1,721 @@
+use std::sync::Arc;
+
+use arrow::{
+    array::{Array as _, ArrayRef, FixedSizeListArray},
+    datatypes::{DataType, Field, Fields, Schema, SchemaRef},
+    json::{ReaderBuilder, reader::infer_json_schema_from_iterator},
+};
+use re_chunk::{Chunk, ChunkId, external::nohash_hasher::IntSet};
+use re_types::ComponentDescriptor;
+
+use crate::mcap::{
+    decode::{McapMessageParser, ParserContext, PluginError},
+    layers::{LayerIdentifier, MessageLayer},
+    util::{McapReader, for_each_message},
+};
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("invalid message on channel {channel}: {source}")]
+    InvalidMessage {
+        channel: String,
+        source: serde_json::Error,
+    },
+
+    #[error("expected a JSON object on channel {channel}, but found {value}")]
+    NotAnObject {
+        channel: String,
+        value: serde_json::Value,
+    },
+
+    #[error("JSON schema {0} is not supported yet")]
+    UnsupportedSchema(serde_json::Value),
+}
+
+struct JsonMessageParser {
+    schema_name: Option<String>,
+
+    /// The Arrow schema of the channel, see [`JsonChannel::arrow_schema`].
+    ///
+    /// If this is `None`, the schema is inferred from the messages instead.
+    arrow_schema: Option<SchemaRef>,
+
+    /// The decoded messages, one per row.
+    messages: Vec<serde_json::Value>,
+}
+
+impl JsonMessageParser {
+    fn new(num_rows: usize, channel: &JsonChannel) -> Self {
+        Self {
+            schema_name: channel.schema_name.clone(),
+            arrow_schema: channel.arrow_schema.clone(),
+            messages: Vec::with_capacity(num_rows),
+        }
+    }
+
+    /// Decodes all messages into one Arrow array per top-level field.
+    fn decode(
+        arrow_schema: &SchemaRef,
+        messages: &[serde_json::Value],
+    ) -> Result<Vec<(Arc<Field>, ArrayRef)>, arrow::error::ArrowError> {
+        let mut decoder = ReaderBuilder::new(Arc::clone(arrow_schema))
+            .with_batch_size(messages.len().max(1))
+            // Values of conflicting types are widened to strings, see [`widen_datatype`].
+            .with_coerce_primitive(true)
+            .build_decoder()?;
+        decoder.serialize(messages)?;
+
+        let Some(batch) = decoder.flush()? else {
+            return Ok(Vec::new());
+        };
+
+        Ok(arrow_schema
+            .fields()
+            .iter()
+            .cloned()
+            .zip(batch.columns().iter().cloned())
+            .collect())
+    }
+}
+
+impl McapMessageParser for JsonMessageParser {
+    fn append(&mut self, _ctx: &mut ParserContext, msg: &mcap::Message<'_>) -> anyhow::Result<()> {
+        re_tracing::profile_function!();
+
+        let value = serde_json::from_slice::<serde_json::Value>(&msg.data).map_err(|err| {
+            Error::InvalidMessage {
+                channel: msg.channel.topic.clone(),
+                source: err,
+            }
+        });
+
+        // We always need to add a row, as the timelines have already been extended.
+        // An empty object results in a row where all fields are null.
+        match value {
+            Ok(value @ serde_json::Value::Object(_)) => {
+                self.messages.push(value);
+                Ok(())
+            }
+            Ok(value) => {
+                self.messages
+                    .push(serde_json::Value::Object(Default::default()));
+                Err(Error::NotAnObject {
+                    channel: msg.channel.topic.clone(),
+                    value,
+                }
+                .into())
+            }
+            Err(err) => {
+                self.messages
+                    .push(serde_json::Value::Object(Default::default()));
+                Err(err.into())
+            }
+        }
+    }
+
+    fn finalize(self: Box<Self>, ctx: ParserContext) -> anyhow::Result<Vec<Chunk>> {
+        re_tracing::profile_function!();
+        let entity_path = ctx.entity_path().clone();
+        let timelines = ctx.build_timelines();
+
+        let Self {
+            schema_name,
+            arrow_schema,
+            messages,
+        } = *self;
+
+        // The schema is only missing for channels that had no valid messages when the layer was
+        // prepared, so there is nothing it would have to be consistent with.
+        let arrow_schema = match arrow_schema {
+            Some(arrow_schema) => Ok(arrow_schema),
+            None => infer_json_schema_from_iterator(messages.iter().map(Ok)).map(Arc::new),
+        };
+
+        let columns = arrow_schema
+            .and_then(|arrow_schema| Self::decode(&arrow_schema, &messages))
+            .map_err(|err| PluginError::Other(anyhow::anyhow!(err)))?;
+
+        if columns.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let components = columns
+            .into_iter()
+            .map(|(field, column)| {
+                // Every row holds a single instance of each field.
+                let list_field = Arc::new(Field::new_list_field(column.data_type().clone(), true));
+                let nulls = column.logical_nulls();
+                let list = FixedSizeListArray::try_new(list_field, 1, column, nulls)?;
+
+                let descriptor = ComponentDescriptor::partial(field.name().as_str());
+                let descriptor = match &schema_name {
+                    Some(schema_name) => descriptor.with_archetype(schema_name.as_str().into()),
+                    None => descriptor,
+                };
+
+                Ok((descriptor, list.into()))
+            })
+            .collect::<Result<_, arrow::error::ArrowError>>()
+            .map_err(|err| PluginError::Other(anyhow::anyhow!(err)))?;
+
+        let chunk = Chunk::from_auto_row_ids(ChunkId::new(), entity_path, timelines, components)
+            .map_err(|err| PluginError::Other(anyhow::anyhow!(err)))?;
+
+        Ok(vec![chunk])
+    }
+}
+
+/// Derives the Arrow type of values that are described by a JSON schema.
+fn datatype_from_json_schema(schema: &serde_json::Value) -> Result<DataType, Error> {
+    let unsupported = || Error::UnsupportedSchema(schema.clone());
+
+    // `type` can either be a single type, or a list of types, which is typically used to make
+    // a value nullable. All Arrow fields are nullable anyway.
+    let ty = match schema.get("type") {
+        Some(serde_json::Value::String(ty)) => Some(ty.as_str()),
+        Some(serde_json::Value::Array(types)) => {
+            let mut types = types
+                .iter()
+                .filter_map(serde_json::Value::as_str)
+                .filter(|ty| *ty != "null");
+            match (types.next(), types.next()) {
+                (ty, None) => Some(ty.unwrap_or("null")),
+                (Some(_), Some(_)) => return Err(unsupported()),
+                (None, Some(_)) => unreachable!(),
+            }
+        }
+        Some(_) => return Err(unsupported()),
+        None => None,
+    };
+
+    let ty = match ty {
+        Some(ty) => ty,
+        None if schema.get("properties").is_some() => "object",
+        None if schema.get("items").is_some() => "array",
+        None => match schema
+            .get("enum")
+            .and_then(|values| values.as_array()?.first())
+            .or_else(|| schema.get("const"))
+        {
+            Some(serde_json::Value::String(_)) => "string",
+            Some(serde_json::Value::Bool(_)) => "boolean",
+            Some(serde_json::Value::Number(number)) if number.is_f64() => "number",
+            Some(serde_json::Value::Number(_)) => "integer",
+            _ => return Err(unsupported()),
+        },
+    };
+
+    Ok(match ty {
+        "null" => DataType::Null,
+        "boolean" => DataType::Boolean,
+        "integer" => DataType::Int64,
+        "number" => DataType::Float64,
+        "string" => DataType::Utf8,
+        "array" => {
+            let items = schema.get("items").ok_or_else(unsupported)?;
+            DataType::new_list(datatype_from_json_schema(items)?, true)
+        }
+        "object" => {
+            let properties = schema
+                .get("properties")
+                .and_then(serde_json::Value::as_object)
+                .ok_or_else(unsupported)?;
+            DataType::Struct(
+                properties
+                    .iter()
+                    .map(|(name, schema)| {
+                        Ok(Field::new(name, datatype_from_json_schema(schema)?, true))
+                    })
+                    .collect::<Result<Fields, Error>>()?,
+            )
+        }
+        _ => return Err(unsupported()),
+    })
+}
+
+/// Returns a type that can hold the values of both `a` and `b`.
+///
+/// Integers are widened to floats, and the fields of objects are merged. Any other conflict
+/// results in strings, which only works as long as the conflicting values are not nested.
+fn widen_datatype(a: &DataType, b: &DataType) -> DataType {
+    match (a, b) {
+        _ if a == b => a.clone(),
+        (DataType::Null, ty) | (ty, DataType::Null) => ty.clone(),
+        (DataType::Int64, DataType::Float64) | (DataType::Float64, DataType::Int64) => {
+            DataType::Float64
+        }
+        (DataType::List(a), DataType::List(b)) => {
+            DataType::new_list(widen_datatype(a.data_type(), b.data_type()), true)
+        }
+        (DataType::Struct(a), DataType::Struct(b)) => DataType::Struct(widen_fields(a, b)),
+        _ => DataType::Utf8,
+    }
+}
+
+/// Merges the fields of two objects, see [`widen_datatype`].
+fn widen_fields(a: &Fields, b: &Fields) -> Fields {
+    let mut fields = a
+        .iter()
+        .map(|field| match b.find(field.name()) {
+            Some((_, other)) => Arc::new(Field::new(
+                field.name(),
+                widen_datatype(field.data_type(), other.data_type()),
+                true,
+            )),
+            None => Arc::clone(field),
+        })
+        .collect::<Vec<_>>();
+    fields.extend(
+        b.iter()
+            .filter(|field| a.find(field.name()).is_none())
+            .cloned(),
+    );
+    fields.into()
+}
+
+/// Derives an Arrow schema from a `jsonschema` MCAP schema.
+///
+/// Each top-level property of the message becomes a separate field.
+fn arrow_schema_from_json_schema(data: &[u8]) -> anyhow::Result<SchemaRef> {
+    let json_schema = serde_json::from_slice::<serde_json::Value>(data)?;
+    match datatype_from_json_schema(&json_schema)? {
+        DataType::Struct(fields) => Ok(Arc::new(Schema::new_with_metadata(
+            fields,
+            Default::default(),
+        ))),
+        _ => Err(Error::UnsupportedSchema(json_schema).into()),
+    }
+}
+
+#[derive(Debug)]
+struct JsonChannel {
+    schema_name: Option<String>,
+
+    /// The Arrow schema of all messages on this channel.
+    ///
+    /// This is fixed before the first MCAP chunk is parsed, so that the columns of a channel
+    /// have the same types in every Rerun chunk.
+    arrow_schema: Option<SchemaRef>,
+}
+
+/// The number of messages of a channel that are checked against its JSON schema, or whose types
+/// are inferred, at once.
+const PREPARE_BATCH_SIZE: usize = 1024;
+
+/// Provides conversion of JSON-encoded MCAP messages.
+///
+/// Like [`super::McapProtobufLayer`], applying this layer results in a direct Arrow
+/// representation of the fields. The Arrow types are derived from the `jsonschema` of the
+/// channel if there is one, and are otherwise inferred from all messages of the channel.
+#[derive(Debug, Default)]
+pub struct McapJsonLayer {
+    channels_per_topic: ahash::HashMap<String, JsonChannel>,
+}
+
+impl MessageLayer for McapJsonLayer {
+    fn identifier() -> LayerIdentifier {
+        "json".into()
+    }
+
+    fn init(&mut self, summary: &mcap::Summary) -> Result<(), PluginError> {
+        for channel in summary.channels.values() {
+            if channel.message_encoding.as_str() != "json" {
+                continue;
+            }
+
+            let schema = channel.schema.as_ref();
+            let arrow_schema = schema
+                .filter(|schema| schema.encoding.as_str() == "jsonschema")
+                .and_then(|schema| match arrow_schema_from_json_schema(&schema.data) {
+                    Ok(arrow_schema) => Some(arrow_schema),
+                    Err(err) => {
+                        re_log::warn_once!(
+                            "Failed to derive Arrow types from JSON schema {}, inferring them instead: {err}",
+                            schema.name
+                        );
+                        None
+                    }
+                });
+
+            self.channels_per_topic.insert(
+                channel.topic.clone(),
+                JsonChannel {
+                    schema_name: schema.map(|schema| schema.name.clone()),
+                    arrow_schema,
+                },
+            );
+        }
+
+        Ok(())
+    }
+
+    fn prepare(
+        &mut self,
+        mcap_reader: &mut dyn McapReader,
+        summary: &mcap::Summary,
+        selected_channels: &IntSet<u16>,
+    ) -> Result<(), PluginError> {
+        re_tracing::profile_function!();
+
+        let mut schema_channels = IntSet::default();
+        let mut inferred_channels = IntSet::default();
+        for channel in summary.channels.values() {
+            if !selected_channels.contains(&channel.id) {
+                continue;
+            }
+            match self.channels_per_topic.get(&channel.topic) {
+                Some(JsonChannel {
+                    arrow_schema: Some(_),
+                    ..
+                }) => {
+                    schema_channels.insert(channel.id);
+                }
+                Some(_) => {
+                    inferred_channels.insert(channel.id);
+                }
+                None => {}
+            }
+        }
+
+        // Messages are checked against the JSON schema of their channel, if any, before any
+        // MCAP chunk gets parsed.
+        let mut mismatched_topics = ahash::HashSet::<String>::default();
+        for_each_json_batch(mcap_reader, summary, &schema_channels, |topic, batch| {
+            if mismatched_topics.contains(topic) {
+                return;
+            }
+
+            if let Some(arrow_schema) = self
+                .channels_per_topic
+                .get(topic)
+                .and_then(|channel| channel.arrow_schema.as_ref())
+                && let Err(err) = JsonMessageParser::decode(arrow_schema, batch)
+            {
+                re_log::warn_once!(
+                    "Messages on {topic:?} don't match their JSON schema, inferring their types instead: {err}"
+                );
+                mismatched_topics.insert(topic.to_owned());
+            }
+        })?;
+
+        inferred_channels.extend(
+            summary
+                .channels
+                .values()
+                .filter(|channel| {
+                    schema_channels.contains(&channel.id)
+                        && mismatched_topics.contains(&channel.topic)
+                })
+                .map(|channel| channel.id),
+        );
+
+        // Otherwise, types are inferred from every message of the channel.
+        let mut inferred_fields = ahash::HashMap::<String, Fields>::default();
+        for_each_json_batch(mcap_reader, summary, &inferred_channels, |topic, batch| {
+            match infer_json_schema_from_iterator(batch.iter().map(Ok)) {
+                Ok(schema) => {
+                    inferred_fields
+                        .entry(topic.to_owned())
+                        .and_modify(|fields| *fields = widen_fields(fields, schema.fields()))
+                        .or_insert_with(|| schema.fields().clone());
+                }
+                Err(err) => {
+                    re_log::warn_once!("Failed to infer the types of messages on {topic:?}: {err}");
+                }
+            }
+        })?;
+
+        for (topic, channel) in &mut self.channels_per_topic {
+            if channel.arrow_schema.is_none() || mismatched_topics.contains(topic) {
+                channel.arrow_schema = inferred_fields
+                    .remove(topic)
+                    .map(|fields| Arc::new(Schema::new_with_metadata(fields, Default::default())));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn message_parser(
+        &self,
+        channel: &mcap::Channel<'_>,
+        num_rows: usize,
+    ) -> Option<Box<dyn McapMessageParser>> {
+        let channel = self.channels_per_topic.get(&channel.topic)?;
+        Some(Box::new(JsonMessageParser::new(num_rows, channel)))
+    }
+}
+
+/// Calls `on_batch` with the messages of each of the given channels, up to
+/// [`PREPARE_BATCH_SIZE`] messages at a time.
+///
+/// Messages that aren't JSON objects are skipped: they are reported when they get parsed.
+fn for_each_json_batch(
+    mcap_reader: &mut dyn McapReader,
+    summary: &mcap::Summary,
+    channel_ids: &IntSet<u16>,
+    mut on_batch: impl FnMut(&str, &[serde_json::Value]),
+) -> Result<(), PluginError> {
+    let mut batch_per_topic = ahash::HashMap::<String, Vec<serde_json::Value>>::default();
+
+    for_each_message(mcap_reader, summary, channel_ids, |msg| {
+        let Ok(value @ serde_json::Value::Object(_)) =
+            serde_json::from_slice::<serde_json::Value>(&msg.data)
+        else {
+            return;
+        };
+
+        let topic = &msg.channel.topic;
+        let batch = batch_per_topic.entry(topic.clone()).or_default();
+        batch.push(value);
+        if batch.len() >= PREPARE_BATCH_SIZE {
+            on_batch(topic, batch);
+            batch.clear();
+        }
+    })?;
+
+    for (topic, batch) in &batch_per_topic {
+        if !batch.is_empty() {
+            on_batch(topic, batch);
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{borrow::Cow, collections::BTreeMap, io::Cursor};
+
+    use re_chunk::{EntityPath, TimePoint};
+    use re_log_types::TimeCell;
+
+    use super::*;
+    use crate::mcap::{filter::McapFilter, layers::Layer as _};
+
+    fn parse(channel: &JsonChannel, messages: &[&str]) -> anyhow::Result<Chunk> {
+        let mcap_channel = Arc::new(mcap::Channel {
+            id: 0,
+            topic: "/telemetry".to_owned(),
+            schema: None,
+            message_encoding: "json".to_owned(),
+            metadata: Default::default(),
+        });
+
+        let mut ctx = ParserContext::new(EntityPath::from("/telemetry"));
+        let mut parser = Box::new(JsonMessageParser::new(messages.len(), channel));
+        for (sequence, data) in messages.iter().enumerate() {
+            ctx.add_timepoint(TimePoint::from([(
+                "log_time",
+                TimeCell::from_timestamp_nanos_since_epoch(sequence as i64),
+            )]));
+            parser.append(
+                &mut ctx,
+                &mcap::Message {
+                    channel: Arc::clone(&mcap_channel),
+                    sequence: sequence as u32,
+                    log_time: sequence as u64,
+                    publish_time: sequence as u64,
+                    data: Cow::Borrowed(data.as_bytes()),
+                },
+            )?;
+        }
+
+        let mut chunks = parser.finalize(ctx)?;
+        assert_eq!(chunks.len(), 1);
+        Ok(chunks.remove(0))
+    }
+
+    fn datatypes(chunk: &Chunk) -> BTreeMap<String, DataType> {
+        chunk
+            .components()
+            .iter()
+            .map(|(descriptor, list)| (descriptor.component.to_string(), list.value_type().clone()))
+            .collect()
+    }
+
+    #[test]
+    fn inferred_types() -> anyhow::Result<()> {
+        let chunk = parse(
+            &JsonChannel {
+                schema_name: None,
+                arrow_schema: None,
+            },
+            &[
+                r#"{ "speed": 1, "label": "a", "position": { "x": 1.5, "y": 2.0 } }"#,
+                r#"{ "speed": 2.5, "tags": ["b", "c"] }"#,
+            ],
+        )?;
+
+        assert_eq!(chunk.num_rows(), 2);
+        assert_eq!(
+            datatypes(&chunk),
+            BTreeMap::from([
+                ("label".to_owned(), DataType::Utf8),
+                (
+                    "position".to_owned(),
+                    DataType::Struct(Fields::from(vec![
+                        Field::new("x", DataType::Float64, true),
+                        Field::new("y", DataType::Float64, true),
+                    ]))
+                ),
+                ("speed".to_owned(), DataType::Float64),
+                ("tags".to_owned(), DataType::new_list(DataType::Utf8, true)),
+            ])
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn types_from_json_schema() -> anyhow::Result<()> {
+        let arrow_schema = arrow_schema_from_json_schema(
+            br#"{
+                "type": "object",
+                "properties": {
+                    "speed": { "type": "integer" },
+                    "status": { "enum": ["ok", "error"] },
+                    "samples": { "type": "array", "items": { "type": ["number", "null"] } }
+                }
+            }"#,
+        )?;
+
+        let chunk = parse(
+            &JsonChannel {
+                schema_name: Some("Telemetry".to_owned()),
+                arrow_schema: Some(arrow_schema),
+            },
+            &[
+                r#"{ "speed": 1, "status": "ok" }"#,
+                r#"{ "samples": [0.5] }"#,
+            ],
+        )?;
+
+        assert_eq!(chunk.num_rows(), 2);
+        assert_eq!(
+            datatypes(&chunk),
+            BTreeMap::from([
+                (
+                    "samples".to_owned(),
+                    DataType::new_list(DataType::Float64, true)
+                ),
+                ("speed".to_owned(), DataType::Int64),
+                ("status".to_owned(), DataType::Utf8),
+            ])
+        );
+
+        Ok(())
+    }
+
+    /// Writes the messages to a single channel, with one MCAP chunk per message.
+    fn write_mcap(schema: Option<&str>, messages: &[&str]) -> Cursor<Vec<u8>> {
+        let mut buffer = Cursor::new(Vec::new());
+        {
+            let mut writer = ::mcap::Writer::new(&mut buffer).unwrap();
+            let schema_id = match schema {
+                Some(schema) => writer
+                    .add_schema("Telemetry", "jsonschema", schema.as_bytes())
+                    .unwrap(),
+                None => 0,
+            };
+            let channel_id = writer
+                .add_channel(schema_id, "/telemetry", "json", &Default::default())
+                .unwrap();
+            for (i, data) in messages.iter().enumerate() {
+                let header = ::mcap::records::MessageHeader {
+                    channel_id,
+                    sequence: i as u32,
+                    log_time: i as u64,
+                    publish_time: i as u64,
+                };
+                writer
+                    .write_to_known_channel(&header, data.as_bytes())
+                    .unwrap();
+                writer.flush().unwrap();
+            }
+            writer.finish().unwrap();
+        }
+        buffer
+    }
+
+    fn process(mut buffer: Cursor<Vec<u8>>) -> Vec<Chunk> {
+        let summary = crate::mcap::util::read_summary(&mut buffer)
+            .unwrap()
+            .unwrap();
+
+        let mut chunks = Vec::new();
+        McapJsonLayer::default()
+            .process(
+                &mut buffer,
+                &summary,
+                &McapFilter::default(),
+                &mut |chunk| chunks.push(chunk),
+            )
+            .unwrap();
+        chunks
+    }
+
+    #[test]
+    fn widened_types() {
+        let a = Fields::from(vec![
+            Field::new("speed", DataType::Int64, true),
+            Field::new("label", DataType::Null, true),
+            Field::new("mode", DataType::Boolean, true),
+        ]);
+        let b = Fields::from(vec![
+            Field::new("speed", DataType::Float64, true),
+            Field::new("label", DataType::Utf8, true),
+            Field::new("mode", DataType::Int64, true),
+            Field::new("tags", DataType::new_list(DataType::Utf8, true), true),
+        ]);
+
+        assert_eq!(
+            widen_fields(&a, &b),
+            Fields::from(vec![
+                Field::new("speed", DataType::Float64, true),
+                Field::new("label", DataType::Utf8, true),
+                Field::new("mode", DataType::Utf8, true),
+                Field::new("tags", DataType::new_list(DataType::Utf8, true), true),
+            ])
+        );
+    }
+
+    #[test]
+    fn inferred_types_across_mcap_chunks() {
+        let chunks = process(write_mcap(
+            None,
+            &[
+                r#"{ "speed": 1 }"#,
+                r#"{ "speed": 2.5, "label": "a" }"#,
+                r#"{ "label": null }"#,
+            ],
+        ));
+
+        assert_eq!(chunks.len(), 3);
+        for chunk in &chunks {
+            assert_eq!(
+                datatypes(chunk),
+                BTreeMap::from([
+                    ("label".to_owned(), DataType::Utf8),
+                    ("speed".to_owned(), DataType::Float64),
+                ])
+            );
+        }
+    }
+
+    #[test]
+    fn mismatched_json_schema_across_mcap_chunks() {
+        let chunks = process(write_mcap(
+            Some(r#"{ "type": "object", "properties": { "speed": { "type": "integer" } } }"#),
+            &[r#"{ "speed": 1 }"#, r#"{ "speed": "fast" }"#],
+        ));
+
+        assert_eq!(chunks.len(), 2);
+        for chunk in &chunks {
+            assert_eq!(
+                datatypes(chunk),
+                BTreeMap::from([("speed".to_owned(), DataType::Utf8)])
+            );
+        }
+    }
+}
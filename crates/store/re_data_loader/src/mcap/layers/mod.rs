@@ -1,19 +1,33 @@
+mod foxglove;
+mod json;
 mod protobuf;
 mod raw;
 mod recording_info;
+mod ros1;
 mod ros2;
 mod schema;
 mod stats;
 
-use re_chunk::{Chunk, EntityPath, external::nohash_hasher::IntMap};
+use re_chunk::{
+    Chunk, EntityPath,
+    external::nohash_hasher::{IntMap, IntSet},
+};
 use std::collections::{BTreeMap, BTreeSet};
 
 pub use self::{
-    protobuf::McapProtobufLayer, raw::McapRawLayer, recording_info::McapRecordingInfoLayer,
+    foxglove::McapFoxgloveLayer, json::McapJsonLayer, protobuf::McapProtobufLayer,
+    raw::McapRawLayer, recording_info::McapRecordingInfoLayer, ros1::McapRos1Layer,
     ros2::McapRos2Layer, schema::McapSchemaLayer, stats::McapStatisticLayer,
 };
 
-use super::decode::{ChannelId, McapMessageParser, ParserContext, PluginError};
+#[cfg(test)]
+pub(crate) use self::ros2::tests::tf_message as ros2_tf_message;
+
+use super::{
+    decode::{ChannelId, McapMessageParser, ParserContext, PluginError},
+    filter::McapFilter,
+    util::McapReader,
+};
 
 /// Globally unique identifier for a layer.
 #[derive(Clone, Debug, PartialOrd, Ord, PartialEq, Eq)]
@@ -54,13 +68,15 @@ pub trait Layer {
 
     /// The processing that needs to happen for this layer.
     ///
-    /// This function has access to the entire MCAP file via `mcap_bytes`.
-    // TODO(#10862): Consider abstracting over `Summary` to allow more convenient / performant indexing.
-    // For example, we probably don't want to store the entire file in memory.
+    /// This function has access to the entire MCAP file via `mcap_reader`, which should
+    /// only be read from piecewise (e.g. one MCAP chunk at a time) to keep memory usage bounded.
+    ///
+    /// Layers that work on individual messages should only process those selected by `filter`.
     fn process(
         &mut self,
-        mcap_bytes: &[u8],
+        mcap_reader: &mut dyn McapReader,
         summary: &::mcap::Summary,
+        filter: &McapFilter,
         emit: &mut dyn FnMut(Chunk),
     ) -> Result<(), PluginError>;
 }
@@ -78,6 +94,21 @@ pub trait MessageLayer {
         Ok(())
     }
 
+    /// Called once after [`Self::init`], before any MCAP chunk gets processed, with access to the
+    /// entire file.
+    ///
+    /// This is where layers gather what they need to know about the file as a whole before they
+    /// can interpret individual messages, since MCAP chunks are then processed independently.
+    /// Only the channels in `selected_channels` will be processed.
+    fn prepare(
+        &mut self,
+        _mcap_reader: &mut dyn McapReader,
+        _summary: &::mcap::Summary,
+        _selected_channels: &IntSet<u16>,
+    ) -> Result<(), PluginError> {
+        Ok(())
+    }
+
     /// Instantites a new [`McapMessageParser`] that expects `num_rows` if it is interested in the current channel.
     ///
     /// Otherwise returns `None`.
@@ -98,22 +129,37 @@ impl<T: MessageLayer> Layer for T {
 
     fn process(
         &mut self,
-        mcap_bytes: &[u8],
+        mcap_reader: &mut dyn McapReader,
         summary: &mcap::Summary,
+        filter: &McapFilter,
         emit: &mut dyn FnMut(Chunk),
     ) -> Result<(), PluginError> {
         re_tracing::profile_scope!("process-message-layer");
         self.init(summary)?;
 
+        let selected_channels = filter.selected_channels(summary);
+        self.prepare(mcap_reader, summary, &selected_channels)?;
+
         for chunk in &summary.chunk_indexes {
+            if !filter.matches_chunk(chunk, &selected_channels) {
+                continue; // never read nor decompressed
+            }
+
             re_tracing::profile_scope!("mcap-chunk");
-            let channel_counts = super::util::get_chunk_message_count(chunk, summary, mcap_bytes)?;
+            let (chunk_bytes, chunk) = super::util::read_chunk(mcap_reader, chunk)?;
+            let channel_counts =
+                super::util::get_chunk_message_count(&chunk, summary, &chunk_bytes)?;
 
             let parsers = summary
-                .read_message_indexes(mcap_bytes, chunk)?
+                .read_message_indexes(&chunk_bytes, &chunk)?
                 .iter()
+                .filter(|(channel, _)| selected_channels.contains(&channel.id))
                 .filter_map(|(channel, msg_offsets)| {
-                    let parser = self.message_parser(channel, msg_offsets.len())?;
+                    let num_rows = msg_offsets
+                        .iter()
+                        .filter(|entry| filter.matches_log_time(entry.log_time))
+                        .count();
+                    let parser = self.message_parser(channel, num_rows)?;
                     let entity_path = EntityPath::from(channel.topic.as_str());
                     let ctx = ParserContext::new(entity_path);
                     Some((ChannelId::from(channel.id), (ctx, parser)))
@@ -128,9 +174,15 @@ impl<T: MessageLayer> Layer for T {
 
             let mut decoder = super::decode::McapChunkDecoder::new(parsers);
 
-            for msg in summary.stream_chunk(mcap_bytes, chunk)? {
+            for msg in summary.stream_chunk(&chunk_bytes, &chunk)? {
                 match msg {
                     Ok(message) => {
+                        if !selected_channels.contains(&message.channel.id)
+                            || !filter.matches_log_time(message.log_time)
+                        {
+                            continue;
+                        }
+
                         if let Err(err) = decoder.decode_next(&message) {
                             re_log::error!(
                                 "Failed to decode message from MCAP file: {err} on channel: {}",
@@ -193,9 +245,12 @@ impl LayerRegistry {
     /// Creates a registry with all builtin layers.
     pub fn all() -> Self {
         Self::empty()
+            .register::<McapFoxgloveLayer>()
+            .register::<McapJsonLayer>()
             .register::<McapProtobufLayer>()
             .register::<McapRawLayer>()
             .register::<McapRecordingInfoLayer>()
+            .register::<McapRos1Layer>()
             .register::<McapRos2Layer>()
             .register::<McapSchemaLayer>()
             .register::<McapStatisticLayer>()
@@ -234,3 +289,87 @@ impl LayerRegistry {
             })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    /// Ten messages on a single `/raw` channel, logged at times 0 through 9, spread over several MCAP chunks.
+    fn raw_mcap() -> (Cursor<Vec<u8>>, ::mcap::Summary) {
+        let mut buffer = Cursor::new(Vec::new());
+        {
+            let mut writer = ::mcap::WriteOptions::new()
+                .chunk_size(Some(64))
+                .create(&mut buffer)
+                .unwrap();
+            let channel_id = writer
+                .add_channel(0, "/raw", "application/octet-stream", &Default::default())
+                .unwrap();
+            for i in 0..10_u8 {
+                let header = ::mcap::records::MessageHeader {
+                    channel_id,
+                    sequence: i.into(),
+                    log_time: i.into(),
+                    publish_time: i.into(),
+                };
+                writer.write_to_known_channel(&header, &[i; 32]).unwrap();
+            }
+            writer.finish().unwrap();
+        }
+
+        let summary = crate::mcap::util::read_summary(&mut buffer)
+            .unwrap()
+            .unwrap();
+        assert!(summary.chunk_indexes.len() > 1);
+
+        (buffer, summary)
+    }
+
+    fn process_raw(filter: &McapFilter) -> Vec<Chunk> {
+        let (mut buffer, summary) = raw_mcap();
+
+        let mut chunks = Vec::new();
+        McapRawLayer
+            .process(&mut buffer, &summary, filter, &mut |chunk| {
+                chunks.push(chunk);
+            })
+            .unwrap();
+        chunks
+    }
+
+    #[test]
+    fn process_one_mcap_chunk_at_a_time() {
+        let (_, summary) = raw_mcap();
+        let chunks = process_raw(&McapFilter::default());
+
+        assert_eq!(chunks.len(), summary.chunk_indexes.len());
+        assert_eq!(
+            chunks.iter().map(|chunk| chunk.num_rows()).sum::<usize>(),
+            10
+        );
+    }
+
+    #[test]
+    fn process_filtered() {
+        let (_, summary) = raw_mcap();
+
+        let chunks = process_raw(&McapFilter {
+            start: Some(3),
+            end: Some(6),
+            ..Default::default()
+        });
+        assert!(chunks.len() < summary.chunk_indexes.len());
+        assert_eq!(
+            chunks.iter().map(|chunk| chunk.num_rows()).sum::<usize>(),
+            4
+        );
+
+        let chunks = process_raw(&McapFilter {
+            exclude_topics: vec![glob::Pattern::new("/r*").unwrap()],
+            ..Default::default()
+        });
+        assert!(chunks.is_empty());
+    }
+}
@@ -326,10 +326,10 @@ impl MessageLayer for McapProtobufLayer {
 
     fn init(&mut self, summary: &mcap::Summary) -> Result<(), PluginError> {
         for channel in summary.channels.values() {
-            let schema = channel
-                .schema
-                .as_ref()
-                .ok_or(PluginError::NoSchema(channel.topic.clone()))?;
+            // Channels without a schema can't be protobuf-encoded.
+            let Some(schema) = channel.schema.as_ref() else {
+                continue;
+            };
 
             if schema.encoding.as_str() != "protobuf" {
                 continue;
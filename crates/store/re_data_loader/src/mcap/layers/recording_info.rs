@@ -16,8 +16,9 @@ impl Layer for McapRecordingInfoLayer {
 
     fn process(
         &mut self,
-        _mcap_bytes: &[u8],
+        _mcap_reader: &mut dyn crate::mcap::util::McapReader,
         summary: &mcap::Summary,
+        _filter: &crate::mcap::filter::McapFilter,
         emit: &mut dyn FnMut(Chunk),
     ) -> std::result::Result<(), PluginError> {
         let properties = summary
@@ -0,0 +1,172 @@
+use crate::mcap::{
+    decode::{McapMessageParser, PluginError},
+    ros1,
+    schema::{
+        RosEncoding,
+        sensor_msgs::{
+            CameraInfoMessageParser, CompressedImageMessageParser, ImageMessageParser,
+            ImuMessageParser, JointStateMessageParser, PointCloud2MessageParser,
+        },
+        std_msgs::StringMessageParser,
+    },
+};
+
+use super::{LayerIdentifier, MessageLayer};
+
+/// The top-level fields of the supported messages, in order.
+///
+/// These need to match the layout of the shared `re_mcap_ros2` definitions.
+fn expected_fields(schema_name: &str) -> Option<&'static [&'static str]> {
+    Some(match schema_name {
+        "std_msgs/String" => &["data"],
+        "sensor_msgs/JointState" => &["header", "name", "position", "velocity", "effort"],
+        "sensor_msgs/Imu" => &[
+            "header",
+            "orientation",
+            "orientation_covariance",
+            "angular_velocity",
+            "angular_velocity_covariance",
+            "linear_acceleration",
+            "linear_acceleration_covariance",
+        ],
+        "sensor_msgs/Image" => &[
+            "header",
+            "height",
+            "width",
+            "encoding",
+            "is_bigendian",
+            "step",
+            "data",
+        ],
+        "sensor_msgs/CameraInfo" => &[
+            "header",
+            "height",
+            "width",
+            "distortion_model",
+            "d",
+            "k",
+            "r",
+            "p",
+            "binning_x",
+            "binning_y",
+            "roi",
+        ],
+        "sensor_msgs/CompressedImage" => &["header", "format", "data"],
+        "sensor_msgs/PointCloud2" => &[
+            "header",
+            "height",
+            "width",
+            "fields",
+            "is_bigendian",
+            "point_step",
+            "row_step",
+            "data",
+            "is_dense",
+        ],
+        _ => return None,
+    })
+}
+
+/// Provides a set of predefined conversion of ROS1 messages, e.g. from bags converted to MCAP.
+///
+/// This shares the conversions of [`super::McapRos2Layer`] for `sensor_msgs` and `std_msgs`,
+/// decoding the messages with [`RosEncoding::Ros1`] instead.
+#[derive(Debug, Default)]
+pub struct McapRos1Layer {
+    /// The topics whose message definitions match one of the supported messages.
+    supported_topics: ahash::HashSet<String>,
+}
+
+impl MessageLayer for McapRos1Layer {
+    fn identifier() -> LayerIdentifier {
+        "ros1msg".into()
+    }
+
+    fn init(&mut self, summary: &mcap::Summary) -> Result<(), PluginError> {
+        for channel in summary.channels.values() {
+            if channel.message_encoding != "ros1" {
+                continue;
+            }
+
+            let Some(schema) = channel.schema.as_ref() else {
+                continue;
+            };
+
+            let Some(expected_fields) = expected_fields(&schema.name) else {
+                re_log::warn_once!(
+                    "Message schema {:?} is currently not supported",
+                    schema.name
+                );
+                continue;
+            };
+
+            let text =
+                std::str::from_utf8(&schema.data).map_err(|err| PluginError::InvalidSchema {
+                    schema: schema.name.clone(),
+                    source: err.into(),
+                })?;
+
+            let definitions = ros1::parse_schema(&schema.name, text).map_err(|err| {
+                PluginError::InvalidSchema {
+                    schema: schema.name.clone(),
+                    source: err.into(),
+                }
+            })?;
+
+            // ROS 1 field names are case-sensitive, but some were renamed to lowercase in ROS 2.
+            let matches_layout = definitions.first().is_some_and(|definition| {
+                definition
+                    .fields
+                    .iter()
+                    .map(|field| field.name.to_lowercase())
+                    .eq(expected_fields.iter().copied())
+            });
+
+            if matches_layout {
+                self.supported_topics.insert(channel.topic.clone());
+            } else {
+                re_log::warn_once!(
+                    "Message definition of {:?} on {:?} differs from the standard one",
+                    schema.name,
+                    channel.topic
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    fn message_parser(
+        &self,
+        channel: &mcap::Channel<'_>,
+        num_rows: usize,
+    ) -> Option<Box<dyn McapMessageParser>> {
+        if !self.supported_topics.contains(&channel.topic) {
+            return None;
+        }
+
+        let encoding = RosEncoding::Ros1;
+        Some(match channel.schema.as_ref()?.name.as_str() {
+            "std_msgs/String" => {
+                Box::new(StringMessageParser::new(num_rows).with_encoding(encoding))
+            }
+            "sensor_msgs/JointState" => {
+                Box::new(JointStateMessageParser::new(num_rows).with_encoding(encoding))
+            }
+            "sensor_msgs/Imu" => Box::new(ImuMessageParser::new(num_rows).with_encoding(encoding)),
+            "sensor_msgs/Image" => {
+                Box::new(ImageMessageParser::new(num_rows).with_encoding(encoding))
+            }
+            "sensor_msgs/CameraInfo" => {
+                Box::new(CameraInfoMessageParser::new(num_rows).with_encoding(encoding))
+            }
+            "sensor_msgs/CompressedImage" => {
+                Box::new(CompressedImageMessageParser::new(num_rows).with_encoding(encoding))
+            }
+            "sensor_msgs/PointCloud2" => {
+                Box::new(PointCloud2MessageParser::new(num_rows).with_encoding(encoding))
+            }
+            _ => return None,
+        })
+    }
+}
@@ -1,33 +1,100 @@
+use std::sync::Arc;
+
+use parking_lot::Mutex;
+use re_chunk::external::nohash_hasher::IntSet;
+
 use crate::mcap::{
-    decode::McapMessageParser,
+    decode::{McapMessageParser, PluginError},
     schema::{
         sensor_msgs::{
             CameraInfoMessageParser, CompressedImageMessageParser, ImageMessageParser,
             ImuMessageParser, JointStateMessageParser, PointCloud2MessageParser,
         },
         std_msgs::StringMessageParser,
+        tf2_msgs::{TfMessageParser, TfTree},
+        visualization_msgs::{MarkerArrayMessageParser, MarkerMessageParser, MarkerState},
     },
+    util::{McapReader, for_each_message},
 };
 
 use super::MessageLayer;
 
+const TF_SCHEMA: &str = "tf2_msgs/msg/TFMessage";
+
 /// Provides a set of predefined conversion of ROS2 messages.
 ///
 /// Additionally, this layer will output Rerun archetypes for visualization in the viewer
 /// for supported ROS2 message types.
 #[derive(Debug, Default)]
-pub struct McapRos2Layer;
+pub struct McapRos2Layer {
+    /// Shared by all `tf2_msgs/msg/TFMessage` and marker parsers, see [`TfTree`].
+    tf_tree: Arc<Mutex<TfTree>>,
+
+    /// Shared by all `visualization_msgs` parsers, see [`MarkerState`].
+    markers: Arc<Mutex<MarkerState>>,
+}
+
+impl McapRos2Layer {
+    /// Whether the messages of `channel` make up the transform tree.
+    pub(crate) fn is_transform_channel(channel: &mcap::Channel<'_>) -> bool {
+        channel.message_encoding == "cdr"
+            && channel
+                .schema
+                .as_ref()
+                .is_some_and(|schema| schema.name == TF_SCHEMA)
+    }
+
+    /// Adds the frames of a message of a transform channel to the transform tree.
+    ///
+    /// All of them must be added before any message is parsed, see [`MessageLayer::prepare`].
+    pub(crate) fn add_transform_frames(&self, msg: &mcap::Message<'_>) {
+        if let Err(err) = self.tf_tree.lock().add_message(msg) {
+            re_log::warn_once!(
+                "Failed to read transform frames on topic {:?}: {err}",
+                msg.channel.topic
+            );
+        }
+    }
+}
 
 impl MessageLayer for McapRos2Layer {
     fn identifier() -> super::LayerIdentifier {
         "ros2msg".into()
     }
 
+    fn prepare(
+        &mut self,
+        mcap_reader: &mut dyn McapReader,
+        summary: &mcap::Summary,
+        selected_channels: &IntSet<u16>,
+    ) -> Result<(), PluginError> {
+        re_tracing::profile_function!();
+
+        // Transforms are logged on the path of their frame in the tree, which must be known in
+        // full before the first chunk is parsed.
+        let tf_channels = summary
+            .channels
+            .values()
+            .filter(|channel| {
+                selected_channels.contains(&channel.id) && Self::is_transform_channel(channel)
+            })
+            .map(|channel| channel.id)
+            .collect();
+
+        for_each_message(mcap_reader, summary, &tf_channels, |msg| {
+            self.add_transform_frames(msg);
+        })
+    }
+
     fn message_parser(
         &self,
         channel: &mcap::Channel<'_>,
         num_rows: usize,
     ) -> Option<Box<dyn McapMessageParser>> {
+        if channel.message_encoding != "cdr" {
+            return None;
+        }
+
         let Some(name) = channel.schema.as_ref().map(|schema| schema.name.as_str()) else {
             re_log::warn_once!(
                 "Encountered ROS2 message without schema in channel {:?}",
@@ -46,6 +113,20 @@ impl MessageLayer for McapRos2Layer {
                 Box::new(CompressedImageMessageParser::new(num_rows))
             }
             "sensor_msgs/msg/PointCloud2" => Box::new(PointCloud2MessageParser::new(num_rows)),
+            TF_SCHEMA => {
+                let is_static = channel.topic.ends_with("tf_static");
+                Box::new(TfMessageParser::new(Arc::clone(&self.tf_tree), is_static))
+            }
+            "visualization_msgs/msg/Marker" => Box::new(MarkerMessageParser::new(
+                num_rows,
+                Arc::clone(&self.tf_tree),
+                Arc::clone(&self.markers),
+            )),
+            "visualization_msgs/msg/MarkerArray" => Box::new(MarkerArrayMessageParser::new(
+                num_rows,
+                Arc::clone(&self.tf_tree),
+                Arc::clone(&self.markers),
+            )),
             _ => {
                 re_log::warn_once!("Message schema {name:?} is currently not supported");
                 return None;
@@ -53,3 +134,101 @@ impl MessageLayer for McapRos2Layer {
         })
     }
 }
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use std::io::Cursor;
+
+    use re_chunk::{Chunk, EntityPath};
+
+    use super::*;
+    use crate::mcap::{filter::McapFilter, layers::Layer as _};
+
+    /// Encodes a `tf2_msgs/msg/TFMessage` with a single transform, as little-endian CDR.
+    pub(crate) fn tf_message(frame_id: &str, child_frame_id: &str) -> Vec<u8> {
+        fn align(buf: &mut Vec<u8>, n: usize) {
+            // Alignment is relative to the end of the 4-byte encapsulation header.
+            while (buf.len() - 4) % n != 0 {
+                buf.push(0);
+            }
+        }
+
+        fn string(buf: &mut Vec<u8>, s: &str) {
+            align(buf, 4);
+            buf.extend_from_slice(&(s.len() as u32 + 1).to_le_bytes());
+            buf.extend_from_slice(s.as_bytes());
+            buf.push(0);
+        }
+
+        let mut buf = vec![0x00, 0x01, 0x00, 0x00];
+        buf.extend_from_slice(&1_u32.to_le_bytes()); // number of transforms
+        buf.extend_from_slice(&0_i32.to_le_bytes()); // stamp.sec
+        buf.extend_from_slice(&0_u32.to_le_bytes()); // stamp.nanosec
+        string(&mut buf, frame_id);
+        string(&mut buf, child_frame_id);
+        align(&mut buf, 8);
+        for value in [0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 1.0_f64] {
+            buf.extend_from_slice(&value.to_le_bytes());
+        }
+        buf
+    }
+
+    #[test]
+    fn tf_tree_is_resolved_across_mcap_chunks() {
+        let mut buffer = Cursor::new(Vec::new());
+        {
+            let mut writer = ::mcap::Writer::new(&mut buffer).unwrap();
+            let schema_id = writer.add_schema(TF_SCHEMA, "ros2msg", &[]).unwrap();
+            let channel_id = writer
+                .add_channel(schema_id, "/tf", "cdr", &Default::default())
+                .unwrap();
+
+            // The parent of `odom` is only known from the second MCAP chunk onwards.
+            let messages = [
+                tf_message("odom", "base_link"),
+                tf_message("map", "odom"),
+                tf_message("odom", "base_link"),
+            ];
+            for (i, data) in messages.iter().enumerate() {
+                let header = ::mcap::records::MessageHeader {
+                    channel_id,
+                    sequence: i as u32,
+                    log_time: i as u64,
+                    publish_time: i as u64,
+                };
+                writer.write_to_known_channel(&header, data).unwrap();
+                writer.flush().unwrap(); // one MCAP chunk per message
+            }
+            writer.finish().unwrap();
+        }
+
+        let summary = crate::mcap::util::read_summary(&mut buffer)
+            .unwrap()
+            .unwrap();
+        assert_eq!(summary.chunk_indexes.len(), 3);
+
+        let mut chunks: Vec<Chunk> = Vec::new();
+        McapRos2Layer::default()
+            .process(
+                &mut buffer,
+                &summary,
+                &McapFilter::default(),
+                &mut |chunk| {
+                    chunks.push(chunk);
+                },
+            )
+            .unwrap();
+
+        let base_link_rows = chunks
+            .iter()
+            .filter(|chunk| chunk.entity_path() == &EntityPath::from("/tf/map/odom/base_link"))
+            .map(|chunk| chunk.num_rows())
+            .sum::<usize>();
+        assert_eq!(base_link_rows, 2);
+        assert!(
+            chunks
+                .iter()
+                .all(|chunk| chunk.entity_path() != &EntityPath::from("/tf/odom/base_link"))
+        );
+    }
+}
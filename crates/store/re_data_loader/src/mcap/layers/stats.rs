@@ -24,8 +24,9 @@ impl Layer for McapStatisticLayer {
 
     fn process(
         &mut self,
-        _mcap_bytes: &[u8],
+        _mcap_reader: &mut dyn crate::mcap::util::McapReader,
         summary: &mcap::Summary,
+        _filter: &crate::mcap::filter::McapFilter,
         emit: &mut dyn FnMut(Chunk),
     ) -> Result<(), PluginError> {
         if let Some(statistics) = summary.stats.as_ref() {
@@ -3,7 +3,10 @@
 pub mod cdr;
 pub(crate) mod dds;
 pub mod decode;
+pub mod export;
+pub mod filter;
 pub mod layers;
+pub mod ros1;
 pub mod schema;
 pub mod util;
 
@@ -0,0 +1,198 @@
+//! Parsing of ROS 1 message definitions, as found in `ros1msg` MCAP schemas.
+//!
+//! A schema consists of the definition of the message itself, followed by the definitions of
+//! all the messages that it depends on, separated by lines of `=` and prefixed with `MSG: <name>`.
+//! See <https://mcap.dev/spec/registry#ros1msg>.
+
+use super::Ros1Error;
+
+/// The length of an array field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArrayLength {
+    /// `type[]`
+    Variable,
+
+    /// `type[N]`
+    Fixed(usize),
+}
+
+/// A single field of a message, e.g. `float64[9] K`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldDefinition {
+    /// The type of the field, or of its elements for arrays, e.g. `float64` or `std_msgs/Header`.
+    pub ty: String,
+
+    pub array: Option<ArrayLength>,
+    pub name: String,
+}
+
+/// A constant of a message, e.g. `uint8 INT8 = 1`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConstantDefinition {
+    pub ty: String,
+    pub name: String,
+    pub value: String,
+}
+
+/// The definition of a single ROS 1 message type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MessageDefinition {
+    /// The full name of the message, e.g. `sensor_msgs/Image`.
+    pub name: String,
+
+    pub fields: Vec<FieldDefinition>,
+    pub constants: Vec<ConstantDefinition>,
+}
+
+impl MessageDefinition {
+    fn parse(name: String, text: &str) -> Result<Self, Ros1Error> {
+        let mut fields = Vec::new();
+        let mut constants = Vec::new();
+
+        for line in text.lines() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let invalid = || Ros1Error::InvalidDefinition(line.to_owned());
+
+            let (ty, rest) = line.split_once(char::is_whitespace).ok_or_else(invalid)?;
+            let rest = rest.trim();
+
+            if let Some((name, value)) = rest.split_once('=') {
+                constants.push(ConstantDefinition {
+                    ty: ty.to_owned(),
+                    name: name.trim().to_owned(),
+                    value: value.trim().to_owned(),
+                });
+                continue;
+            }
+
+            let (ty, array) = match ty.split_once('[') {
+                Some((ty, length)) => {
+                    let length = length.strip_suffix(']').ok_or_else(invalid)?;
+                    let array = if length.is_empty() {
+                        ArrayLength::Variable
+                    } else {
+                        ArrayLength::Fixed(length.parse().map_err(|_err| invalid())?)
+                    };
+                    (ty, Some(array))
+                }
+                None => (ty, None),
+            };
+
+            fields.push(FieldDefinition {
+                ty: ty.to_owned(),
+                array,
+                name: rest.to_owned(),
+            });
+        }
+
+        Ok(Self {
+            name,
+            fields,
+            constants,
+        })
+    }
+}
+
+/// Parses a `ros1msg` schema named `name` into the definition of the message itself, followed
+/// by the definitions of its dependencies.
+pub fn parse_schema(name: &str, text: &str) -> Result<Vec<MessageDefinition>, Ros1Error> {
+    let mut definitions = Vec::new();
+    let mut current_name = name.to_owned();
+    let mut current_text = String::new();
+
+    for line in text.lines() {
+        if line.starts_with("==") && line.trim().chars().all(|c| c == '=') {
+            definitions.push(MessageDefinition::parse(
+                std::mem::take(&mut current_name),
+                &std::mem::take(&mut current_text),
+            )?);
+        } else if let Some(dependency) = line.trim().strip_prefix("MSG:") {
+            current_name = dependency.trim().to_owned();
+        } else {
+            current_text.push_str(line);
+            current_text.push('\n');
+        }
+    }
+
+    definitions.push(MessageDefinition::parse(current_name, &current_text)?);
+
+    Ok(definitions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_camera_info() -> Result<(), Ros1Error> {
+        let definitions = parse_schema(
+            "sensor_msgs/CameraInfo",
+            "\
+# This message defines meta information for a camera.
+Header header # Metadata
+uint32 height
+string distortion_model
+float64[] D
+float64[9]  K
+RegionOfInterest roi
+
+================================================================================
+MSG: std_msgs/Header
+uint32 seq
+time stamp
+string frame_id
+
+================================================================================
+MSG: sensor_msgs/RegionOfInterest
+uint32 x_offset
+bool do_rectify
+uint8 CONSTANT = 3
+",
+        )?;
+
+        let names = definitions
+            .iter()
+            .map(|definition| definition.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            [
+                "sensor_msgs/CameraInfo",
+                "std_msgs/Header",
+                "sensor_msgs/RegionOfInterest"
+            ]
+        );
+
+        let camera_info = &definitions[0];
+        assert_eq!(
+            camera_info
+                .fields
+                .iter()
+                .map(|field| (field.ty.as_str(), field.array, field.name.as_str()))
+                .collect::<Vec<_>>(),
+            [
+                ("Header", None, "header"),
+                ("uint32", None, "height"),
+                ("string", None, "distortion_model"),
+                ("float64", Some(ArrayLength::Variable), "D"),
+                ("float64", Some(ArrayLength::Fixed(9)), "K"),
+                ("RegionOfInterest", None, "roi"),
+            ]
+        );
+
+        assert_eq!(
+            definitions[2].constants,
+            [ConstantDefinition {
+                ty: "uint8".to_owned(),
+                name: "CONSTANT".to_owned(),
+                value: "3".to_owned(),
+            }]
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,162 @@
+//! Decoding utilities for messages recorded with ROS 1, e.g. bags converted to MCAP.
+//!
+//! Unlike ROS 2, ROS 1 doesn't use CDR but its own, simpler serialization format. The schemas of
+//! such channels are `ros1msg` message definitions, which can be parsed with [`parse_schema`].
+
+mod de;
+mod definition;
+
+use serde::Deserialize;
+use thiserror::Error;
+
+pub use self::definition::{
+    ArrayLength, ConstantDefinition, FieldDefinition, MessageDefinition, parse_schema,
+};
+
+/// Decode a ROS 1 message into a `T`.
+///
+/// `T` is expected to follow the field order of the ROS 1 message definition, see
+/// [`de::Deserializer`] for how the definitions of `re_mcap_ros2` are reused.
+pub fn try_decode_message<'d, T: Deserialize<'d>>(msg: &'d [u8]) -> Result<T, Ros1Error> {
+    let mut deserializer = de::Deserializer::new(msg);
+    let value = T::deserialize(&mut deserializer)?;
+
+    if deserializer.remaining() > 0 {
+        return Err(Ros1Error::TrailingBytes(deserializer.remaining()));
+    }
+
+    Ok(value)
+}
+
+/// Errors from ROS 1 decoding.
+#[derive(Error, Debug)]
+pub enum Ros1Error {
+    #[error("Unexpected end of message: needed {needed} bytes, but only {remaining} remain")]
+    UnexpectedEof { needed: usize, remaining: usize },
+
+    #[error("Message has {0} unexpected trailing bytes")]
+    TrailingBytes(usize),
+
+    #[error("Invalid boolean value: {0}")]
+    InvalidBool(u8),
+
+    #[error("Invalid string: {0}")]
+    InvalidUtf8(std::str::Utf8Error),
+
+    #[error("The ROS 1 format is not self-describing")]
+    NotSelfDescribing,
+
+    #[error("ROS 1 messages can't contain {0}")]
+    Unsupported(&'static str),
+
+    #[error("Invalid message definition: {0:?}")]
+    InvalidDefinition(String),
+
+    #[error("{0}")]
+    Custom(String),
+}
+
+impl serde::de::Error for Ros1Error {
+    fn custom<T: std::fmt::Display>(msg: T) -> Self {
+        Self::Custom(msg.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use re_mcap_ros2::sensor_msgs::{Image, JointState, PointFieldDatatype};
+
+    use super::*;
+
+    /// Serializes values in the ROS 1 format.
+    #[derive(Default)]
+    struct Writer(Vec<u8>);
+
+    impl Writer {
+        fn u8(mut self, value: u8) -> Self {
+            self.0.push(value);
+            self
+        }
+
+        fn u32(mut self, value: u32) -> Self {
+            self.0.extend_from_slice(&value.to_le_bytes());
+            self
+        }
+
+        fn f64s(mut self, values: &[f64]) -> Self {
+            self = self.u32(values.len() as u32);
+            for value in values {
+                self.0.extend_from_slice(&value.to_le_bytes());
+            }
+            self
+        }
+
+        fn string(mut self, value: &str) -> Self {
+            self = self.u32(value.len() as u32);
+            self.0.extend_from_slice(value.as_bytes());
+            self
+        }
+
+        fn header(self, frame_id: &str) -> Self {
+            self.u32(42) // seq
+                .u32(1)
+                .u32(500)
+                .string(frame_id)
+        }
+    }
+
+    #[test]
+    fn decode_joint_state() -> Result<(), Ros1Error> {
+        let Writer(data) = Writer::default()
+            .header("base")
+            .u32(2)
+            .string("shoulder")
+            .string("elbow")
+            .f64s(&[0.5, -0.5])
+            .f64s(&[])
+            .f64s(&[1.0, 2.0]);
+
+        let joint_state: JointState = try_decode_message(&data)?;
+        assert_eq!(joint_state.header.stamp.as_nanos(), 1_000_000_500);
+        assert_eq!(joint_state.header.frame_id, "base");
+        assert_eq!(joint_state.name, ["shoulder", "elbow"]);
+        assert_eq!(joint_state.position, [0.5, -0.5]);
+        assert!(joint_state.velocity.is_empty());
+        assert_eq!(joint_state.effort, [1.0, 2.0]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn decode_image() -> Result<(), Ros1Error> {
+        let Writer(data) = Writer::default()
+            .header("camera")
+            .u32(1)
+            .u32(2)
+            .string("mono8")
+            .u8(0)
+            .u32(2)
+            .u32(2)
+            .u8(7)
+            .u8(8);
+
+        let image: Image<'_> = try_decode_message(&data)?;
+        assert_eq!((image.height, image.width), (1, 2));
+        assert_eq!(image.encoding, "mono8");
+        assert_eq!(image.data.as_ref(), [7, 8]);
+
+        assert!(matches!(
+            try_decode_message::<Image<'_>>(&data[..data.len() - 1]),
+            Err(Ros1Error::UnexpectedEof { .. })
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn decode_point_field_datatype() -> Result<(), Ros1Error> {
+        let datatype: PointFieldDatatype = try_decode_message(&[7])?;
+        assert!(matches!(datatype, PointFieldDatatype::Float32));
+        Ok(())
+    }
+}
@@ -0,0 +1,118 @@
+//! Decoding of Foxglove messages into the `serde` types in [`super::messages`].
+//!
+//! JSON messages are deserialized directly, while protobuf messages are first decoded via
+//! reflection and then fed to `serde` through [`ProtobufValue`], so that both encodings share
+//! the same message definitions.
+
+use base64::Engine as _;
+use prost_reflect::{DynamicMessage, MessageDescriptor, Value};
+use serde::{
+    Deserializer,
+    de::{
+        DeserializeOwned, Error as _, IntoDeserializer, Visitor,
+        value::{Error, MapDeserializer, SeqDeserializer},
+    },
+    forward_to_deserialize_any,
+};
+
+/// How the messages of a channel are encoded.
+#[derive(Debug, Clone)]
+pub enum MessageEncoding {
+    Protobuf(MessageDescriptor),
+    Json,
+}
+
+impl MessageEncoding {
+    /// Decodes a single message.
+    pub fn decode<T: DeserializeOwned>(&self, data: &[u8]) -> anyhow::Result<T> {
+        match self {
+            Self::Protobuf(message_descriptor) => {
+                let message = DynamicMessage::decode(message_descriptor.clone(), data)?;
+                Ok(T::deserialize(ProtobufValue(&Value::Message(message)))?)
+            }
+            Self::Json => Ok(serde_json::from_slice(data)?),
+        }
+    }
+}
+
+/// A `serde` [`Deserializer`] for a reflected protobuf value.
+///
+/// Messages are exposed as maps keyed by their protobuf field names, enums as their numeric value.
+/// Fields that are not set are omitted, so the target types should use `#[serde(default)]`.
+#[derive(Clone, Copy)]
+struct ProtobufValue<'a>(&'a Value);
+
+impl<'de> Deserializer<'de> for ProtobufValue<'_> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self.0 {
+            Value::Bool(v) => visitor.visit_bool(*v),
+            Value::I32(v) | Value::EnumNumber(v) => visitor.visit_i32(*v),
+            Value::I64(v) => visitor.visit_i64(*v),
+            Value::U32(v) => visitor.visit_u32(*v),
+            Value::U64(v) => visitor.visit_u64(*v),
+            Value::F32(v) => visitor.visit_f32(*v),
+            Value::F64(v) => visitor.visit_f64(*v),
+            Value::String(v) => visitor.visit_str(v),
+            Value::Bytes(v) => visitor.visit_bytes(v),
+            Value::List(values) => {
+                visitor.visit_seq(SeqDeserializer::new(values.iter().map(ProtobufValue)))
+            }
+            Value::Message(message) => visitor.visit_map(MapDeserializer::new(
+                message
+                    .fields()
+                    .map(|(field, value)| (field.name().to_owned(), ProtobufValue(value))),
+            )),
+            Value::Map(_) => Err(Error::custom("protobuf maps are not supported")),
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        // Unset fields are never visited in the first place.
+        visitor.visit_some(self)
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct enum identifier ignored_any
+    }
+}
+
+impl IntoDeserializer<'_, Error> for ProtobufValue<'_> {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self::Deserializer {
+        self
+    }
+}
+
+/// Deserializes a `bytes` field: raw bytes in protobuf, base64 in JSON.
+pub fn bytes<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+    struct BytesVisitor;
+
+    impl Visitor<'_> for BytesVisitor {
+        type Value = Vec<u8>;
+
+        fn expecting(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.write_str("bytes or a base64 string")
+        }
+
+        fn visit_bytes<E: serde::de::Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+            Ok(v.to_vec())
+        }
+
+        fn visit_byte_buf<E: serde::de::Error>(self, v: Vec<u8>) -> Result<Self::Value, E> {
+            Ok(v)
+        }
+
+        fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<Self::Value, E> {
+            base64::engine::general_purpose::STANDARD
+                .decode(v)
+                .map_err(E::custom)
+        }
+    }
+
+    deserializer.deserialize_any(BytesVisitor)
+}
@@ -0,0 +1,308 @@
+//! The subset of the Foxglove schemas that we know how to visualize.
+//!
+//! Based on <https://github.com/foxglove/foxglove-sdk/tree/main/schemas>.
+//! Only the fields that we make use of are declared, everything else is ignored.
+
+use serde::Deserialize;
+
+use super::de::bytes;
+
+/// A timestamp, as either a `google.protobuf.Timestamp` or a Foxglove JSON `Time`.
+#[derive(Debug, Default, Clone, Copy, Deserialize)]
+#[serde(default)]
+pub struct Time {
+    #[serde(alias = "seconds")]
+    pub sec: i64,
+
+    #[serde(alias = "nanos")]
+    pub nsec: i64,
+}
+
+impl Time {
+    pub fn as_nanos(&self) -> i64 {
+        self.sec * 1_000_000_000 + self.nsec
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, Deserialize)]
+#[serde(default)]
+pub struct Vector3 {
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+}
+
+#[derive(Debug, Default, Clone, Copy, Deserialize)]
+#[serde(default)]
+pub struct Quaternion {
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+    pub w: f64,
+}
+
+#[derive(Debug, Default, Clone, Copy, Deserialize)]
+#[serde(default)]
+pub struct Pose {
+    pub position: Vector3,
+    pub orientation: Quaternion,
+}
+
+/// A color in RGBA format, each channel in `[0, 1]`.
+#[derive(Debug, Default, Clone, Copy, Deserialize)]
+#[serde(default)]
+pub struct Color {
+    pub r: f64,
+    pub g: f64,
+    pub b: f64,
+    pub a: f64,
+}
+
+/// `foxglove.CompressedImage`
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct CompressedImage {
+    pub timestamp: Time,
+
+    #[serde(deserialize_with = "bytes")]
+    pub data: Vec<u8>,
+
+    /// `jpeg`, `png`, `webp` or `avif`.
+    pub format: String,
+}
+
+/// Numeric type of a [`PackedElementField`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(from = "i32")]
+pub enum NumericType {
+    #[default]
+    Unknown,
+    UInt8,
+    Int8,
+    UInt16,
+    Int16,
+    UInt32,
+    Int32,
+    Float32,
+    Float64,
+}
+
+impl From<i32> for NumericType {
+    fn from(value: i32) -> Self {
+        match value {
+            1 => Self::UInt8,
+            2 => Self::Int8,
+            3 => Self::UInt16,
+            4 => Self::Int16,
+            5 => Self::UInt32,
+            6 => Self::Int32,
+            7 => Self::Float32,
+            8 => Self::Float64,
+            _ => Self::Unknown,
+        }
+    }
+}
+
+/// A field present within each element of a [`PointCloud`].
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct PackedElementField {
+    pub name: String,
+    pub offset: u32,
+    pub r#type: NumericType,
+}
+
+/// `foxglove.PointCloud`
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct PointCloud {
+    pub timestamp: Time,
+    pub pose: Pose,
+    pub point_stride: u32,
+    pub fields: Vec<PackedElementField>,
+
+    /// Little-endian, `point_stride` bytes per point.
+    #[serde(deserialize_with = "bytes")]
+    pub data: Vec<u8>,
+}
+
+/// `foxglove.FrameTransform`
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct FrameTransform {
+    pub timestamp: Time,
+    pub parent_frame_id: String,
+    pub child_frame_id: String,
+    pub translation: Vector3,
+    pub rotation: Quaternion,
+}
+
+/// `foxglove.FrameTransforms`
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct FrameTransforms {
+    pub transforms: Vec<FrameTransform>,
+}
+
+/// `foxglove.LocationFix`
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct LocationFix {
+    pub timestamp: Time,
+    pub latitude: f64,
+    pub longitude: f64,
+}
+
+/// `foxglove.SceneUpdate`
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct SceneUpdate {
+    pub deletions: Vec<SceneEntityDeletion>,
+    pub entities: Vec<SceneEntity>,
+}
+
+/// `foxglove.SceneEntityDeletion`
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct SceneEntityDeletion {
+    pub timestamp: Time,
+
+    /// [`Self::MATCHING_ID`] or [`Self::ALL`].
+    pub r#type: i32,
+    pub id: String,
+}
+
+impl SceneEntityDeletion {
+    pub const MATCHING_ID: i32 = 0;
+    pub const ALL: i32 = 1;
+}
+
+/// `foxglove.SceneEntity`
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct SceneEntity {
+    pub timestamp: Time,
+    pub id: String,
+    pub arrows: Vec<ArrowPrimitive>,
+    pub cubes: Vec<CubePrimitive>,
+    pub spheres: Vec<SpherePrimitive>,
+    pub cylinders: Vec<CylinderPrimitive>,
+    pub lines: Vec<LinePrimitive>,
+    pub triangles: Vec<TriangleListPrimitive>,
+    pub texts: Vec<TextPrimitive>,
+    pub models: Vec<ModelPrimitive>,
+}
+
+/// `foxglove.ArrowPrimitive`, pointing along the x-axis of its pose.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct ArrowPrimitive {
+    pub pose: Pose,
+    pub shaft_length: f64,
+    pub shaft_diameter: f64,
+    pub head_length: f64,
+    pub color: Color,
+}
+
+/// `foxglove.CubePrimitive`
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct CubePrimitive {
+    pub pose: Pose,
+    pub size: Vector3,
+    pub color: Color,
+}
+
+/// `foxglove.SpherePrimitive`
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct SpherePrimitive {
+    pub pose: Pose,
+    pub size: Vector3,
+    pub color: Color,
+}
+
+/// `foxglove.CylinderPrimitive`, along the z-axis of its pose.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct CylinderPrimitive {
+    pub pose: Pose,
+    pub size: Vector3,
+    pub color: Color,
+}
+
+/// Type of a [`LinePrimitive`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(from = "i32")]
+pub enum LineType {
+    #[default]
+    Strip,
+    Loop,
+    List,
+}
+
+impl From<i32> for LineType {
+    fn from(value: i32) -> Self {
+        match value {
+            1 => Self::Loop,
+            2 => Self::List,
+            _ => Self::Strip,
+        }
+    }
+}
+
+/// `foxglove.LinePrimitive`
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct LinePrimitive {
+    pub r#type: LineType,
+    pub pose: Pose,
+    pub thickness: f64,
+
+    /// If true, `thickness` is in screen pixels rather than scene units.
+    pub scale_invariant: bool,
+    pub points: Vec<Vector3>,
+    pub color: Color,
+
+    /// Indices into `points`, all of them in order if empty.
+    pub indices: Vec<u32>,
+}
+
+/// `foxglove.TriangleListPrimitive`
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct TriangleListPrimitive {
+    pub pose: Pose,
+    pub points: Vec<Vector3>,
+    pub color: Color,
+
+    /// Per-vertex colors, overriding `color` if non-empty.
+    pub colors: Vec<Color>,
+
+    /// Indices into `points`, all of them in order if empty.
+    pub indices: Vec<u32>,
+}
+
+/// `foxglove.TextPrimitive`
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct TextPrimitive {
+    pub pose: Pose,
+    pub color: Color,
+    pub text: String,
+}
+
+/// `foxglove.ModelPrimitive`
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct ModelPrimitive {
+    pub pose: Pose,
+    pub scale: Vector3,
+
+    /// Only embedded models are supported.
+    pub url: String,
+    pub media_type: String,
+
+    #[serde(deserialize_with = "bytes")]
+    pub data: Vec<u8>,
+}
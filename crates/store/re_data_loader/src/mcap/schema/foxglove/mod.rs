@@ -0,0 +1,9 @@
+//! Support for the [Foxglove well-known schemas](https://docs.foxglove.dev/docs/visualization/message-schemas/introduction),
+//! in both their protobuf and JSON encodings.
+
+mod de;
+mod messages;
+mod parser;
+
+pub use de::MessageEncoding;
+pub use parser::{FoxgloveMessageParser, FoxgloveSchema};
@@ -0,0 +1,718 @@
+use std::{collections::BTreeMap, sync::Arc};
+
+use parking_lot::Mutex;
+use re_chunk::{Chunk, EntityPath, TimePoint};
+use re_log_types::EntityPathPart;
+use re_types::{
+    AsComponents as _,
+    archetypes::{
+        Arrows3D, Asset3D, Boxes3D, Clear, Cylinders3D, Ellipsoids3D, EncodedImage, GeoPoints,
+        LineStrips3D, Mesh3D, Points3D, Transform3D,
+    },
+    components::{self, MediaType, Radius},
+    external::glam::{DQuat, DVec3},
+};
+
+use crate::mcap::{
+    decode::{McapMessageParser, ParserContext, RowsPerEntity},
+    schema::tf2_msgs::TfTree,
+};
+
+use super::{
+    de::MessageEncoding,
+    messages::{
+        self, CompressedImage, FrameTransform, FrameTransforms, LineType, LocationFix, NumericType,
+        PointCloud, Pose, SceneEntity, SceneEntityDeletion, SceneUpdate,
+    },
+};
+
+/// The Foxglove schemas that we map onto Rerun archetypes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FoxgloveSchema {
+    CompressedImage,
+    PointCloud,
+    FrameTransform,
+    FrameTransforms,
+    LocationFix,
+    SceneUpdate,
+}
+
+impl FoxgloveSchema {
+    /// Recognizes a schema from its fully qualified name, e.g. `foxglove.PointCloud`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "foxglove.CompressedImage" => Self::CompressedImage,
+            "foxglove.PointCloud" => Self::PointCloud,
+            "foxglove.FrameTransform" => Self::FrameTransform,
+            "foxglove.FrameTransforms" => Self::FrameTransforms,
+            "foxglove.LocationFix" => Self::LocationFix,
+            "foxglove.SceneUpdate" => Self::SceneUpdate,
+            _ => return None,
+        })
+    }
+
+    /// Records the parents of the frames of a `FrameTransform(s)` message in `tf_tree`.
+    ///
+    /// Does nothing for the other schemas.
+    pub fn add_to_tf_tree(
+        self,
+        encoding: &MessageEncoding,
+        data: &[u8],
+        tf_tree: &mut TfTree,
+    ) -> anyhow::Result<()> {
+        let transforms = match self {
+            Self::FrameTransform => vec![encoding.decode::<FrameTransform>(data)?],
+            Self::FrameTransforms => encoding.decode::<FrameTransforms>(data)?.transforms,
+            Self::CompressedImage | Self::PointCloud | Self::LocationFix | Self::SceneUpdate => {
+                return Ok(());
+            }
+        };
+
+        for transform in transforms {
+            tf_tree.set_parent(&transform.child_frame_id, &transform.parent_frame_id);
+        }
+
+        Ok(())
+    }
+}
+
+/// Parses messages of one of the [`FoxgloveSchema`]s into Rerun archetypes.
+///
+/// Most messages are logged on the entity of their topic. The exceptions are transforms, which
+/// are logged on the entity of their child frame (see [`TfTree`]), and scene entities, which are
+/// logged on `<topic>/<id>`.
+///
+/// There is one parser per channel and MCAP chunk, so scene updates and deletions are mapped onto
+/// clears that don't depend on what previous messages contained.
+pub struct FoxgloveMessageParser {
+    schema: FoxgloveSchema,
+    encoding: MessageEncoding,
+    rows: RowsPerEntity,
+
+    tf_tree: Arc<Mutex<TfTree>>,
+    transforms_per_frame: BTreeMap<String, Vec<(TimePoint, Transform3D)>>,
+}
+
+impl FoxgloveMessageParser {
+    /// Create a new [`FoxgloveMessageParser`]
+    pub fn new(
+        schema: FoxgloveSchema,
+        encoding: MessageEncoding,
+        tf_tree: Arc<Mutex<TfTree>>,
+    ) -> Self {
+        Self {
+            schema,
+            encoding,
+            rows: Default::default(),
+            tf_tree,
+            transforms_per_frame: Default::default(),
+        }
+    }
+
+    fn add_frame_transform(&mut self, msg: &mcap::Message<'_>, transform: FrameTransform) {
+        let FrameTransform {
+            timestamp,
+            parent_frame_id,
+            child_frame_id,
+            translation,
+            rotation,
+        } = transform;
+
+        // Usually a no-op, since the layer builds the whole tree before parsing any chunk.
+        self.tf_tree
+            .lock()
+            .set_parent(&child_frame_id, &parent_frame_id);
+
+        let transform = Transform3D::update_fields()
+            .with_translation([translation.x, translation.y, translation.z])
+            .with_quaternion(to_quaternion(to_dquat(&rotation)));
+
+        self.transforms_per_frame
+            .entry(child_frame_id)
+            .or_default()
+            .push((
+                RowsPerEntity::timepoint(msg, timestamp.as_nanos()),
+                transform,
+            ));
+    }
+
+    fn add_scene_update(
+        &mut self,
+        topic_path: &EntityPath,
+        msg: &mcap::Message<'_>,
+        update: SceneUpdate,
+    ) {
+        let SceneUpdate {
+            deletions,
+            entities,
+        } = update;
+
+        for SceneEntityDeletion {
+            timestamp,
+            r#type,
+            id,
+        } in deletions
+        {
+            let entity_path = match r#type {
+                SceneEntityDeletion::MATCHING_ID => scene_entity_path(topic_path, &id),
+                SceneEntityDeletion::ALL => topic_path.clone(),
+                r#type => {
+                    re_log::warn_once!("Unknown scene entity deletion type {type}");
+                    continue;
+                }
+            };
+
+            self.rows.push(
+                entity_path,
+                RowsPerEntity::timepoint(msg, timestamp.as_nanos()),
+                Clear::recursive().as_serialized_batches(),
+            );
+        }
+
+        for entity in entities {
+            let entity_path = scene_entity_path(topic_path, &entity.id);
+            let timepoint = RowsPerEntity::timepoint(msg, entity.timestamp.as_nanos());
+
+            // An update replaces everything there was about this entity, which might have been
+            // logged by any previous message.
+            self.rows.push(
+                entity_path.clone(),
+                timepoint.clone(),
+                Clear::recursive().as_serialized_batches(),
+            );
+
+            add_scene_entity(&mut self.rows, entity_path, &timepoint, entity);
+        }
+    }
+}
+
+impl McapMessageParser for FoxgloveMessageParser {
+    fn append(&mut self, ctx: &mut ParserContext, msg: &mcap::Message<'_>) -> anyhow::Result<()> {
+        re_tracing::profile_function!();
+
+        let topic_path = ctx.entity_path();
+
+        match self.schema {
+            FoxgloveSchema::CompressedImage => {
+                let CompressedImage {
+                    timestamp,
+                    data,
+                    format: _,
+                } = self.encoding.decode(&msg.data)?;
+
+                self.rows.push(
+                    topic_path.clone(),
+                    RowsPerEntity::timepoint(msg, timestamp.as_nanos()),
+                    EncodedImage::from_file_contents(data).as_serialized_batches(),
+                );
+            }
+
+            FoxgloveSchema::PointCloud => {
+                let point_cloud: PointCloud = self.encoding.decode(&msg.data)?;
+                let timepoint = RowsPerEntity::timepoint(msg, point_cloud.timestamp.as_nanos());
+
+                if let Some(points) = point_cloud_to_points(&point_cloud) {
+                    self.rows.push(
+                        topic_path.clone(),
+                        timepoint,
+                        pose_to_transform(&point_cloud.pose)
+                            .as_serialized_batches()
+                            .into_iter()
+                            .chain(points.as_serialized_batches()),
+                    );
+                }
+            }
+
+            FoxgloveSchema::FrameTransform => {
+                let transform: FrameTransform = self.encoding.decode(&msg.data)?;
+                self.add_frame_transform(msg, transform);
+            }
+
+            FoxgloveSchema::FrameTransforms => {
+                let FrameTransforms { transforms } = self.encoding.decode(&msg.data)?;
+                for transform in transforms {
+                    self.add_frame_transform(msg, transform);
+                }
+            }
+
+            FoxgloveSchema::LocationFix => {
+                let LocationFix {
+                    timestamp,
+                    latitude,
+                    longitude,
+                } = self.encoding.decode(&msg.data)?;
+
+                self.rows.push(
+                    topic_path.clone(),
+                    RowsPerEntity::timepoint(msg, timestamp.as_nanos()),
+                    GeoPoints::from_lat_lon([(latitude, longitude)]).as_serialized_batches(),
+                );
+            }
+
+            FoxgloveSchema::SceneUpdate => {
+                let update: SceneUpdate = self.encoding.decode(&msg.data)?;
+                let topic_path = topic_path.clone();
+                self.add_scene_update(&topic_path, msg, update);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn finalize(self: Box<Self>, _ctx: ParserContext) -> anyhow::Result<Vec<Chunk>> {
+        let Self {
+            schema: _,
+            encoding: _,
+            mut rows,
+            tf_tree,
+            transforms_per_frame,
+        } = *self;
+
+        {
+            let tf_tree = tf_tree.lock();
+            for (frame_id, transforms) in transforms_per_frame {
+                let entity_path = tf_tree.entity_path(&frame_id);
+                for (timepoint, transform) in transforms {
+                    rows.push(
+                        entity_path.clone(),
+                        timepoint,
+                        transform.as_serialized_batches(),
+                    );
+                }
+            }
+        }
+
+        rows.into_chunks()
+    }
+}
+
+fn scene_entity_path(topic_path: &EntityPath, id: &str) -> EntityPath {
+    if id.is_empty() {
+        topic_path.clone()
+    } else {
+        topic_path.join(&EntityPath::from(vec![EntityPathPart::new(id)]))
+    }
+}
+
+fn to_dvec3(v: &messages::Vector3) -> DVec3 {
+    DVec3::new(v.x, v.y, v.z)
+}
+
+fn to_dquat(q: &messages::Quaternion) -> DQuat {
+    let q = DQuat::from_xyzw(q.x, q.y, q.z, q.w);
+    if q.length_squared() == 0.0 {
+        // Unset orientations are all zeroes in protobuf.
+        DQuat::IDENTITY
+    } else {
+        q.normalize()
+    }
+}
+
+fn to_position(v: DVec3) -> [f32; 3] {
+    v.as_vec3().to_array()
+}
+
+fn to_quaternion(q: DQuat) -> re_types::datatypes::Quaternion {
+    re_types::datatypes::Quaternion::from_xyzw(q.as_quat().to_array())
+}
+
+fn to_color(color: &messages::Color) -> components::Color {
+    let to_u8 = |c: f64| (c.clamp(0.0, 1.0) * 255.0).round() as u8;
+    components::Color::from_unmultiplied_rgba(
+        to_u8(color.r),
+        to_u8(color.g),
+        to_u8(color.b),
+        to_u8(color.a),
+    )
+}
+
+fn pose_to_transform(pose: &Pose) -> Transform3D {
+    Transform3D::update_fields()
+        .with_translation(to_position(to_dvec3(&pose.position)))
+        .with_quaternion(to_quaternion(to_dquat(&pose.orientation)))
+}
+
+/// Applies the pose to points expressed in its local frame.
+fn transform_points<'a>(
+    pose: &Pose,
+    points: impl IntoIterator<Item = &'a messages::Vector3>,
+) -> impl Iterator<Item = [f32; 3]> {
+    let rotation = to_dquat(&pose.orientation);
+    let translation = to_dvec3(&pose.position);
+    points
+        .into_iter()
+        .map(move |point| to_position(rotation * to_dvec3(point) + translation))
+}
+
+/// Reads a single little-endian value out of a packed point.
+fn read_field(point: &[u8], offset: usize, numeric_type: NumericType) -> Option<f64> {
+    fn read<const N: usize>(point: &[u8], offset: usize) -> Option<[u8; N]> {
+        point.get(offset..offset + N)?.try_into().ok()
+    }
+
+    Some(match numeric_type {
+        NumericType::Unknown => return None,
+        NumericType::UInt8 => u8::from_le_bytes(read(point, offset)?) as f64,
+        NumericType::Int8 => i8::from_le_bytes(read(point, offset)?) as f64,
+        NumericType::UInt16 => u16::from_le_bytes(read(point, offset)?) as f64,
+        NumericType::Int16 => i16::from_le_bytes(read(point, offset)?) as f64,
+        NumericType::UInt32 => u32::from_le_bytes(read(point, offset)?) as f64,
+        NumericType::Int32 => i32::from_le_bytes(read(point, offset)?) as f64,
+        NumericType::Float32 => f32::from_le_bytes(read(point, offset)?) as f64,
+        NumericType::Float64 => f64::from_le_bytes(read(point, offset)?),
+    })
+}
+
+fn point_cloud_to_points(point_cloud: &PointCloud) -> Option<Points3D> {
+    let PointCloud {
+        point_stride,
+        fields,
+        data,
+        ..
+    } = point_cloud;
+
+    let field = |name: &str| {
+        fields
+            .iter()
+            .find(|field| field.name == name)
+            .map(|field| (field.offset as usize, field.r#type))
+    };
+
+    let (Some(x), Some(y), Some(z)) = (field("x"), field("y"), field("z")) else {
+        re_log::warn_once!("Point clouds without `x`, `y` and `z` fields are not supported");
+        return None;
+    };
+
+    if *point_stride == 0 {
+        return None;
+    }
+    let points = data.chunks_exact(*point_stride as usize);
+
+    let positions = points
+        .clone()
+        .map(|point| {
+            [x, y, z].map(|(offset, numeric_type)| {
+                read_field(point, offset, numeric_type).unwrap_or_default() as f32
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let mut archetype = Points3D::new(positions);
+
+    if let (Some(red), Some(green), Some(blue)) = (field("red"), field("green"), field("blue")) {
+        let alpha = field("alpha");
+        let channel = |point: &[u8], (offset, numeric_type): (usize, NumericType)| {
+            let value = read_field(point, offset, numeric_type).unwrap_or_default();
+            if numeric_type == NumericType::UInt8 {
+                value as u8
+            } else {
+                (value.clamp(0.0, 1.0) * 255.0).round() as u8
+            }
+        };
+
+        archetype = archetype.with_colors(points.map(|point| {
+            components::Color::from_unmultiplied_rgba(
+                channel(point, red),
+                channel(point, green),
+                channel(point, blue),
+                alpha.map_or(255, |alpha| channel(point, alpha)),
+            )
+        }));
+    }
+
+    Some(archetype)
+}
+
+/// Logs all the primitives of a scene entity, in the entity's frame.
+fn add_scene_entity(
+    rows: &mut RowsPerEntity,
+    entity_path: EntityPath,
+    timepoint: &TimePoint,
+    entity: SceneEntity,
+) {
+    let SceneEntity {
+        timestamp: _,
+        id: _,
+        arrows,
+        cubes,
+        spheres,
+        cylinders,
+        lines,
+        triangles,
+        texts,
+        models,
+    } = entity;
+
+    let mut batches = Vec::new();
+
+    if !arrows.is_empty() {
+        batches.extend(
+            Arrows3D::from_vectors(arrows.iter().map(|arrow| {
+                let length = arrow.shaft_length + arrow.head_length;
+                to_position(to_dquat(&arrow.pose.orientation) * DVec3::new(length, 0.0, 0.0))
+            }))
+            .with_origins(
+                arrows
+                    .iter()
+                    .map(|arrow| to_position(to_dvec3(&arrow.pose.position))),
+            )
+            .with_radii(
+                arrows
+                    .iter()
+                    .map(|arrow| (arrow.shaft_diameter * 0.5) as f32),
+            )
+            .with_colors(arrows.iter().map(|arrow| to_color(&arrow.color)))
+            .as_serialized_batches(),
+        );
+    }
+
+    if !cubes.is_empty() {
+        batches.extend(
+            Boxes3D::from_centers_and_half_sizes(
+                cubes
+                    .iter()
+                    .map(|cube| to_position(to_dvec3(&cube.pose.position))),
+                cubes
+                    .iter()
+                    .map(|cube| to_position(to_dvec3(&cube.size) * 0.5)),
+            )
+            .with_quaternions(
+                cubes
+                    .iter()
+                    .map(|cube| to_quaternion(to_dquat(&cube.pose.orientation))),
+            )
+            .with_colors(cubes.iter().map(|cube| to_color(&cube.color)))
+            .as_serialized_batches(),
+        );
+    }
+
+    if !spheres.is_empty() {
+        batches.extend(
+            Ellipsoids3D::from_centers_and_half_sizes(
+                spheres
+                    .iter()
+                    .map(|sphere| to_position(to_dvec3(&sphere.pose.position))),
+                spheres
+                    .iter()
+                    .map(|sphere| to_position(to_dvec3(&sphere.size) * 0.5)),
+            )
+            .with_quaternions(
+                spheres
+                    .iter()
+                    .map(|sphere| to_quaternion(to_dquat(&sphere.pose.orientation))),
+            )
+            .with_colors(spheres.iter().map(|sphere| to_color(&sphere.color)))
+            .as_serialized_batches(),
+        );
+    }
+
+    if !cylinders.is_empty() {
+        batches.extend(
+            Cylinders3D::from_lengths_and_radii(
+                cylinders.iter().map(|cylinder| cylinder.size.z as f32),
+                cylinders
+                    .iter()
+                    .map(|cylinder| (cylinder.size.x * 0.5) as f32),
+            )
+            .with_centers(
+                cylinders
+                    .iter()
+                    .map(|cylinder| to_position(to_dvec3(&cylinder.pose.position))),
+            )
+            .with_quaternions(
+                cylinders
+                    .iter()
+                    .map(|cylinder| to_quaternion(to_dquat(&cylinder.pose.orientation))),
+            )
+            .with_colors(cylinders.iter().map(|cylinder| to_color(&cylinder.color)))
+            .as_serialized_batches(),
+        );
+    }
+
+    if !lines.is_empty() {
+        let mut strips = Vec::new();
+        let mut radii = Vec::new();
+        let mut colors = Vec::new();
+
+        for line in &lines {
+            let points = transform_points(&line.pose, &line.points).collect::<Vec<_>>();
+            let points = if line.indices.is_empty() {
+                points
+            } else {
+                line.indices
+                    .iter()
+                    .filter_map(|index| points.get(*index as usize).copied())
+                    .collect()
+            };
+
+            let line_strips = match line.r#type {
+                LineType::Strip => vec![points],
+                LineType::Loop => {
+                    let mut points = points;
+                    if let Some(first) = points.first().copied() {
+                        points.push(first);
+                    }
+                    vec![points]
+                }
+                LineType::List => points.chunks_exact(2).map(<[_]>::to_vec).collect(),
+            };
+
+            let radius = if line.scale_invariant {
+                Radius::new_ui_points((line.thickness * 0.5) as f32)
+            } else {
+                Radius::new_scene_units((line.thickness * 0.5) as f32)
+            };
+
+            radii.extend(std::iter::repeat_n(radius, line_strips.len()));
+            colors.extend(std::iter::repeat_n(
+                to_color(&line.color),
+                line_strips.len(),
+            ));
+            strips.extend(line_strips);
+        }
+
+        batches.extend(
+            LineStrips3D::new(strips)
+                .with_radii(radii)
+                .with_colors(colors)
+                .as_serialized_batches(),
+        );
+    }
+
+    if !triangles.is_empty() {
+        // All triangle lists are merged into a single mesh.
+        let mut positions = Vec::new();
+        let mut vertex_colors = Vec::new();
+        let mut indices = Vec::new();
+
+        for triangle_list in &triangles {
+            let offset = positions.len() as u32;
+            positions.extend(transform_points(&triangle_list.pose, &triangle_list.points));
+
+            let num_points = triangle_list.points.len();
+            if triangle_list.colors.len() == num_points {
+                vertex_colors.extend(triangle_list.colors.iter().map(to_color));
+            } else {
+                vertex_colors.extend(std::iter::repeat_n(
+                    to_color(&triangle_list.color),
+                    num_points,
+                ));
+            }
+
+            let list_indices = if triangle_list.indices.is_empty() {
+                (0..num_points as u32).collect()
+            } else {
+                triangle_list.indices.clone()
+            };
+            indices.extend(
+                list_indices
+                    .chunks_exact(3)
+                    .map(|triangle| [triangle[0], triangle[1], triangle[2]].map(|i| i + offset)),
+            );
+        }
+
+        batches.extend(
+            Mesh3D::new(positions)
+                .with_triangle_indices(indices)
+                .with_vertex_colors(vertex_colors)
+                .as_serialized_batches(),
+        );
+    }
+
+    if !texts.is_empty() {
+        batches.extend(
+            Points3D::new(
+                texts
+                    .iter()
+                    .map(|text| to_position(to_dvec3(&text.pose.position))),
+            )
+            .with_labels(texts.iter().map(|text| text.text.clone()))
+            .with_colors(texts.iter().map(|text| to_color(&text.color)))
+            .with_show_labels(true)
+            .as_serialized_batches(),
+        );
+    }
+
+    // There can only be a single asset per entity.
+    for (index, model) in models.into_iter().enumerate() {
+        if model.data.is_empty() {
+            re_log::warn_once!(
+                "Models referenced by URL are not supported: {:?}",
+                model.url
+            );
+            continue;
+        }
+
+        let media_type = (!model.media_type.is_empty()).then(|| MediaType::from(model.media_type));
+        rows.push(
+            entity_path.join(&EntityPath::from(format!("models/{index}"))),
+            timepoint.clone(),
+            pose_to_transform(&model.pose)
+                .with_scale(to_position(to_dvec3(&model.scale)))
+                .as_serialized_batches()
+                .into_iter()
+                .chain(Asset3D::from_file_contents(model.data, media_type).as_serialized_batches()),
+        );
+    }
+
+    rows.push(entity_path, timepoint.clone(), batches);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mcap::util::test_message;
+
+    #[test]
+    fn scene_update_entity_paths() -> anyhow::Result<()> {
+        let topic_path = EntityPath::from("/scene");
+        let msg = test_message("/scene", "json");
+
+        let mut parser = FoxgloveMessageParser::new(
+            FoxgloveSchema::SceneUpdate,
+            MessageEncoding::Json,
+            Default::default(),
+        );
+
+        for update in [
+            r#"{
+                "entities": [
+                    {
+                        "timestamp": { "sec": 1, "nsec": 0 },
+                        "id": "robot",
+                        "cubes": [{ "size": { "x": 1, "y": 2, "z": 3 }, "color": { "r": 1, "a": 1 } }],
+                        "texts": [{ "text": "hello" }]
+                    },
+                    {
+                        "timestamp": { "sec": 1, "nsec": 0 },
+                        "id": "path",
+                        "lines": [{ "type": 1, "thickness": 0.1, "points": [{ "x": 0 }, { "x": 1 }] }]
+                    }
+                ]
+            }"#,
+            // Replaces the previous `robot`.
+            r#"{ "entities": [{ "timestamp": { "sec": 2, "nsec": 0 }, "id": "robot" }] }"#,
+            r#"{ "deletions": [{ "timestamp": { "sec": 3, "nsec": 0 }, "type": 1 }] }"#,
+        ] {
+            let update = MessageEncoding::Json.decode(update.as_bytes())?;
+            parser.add_scene_update(&topic_path, &msg, update);
+        }
+
+        let chunks = parser.rows.into_chunks()?;
+
+        let num_rows_per_entity = chunks
+            .iter()
+            .map(|chunk| (chunk.entity_path().to_string(), chunk.num_rows()))
+            .collect::<BTreeMap<_, _>>();
+        assert_eq!(
+            num_rows_per_entity,
+            BTreeMap::from([
+                ("/scene".to_owned(), 1),
+                ("/scene/path".to_owned(), 2),
+                ("/scene/robot".to_owned(), 4),
+            ])
+        );
+
+        Ok(())
+    }
+}
@@ -1,5 +1,8 @@
+pub mod foxglove;
 pub mod sensor_msgs;
 pub mod std_msgs;
+pub mod tf2_msgs;
+pub mod visualization_msgs;
 
 use std::sync::Arc;
 
@@ -8,6 +11,32 @@ use arrow::{
     datatypes::{DataType, Field},
 };
 use re_types::{Loggable as _, components};
+use serde::Deserialize;
+
+use super::{cdr, ros1};
+
+/// The wire format of ROS messages, which depends on the ROS version that recorded them.
+///
+/// The message definitions of `re_mcap_ros2` are shared between both versions.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum RosEncoding {
+    /// ROS 2 messages, see [`cdr::try_decode_message`].
+    #[default]
+    Cdr,
+
+    /// ROS 1 messages, see [`ros1::try_decode_message`].
+    Ros1,
+}
+
+impl RosEncoding {
+    /// Decode a single message into a `T`.
+    pub fn decode<'d, T: Deserialize<'d>>(self, msg: &'d [u8]) -> anyhow::Result<T> {
+        Ok(match self {
+            Self::Cdr => cdr::try_decode_message(msg)?,
+            Self::Ros1 => ros1::try_decode_message(msg)?,
+        })
+    }
+}
 
 pub(crate) fn fixed_size_list_builder<T: arrow::array::ArrayBuilder + Default>(
     value_length: i32,
@@ -11,9 +11,8 @@ use re_mcap_ros2::sensor_msgs;
 use re_types::{ComponentDescriptor, archetypes::Pinhole};
 
 use crate::mcap::{
-    cdr,
     decode::{McapMessageParser, ParserContext, PluginError},
-    schema::fixed_size_list_builder,
+    schema::{RosEncoding, fixed_size_list_builder},
 };
 
 /// Plugin that parses `sensor_msgs/msg/CameraInfo` messages.
@@ -34,6 +33,8 @@ pub struct CameraInfoMessageParser {
     frame_ids: FixedSizeListBuilder<StringBuilder>,
     image_from_cameras: Vec<[f32; 9]>,
     resolutions: Vec<(f32, f32)>,
+
+    encoding: RosEncoding,
 }
 
 impl CameraInfoMessageParser {
@@ -73,8 +74,15 @@ impl CameraInfoMessageParser {
             frame_ids: fixed_size_list_builder(1, num_rows),
             image_from_cameras: Vec::with_capacity(num_rows),
             resolutions: Vec::with_capacity(num_rows),
+            encoding: RosEncoding::default(),
         }
     }
+
+    /// Decode the messages from the given wire format instead of CDR.
+    pub fn with_encoding(mut self, encoding: RosEncoding) -> Self {
+        self.encoding = encoding;
+        self
+    }
 }
 
 impl McapMessageParser for CameraInfoMessageParser {
@@ -91,7 +99,7 @@ impl McapMessageParser for CameraInfoMessageParser {
             binning_x,
             binning_y,
             roi,
-        } = cdr::try_decode_message::<sensor_msgs::CameraInfo>(&msg.data)?;
+        } = self.encoding.decode::<sensor_msgs::CameraInfo>(&msg.data)?;
 
         // add the sensor timestamp to the context, `log_time` and `publish_time` are added automatically
         ctx.add_time_cell(
@@ -182,6 +190,7 @@ impl McapMessageParser for CameraInfoMessageParser {
             mut rois,
             image_from_cameras,
             resolutions,
+            encoding: _,
         } = *self;
 
         let entity_path = ctx.entity_path().clone();
@@ -11,8 +11,8 @@ use re_types::{
 };
 
 use crate::mcap::{
-    cdr,
     decode::{McapMessageParser, ParserContext, PluginError},
+    schema::RosEncoding,
 };
 
 /// Plugin that parses `sensor_msgs/msg/CompressedImage` messages.
@@ -23,6 +23,8 @@ pub struct CompressedImageMessageParser {
     blobs: Vec<Vec<u8>>,
     formats: FixedSizeListBuilder<StringBuilder>,
     is_h264: bool,
+
+    encoding: RosEncoding,
 }
 
 impl CompressedImageMessageParser {
@@ -33,8 +35,15 @@ impl CompressedImageMessageParser {
             blobs: Vec::with_capacity(num_rows),
             formats: FixedSizeListBuilder::with_capacity(StringBuilder::new(), 1, num_rows),
             is_h264: false,
+            encoding: RosEncoding::default(),
         }
     }
+
+    /// Decode the messages from the given wire format instead of CDR.
+    pub fn with_encoding(mut self, encoding: RosEncoding) -> Self {
+        self.encoding = encoding;
+        self
+    }
 }
 
 impl McapMessageParser for CompressedImageMessageParser {
@@ -44,7 +53,9 @@ impl McapMessageParser for CompressedImageMessageParser {
             header,
             data,
             format,
-        } = cdr::try_decode_message::<sensor_msgs::CompressedImage<'_>>(&msg.data)?;
+        } = self
+            .encoding
+            .decode::<sensor_msgs::CompressedImage<'_>>(&msg.data)?;
 
         // add the sensor timestamp to the context, `log_time` and `publish_time` are added automatically
         ctx.add_time_cell(
@@ -71,6 +82,7 @@ impl McapMessageParser for CompressedImageMessageParser {
             blobs,
             mut formats,
             is_h264,
+            encoding: _,
         } = *self;
 
         let entity_path = ctx.entity_path().clone();
@@ -7,8 +7,8 @@ use re_types::{
 };
 
 use crate::mcap::{
-    cdr,
     decode::{McapMessageParser, ParserContext, PluginError},
+    schema::RosEncoding,
 };
 
 /// Plugin that parses `sensor_msgs/msg/CompressedImage` messages.
@@ -22,6 +22,8 @@ pub struct ImageMessageParser {
     blobs: Vec<Vec<u8>>,
     image_formats: Vec<ImageFormat>,
     is_depth_image: bool,
+
+    encoding: RosEncoding,
 }
 
 impl ImageMessageParser {
@@ -30,8 +32,15 @@ impl ImageMessageParser {
             blobs: Vec::with_capacity(num_rows),
             image_formats: Vec::with_capacity(num_rows),
             is_depth_image: false,
+            encoding: RosEncoding::default(),
         }
     }
+
+    /// Decode the messages from the given wire format instead of CDR.
+    pub fn with_encoding(mut self, encoding: RosEncoding) -> Self {
+        self.encoding = encoding;
+        self
+    }
 }
 
 impl McapMessageParser for ImageMessageParser {
@@ -47,7 +56,7 @@ impl McapMessageParser for ImageMessageParser {
             encoding,
             is_bigendian,
             step,
-        } = cdr::try_decode_message::<sensor_msgs::Image<'_>>(&msg.data)?;
+        } = self.encoding.decode::<sensor_msgs::Image<'_>>(&msg.data)?;
 
         // add the sensor timestamp to the context, `log_time` and `publish_time` are added automatically
         ctx.add_time_cell(
@@ -74,6 +83,7 @@ impl McapMessageParser for ImageMessageParser {
             blobs,
             image_formats,
             is_depth_image,
+            encoding: _,
         } = *self;
 
         let entity_path = ctx.entity_path().clone();
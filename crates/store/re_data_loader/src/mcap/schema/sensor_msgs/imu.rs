@@ -10,8 +10,8 @@ use re_types::{
 };
 
 use crate::mcap::{
-    cdr,
     decode::{McapMessageParser, ParserContext, PluginError},
+    schema::RosEncoding,
 };
 
 /// Plugin that parses `sensor_msgs/msg/Imu` messages.
@@ -31,6 +31,8 @@ pub struct ImuMessageParser {
     orientation_covariance: FixedSizeListBuilder<Float64Builder>,
     angular_velocity_covariance: FixedSizeListBuilder<Float64Builder>,
     linear_acceleration_covariance: FixedSizeListBuilder<Float64Builder>,
+
+    encoding: RosEncoding,
 }
 
 impl ImuMessageParser {
@@ -44,9 +46,16 @@ impl ImuMessageParser {
             orientation_covariance: fixed_size_list_builder(9, num_rows),
             angular_velocity_covariance: fixed_size_list_builder(9, num_rows),
             linear_acceleration_covariance: fixed_size_list_builder(9, num_rows),
+            encoding: RosEncoding::default(),
         }
     }
 
+    /// Decode the messages from the given wire format instead of CDR.
+    pub fn with_encoding(mut self, encoding: RosEncoding) -> Self {
+        self.encoding = encoding;
+        self
+    }
+
     /// Helper function to create a metadata chunk for the Imu messages.
     fn metadata_chunk(entity_path: EntityPath) -> ChunkResult<Chunk> {
         Chunk::builder(entity_path)
@@ -68,7 +77,9 @@ impl ImuMessageParser {
 
 impl McapMessageParser for ImuMessageParser {
     fn append(&mut self, ctx: &mut ParserContext, msg: &mcap::Message<'_>) -> anyhow::Result<()> {
-        let imu = cdr::try_decode_message::<sensor_msgs::Imu>(msg.data.as_ref())
+        let imu = self
+            .encoding
+            .decode::<sensor_msgs::Imu>(msg.data.as_ref())
             .map_err(|err| PluginError::Other(anyhow::anyhow!(err)))?;
 
         // add the sensor timestamp to the context, `log_time` and `publish_time` are added automatically
@@ -124,6 +135,7 @@ impl McapMessageParser for ImuMessageParser {
             mut orientation_covariance,
             mut angular_velocity_covariance,
             mut linear_acceleration_covariance,
+            encoding: _,
         } = *self;
 
         let data_chunk = Chunk::from_auto_row_ids(
@@ -7,8 +7,8 @@ use re_mcap_ros2::sensor_msgs;
 use re_types::archetypes::{Scalars, SeriesLines};
 
 use crate::mcap::{
-    cdr,
     decode::{McapMessageParser, ParserContext, PluginError},
+    schema::RosEncoding,
 };
 
 /// Plugin that parses `sensor_msgs/msg/JointState` messages.
@@ -20,6 +20,8 @@ pub struct JointStateMessageParser {
     positions: ListBuilder<Float64Builder>,
     velocities: ListBuilder<Float64Builder>,
     efforts: ListBuilder<Float64Builder>,
+
+    encoding: RosEncoding,
 }
 
 impl JointStateMessageParser {
@@ -30,8 +32,15 @@ impl JointStateMessageParser {
             positions: ListBuilder::with_capacity(Float64Builder::new(), num_rows),
             velocities: ListBuilder::with_capacity(Float64Builder::new(), num_rows),
             efforts: ListBuilder::with_capacity(Float64Builder::new(), num_rows),
+            encoding: RosEncoding::default(),
         }
     }
+
+    /// Decode the messages from the given wire format instead of CDR.
+    pub fn with_encoding(mut self, encoding: RosEncoding) -> Self {
+        self.encoding = encoding;
+        self
+    }
 }
 
 impl McapMessageParser for JointStateMessageParser {
@@ -42,7 +51,9 @@ impl McapMessageParser for JointStateMessageParser {
             position,
             velocity,
             effort,
-        } = cdr::try_decode_message::<sensor_msgs::JointState>(msg.data.as_ref())
+        } = self
+            .encoding
+            .decode::<sensor_msgs::JointState>(msg.data.as_ref())
             .map_err(|err| PluginError::Other(anyhow::anyhow!(err)))?;
 
         // add the sensor timestamp to the context, `log_time` and `publish_time` are added automatically
@@ -77,6 +88,7 @@ impl McapMessageParser for JointStateMessageParser {
             mut positions,
             mut velocities,
             mut efforts,
+            encoding: _,
         } = *self;
 
         let names_components = joint_names.finish();
@@ -19,9 +19,8 @@ use re_types::{
 use std::collections::HashMap;
 
 use crate::mcap::{
-    cdr,
     decode::{McapMessageParser, ParserContext, PluginError},
-    schema::blob_list_builder,
+    schema::{RosEncoding, blob_list_builder},
 };
 
 pub struct PointCloud2MessageParser {
@@ -39,6 +38,8 @@ pub struct PointCloud2MessageParser {
     // We lazily create this, only if we can interpret the point cloud semantically.
     // For now, this is the case if there are fields with names `x`,`y`, and `z` present.
     points_3ds: Option<Vec<archetypes::Points3D>>,
+
+    encoding: RosEncoding,
 }
 
 impl PointCloud2MessageParser {
@@ -77,8 +78,16 @@ impl PointCloud2MessageParser {
             is_dense: fixed_size_list_builder(1, num_rows),
 
             points_3ds: None,
+
+            encoding: RosEncoding::default(),
         }
     }
+
+    /// Decode the messages from the given wire format instead of CDR.
+    pub fn with_encoding(mut self, encoding: RosEncoding) -> Self {
+        self.encoding = encoding;
+        self
+    }
 }
 
 fn access(data: &[u8], datatype: PointFieldDatatype, is_big_endian: bool) -> std::io::Result<f32> {
@@ -170,7 +179,9 @@ impl Iterator for Position3DIter<'_> {
 
 impl McapMessageParser for PointCloud2MessageParser {
     fn append(&mut self, ctx: &mut ParserContext, msg: &mcap::Message<'_>) -> anyhow::Result<()> {
-        let point_cloud = cdr::try_decode_message::<sensor_msgs::PointCloud2>(msg.data.as_ref())
+        let point_cloud = self
+            .encoding
+            .decode::<sensor_msgs::PointCloud2>(msg.data.as_ref())
             .map_err(|err| PluginError::Other(anyhow::anyhow!(err)))?;
 
         let cell = TimeCell::from_timestamp_nanos_since_epoch(point_cloud.header.stamp.as_nanos());
@@ -189,6 +200,8 @@ impl McapMessageParser for PointCloud2MessageParser {
             is_dense,
 
             points_3ds,
+
+            encoding: _,
         } = self;
 
         let mut timepoint = TimePoint::default();
@@ -288,6 +301,8 @@ impl McapMessageParser for PointCloud2MessageParser {
             mut is_dense,
 
             points_3ds,
+
+            encoding: _,
         } = *self;
 
         let mut chunks = Vec::new();
@@ -3,34 +3,43 @@ use re_mcap_ros2::std_msgs;
 use re_types::archetypes::TextDocument;
 
 use crate::mcap::{
-    cdr,
     decode::{McapMessageParser, ParserContext, PluginError},
+    schema::RosEncoding,
 };
 
 /// Plugin that parses `std_msgs/msg/String` messages.
 pub struct StringMessageParser {
     /// The text content from String messages.
     texts: Vec<String>,
+
+    encoding: RosEncoding,
 }
 
 impl StringMessageParser {
     pub fn new(num_rows: usize) -> Self {
         Self {
             texts: Vec::with_capacity(num_rows),
+            encoding: RosEncoding::default(),
         }
     }
+
+    /// Decode the messages from the given wire format instead of CDR.
+    pub fn with_encoding(mut self, encoding: RosEncoding) -> Self {
+        self.encoding = encoding;
+        self
+    }
 }
 
 impl McapMessageParser for StringMessageParser {
     fn append(&mut self, _ctx: &mut ParserContext, msg: &mcap::Message<'_>) -> anyhow::Result<()> {
         let std_msgs::StringMessage { data } =
-            cdr::try_decode_message::<std_msgs::StringMessage>(&msg.data)?;
+            self.encoding.decode::<std_msgs::StringMessage>(&msg.data)?;
         self.texts.push(data);
         Ok(())
     }
 
     fn finalize(self: Box<Self>, ctx: ParserContext) -> anyhow::Result<Vec<re_chunk::Chunk>> {
-        let Self { texts } = *self;
+        let Self { texts, encoding: _ } = *self;
 
         let entity_path = ctx.entity_path().clone();
         let timelines = ctx.build_timelines();
@@ -0,0 +1,3 @@
+mod tf_message;
+
+pub use tf_message::*;
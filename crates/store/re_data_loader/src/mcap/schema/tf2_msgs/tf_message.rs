@@ -0,0 +1,227 @@
+use std::sync::Arc;
+
+use ahash::HashMap;
+use parking_lot::Mutex;
+use re_chunk::{Chunk, EntityPath};
+use re_log_types::EntityPathPart;
+use re_mcap_ros2::{geometry_msgs::TransformStamped, tf2_msgs::TFMessage};
+use re_types::{AsComponents as _, archetypes::Transform3D, datatypes::Quaternion};
+
+use crate::mcap::{
+    cdr,
+    decode::{McapMessageParser, ParserContext, PluginError, RowsPerEntity},
+};
+
+/// The transform tree, as described by all the `tf2_msgs/msg/TFMessage` channels of an MCAP file.
+///
+/// This is shared between the parsers of the `/tf` and `/tf_static` channels, so that frames
+/// end up in the same entity hierarchy regardless of which channel they were published on.
+///
+/// MCAP chunks are parsed independently of each other, so the tree is built up front from all
+/// the transform messages of the file (see [`Self::add_message`]): that way a frame always
+/// ends up on the same entity path, no matter which chunk its transforms come from.
+#[derive(Debug, Default)]
+pub struct TfTree {
+    /// The parent of each known frame, keyed by child frame.
+    parent_per_frame: HashMap<String, String>,
+}
+
+impl TfTree {
+    /// All transform frames are logged as descendants of this entity.
+    pub const ROOT: &str = "tf";
+
+    /// Records `parent_frame_id` as the parent of `frame_id`.
+    ///
+    /// Only the first parent of a frame is kept: the entity path of a frame must not change
+    /// over the course of a recording, otherwise its transforms would be split across entities.
+    pub fn set_parent(&mut self, frame_id: &str, parent_frame_id: &str) {
+        match self.parent_per_frame.get(frame_id) {
+            Some(previous) if previous != parent_frame_id => {
+                re_log::warn_once!(
+                    "Frame {frame_id:?} was reparented from {previous:?} to {parent_frame_id:?}, \
+                     its transforms are kept under {previous:?}"
+                );
+            }
+            Some(_) => {}
+            None => {
+                self.parent_per_frame
+                    .insert(frame_id.to_owned(), parent_frame_id.to_owned());
+            }
+        }
+    }
+
+    /// Records the parents of all the transforms of a `tf2_msgs/msg/TFMessage`.
+    pub fn add_message(&mut self, msg: &mcap::Message<'_>) -> Result<(), PluginError> {
+        let TFMessage { transforms } = cdr::try_decode_message::<TFMessage>(msg.data.as_ref())
+            .map_err(|err| PluginError::Other(anyhow::anyhow!(err)))?;
+
+        for TransformStamped {
+            header,
+            child_frame_id,
+            ..
+        } in &transforms
+        {
+            self.set_parent(
+                normalize_frame_id(child_frame_id),
+                normalize_frame_id(&header.frame_id),
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Whether the given frame appears in any transform, either as a child or as a parent.
+    pub fn contains(&self, frame_id: &str) -> bool {
+        self.parent_per_frame.contains_key(frame_id)
+            || self
+                .parent_per_frame
+                .values()
+                .any(|parent| parent == frame_id)
+    }
+
+    /// The entity path of the given frame, e.g. `/tf/map/odom/base_link` for `base_link`.
+    ///
+    /// Frames whose ancestors are not known (yet) are rooted at their oldest known ancestor.
+    pub fn entity_path(&self, frame_id: &str) -> EntityPath {
+        let mut frames = vec![frame_id];
+        while let Some(parent) = frames
+            .last()
+            .and_then(|frame_id| self.parent_per_frame.get(*frame_id))
+        {
+            if frames.contains(&parent.as_str()) {
+                re_log::warn_once!("The transform tree contains a cycle at frame {parent:?}");
+                break;
+            }
+            frames.push(parent);
+        }
+
+        std::iter::once(EntityPathPart::new(Self::ROOT))
+            .chain(frames.into_iter().rev().map(EntityPathPart::new))
+            .collect::<Vec<_>>()
+            .into()
+    }
+}
+
+/// Plugin that parses `tf2_msgs/msg/TFMessage` messages.
+#[derive(Default)]
+pub struct TfMessageSchemaPlugin;
+
+/// Parses `tf2_msgs/msg/TFMessage` messages into [`Transform3D`]s.
+///
+/// Each transform is logged on the entity of its child frame, see [`TfTree::entity_path`].
+pub struct TfMessageParser {
+    tf_tree: Arc<Mutex<TfTree>>,
+
+    /// Whether these transforms come from the `/tf_static` topic, in which case they are logged as static.
+    is_static: bool,
+
+    rows: RowsPerEntity,
+}
+
+impl TfMessageParser {
+    /// Create a new [`TfMessageParser`]
+    pub fn new(tf_tree: Arc<Mutex<TfTree>>, is_static: bool) -> Self {
+        Self {
+            tf_tree,
+            is_static,
+            rows: Default::default(),
+        }
+    }
+}
+
+/// `tf2` ignores leading slashes in frame ids, and so do we.
+pub(crate) fn normalize_frame_id(frame_id: &str) -> &str {
+    frame_id.trim_start_matches('/')
+}
+
+impl McapMessageParser for TfMessageParser {
+    fn append(&mut self, _ctx: &mut ParserContext, msg: &mcap::Message<'_>) -> anyhow::Result<()> {
+        let TFMessage { transforms } = cdr::try_decode_message::<TFMessage>(msg.data.as_ref())
+            .map_err(|err| PluginError::Other(anyhow::anyhow!(err)))?;
+
+        // A single message contains many transforms, each with their own entity path and
+        // timestamp, hence the use of `RowsPerEntity`.
+        let mut tf_tree = self.tf_tree.lock();
+        for TransformStamped {
+            header,
+            child_frame_id,
+            transform,
+        } in transforms
+        {
+            let frame_id = normalize_frame_id(&child_frame_id);
+            tf_tree.set_parent(frame_id, normalize_frame_id(&header.frame_id));
+
+            let translation = transform.translation;
+            let rotation = transform.rotation;
+            let transform = Transform3D::update_fields()
+                .with_translation([translation.x, translation.y, translation.z])
+                .with_quaternion(Quaternion::from_xyzw([
+                    rotation.x as f32,
+                    rotation.y as f32,
+                    rotation.z as f32,
+                    rotation.w as f32,
+                ]));
+
+            let entity_path = tf_tree.entity_path(frame_id);
+            if self.is_static {
+                // Static data only ever keeps the latest value anyway.
+                self.rows
+                    .set_static(entity_path, transform.as_serialized_batches());
+            } else {
+                self.rows.push(
+                    entity_path,
+                    RowsPerEntity::timepoint(msg, header.stamp.as_nanos()),
+                    transform.as_serialized_batches(),
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    fn finalize(self: Box<Self>, _ctx: ParserContext) -> anyhow::Result<Vec<Chunk>> {
+        self.rows.into_chunks()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tf_tree_entity_paths() {
+        let mut tf_tree = TfTree::default();
+        tf_tree.set_parent("base_link", "odom");
+        tf_tree.set_parent("odom", "map");
+        tf_tree.set_parent("robot/camera", "base_link");
+
+        assert_eq!(tf_tree.entity_path("map"), EntityPath::from("/tf/map"));
+        assert_eq!(
+            tf_tree.entity_path("base_link"),
+            EntityPath::from("/tf/map/odom/base_link")
+        );
+        assert_eq!(
+            tf_tree.entity_path("robot/camera"),
+            EntityPath::from("/tf/map/odom/base_link/robot\\/camera")
+        );
+
+        // Cycles must not hang.
+        tf_tree.set_parent("map", "base_link");
+        assert_eq!(
+            tf_tree.entity_path("map"),
+            EntityPath::from("/tf/odom/base_link/map")
+        );
+    }
+
+    #[test]
+    fn tf_tree_keeps_first_parent() {
+        let mut tf_tree = TfTree::default();
+        tf_tree.set_parent("base_link", "odom");
+        tf_tree.set_parent("base_link", "map");
+
+        assert_eq!(
+            tf_tree.entity_path("base_link"),
+            EntityPath::from("/tf/odom/base_link")
+        );
+    }
+}
@@ -0,0 +1,557 @@
+use std::{collections::BTreeSet, sync::Arc};
+
+use ahash::HashMap;
+use parking_lot::Mutex;
+use re_chunk::{Chunk, EntityPath};
+use re_log_types::EntityPathPart;
+use re_mcap_ros2::{
+    geometry_msgs::Point,
+    std_msgs::ColorRGBA,
+    visualization_msgs::{Marker, MarkerArray},
+};
+use re_types::{
+    AsComponents as _, SerializedComponentBatch,
+    archetypes::{
+        Arrows3D, Asset3D, Boxes3D, Clear, Cylinders3D, Ellipsoids3D, LineStrips3D, Mesh3D,
+        Points3D, TextDocument, Transform3D,
+    },
+    components::{Color, MediaType},
+    datatypes::Quaternion,
+};
+
+use crate::mcap::{
+    cdr,
+    decode::{McapMessageParser, ParserContext, PluginError, RowsPerEntity},
+    schema::tf2_msgs::{TfTree, normalize_frame_id},
+};
+
+/// Plugin that parses `visualization_msgs/msg/Marker` and `visualization_msgs/msg/MarkerArray` messages.
+#[derive(Default)]
+pub struct MarkerSchemaPlugin;
+
+/// The markers logged so far on all the marker channels of an MCAP file.
+///
+/// Markers are stateful: messages modify and delete markers that were added by earlier messages,
+/// possibly from another MCAP chunk. This is shared by all the marker parsers of a file so that
+/// this state carries over from one chunk to the next.
+#[derive(Debug, Default)]
+pub struct MarkerState {
+    /// The type of the marker last logged on each entity, so that we can clear
+    /// the previous geometry when a marker changes type.
+    type_per_entity: HashMap<EntityPath, i32>,
+
+    /// The entities under which the markers of each topic were logged so far, see [`MarkerRows`].
+    roots_per_topic: HashMap<EntityPath, BTreeSet<EntityPath>>,
+}
+
+/// Parses `visualization_msgs/msg/Marker` messages.
+///
+/// Each marker is logged on `<frame>/<topic>/<ns>/<id>`, see [`MarkerRows`].
+pub struct MarkerMessageParser {
+    rows: MarkerRows,
+}
+
+impl MarkerMessageParser {
+    /// Create a new [`MarkerMessageParser`]
+    pub fn new(
+        _num_rows: usize,
+        tf_tree: Arc<Mutex<TfTree>>,
+        state: Arc<Mutex<MarkerState>>,
+    ) -> Self {
+        Self {
+            rows: MarkerRows::new(tf_tree, state),
+        }
+    }
+}
+
+impl McapMessageParser for MarkerMessageParser {
+    fn append(&mut self, ctx: &mut ParserContext, msg: &mcap::Message<'_>) -> anyhow::Result<()> {
+        let marker = cdr::try_decode_message::<Marker<'_>>(msg.data.as_ref())
+            .map_err(|err| PluginError::Other(anyhow::anyhow!(err)))?;
+
+        self.rows.add(ctx.entity_path(), msg, &marker);
+
+        Ok(())
+    }
+
+    fn finalize(self: Box<Self>, _ctx: ParserContext) -> anyhow::Result<Vec<Chunk>> {
+        self.rows.into_chunks()
+    }
+}
+
+/// Parses `visualization_msgs/msg/MarkerArray` messages.
+///
+/// Each marker is logged on `<frame>/<topic>/<ns>/<id>`, see [`MarkerRows`].
+pub struct MarkerArrayMessageParser {
+    rows: MarkerRows,
+}
+
+impl MarkerArrayMessageParser {
+    /// Create a new [`MarkerArrayMessageParser`]
+    pub fn new(
+        _num_rows: usize,
+        tf_tree: Arc<Mutex<TfTree>>,
+        state: Arc<Mutex<MarkerState>>,
+    ) -> Self {
+        Self {
+            rows: MarkerRows::new(tf_tree, state),
+        }
+    }
+}
+
+impl McapMessageParser for MarkerArrayMessageParser {
+    fn append(&mut self, ctx: &mut ParserContext, msg: &mcap::Message<'_>) -> anyhow::Result<()> {
+        let MarkerArray { markers } = cdr::try_decode_message::<MarkerArray<'_>>(msg.data.as_ref())
+            .map_err(|err| PluginError::Other(anyhow::anyhow!(err)))?;
+
+        for marker in &markers {
+            self.rows.add(ctx.entity_path(), msg, marker);
+        }
+
+        Ok(())
+    }
+
+    fn finalize(self: Box<Self>, _ctx: ParserContext) -> anyhow::Result<Vec<Chunk>> {
+        self.rows.into_chunks()
+    }
+}
+
+/// Accumulates the rows generated by the markers of a single channel.
+///
+/// Markers are logged under the entity of their transform frame (see [`TfTree`]), so that they
+/// move along with it, e.g. `/tf/map/base_link/markers/planner/1` for marker `1` of namespace
+/// `planner`, published on `/markers` in the `base_link` frame.
+/// Markers whose frame is unknown are logged under the topic directly.
+///
+/// A single message can affect many entities, hence the use of [`RowsPerEntity`].
+struct MarkerRows {
+    rows: RowsPerEntity,
+    tf_tree: Arc<Mutex<TfTree>>,
+    state: Arc<Mutex<MarkerState>>,
+}
+
+impl MarkerRows {
+    fn new(tf_tree: Arc<Mutex<TfTree>>, state: Arc<Mutex<MarkerState>>) -> Self {
+        Self {
+            rows: Default::default(),
+            tf_tree,
+            state,
+        }
+    }
+
+    fn add(&mut self, topic_path: &EntityPath, msg: &mcap::Message<'_>, marker: &Marker<'_>) {
+        let timepoint = RowsPerEntity::timepoint(msg, marker.header.stamp.as_nanos());
+
+        let state = Arc::clone(&self.state);
+        let mut state = state.lock();
+        let MarkerState {
+            type_per_entity,
+            roots_per_topic,
+        } = &mut *state;
+
+        match marker.action {
+            Marker::DELETE => {
+                let entity_path = marker_entity_path(&self.root(topic_path, marker), marker);
+                type_per_entity.remove(&entity_path);
+                self.rows.push(
+                    entity_path,
+                    timepoint,
+                    Clear::flat().as_serialized_batches(),
+                );
+            }
+
+            Marker::DELETEALL => {
+                // Deletes every marker of the topic, whatever their namespace and frame.
+                for root in roots_per_topic.get(topic_path).into_iter().flatten() {
+                    type_per_entity.retain(|entity_path, _| !entity_path.starts_with(root));
+                    self.rows.push(
+                        root.clone(),
+                        timepoint.clone(),
+                        Clear::recursive().as_serialized_batches(),
+                    );
+                }
+            }
+
+            Marker::ADD => {
+                let root = self.root(topic_path, marker);
+                let entity_path = marker_entity_path(&root, marker);
+
+                let Some(geometry) = marker_geometry(marker) else {
+                    return;
+                };
+
+                roots_per_topic
+                    .entry(topic_path.clone())
+                    .or_default()
+                    .insert(root);
+
+                if type_per_entity
+                    .insert(entity_path.clone(), marker.r#type)
+                    .is_some_and(|previous_type| previous_type != marker.r#type)
+                {
+                    self.rows.push(
+                        entity_path.clone(),
+                        timepoint.clone(),
+                        Clear::flat().as_serialized_batches(),
+                    );
+                }
+
+                let position = &marker.pose.position;
+                let orientation = &marker.pose.orientation;
+                let transform = Transform3D::update_fields()
+                    .with_translation([position.x, position.y, position.z])
+                    .with_quaternion(Quaternion::from_xyzw([
+                        orientation.x as f32,
+                        orientation.y as f32,
+                        orientation.z as f32,
+                        orientation.w as f32,
+                    ]));
+
+                let batches = transform
+                    .as_serialized_batches()
+                    .into_iter()
+                    .chain(geometry);
+                self.rows.push(entity_path, timepoint, batches);
+            }
+
+            action => {
+                re_log::warn_once!("Unknown marker action {action} on {topic_path}");
+            }
+        }
+    }
+
+    /// The entity under which the markers of this topic are logged in the frame of `marker`.
+    fn root(&self, topic_path: &EntityPath, marker: &Marker<'_>) -> EntityPath {
+        let frame_id = normalize_frame_id(&marker.header.frame_id);
+        let tf_tree = self.tf_tree.lock();
+        if tf_tree.contains(frame_id) {
+            tf_tree.entity_path(frame_id).join(topic_path)
+        } else {
+            topic_path.clone()
+        }
+    }
+
+    fn into_chunks(self) -> anyhow::Result<Vec<Chunk>> {
+        self.rows.into_chunks()
+    }
+}
+
+/// `<root>/<ns>/<id>`, or `<root>/<id>` if the marker has no namespace.
+fn marker_entity_path(root: &EntityPath, marker: &Marker<'_>) -> EntityPath {
+    let ns = (!marker.ns.is_empty()).then(|| EntityPathPart::new(&marker.ns));
+    root.join(&EntityPath::from(
+        ns.into_iter()
+            .chain([EntityPathPart::new(marker.id.to_string())])
+            .collect::<Vec<_>>(),
+    ))
+}
+
+fn to_position(point: &Point) -> [f32; 3] {
+    [point.x as f32, point.y as f32, point.z as f32]
+}
+
+fn to_color(color: &ColorRGBA) -> Color {
+    let to_u8 = |c: f32| (c.clamp(0.0, 1.0) * 255.0).round() as u8;
+    Color::from_unmultiplied_rgba(
+        to_u8(color.r),
+        to_u8(color.g),
+        to_u8(color.b),
+        to_u8(color.a),
+    )
+}
+
+/// Per-point colors if there are as many as there are points, the marker color otherwise.
+fn point_colors(marker: &Marker<'_>) -> Vec<Color> {
+    if !marker.colors.is_empty() && marker.colors.len() == marker.points.len() {
+        marker.colors.iter().map(to_color).collect()
+    } else {
+        vec![to_color(&marker.color)]
+    }
+}
+
+/// Maps the shape of a marker onto the closest Rerun archetype, in the marker's local frame.
+fn marker_geometry(marker: &Marker<'_>) -> Option<Vec<SerializedComponentBatch>> {
+    let scale = [
+        marker.scale.x as f32,
+        marker.scale.y as f32,
+        marker.scale.z as f32,
+    ];
+    let half_size = scale.map(|s| s * 0.5);
+    let color = to_color(&marker.color);
+    let positions = marker.points.iter().map(to_position);
+
+    let batches = match marker.r#type {
+        Marker::ARROW => {
+            if let [start, end, ..] = marker.points.as_slice() {
+                let (start, end) = (to_position(start), to_position(end));
+                Arrows3D::from_vectors([[end[0] - start[0], end[1] - start[1], end[2] - start[2]]])
+                    .with_origins([start])
+                    .with_radii([half_size[0]])
+            } else {
+                // Pointing along the x-axis, `scale.x` long.
+                Arrows3D::from_vectors([[scale[0], 0.0, 0.0]]).with_radii([half_size[1]])
+            }
+            .with_colors([color])
+            .as_serialized_batches()
+        }
+
+        Marker::CUBE => Boxes3D::from_half_sizes([half_size])
+            .with_colors([color])
+            .as_serialized_batches(),
+
+        Marker::SPHERE => Ellipsoids3D::from_half_sizes([half_size])
+            .with_colors([color])
+            .as_serialized_batches(),
+
+        Marker::CYLINDER => Cylinders3D::from_lengths_and_radii([scale[2]], [half_size[0]])
+            .with_colors([color])
+            .as_serialized_batches(),
+
+        Marker::LINE_STRIP => LineStrips3D::new([positions.collect::<Vec<_>>()])
+            .with_radii([half_size[0]])
+            .with_colors([color])
+            .as_serialized_batches(),
+
+        Marker::LINE_LIST => {
+            let positions = positions.collect::<Vec<_>>();
+            let colors = point_colors(marker);
+            let colors = if colors.len() == positions.len() {
+                // One color per segment: RViz interpolates, we use the start color.
+                colors.into_iter().step_by(2).collect()
+            } else {
+                colors
+            };
+            LineStrips3D::new(positions.chunks_exact(2))
+                .with_radii([half_size[0]])
+                .with_colors(colors)
+                .as_serialized_batches()
+        }
+
+        Marker::CUBE_LIST => Boxes3D::from_centers_and_half_sizes(positions, [half_size])
+            .with_colors(point_colors(marker))
+            .as_serialized_batches(),
+
+        Marker::SPHERE_LIST => Ellipsoids3D::from_centers_and_half_sizes(positions, [half_size])
+            .with_colors(point_colors(marker))
+            .as_serialized_batches(),
+
+        Marker::POINTS => Points3D::new(positions)
+            .with_radii([half_size[0]])
+            .with_colors(point_colors(marker))
+            .as_serialized_batches(),
+
+        Marker::TEXT_VIEW_FACING => TextDocument::new(marker.text.as_str()).as_serialized_batches(),
+
+        Marker::MESH_RESOURCE => {
+            if marker.mesh_file.data.is_empty() {
+                re_log::warn_once!(
+                    "Mesh resources referenced by URI are not supported: {:?}",
+                    marker.mesh_resource
+                );
+                return None;
+            }
+
+            Asset3D::from_file_contents(
+                marker.mesh_file.data.to_vec(),
+                MediaType::guess_from_path(&marker.mesh_file.filename),
+            )
+            .as_serialized_batches()
+        }
+
+        Marker::TRIANGLE_LIST => {
+            let mesh = Mesh3D::new(positions).with_albedo_factor(color.0);
+            if !marker.colors.is_empty() && marker.colors.len() == marker.points.len() {
+                mesh.with_vertex_colors(marker.colors.iter().map(to_color))
+            } else {
+                mesh
+            }
+            .as_serialized_batches()
+        }
+
+        marker_type => {
+            re_log::warn_once!("Unsupported marker type {marker_type}");
+            return None;
+        }
+    };
+
+    Some(batches)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{borrow::Cow, collections::BTreeMap};
+
+    use re_mcap_ros2::{
+        builtin_interfaces::{Duration, Time},
+        geometry_msgs::{Pose, Quaternion, Vector3},
+        sensor_msgs::CompressedImage,
+        std_msgs::Header,
+        visualization_msgs::MeshFile,
+    };
+
+    use super::*;
+    use crate::mcap::util::test_message;
+
+    fn marker(ns: &str, id: i32, r#type: i32, action: i32) -> Marker<'static> {
+        let header = Header {
+            stamp: Time { sec: 1, nanosec: 0 },
+            frame_id: "map".to_owned(),
+        };
+        let color = ColorRGBA {
+            r: 1.0,
+            g: 0.0,
+            b: 0.0,
+            a: 1.0,
+        };
+
+        Marker {
+            header: header.clone(),
+            ns: ns.to_owned(),
+            id,
+            r#type,
+            action,
+            pose: Pose {
+                position: Point {
+                    x: 1.0,
+                    y: 2.0,
+                    z: 3.0,
+                },
+                orientation: Quaternion {
+                    x: 0.0,
+                    y: 0.0,
+                    z: 0.0,
+                    w: 1.0,
+                },
+            },
+            scale: Vector3 {
+                x: 1.0,
+                y: 1.0,
+                z: 1.0,
+            },
+            color,
+            lifetime: Duration { sec: 0, nanosec: 0 },
+            frame_locked: false,
+            points: vec![
+                Point {
+                    x: 0.0,
+                    y: 0.0,
+                    z: 0.0,
+                },
+                Point {
+                    x: 1.0,
+                    y: 0.0,
+                    z: 0.0,
+                },
+            ],
+            colors: vec![],
+            texture_resource: String::new(),
+            texture: CompressedImage {
+                header,
+                format: String::new(),
+                data: Cow::Borrowed(&[]),
+            },
+            uv_coordinates: vec![],
+            text: String::new(),
+            mesh_resource: String::new(),
+            mesh_file: MeshFile {
+                filename: String::new(),
+                data: Cow::Borrowed(&[]),
+            },
+            mesh_use_embedded_materials: false,
+        }
+    }
+
+    fn num_rows_per_entity(chunks: &[Chunk]) -> BTreeMap<String, usize> {
+        let mut num_rows_per_entity = BTreeMap::new();
+        for chunk in chunks {
+            *num_rows_per_entity
+                .entry(chunk.entity_path().to_string())
+                .or_default() += chunk.num_rows();
+        }
+        num_rows_per_entity
+    }
+
+    #[test]
+    fn marker_entity_paths() -> anyhow::Result<()> {
+        let topic_path = EntityPath::from("/markers");
+        let msg = test_message("/markers", "cdr");
+
+        let mut rows = MarkerRows::new(Default::default(), Default::default());
+        for marker in [
+            marker("planner", 1, Marker::CUBE, Marker::ADD),
+            marker("planner", 2, Marker::LINE_STRIP, Marker::ADD),
+            marker("", 3, Marker::POINTS, Marker::ADD),
+            // Changes type: the previous geometry must be cleared.
+            marker("planner", 1, Marker::SPHERE, Marker::ADD),
+            marker("planner", 2, Marker::CUBE, Marker::DELETE),
+            marker("planner", 0, Marker::CUBE, Marker::DELETEALL),
+        ] {
+            rows.add(&topic_path, &msg, &marker);
+        }
+
+        let chunks = rows.into_chunks()?;
+
+        assert_eq!(
+            num_rows_per_entity(&chunks),
+            BTreeMap::from([
+                ("/markers".to_owned(), 1), // `DELETEALL` clears the whole topic
+                ("/markers/3".to_owned(), 1),
+                ("/markers/planner/1".to_owned(), 3),
+                ("/markers/planner/2".to_owned(), 2),
+            ])
+        );
+
+        Ok(())
+    }
+
+    /// Each MCAP chunk gets its own parser, but they all share the same state.
+    #[test]
+    fn markers_across_mcap_chunks() -> anyhow::Result<()> {
+        let topic_path = EntityPath::from("/markers");
+        let msg = test_message("/markers", "cdr");
+
+        let tf_tree = Arc::new(Mutex::new(TfTree::default()));
+        tf_tree.lock().set_parent("map", "world");
+        let state = Arc::new(Mutex::new(MarkerState::default()));
+
+        let mut first_chunk = MarkerRows::new(Arc::clone(&tf_tree), Arc::clone(&state));
+        first_chunk.add(
+            &topic_path,
+            &msg,
+            &marker("planner", 1, Marker::CUBE, Marker::ADD),
+        );
+
+        let mut unknown_frame = marker("planner", 2, Marker::CUBE, Marker::ADD);
+        unknown_frame.header.frame_id = "unknown".to_owned();
+        first_chunk.add(&topic_path, &msg, &unknown_frame);
+
+        let mut second_chunk = MarkerRows::new(Arc::clone(&tf_tree), Arc::clone(&state));
+        for marker in [
+            // Changes type since the previous chunk: the previous geometry must be cleared.
+            marker("planner", 1, Marker::SPHERE, Marker::ADD),
+            // Clears both frames.
+            marker("", 0, Marker::CUBE, Marker::DELETEALL),
+        ] {
+            second_chunk.add(&topic_path, &msg, &marker);
+        }
+
+        let chunks = first_chunk
+            .into_chunks()?
+            .into_iter()
+            .chain(second_chunk.into_chunks()?)
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            num_rows_per_entity(&chunks),
+            BTreeMap::from([
+                ("/markers".to_owned(), 1),
+                ("/markers/planner/2".to_owned(), 1),
+                ("/tf/world/map/markers".to_owned(), 1),
+                ("/tf/world/map/markers/planner/1".to_owned(), 3),
+            ])
+        );
+        assert!(state.lock().type_per_entity.is_empty());
+
+        Ok(())
+    }
+}
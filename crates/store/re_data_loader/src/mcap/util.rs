@@ -1,13 +1,18 @@
-use std::io::{Read, Seek};
+use std::io::{Read, Seek, SeekFrom};
 
 use mcap::{
     Summary,
     records::ChunkIndex,
     sans_io::{SummaryReadEvent, SummaryReader},
 };
-use re_chunk::external::nohash_hasher::IntMap;
+use re_chunk::external::nohash_hasher::{IntMap, IntSet};
 
-use crate::mcap::decode::ChannelId;
+use crate::mcap::decode::{ChannelId, PluginError};
+
+/// A seekable source of MCAP data, e.g. a [`std::fs::File`] or a [`std::io::Cursor`].
+pub trait McapReader: Read + Seek {}
+
+impl<T: Read + Seek + ?Sized> McapReader for T {}
 
 /// Read out the summary of an MCAP file.
 pub fn read_summary<R: Read + Seek>(mut reader: R) -> anyhow::Result<Option<Summary>> {
@@ -43,3 +48,134 @@ pub fn get_chunk_message_count(
         .map(|(channel, msg_offsets)| (channel.id.into(), msg_offsets.len()))
         .collect())
 }
+
+/// Reads a single MCAP chunk, followed by its message indexes, into memory.
+///
+/// Returns the bytes along with a copy of `chunk_index` whose offsets are relative to them,
+/// so that the result can be used with [`Summary::stream_chunk`] and [`Summary::read_message_indexes`]
+/// without having the entire file in memory.
+pub fn read_chunk(
+    reader: &mut dyn McapReader,
+    chunk_index: &ChunkIndex,
+) -> std::io::Result<(Vec<u8>, ChunkIndex)> {
+    re_tracing::profile_function!();
+
+    let invalid_data = |msg: &str| std::io::Error::new(std::io::ErrorKind::InvalidData, msg);
+
+    let start = chunk_index.chunk_start_offset;
+    let len = chunk_index
+        .chunk_length
+        .checked_add(chunk_index.message_index_length)
+        .ok_or_else(|| invalid_data("MCAP chunk length overflows"))?;
+
+    // The lengths come straight from the file, so we don't trust them for allocating: a corrupt
+    // index must not make us allocate more than what is actually there.
+    let mut bytes = Vec::new();
+    reader.seek(SeekFrom::Start(start))?;
+    reader.take(len).read_to_end(&mut bytes)?;
+    if bytes.len() as u64 != len {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::UnexpectedEof,
+            "MCAP chunk extends past the end of the file",
+        ));
+    }
+
+    let mut chunk_index = chunk_index.clone();
+    chunk_index.chunk_start_offset = 0;
+    for offset in chunk_index.message_index_offsets.values_mut() {
+        *offset = offset
+            .checked_sub(start)
+            .ok_or_else(|| invalid_data("MCAP message index precedes its chunk"))?;
+    }
+
+    Ok((bytes, chunk_index))
+}
+
+/// Calls `on_message` for every message of the given channels, in file order.
+///
+/// Only the MCAP chunks that may contain one of these channels are read and decompressed.
+pub fn for_each_message(
+    reader: &mut dyn McapReader,
+    summary: &Summary,
+    channel_ids: &IntSet<u16>,
+    mut on_message: impl FnMut(&::mcap::Message<'_>),
+) -> Result<(), PluginError> {
+    if channel_ids.is_empty() {
+        return Ok(());
+    }
+
+    re_tracing::profile_function!();
+
+    for chunk_index in &summary.chunk_indexes {
+        // Without message indexes, we can't know which channels are in the chunk.
+        if !chunk_index.message_index_offsets.is_empty()
+            && !chunk_index
+                .message_index_offsets
+                .keys()
+                .any(|channel_id| channel_ids.contains(channel_id))
+        {
+            continue;
+        }
+
+        let (chunk_bytes, chunk_index) = read_chunk(reader, chunk_index)?;
+        for msg in summary.stream_chunk(&chunk_bytes, &chunk_index)? {
+            let msg = msg?;
+            if channel_ids.contains(&msg.channel.id) {
+                on_message(&msg);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// A message on a schemaless channel, with an empty payload, for parsers that are tested on
+/// already decoded messages.
+#[cfg(test)]
+pub(crate) fn test_message(topic: &str, message_encoding: &str) -> ::mcap::Message<'static> {
+    ::mcap::Message {
+        channel: std::sync::Arc::new(::mcap::Channel {
+            id: 0,
+            topic: topic.to_owned(),
+            schema: None,
+            message_encoding: message_encoding.to_owned(),
+            metadata: Default::default(),
+        }),
+        sequence: 0,
+        log_time: 0,
+        publish_time: 0,
+        data: std::borrow::Cow::Borrowed(&[]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    #[test]
+    fn read_chunk_rejects_corrupt_lengths() {
+        let mut reader = Cursor::new(vec![0_u8; 16]);
+        let chunk_index = |chunk_length, message_index_length| ChunkIndex {
+            message_start_time: 0,
+            message_end_time: 0,
+            chunk_start_offset: 4,
+            chunk_length,
+            message_index_offsets: Default::default(),
+            message_index_length,
+            compression: String::new(),
+            compressed_size: 0,
+            uncompressed_size: 0,
+        };
+
+        let (bytes, _) = read_chunk(&mut reader, &chunk_index(8, 4)).unwrap();
+        assert_eq!(bytes.len(), 12);
+
+        let err = read_chunk(&mut reader, &chunk_index(u64::MAX, 1)).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+
+        let err = read_chunk(&mut reader, &chunk_index(u64::MAX / 2, 0)).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::UnexpectedEof);
+    }
+}
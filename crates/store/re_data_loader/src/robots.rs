@@ -0,0 +1,134 @@
+//! Animating robots with joint positions that are loaded separately from their URDF file.
+//!
+//! The [`crate::UrdfDataLoader`] registers every robot it loads with the recording it is loaded
+//! into. The joint positions of all other data loaded into that recording, e.g. the
+//! `sensor_msgs/JointState` messages of an MCAP file opened along with the URDF file, are then
+//! converted into transforms of the robot's links, see [`UrdfTree::joint_transform_chunks`].
+//!
+//! Robots are only shared between the loads that use the same [`Robots`], see
+//! [`crate::DataLoaderSettings::robots`]. Files are loaded in parallel, so joint states can arrive
+//! before the robot they move. A bounded number of these are kept until a robot is registered with
+//! their recording, or until the recording is forgotten with [`Robots::forget_store`].
+
+use std::sync::Arc;
+
+use ahash::HashMap;
+use parking_lot::Mutex;
+
+use re_chunk::Chunk;
+use re_log_types::StoreId;
+use re_types::archetypes::Scalars;
+
+use crate::{DataLoader as _, LoadedData, UrdfDataLoader, UrdfTree};
+
+/// The maximum number of joint state chunks kept per recording while waiting for a robot.
+///
+/// Most recordings never get a robot, in which case these would be kept for nothing.
+const MAX_PENDING_JOINT_STATES: usize = 1024;
+
+#[derive(Default)]
+struct StoreRobots {
+    trees: Vec<Arc<UrdfTree>>,
+
+    /// Joint states that were loaded before any robot.
+    ///
+    /// Chunks share their data, so keeping them around is cheap.
+    pending_joint_states: Vec<Chunk>,
+}
+
+/// The robots loaded into each recording, shared by all the loads of a session.
+///
+/// Cloning this is cheap, and the clones share the same robots. Everything is dropped along with
+/// the last clone.
+#[derive(Clone, Default)]
+pub struct Robots(Arc<Mutex<HashMap<StoreId, StoreRobots>>>);
+
+impl std::fmt::Debug for Robots {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Robots")
+            .field("num_stores", &self.0.lock().len())
+            .finish()
+    }
+}
+
+impl Robots {
+    /// Registers a robot with a recording, and returns the transforms that result from the joint
+    /// states that were already loaded into it.
+    pub(crate) fn add_robot(&self, store_id: &StoreId, tree: UrdfTree) -> Vec<Chunk> {
+        let tree = Arc::new(tree);
+
+        let pending_joint_states = {
+            let mut robots = self.0.lock();
+            let robots = robots.entry(store_id.clone()).or_default();
+            robots.trees.push(Arc::clone(&tree));
+            std::mem::take(&mut robots.pending_joint_states)
+        };
+
+        pending_joint_states
+            .iter()
+            .flat_map(|chunk| joint_transform_chunks(&tree, chunk))
+            .collect()
+    }
+
+    /// The transforms that the joint positions in `data` result in, for all the robots of its
+    /// recording.
+    pub(crate) fn joint_transforms(&self, data: &LoadedData) -> Vec<LoadedData> {
+        let LoadedData::Chunk(_, store_id, chunk) = data else {
+            return Vec::new();
+        };
+
+        // Checked before locking, as this runs for every chunk: joint positions are always scalars.
+        if !chunk
+            .components()
+            .contains_component(&Scalars::descriptor_scalars())
+        {
+            return Vec::new();
+        }
+
+        let trees = {
+            let mut robots = self.0.lock();
+            match robots.get(store_id) {
+                Some(robots) if !robots.trees.is_empty() => robots.trees.clone(),
+                _ if !UrdfTree::is_joint_state(chunk) => return Vec::new(),
+                _ => {
+                    let pending_joint_states = &mut robots
+                        .entry(store_id.clone())
+                        .or_default()
+                        .pending_joint_states;
+                    if pending_joint_states.len() < MAX_PENDING_JOINT_STATES {
+                        pending_joint_states.push(chunk.clone());
+                    } else {
+                        re_log::warn_once!(
+                            "Too many joint states were loaded before any robot: only the first \
+                             {MAX_PENDING_JOINT_STATES} chunks will animate robots loaded later"
+                        );
+                    }
+                    return Vec::new();
+                }
+            }
+        };
+
+        trees
+            .iter()
+            .flat_map(|tree| joint_transform_chunks(tree, chunk))
+            .map(|chunk| LoadedData::Chunk(UrdfDataLoader.name(), store_id.clone(), chunk))
+            .collect()
+    }
+
+    /// Drops the robots and pending joint states of a recording.
+    pub fn forget_store(&self, store_id: &StoreId) {
+        self.0.lock().remove(store_id);
+    }
+
+    /// Drops the robots and pending joint states of all recordings, see [`Self::forget_store`].
+    pub fn forget_all_stores(&self) {
+        self.0.lock().clear();
+    }
+}
+
+fn joint_transform_chunks(tree: &UrdfTree, chunk: &Chunk) -> Vec<Chunk> {
+    tree.joint_transform_chunks(chunk).unwrap_or_else(|err| {
+        re_log::warn_once!("Failed to animate the joints of a robot: {err}");
+        Vec::new()
+    })
+}
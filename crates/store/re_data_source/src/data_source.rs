@@ -166,6 +166,25 @@ impl DataSource {
         connection_registry: &ConnectionRegistryHandle,
         on_cmd: Box<dyn Fn(DataSourceCommand) + Send + Sync>,
         on_msg: Option<Box<dyn Fn() + Send + Sync>>,
+    ) -> anyhow::Result<StreamSource> {
+        self.stream_with_settings(
+            connection_registry,
+            on_cmd,
+            on_msg,
+            re_data_loader::DataLoaderSettings::recommended(RecordingId::random()),
+        )
+    }
+
+    /// Like [`Self::stream`], but files are loaded with the given [`re_data_loader::DataLoaderSettings`].
+    ///
+    /// This is how e.g. [`re_data_loader::DataLoaderSettings::rrd_query`] is set. Its
+    /// `opened_store_id` and `force_store_info` are always derived from the [`FileSource`].
+    pub fn stream_with_settings(
+        self,
+        connection_registry: &ConnectionRegistryHandle,
+        on_cmd: Box<dyn Fn(DataSourceCommand) + Send + Sync>,
+        on_msg: Option<Box<dyn Fn() + Send + Sync>>,
+        settings: re_data_loader::DataLoaderSettings,
     ) -> anyhow::Result<StreamSource> {
         re_tracing::profile_function!();
 
@@ -183,14 +202,13 @@ impl DataSource {
                     SmartChannelSource::File(path.clone()),
                 );
 
-                // This recording will be communicated to all `DataLoader`s, which may or may not
-                // decide to use it depending on whether they want to share a common recording
-                // or not.
-                let shared_recording_id = RecordingId::random();
+                // The recording of `settings` will be communicated to all `DataLoader`s, which may
+                // or may not decide to use it depending on whether they want to share a common
+                // recording or not.
                 let settings = re_data_loader::DataLoaderSettings {
                     opened_store_id: file_source.recommended_store_id().cloned(),
                     force_store_info: file_source.force_store_info(),
-                    ..re_data_loader::DataLoaderSettings::recommended(shared_recording_id)
+                    ..settings
                 };
                 re_data_loader::load_from_path(&settings, file_source, &path, &tx)
                     .with_context(|| format!("{path:?}"))?;
@@ -210,14 +228,13 @@ impl DataSource {
                     SmartChannelSource::File(name.clone().into()),
                 );
 
-                // This `StoreId` will be communicated to all `DataLoader`s, which may or may not
-                // decide to use it depending on whether they want to share a common recording
-                // or not.
-                let shared_recording_id = RecordingId::random();
+                // The recording of `settings` will be communicated to all `DataLoader`s, which may
+                // or may not decide to use it depending on whether they want to share a common
+                // recording or not.
                 let settings = re_data_loader::DataLoaderSettings {
                     opened_store_id: file_source.recommended_store_id().cloned(),
                     force_store_info: file_source.force_store_info(),
-                    ..re_data_loader::DataLoaderSettings::recommended(shared_recording_id)
+                    ..settings
                 };
                 re_data_loader::load_from_file_contents(
                     &settings,
@@ -0,0 +1,395 @@
+//! Resampling of component data in between the index values it was logged at.
+//!
+//! See [`re_chunk_store::InterpolationStrategy`].
+
+use std::sync::Arc;
+
+use arrow::{
+    array::{
+        Array as _, ArrayRef as ArrowArrayRef, AsArray as _,
+        FixedSizeListArray as ArrowFixedSizeListArray, Float32Array as ArrowFloat32Array,
+        Float64Array as ArrowFloat64Array, ListArray as ArrowListArray,
+    },
+    buffer::OffsetBuffer as ArrowOffsetBuffer,
+    datatypes::{DataType as ArrowDataType, Float32Type, Float64Type},
+};
+
+use re_chunk::{EntityPath, LatestAtQuery, RangeQuery, RowId, TimeInt, TimelineName};
+use re_chunk_store::ChunkStore;
+use re_log_types::AbsoluteTimeRange;
+use re_query::QueryCache;
+use re_types_core::{ComponentDescriptor, ComponentType};
+
+// ---
+
+/// How the data of a given component gets interpolated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum InterpolationKind {
+    /// Component-wise linear interpolation.
+    Linear,
+
+    /// Spherical linear interpolation of `[x, y, z, w]` quaternions.
+    Slerp,
+
+    /// Whichever of the two surrounding values is closest.
+    Nearest,
+}
+
+impl InterpolationKind {
+    fn from_component_type(component_type: Option<ComponentType>) -> Self {
+        match component_type
+            .as_ref()
+            .map(|component_type| component_type.full_name())
+        {
+            Some("rerun.components.Scalar" | "rerun.components.Translation3D") => Self::Linear,
+            Some("rerun.components.RotationQuat") => Self::Slerp,
+            _ => Self::Nearest,
+        }
+    }
+}
+
+/// Computes the value of the given component at `time`, by interpolating in between the values
+/// that directly surround it.
+///
+/// Returns a single-row list array, or `None` if there isn't data on both sides of `time`.
+pub fn interpolate_at(
+    store: &ChunkStore,
+    cache: &QueryCache,
+    entity_path: &EntityPath,
+    component_descr: &ComponentDescriptor,
+    timeline: TimelineName,
+    time: TimeInt,
+) -> Option<ArrowListArray> {
+    re_tracing::profile_function!();
+
+    let results = cache.latest_at(
+        &LatestAtQuery::new(timeline, time),
+        entity_path,
+        [component_descr],
+    );
+    let prev = results.components.into_values().next()?;
+    let (prev_time, _) = prev.index(&timeline)?;
+    let prev = prev.components().get(component_descr)?.clone();
+
+    if prev_time.is_static() {
+        return None;
+    }
+    if prev_time == time {
+        return Some(prev);
+    }
+
+    let (next_time, next) = earliest_after(
+        store,
+        entity_path,
+        component_descr,
+        timeline,
+        time,
+        time.as_i64() - prev_time.as_i64(),
+    )?;
+
+    let t = (time.as_i64() - prev_time.as_i64()) as f64
+        / (next_time.as_i64() - prev_time.as_i64()) as f64;
+    let nearest = if t <= 0.5 { &prev } else { &next };
+
+    let interpolated = match InterpolationKind::from_component_type(component_descr.component_type)
+    {
+        InterpolationKind::Linear => lerp_array(&prev.value(0), &next.value(0), t),
+        InterpolationKind::Slerp => slerp_array(&prev.value(0), &next.value(0), t),
+        InterpolationKind::Nearest => None,
+    };
+
+    // Falls back to nearest if the data cannot be interpolated (mismatched lengths, nulls, …).
+    let Some(interpolated) = interpolated else {
+        return Some(nearest.clone());
+    };
+
+    let ArrowDataType::List(field) = prev.data_type() else {
+        return Some(nearest.clone());
+    };
+    ArrowListArray::try_new(
+        field.clone(),
+        ArrowOffsetBuffer::from_lengths([interpolated.len()]),
+        interpolated,
+        None,
+    )
+    .ok()
+}
+
+/// Returns the first value of the given component that was logged strictly after `time`, as a
+/// single-row list array.
+///
+/// If several values share the same index value, the one with the highest [`RowId`] wins.
+///
+/// Rather than querying the whole remainder of the timeline, which would make resampling an entire
+/// recording quadratic, this starts with a window of `initial_window` (typically the distance to
+/// the previous value) and doubles it until something is found.
+fn earliest_after(
+    store: &ChunkStore,
+    entity_path: &EntityPath,
+    component_descr: &ComponentDescriptor,
+    timeline: TimelineName,
+    time: TimeInt,
+    initial_window: i64,
+) -> Option<(TimeInt, ArrowListArray)> {
+    let mut window = initial_window.max(1);
+    loop {
+        let end = TimeInt::new_temporal(time.as_i64().saturating_add(window));
+        let range = AbsoluteTimeRange::new(time.inc(), end);
+
+        if let Some(earliest) =
+            earliest_in_range(store, entity_path, component_descr, timeline, range)
+        {
+            return Some(earliest);
+        }
+
+        if end >= TimeInt::MAX {
+            return None;
+        }
+
+        window = window.saturating_mul(2);
+    }
+}
+
+/// Returns the first value of the given component within `range`, as a single-row list array.
+///
+/// If several values share the same index value, the one with the highest [`RowId`] wins.
+fn earliest_in_range(
+    store: &ChunkStore,
+    entity_path: &EntityPath,
+    component_descr: &ComponentDescriptor,
+    timeline: TimelineName,
+    range: AbsoluteTimeRange,
+) -> Option<(TimeInt, ArrowListArray)> {
+    let query = RangeQuery::new(timeline, range);
+    let chunks = store.range_relevant_chunks(&query, entity_path, component_descr);
+
+    let (min, max) = (range.min().as_i64(), range.max().as_i64());
+
+    let mut earliest: Option<(TimeInt, RowId, ArrowListArray)> = None;
+    for chunk in chunks.iter().filter(|chunk| !chunk.is_static()) {
+        let (Some(time_column), Some(list_array)) = (
+            chunk.timelines().get(&timeline),
+            chunk.components().get(component_descr),
+        ) else {
+            continue;
+        };
+
+        let times = time_column.times_raw();
+
+        // Sorted chunks can be large, don't go through all of their rows for every sample.
+        let start_idx = if time_column.is_sorted() {
+            times.partition_point(|cur_time| *cur_time < min)
+        } else {
+            0
+        };
+
+        let rows = times
+            .iter()
+            .zip(chunk.row_ids_slice())
+            .enumerate()
+            .skip(start_idx);
+        for (row_idx, (cur_time, cur_row_id)) in rows {
+            if *cur_time > max {
+                if time_column.is_sorted() {
+                    break;
+                }
+                continue;
+            }
+            if *cur_time < min || list_array.is_null(row_idx) {
+                continue;
+            }
+
+            let cur_time = TimeInt::new_temporal(*cur_time);
+            let is_earlier = earliest.as_ref().is_none_or(|(time, row_id, _)| {
+                cur_time < *time || (cur_time == *time && cur_row_id > row_id)
+            });
+            if is_earlier {
+                earliest = Some((cur_time, *cur_row_id, list_array.slice(row_idx, 1)));
+            }
+        }
+    }
+
+    earliest.map(|(time, _row_id, list_array)| (time, list_array))
+}
+
+// ---
+
+/// Component-wise linear interpolation of float arrays, possibly nested in fixed-size lists.
+fn lerp_array(a: &ArrowArrayRef, b: &ArrowArrayRef, t: f64) -> Option<ArrowArrayRef> {
+    if a.data_type() != b.data_type()
+        || a.len() != b.len()
+        || a.null_count() > 0
+        || b.null_count() > 0
+    {
+        return None;
+    }
+
+    match a.data_type() {
+        ArrowDataType::Float32 => {
+            let (a, b) = (
+                a.as_primitive::<Float32Type>(),
+                b.as_primitive::<Float32Type>(),
+            );
+            let values = a
+                .values()
+                .iter()
+                .zip(b.values())
+                .map(|(a, b)| (*a as f64 + (*b as f64 - *a as f64) * t) as f32);
+            Some(Arc::new(ArrowFloat32Array::from_iter_values(values)))
+        }
+
+        ArrowDataType::Float64 => {
+            let (a, b) = (
+                a.as_primitive::<Float64Type>(),
+                b.as_primitive::<Float64Type>(),
+            );
+            let values = a
+                .values()
+                .iter()
+                .zip(b.values())
+                .map(|(a, b)| a + (b - a) * t);
+            Some(Arc::new(ArrowFloat64Array::from_iter_values(values)))
+        }
+
+        ArrowDataType::FixedSizeList(field, size) => {
+            let (a, b) = (a.as_fixed_size_list(), b.as_fixed_size_list());
+            let values = lerp_array(a.values(), b.values(), t)?;
+            ArrowFixedSizeListArray::try_new(field.clone(), *size, values, None)
+                .ok()
+                .map(|array| Arc::new(array) as ArrowArrayRef)
+        }
+
+        _ => None,
+    }
+}
+
+/// Spherical linear interpolation of arrays of `[x, y, z, w]` quaternions.
+fn slerp_array(a: &ArrowArrayRef, b: &ArrowArrayRef, t: f64) -> Option<ArrowArrayRef> {
+    if a.data_type() != b.data_type()
+        || a.len() != b.len()
+        || a.null_count() > 0
+        || b.null_count() > 0
+    {
+        return None;
+    }
+
+    let ArrowDataType::FixedSizeList(field, 4) = a.data_type() else {
+        return None;
+    };
+    let (a, b) = (a.as_fixed_size_list(), b.as_fixed_size_list());
+
+    let (a_values, b_values): (Vec<f64>, Vec<f64>) = match field.data_type() {
+        ArrowDataType::Float32 => (
+            a.values()
+                .as_primitive::<Float32Type>()
+                .values()
+                .iter()
+                .map(|v| *v as f64)
+                .collect(),
+            b.values()
+                .as_primitive::<Float32Type>()
+                .values()
+                .iter()
+                .map(|v| *v as f64)
+                .collect(),
+        ),
+        ArrowDataType::Float64 => (
+            a.values().as_primitive::<Float64Type>().values().to_vec(),
+            b.values().as_primitive::<Float64Type>().values().to_vec(),
+        ),
+        _ => return None,
+    };
+
+    let values = a_values
+        .chunks_exact(4)
+        .zip(b_values.chunks_exact(4))
+        .flat_map(|(a, b)| slerp([a[0], a[1], a[2], a[3]], [b[0], b[1], b[2], b[3]], t));
+
+    let values: ArrowArrayRef = match field.data_type() {
+        ArrowDataType::Float32 => Arc::new(ArrowFloat32Array::from_iter_values(
+            values.map(|v| v as f32),
+        )),
+        _ => Arc::new(ArrowFloat64Array::from_iter_values(values)),
+    };
+
+    ArrowFixedSizeListArray::try_new(field.clone(), 4, values, None)
+        .ok()
+        .map(|array| Arc::new(array) as ArrowArrayRef)
+}
+
+fn slerp(a: [f64; 4], mut b: [f64; 4], t: f64) -> [f64; 4] {
+    let mut dot: f64 = a.iter().zip(&b).map(|(a, b)| a * b).sum();
+
+    // Always take the shortest path.
+    if dot < 0.0 {
+        b = b.map(|v| -v);
+        dot = -dot;
+    }
+
+    let (s0, s1) = if dot > 0.9995 {
+        // Nearly parallel: fall back to linear interpolation to avoid dividing by ~zero.
+        (1.0 - t, t)
+    } else {
+        let theta = dot.acos();
+        let sin_theta = theta.sin();
+        (
+            ((1.0 - t) * theta).sin() / sin_theta,
+            (t * theta).sin() / sin_theta,
+        )
+    };
+
+    let q = [
+        s0 * a[0] + s1 * b[0],
+        s0 * a[1] + s1 * b[1],
+        s0 * a[2] + s1 * b[2],
+        s0 * a[3] + s1 * b[3],
+    ];
+
+    let norm = q.iter().map(|v| v * v).sum::<f64>().sqrt();
+    if norm > 0.0 { q.map(|v| v / norm) } else { q }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slerp_halfway() {
+        // Identity to 90° around Z.
+        let a = [0.0, 0.0, 0.0, 1.0];
+        let b = [
+            0.0,
+            0.0,
+            std::f64::consts::FRAC_1_SQRT_2,
+            std::f64::consts::FRAC_1_SQRT_2,
+        ];
+
+        let q = slerp(a, b, 0.5);
+        let expected = [
+            0.0,
+            0.0,
+            (std::f64::consts::PI / 8.0).sin(),
+            (std::f64::consts::PI / 8.0).cos(),
+        ];
+        for (q, expected) in q.iter().zip(expected) {
+            assert!((q - expected).abs() < 1e-9, "{q:?} != {expected:?}");
+        }
+
+        assert_eq!(slerp(a, b, 0.0), a);
+    }
+
+    #[test]
+    fn lerp_nested() {
+        let a: ArrowArrayRef = Arc::new(ArrowFloat64Array::from(vec![0.0, 10.0]));
+        let b: ArrowArrayRef = Arc::new(ArrowFloat64Array::from(vec![1.0, 20.0]));
+
+        let c = lerp_array(&a, &b, 0.25).unwrap();
+        assert_eq!(
+            c.as_primitive::<Float64Type>().values().to_vec(),
+            vec![0.25, 12.5]
+        );
+
+        let d: ArrowArrayRef = Arc::new(ArrowFloat64Array::from(vec![1.0]));
+        assert!(lerp_array(&a, &d, 0.25).is_none());
+    }
+}
@@ -1,6 +1,7 @@
 //! The Rerun public data APIs. Get dataframes back from your Rerun datastore.
 
 mod engine;
+mod interpolation;
 mod query;
 
 pub use self::engine::QueryEngine;
@@ -8,8 +9,8 @@ pub use self::query::QueryHandle;
 
 #[doc(no_inline)]
 pub use self::external::re_chunk_store::{
-    ChunkStoreConfig, ChunkStoreHandle, Index, IndexRange, IndexValue, QueryExpression,
-    SparseFillStrategy, ViewContentsSelector,
+    ChunkStoreConfig, ChunkStoreHandle, Index, IndexRange, IndexValue, InterpolationStrategy,
+    IsNotNullFilter, IsNotNullOperator, QueryExpression, SparseFillStrategy, ViewContentsSelector,
 };
 #[doc(no_inline)]
 pub use self::external::re_log_types::{
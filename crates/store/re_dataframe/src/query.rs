@@ -17,7 +17,7 @@ use arrow::{
         SchemaRef as ArrowSchemaRef,
     },
 };
-use itertools::{Either, Itertools as _};
+use itertools::Itertools as _;
 use nohash_hasher::{IntMap, IntSet};
 
 use re_arrow_util::{ArrowArrayDowncastRef as _, into_arrow_ref};
@@ -27,7 +27,7 @@ use re_chunk::{
 };
 use re_chunk_store::{
     ChunkStore, ColumnDescriptor, ComponentColumnDescriptor, Index, IndexColumnDescriptor,
-    IndexValue, QueryExpression, SparseFillStrategy,
+    IndexValue, InterpolationStrategy, IsNotNullOperator, QueryExpression, SparseFillStrategy,
 };
 use re_log_types::AbsoluteTimeRange;
 use re_query::{QueryCache, StorageEngineLike};
@@ -146,6 +146,10 @@ struct QueryHandleState {
     unique_index_values: Vec<IndexValue>,
 }
 
+/// For each column of the `filtered_is_not_null` filter, the index of its chunks in the view
+/// contents (or `usize::MAX` if it isn't part of the view), along with how to combine them.
+type ViewPovChunksIdx = (Vec<usize>, IsNotNullOperator);
+
 impl<E: StorageEngineLike> QueryHandle<E> {
     pub(crate) fn new(engine: E, query: QueryExpression) -> Self {
         Self {
@@ -302,24 +306,47 @@ impl<E: StorageEngineLike> QueryHandle<E> {
         } else {
             re_tracing::profile_scope!("index_values");
 
-            let mut view_chunks = view_chunks.iter();
-            let view_chunks = if let Some(view_pov_chunks_idx) = view_pov_chunks_idx {
-                Either::Left(view_chunks.nth(view_pov_chunks_idx).into_iter())
-            } else {
-                Either::Right(view_chunks)
-            };
-
-            let mut all_unique_index_values: BTreeSet<TimeInt> = view_chunks
-                .flat_map(|chunks| {
-                    chunks.iter().filter_map(|(_cursor, chunk)| {
+            let index_values = |chunks: &[(AtomicU64, Chunk)]| -> BTreeSet<TimeInt> {
+                chunks
+                    .iter()
+                    .filter_map(|(_cursor, chunk)| {
                         chunk
                             .timelines()
                             .get(&filtered_index)
                             .map(|time_column| time_column.times())
                     })
-                })
-                .flatten()
-                .collect();
+                    .flatten()
+                    .collect()
+            };
+
+            let mut all_unique_index_values: BTreeSet<TimeInt> =
+                if let Some((view_pov_chunks_idx, operator)) = view_pov_chunks_idx {
+                    // NOTE: Columns that aren't part of the view contents have no index values.
+                    let mut pov_index_values = view_pov_chunks_idx.iter().map(|idx| {
+                        view_chunks
+                            .get(*idx)
+                            .map(|chunks| index_values(chunks))
+                            .unwrap_or_default()
+                    });
+
+                    match operator {
+                        IsNotNullOperator::And => pov_index_values
+                            .next()
+                            .map(|first| {
+                                pov_index_values.fold(first, |acc, index_values| {
+                                    acc.intersection(&index_values).copied().collect()
+                                })
+                            })
+                            .unwrap_or_default(),
+
+                        IsNotNullOperator::Or => pov_index_values.flatten().collect(),
+                    }
+                } else {
+                    view_chunks
+                        .iter()
+                        .flat_map(|chunks| index_values(chunks))
+                        .collect()
+                };
 
             if let Some(filtered_index_values) = self.query.filtered_index_values.as_ref() {
                 all_unique_index_values.retain(|time| filtered_index_values.contains(time));
@@ -473,8 +500,16 @@ impl<E: StorageEngineLike> QueryHandle<E> {
         cache: &QueryCache,
         query: &RangeQuery,
         view_contents: &[ColumnDescriptor],
-    ) -> (Option<usize>, Vec<Vec<(AtomicU64, Chunk)>>) {
-        let mut view_pov_chunks_idx = self.query.filtered_is_not_null.as_ref().map(|_| usize::MAX);
+    ) -> (Option<ViewPovChunksIdx>, Vec<Vec<(AtomicU64, Chunk)>>) {
+        let povs = self
+            .query
+            .filtered_is_not_null
+            .as_ref()
+            .filter(|filter| !filter.columns.is_empty());
+
+        // NOTE: `usize::MAX` is used for columns that aren't part of the view contents.
+        let mut view_pov_chunks_idx =
+            povs.map(|filter| (vec![usize::MAX; filter.columns.len()], filter.operator));
 
         let view_chunks = view_contents
             .iter()
@@ -487,10 +522,14 @@ impl<E: StorageEngineLike> QueryHandle<E> {
                         .fetch_chunks(store, cache, query, &column.entity_path, [&column.into()])
                         .unwrap_or_default();
 
-                    if let Some(pov) = self.query.filtered_is_not_null.as_ref()
-                        && column.matches(pov)
+                    if let Some(filter) = povs
+                        && let Some((povs_idx, _)) = view_pov_chunks_idx.as_mut()
                     {
-                        view_pov_chunks_idx = Some(idx);
+                        for (pov, pov_idx) in filter.columns.iter().zip(povs_idx) {
+                            if column.matches(pov) {
+                                *pov_idx = idx;
+                            }
+                        }
                     }
 
                     chunks
@@ -891,6 +930,7 @@ impl<E: StorageEngineLike> QueryHandle<E> {
         ///
         /// Possibly retrofilled, see [`QueryExpression::sparse_fill_strategy`].
         #[derive(Debug)]
+        #[expect(clippy::enum_variant_names)]
         enum StreamingJoinState<'a> {
             /// Incoming data for the current iteration.
             StreamingJoinState(StreamingJoinStateEntry<'a>),
@@ -899,6 +939,11 @@ impl<E: StorageEngineLike> QueryHandle<E> {
             ///
             /// See [`QueryExpression::sparse_fill_strategy`].
             Retrofilled(UnitChunkShared),
+
+            /// Data interpolated from the surrounding values, as a single-row list array.
+            ///
+            /// See [`QueryExpression::interpolation_strategy`].
+            Interpolated(arrow::array::ListArray),
         }
 
         // Although that's a synchronous lock, we probably don't need to worry about it until
@@ -1033,10 +1078,52 @@ impl<E: StorageEngineLike> QueryHandle<E> {
             }
         }
 
+        if self.query.interpolation_strategy == InterpolationStrategy::Interpolate
+            && self.query.using_index_values.is_some()
+        {
+            // Everything that yielded `null` for the current iteration.
+            let null_streaming_states = view_streaming_state
+                .iter_mut()
+                .enumerate()
+                .filter(|(_view_idx, streaming_state)| streaming_state.is_none());
+
+            for (view_idx, streaming_state) in null_streaming_states {
+                let Some(ColumnDescriptor::Component(descr)) =
+                    state.view_contents.get_index_or_component(view_idx)
+                else {
+                    continue;
+                };
+
+                let Some(component_descriptor) = store.entity_component_descriptor(
+                    &descr.entity_path,
+                    descr.archetype,
+                    descr.component,
+                ) else {
+                    continue;
+                };
+
+                *streaming_state = crate::interpolation::interpolate_at(
+                    store,
+                    cache,
+                    &descr.entity_path,
+                    &component_descriptor,
+                    state.filtered_index,
+                    *cur_index_value,
+                )
+                .map(StreamingJoinState::Interpolated);
+            }
+        }
+
         match self.query.sparse_fill_strategy {
             SparseFillStrategy::None => {}
 
-            SparseFillStrategy::LatestAtGlobal => {
+            SparseFillStrategy::LatestAtGlobal | SparseFillStrategy::LatestAtView => {
+                // View-scoped latest-at must not pick up anything from before the filtered range.
+                let view_range = (self.query.sparse_fill_strategy
+                    == SparseFillStrategy::LatestAtView)
+                    .then_some(self.query.filtered_index_range)
+                    .flatten();
+
                 // Everything that yielded `null` for the current iteration.
                 let null_streaming_states = view_streaming_state
                     .iter_mut()
@@ -1084,6 +1171,13 @@ impl<E: StorageEngineLike> QueryHandle<E> {
                         .components
                         .into_values()
                         .next()
+                        .filter(|unit| {
+                            view_range.is_none_or(|view_range| {
+                                unit.index(&state.filtered_index).is_some_and(|(time, _)| {
+                                    time.is_static() || view_range.contains(time)
+                                })
+                            })
+                        })
                         .map(|unit| StreamingJoinState::Retrofilled(unit.clone()));
                 }
             }
@@ -1114,15 +1208,20 @@ impl<E: StorageEngineLike> QueryHandle<E> {
                 .flatten()
                 .flat_map(|streaming_state| {
                     match streaming_state {
-                        StreamingJoinState::StreamingJoinState(s) => s.chunk.timelines(),
-                        StreamingJoinState::Retrofilled(unit) => unit.timelines(),
+                        StreamingJoinState::StreamingJoinState(s) => Some(s.chunk.timelines()),
+                        StreamingJoinState::Retrofilled(unit) => Some(unit.timelines()),
+
+                        // Interpolated values don't exist at any particular index value.
+                        StreamingJoinState::Interpolated(_) => None,
                     }
-                    .values()
+                    .into_iter()
+                    .flat_map(|timelines| timelines.values())
                     // NOTE: Cannot fail, just want to stay away from unwraps.
                     .filter_map(move |time_column| {
                         let cursor = match streaming_state {
                             StreamingJoinState::StreamingJoinState(s) => s.cursor as usize,
-                            StreamingJoinState::Retrofilled(_) => 0,
+                            StreamingJoinState::Retrofilled(_)
+                            | StreamingJoinState::Interpolated(_) => 0,
                         };
                         time_column
                             .times_raw()
@@ -1200,6 +1299,8 @@ impl<E: StorageEngineLike> QueryHandle<E> {
                         })?;
                         unit.components().get(&component_desc).cloned()
                     }
+
+                    StreamingJoinState::Interpolated(list_array) => Some(list_array.clone()),
                 };
 
 
@@ -1338,7 +1439,8 @@ mod tests {
 
     use re_chunk::{Chunk, ChunkId, ComponentIdentifier, RowId, TimePoint};
     use re_chunk_store::{
-        AbsoluteTimeRange, ChunkStore, ChunkStoreConfig, ChunkStoreHandle, QueryExpression, TimeInt,
+        AbsoluteTimeRange, ChunkStore, ChunkStoreConfig, ChunkStoreHandle, IsNotNullFilter,
+        QueryExpression, TimeInt,
     };
     use re_format_arrow::format_record_batch;
     use re_log_types::{
@@ -1381,6 +1483,7 @@ mod tests {
     // * [x] filtered_is_not_null
     // * [x] sparse_fill_strategy
     // * [x] using_index_values
+    // * [x] interpolation_strategy
     //
     // In addition to those, some much needed extras:
     // * [x] num_rows
@@ -1481,6 +1584,40 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn sparse_fill_strategy_latestatview() -> anyhow::Result<()> {
+        re_log::setup_logging();
+
+        let store = ChunkStoreHandle::new(create_nasty_store()?);
+        eprintln!("{store}");
+        let query_cache = QueryCache::new_handle(store.clone());
+        let query_engine = QueryEngine::new(store.clone(), query_cache.clone());
+
+        let filtered_index = Some(TimelineName::new("frame_nr"));
+        let query = QueryExpression {
+            filtered_index,
+            filtered_index_range: Some(AbsoluteTimeRange::new(55, 70)),
+            sparse_fill_strategy: SparseFillStrategy::LatestAtView,
+            ..Default::default()
+        };
+        eprintln!("{query:#?}:");
+
+        let query_handle = query_engine.query(query.clone());
+        assert_eq!(
+            query_engine.query(query.clone()).into_iter().count() as u64,
+            query_handle.num_rows()
+        );
+        let dataframe = concat_batches(
+            query_handle.schema(),
+            &query_handle.batch_iter().collect_vec(),
+        )?;
+        eprintln!("{}", format_record_batch(&dataframe.clone()));
+
+        assert_snapshot!(DisplayRB(dataframe));
+
+        Ok(())
+    }
+
     #[test]
     fn filtered_index_range() -> anyhow::Result<()> {
         re_log::setup_logging();
@@ -1626,6 +1763,99 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn using_index_values_interpolated() -> anyhow::Result<()> {
+        use re_types::archetypes::{Scalars, Transform3D};
+        use re_types::datatypes::Quaternion;
+
+        re_log::setup_logging();
+
+        let store = ChunkStore::new_handle(
+            re_log_types::StoreId::random(re_log_types::StoreKind::Recording, "test_app"),
+            ChunkStoreConfig::COMPACTION_DISABLED,
+        );
+
+        let entity_path = EntityPath::from("/robot");
+        let frame1 = TimeInt::new_temporal(10);
+        let frame2 = TimeInt::new_temporal(20);
+
+        let chunk = Chunk::builder(entity_path.clone())
+            .with_archetype(
+                RowId::new(),
+                [build_frame_nr(frame1)],
+                &Scalars::single(1.0),
+            )
+            .with_archetype(
+                RowId::new(),
+                [build_frame_nr(frame1)],
+                &Transform3D::update_fields()
+                    .with_translation([0.0, 0.0, 0.0])
+                    .with_quaternion(Quaternion::IDENTITY),
+            )
+            .with_archetype(
+                RowId::new(),
+                [build_frame_nr(frame2)],
+                &Scalars::single(3.0),
+            )
+            .with_archetype(
+                RowId::new(),
+                [build_frame_nr(frame2)],
+                &Transform3D::update_fields()
+                    .with_translation([2.0, 4.0, 6.0])
+                    .with_quaternion(Quaternion::from_xyzw([
+                        0.0,
+                        0.0,
+                        std::f32::consts::FRAC_1_SQRT_2,
+                        std::f32::consts::FRAC_1_SQRT_2,
+                    ])),
+            )
+            .build()?;
+        store.write().insert_chunk(&Arc::new(chunk))?;
+
+        let query_cache = QueryCache::new_handle(store.clone());
+        let query_engine = QueryEngine::new(store.clone(), query_cache.clone());
+
+        let query = QueryExpression {
+            view_contents: Some(
+                [(
+                    entity_path,
+                    Some(
+                        [
+                            Scalars::descriptor_scalars().component,
+                            Transform3D::descriptor_translation().component,
+                            Transform3D::descriptor_quaternion().component,
+                        ]
+                        .into_iter()
+                        .collect(),
+                    ),
+                )]
+                .into_iter()
+                .collect(),
+            ),
+            filtered_index: Some(TimelineName::new("frame_nr")),
+            using_index_values: Some(
+                [0, 10, 15, 20, 25]
+                    .into_iter()
+                    .map(TimeInt::new_temporal)
+                    .collect(),
+            ),
+            interpolation_strategy: InterpolationStrategy::Interpolate,
+            ..Default::default()
+        };
+        eprintln!("{query:#?}:");
+
+        let query_handle = query_engine.query(query.clone());
+        let dataframe = concat_batches(
+            query_handle.schema(),
+            &query_handle.batch_iter().collect_vec(),
+        )?;
+        eprintln!("{}", format_record_batch(&dataframe.clone()));
+
+        assert_snapshot!(DisplayRB(dataframe));
+
+        Ok(())
+    }
+
     #[test]
     fn filtered_is_not_null() -> anyhow::Result<()> {
         re_log::setup_logging();
@@ -1644,10 +1874,13 @@ mod tests {
 
             let query = QueryExpression {
                 filtered_index,
-                filtered_is_not_null: Some(ComponentColumnSelector {
-                    entity_path: "no/such/entity".into(),
-                    component: component.to_string(),
-                }),
+                filtered_is_not_null: Some(
+                    ComponentColumnSelector {
+                        entity_path: "no/such/entity".into(),
+                        component: component.to_string(),
+                    }
+                    .into(),
+                ),
                 ..Default::default()
             };
             eprintln!("{query:#?}:");
@@ -1670,10 +1903,13 @@ mod tests {
         {
             let query = QueryExpression {
                 filtered_index,
-                filtered_is_not_null: Some(ComponentColumnSelector {
-                    entity_path: entity_path.clone(),
-                    component: "AFieldThatDoesntExist".to_owned(),
-                }),
+                filtered_is_not_null: Some(
+                    ComponentColumnSelector {
+                        entity_path: entity_path.clone(),
+                        component: "AFieldThatDoesntExist".to_owned(),
+                    }
+                    .into(),
+                ),
                 ..Default::default()
             };
             eprintln!("{query:#?}:");
@@ -1698,10 +1934,13 @@ mod tests {
 
             let query = QueryExpression {
                 filtered_index,
-                filtered_is_not_null: Some(ComponentColumnSelector {
-                    entity_path: entity_path.clone(),
-                    component: component.to_string(),
-                }),
+                filtered_is_not_null: Some(
+                    ComponentColumnSelector {
+                        entity_path: entity_path.clone(),
+                        component: component.to_string(),
+                    }
+                    .into(),
+                ),
                 ..Default::default()
             };
             eprintln!("{query:#?}:");
@@ -1726,10 +1965,13 @@ mod tests {
 
             let query = QueryExpression {
                 filtered_index,
-                filtered_is_not_null: Some(ComponentColumnSelector {
-                    entity_path: entity_path.clone(),
-                    component: component.to_string(),
-                }),
+                filtered_is_not_null: Some(
+                    ComponentColumnSelector {
+                        entity_path: entity_path.clone(),
+                        component: component.to_string(),
+                    }
+                    .into(),
+                ),
                 ..Default::default()
             };
             eprintln!("{query:#?}:");
@@ -1751,6 +1993,93 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn filtered_is_not_null_multi() -> anyhow::Result<()> {
+        re_log::setup_logging();
+
+        let store = ChunkStoreHandle::new(create_nasty_store()?);
+        eprintln!("{store}");
+        let query_cache = QueryCache::new_handle(store.clone());
+        let query_engine = QueryEngine::new(store.clone(), query_cache.clone());
+
+        let filtered_index = Some(TimelineName::new("frame_nr"));
+        let entity_path: EntityPath = "this/that".into();
+
+        let columns = [MyPoints::descriptor_points(), MyPoints::descriptor_colors()].map(|descr| {
+            ComponentColumnSelector {
+                entity_path: entity_path.clone(),
+                component: descr.component.to_string(),
+            }
+        });
+
+        // MyPoint AND MyColor
+        {
+            let query = QueryExpression {
+                filtered_index,
+                filtered_is_not_null: Some(IsNotNullFilter::all(columns.clone())),
+                ..Default::default()
+            };
+            eprintln!("{query:#?}:");
+
+            let query_handle = query_engine.query(query.clone());
+            assert_eq!(
+                query_engine.query(query.clone()).into_iter().count() as u64,
+                query_handle.num_rows()
+            );
+            let dataframe = concat_batches(
+                query_handle.schema(),
+                &query_handle.batch_iter().collect_vec(),
+            )?;
+            eprintln!("{}", format_record_batch(&dataframe.clone()));
+
+            assert_snapshot!(DisplayRB(dataframe));
+        }
+
+        // MyPoint OR MyColor
+        {
+            let query = QueryExpression {
+                filtered_index,
+                filtered_is_not_null: Some(IsNotNullFilter::any(columns.clone())),
+                ..Default::default()
+            };
+            eprintln!("{query:#?}:");
+
+            let query_handle = query_engine.query(query.clone());
+            assert_eq!(
+                query_engine.query(query.clone()).into_iter().count() as u64,
+                query_handle.num_rows()
+            );
+            let dataframe = concat_batches(
+                query_handle.schema(),
+                &query_handle.batch_iter().collect_vec(),
+            )?;
+            eprintln!("{}", format_record_batch(&dataframe.clone()));
+
+            assert_snapshot!(DisplayRB(dataframe));
+        }
+
+        // MyPoint AND non-existing component
+        {
+            let query = QueryExpression {
+                filtered_index,
+                filtered_is_not_null: Some(IsNotNullFilter::all([
+                    columns[0].clone(),
+                    ComponentColumnSelector {
+                        entity_path: entity_path.clone(),
+                        component: "AFieldThatDoesntExist".to_owned(),
+                    },
+                ])),
+                ..Default::default()
+            };
+            eprintln!("{query:#?}:");
+
+            let query_handle = query_engine.query(query.clone());
+            assert_eq!(0, query_handle.num_rows());
+        }
+
+        Ok(())
+    }
+
     #[test]
     fn view_contents() -> anyhow::Result<()> {
         re_log::setup_logging();
@@ -2183,10 +2512,13 @@ mod tests {
             let ComponentDescriptor { component, .. } = MyPoints::descriptor_points();
             let query = QueryExpression {
                 filtered_index,
-                filtered_is_not_null: Some(ComponentColumnSelector {
-                    entity_path: entity_path.clone(),
-                    component: component.to_string(),
-                }),
+                filtered_is_not_null: Some(
+                    ComponentColumnSelector {
+                        entity_path: entity_path.clone(),
+                        component: component.to_string(),
+                    }
+                    .into(),
+                ),
                 ..Default::default()
             };
             eprintln!("{query:#?}:");
@@ -2342,6 +2674,7 @@ mod tests {
             filtered_index_range: None,
             filtered_index_values: None,
             using_index_values: None,
+            interpolation_strategy: re_chunk_store::InterpolationStrategy::None,
             filtered_is_not_null: None,
             sparse_fill_strategy: re_chunk_store::SparseFillStrategy::None,
             selection: None,
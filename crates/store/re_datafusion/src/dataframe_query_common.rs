@@ -13,7 +13,7 @@ use datafusion::physical_plan::ExecutionPlan;
 use datafusion::physical_plan::coalesce_batches::CoalesceBatchesExec;
 use re_dataframe::external::re_chunk::ChunkId;
 use re_dataframe::external::re_chunk_store::ChunkStore;
-use re_dataframe::{Index, QueryExpression};
+use re_dataframe::{Index, IsNotNullFilter, QueryExpression, SparseFillStrategy};
 use re_grpc_client::{ConnectionClient, ConnectionRegistryHandle};
 use re_log_encoding::codec::wire::decoder::Decode as _;
 use re_log_types::EntryId;
@@ -230,19 +230,64 @@ impl DataframeQueryTableProvider {
         None
     }
 
+    /// Collects the columns of an `IS NOT NULL` expression, or of a disjunction of those
+    /// (e.g. `a IS NOT NULL OR b IS NOT NULL`).
+    fn is_neq_null_any(expr: &Expr) -> Option<Vec<ComponentColumnSelector>> {
+        if let Expr::BinaryExpr(binary) = expr
+            && binary.op == Operator::Or
+        {
+            let mut columns = Self::is_neq_null_any(binary.left.as_ref())?;
+            columns.extend(Self::is_neq_null_any(binary.right.as_ref())?);
+            return Some(columns);
+        }
+
+        Self::is_neq_null(expr)
+            .and_then(Self::selector_from_column)
+            .map(|selector| vec![selector])
+    }
+
     /// For a given input expression, check to see if it can match the supported
-    /// row filtering. We can currently filter out rows for which a specific
-    /// component of one entity is not null. We do this by checking the column
+    /// row filtering. We can currently filter out rows for which specific
+    /// components are not null. We do this by checking the column
     /// name matches the entity path and component naming conventions, which
     /// should always be true at the level of this call. We attempt to match
     /// a few different logically equivalent variants the user may pass.
+    ///
+    /// Returns the resulting filter, as well as which of the input expressions it covers.
+    ///
+    /// `DataFusion` filters are conjunctive: all single-column filters are combined with
+    /// [`re_dataframe::IsNotNullOperator::And`]. A disjunction of filters can only be pushed down on its own,
+    /// i.e. if there are no single-column filters to combine it with.
     fn compute_column_is_neq_null_filter(
         filters: &[&Expr],
-    ) -> Vec<Option<ComponentColumnSelector>> {
-        filters
+    ) -> (Option<IsNotNullFilter>, Vec<bool>) {
+        let columns = filters
+            .iter()
+            .map(|expr| Self::is_neq_null_any(expr))
+            .collect::<Vec<_>>();
+
+        let and_columns = columns
             .iter()
-            .map(|expr| Self::is_neq_null(expr).and_then(Self::selector_from_column))
-            .collect()
+            .flatten()
+            .filter(|columns| columns.len() == 1)
+            .flatten()
+            .cloned()
+            .collect::<Vec<_>>();
+
+        if !and_columns.is_empty() {
+            let pushed = columns
+                .iter()
+                .map(|columns| columns.as_ref().is_some_and(|columns| columns.len() == 1))
+                .collect();
+            return (Some(IsNotNullFilter::all(and_columns)), pushed);
+        }
+
+        let Some(or_idx) = columns.iter().position(Option::is_some) else {
+            return (None, vec![false; filters.len()]);
+        };
+
+        let pushed = (0..filters.len()).map(|idx| idx == or_idx).collect();
+        (columns[or_idx].clone().map(IsNotNullFilter::any), pushed)
     }
 }
 
@@ -270,12 +315,9 @@ impl TableProvider for DataframeQueryTableProvider {
     ) -> datafusion::common::Result<Arc<dyn ExecutionPlan>> {
         let mut query_expression = self.query_expression.clone();
 
-        // Find the first column selection that is a component
         let filters = filters.iter().collect::<Vec<_>>();
-        query_expression.filtered_is_not_null = Self::compute_column_is_neq_null_filter(&filters)
-            .into_iter()
-            .flatten()
-            .next();
+        let (filtered_is_not_null, _) = Self::compute_column_is_neq_null_filter(&filters);
+        query_expression.filtered_is_not_null = filtered_is_not_null;
 
         crate::PartitionStreamExec::try_new(
             &self.schema,
@@ -298,26 +340,17 @@ impl TableProvider for DataframeQueryTableProvider {
         &self,
         filters: &[&Expr],
     ) -> datafusion::common::Result<Vec<TableProviderFilterPushDown>> {
-        let filter_columns = Self::compute_column_is_neq_null_filter(filters);
-        let non_null_columns = filter_columns.iter().flatten().collect::<Vec<_>>();
-        if let Some(col) = non_null_columns.first() {
-            let col = *col;
-            Ok(filter_columns
-                .iter()
-                .map(|filter| {
-                    if Some(col) == filter.as_ref() {
-                        TableProviderFilterPushDown::Exact
-                    } else {
-                        TableProviderFilterPushDown::Unsupported
-                    }
-                })
-                .collect::<Vec<_>>())
-        } else {
-            Ok(vec![
-                TableProviderFilterPushDown::Unsupported;
-                filters.len()
-            ])
-        }
+        let (_, pushed) = Self::compute_column_is_neq_null_filter(filters);
+        Ok(pushed
+            .into_iter()
+            .map(|pushed| {
+                if pushed {
+                    TableProviderFilterPushDown::Exact
+                } else {
+                    TableProviderFilterPushDown::Unsupported
+                }
+            })
+            .collect())
     }
 }
 
@@ -539,7 +572,15 @@ pub(crate) fn time_array_ref_to_i64(time_array: &ArrayRef) -> Result<Int64Array,
 }
 
 pub fn query_from_query_expression(query_expression: &QueryExpression) -> Query {
-    let latest_at = if query_expression.is_static() {
+    // View-scoped sparse filling never looks before the start of the range, so there is no need
+    // to fetch anything but static data on top of the range itself.
+    let is_view_scoped_range = query_expression.sparse_fill_strategy
+        == SparseFillStrategy::LatestAtView
+        && query_expression.filtered_index_range.is_some()
+        && query_expression.filtered_index_values.is_none()
+        && query_expression.using_index_values.is_none();
+
+    let latest_at = if query_expression.is_static() || is_view_scoped_range {
         Some(QueryLatestAt::new_static())
     } else {
         query_expression
@@ -599,3 +640,138 @@ impl PartialOrd for ChunkInfo {
         Some(self.cmp(other))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use datafusion::common::ScalarValue;
+    use datafusion::prelude::{col, lit};
+    use re_log_types::{AbsoluteTimeRange, TimeInt, TimelineName};
+
+    use super::*;
+
+    fn selector(column: &str) -> ComponentColumnSelector {
+        ComponentColumnSelector::from_str(column).unwrap()
+    }
+
+    #[test]
+    fn is_neq_null_any() {
+        let expr = col("/points:Points3D:positions").is_not_null();
+        assert_eq!(
+            DataframeQueryTableProvider::is_neq_null_any(&expr),
+            Some(vec![selector("/points:Points3D:positions")])
+        );
+
+        let expr = col("/points:Points3D:positions")
+            .is_not_null()
+            .or(col("/points:Points3D:colors").not_eq(lit(ScalarValue::Null)))
+            .or(!col("/boxes:Boxes3D:sizes").is_null());
+        assert_eq!(
+            DataframeQueryTableProvider::is_neq_null_any(&expr),
+            Some(vec![
+                selector("/points:Points3D:positions"),
+                selector("/points:Points3D:colors"),
+                selector("/boxes:Boxes3D:sizes"),
+            ])
+        );
+
+        // A disjunction can only be pushed down if all of its terms can.
+        let expr = col("/points:Points3D:positions")
+            .is_not_null()
+            .or(col("/points:Points3D:colors").is_null());
+        assert_eq!(DataframeQueryTableProvider::is_neq_null_any(&expr), None);
+
+        let expr = col("/points:Points3D:positions")
+            .is_not_null()
+            .and(col("/points:Points3D:colors").is_not_null());
+        assert_eq!(DataframeQueryTableProvider::is_neq_null_any(&expr), None);
+    }
+
+    #[test]
+    fn compute_column_is_neq_null_filter() {
+        let positions = col("/points:Points3D:positions").is_not_null();
+        let colors = col("/points:Points3D:colors").is_not_null();
+        let positions_or_colors = positions.clone().or(colors.clone());
+        let unsupported = col("/points:Points3D:positions").is_null();
+
+        let (filter, pushed) = DataframeQueryTableProvider::compute_column_is_neq_null_filter(&[]);
+        assert_eq!(filter, None);
+        assert!(pushed.is_empty());
+
+        let (filter, pushed) =
+            DataframeQueryTableProvider::compute_column_is_neq_null_filter(&[&unsupported]);
+        assert_eq!(filter, None);
+        assert_eq!(pushed, vec![false]);
+
+        // Single-column filters are combined with `And`.
+        let (filter, pushed) = DataframeQueryTableProvider::compute_column_is_neq_null_filter(&[
+            &positions,
+            &unsupported,
+            &colors,
+        ]);
+        assert_eq!(
+            filter,
+            Some(IsNotNullFilter::all([
+                selector("/points:Points3D:positions"),
+                selector("/points:Points3D:colors"),
+            ]))
+        );
+        assert_eq!(pushed, vec![true, false, true]);
+
+        // A disjunction on its own is pushed down with `Or`.
+        let (filter, pushed) = DataframeQueryTableProvider::compute_column_is_neq_null_filter(&[
+            &unsupported,
+            &positions_or_colors,
+        ]);
+        assert_eq!(
+            filter,
+            Some(IsNotNullFilter::any([
+                selector("/points:Points3D:positions"),
+                selector("/points:Points3D:colors"),
+            ]))
+        );
+        assert_eq!(pushed, vec![false, true]);
+
+        // ...but not along with single-column filters, which take precedence.
+        let (filter, pushed) = DataframeQueryTableProvider::compute_column_is_neq_null_filter(&[
+            &positions_or_colors,
+            &colors,
+        ]);
+        assert_eq!(
+            filter,
+            Some(IsNotNullFilter::all([selector("/points:Points3D:colors")]))
+        );
+        assert_eq!(pushed, vec![false, true]);
+    }
+
+    #[test]
+    fn query_from_query_expression_sparse_fill() {
+        let range_query = |sparse_fill_strategy| QueryExpression {
+            filtered_index: Some(TimelineName::new("frame")),
+            filtered_index_range: Some(AbsoluteTimeRange::new(10, 20)),
+            sparse_fill_strategy,
+            ..Default::default()
+        };
+
+        // Global sparse filling needs the latest-at state at the start of the range.
+        let query = query_from_query_expression(&range_query(SparseFillStrategy::LatestAtGlobal));
+        let latest_at = query.latest_at.unwrap();
+        assert_eq!(latest_at.index.as_deref(), Some("frame"));
+        assert_eq!(latest_at.at, TimeInt::new_temporal(10));
+
+        // View-scoped sparse filling only needs static data on top of the range.
+        let query = query_from_query_expression(&range_query(SparseFillStrategy::LatestAtView));
+        assert!(query.latest_at.unwrap().is_static());
+        let range = query.range.unwrap();
+        assert_eq!(range.index, "frame");
+        assert_eq!(range.index_range, AbsoluteTimeRange::new(10, 20));
+
+        // Explicit index values are sampled with a latest-at either way.
+        let query = query_from_query_expression(&QueryExpression {
+            filtered_index_values: Some([TimeInt::new_temporal(15)].into()),
+            ..range_query(SparseFillStrategy::LatestAtView)
+        });
+        let latest_at = query.latest_at.unwrap();
+        assert_eq!(latest_at.index.as_deref(), Some("frame"));
+        assert_eq!(latest_at.at, TimeInt::new_temporal(15));
+    }
+}
@@ -27,6 +27,11 @@ use crate::{Error, TimesPerTimeline, ingestion_statistics::IngestionStatistics};
 /// See [`GarbageCollectionOptions::time_budget`].
 pub const DEFAULT_GC_TIME_BUDGET: std::time::Duration = std::time::Duration::from_micros(3500); // empirical
 
+/// How often [`EntityDb::add_chunk`] enforces the [`ChunkStoreConfig::time_to_live`], at most.
+///
+/// Collecting garbage on every single insertion would dominate ingestion.
+const TIME_TO_LIVE_GC_INTERVAL: std::time::Duration = std::time::Duration::from_millis(100);
+
 // ----------------------------------------------------------------------------¨
 
 /// What class of [`EntityDb`] is this?
@@ -70,6 +75,11 @@ pub struct EntityDb {
     /// Keeps track of the last time data was inserted into this store (viewer wall-clock).
     last_modified_at: web_time::Instant,
 
+    /// The last time [`ChunkStoreConfig::time_to_live`] was enforced (viewer wall-clock).
+    ///
+    /// See [`TIME_TO_LIVE_GC_INTERVAL`].
+    last_time_to_live_gc_at: Option<web_time::Instant>,
+
     /// The highest `RowId` in the store,
     /// which corresponds to the last edit time.
     /// Ignores deletions.
@@ -126,6 +136,7 @@ impl EntityDb {
             data_source: None,
             set_store_info: None,
             last_modified_at: web_time::Instant::now(),
+            last_time_to_live_gc_at: None,
             latest_row_id: None,
             entity_path_from_hash: Default::default(),
             times_per_timeline: Default::default(),
@@ -550,7 +561,7 @@ impl EntityDb {
             // overwritten static data leading to dangling chunks.
             let entity_paths_with_deletions = store_events
                 .iter()
-                .filter(|event| event.kind == ChunkStoreDiffKind::Deletion)
+                .filter(|event| event.kind == ChunkStoreDiffKind::Deletion && !event.evicted)
                 .map(|event| event.chunk.entity_path().clone())
                 .collect();
 
@@ -564,6 +575,26 @@ impl EntityDb {
             self.stats.on_events(timestamps, &store_events);
         }
 
+        let time_to_live = engine.store().config().time_to_live;
+        drop(engine);
+
+        // Enforce the sliding window, if any, now that the latest time might have moved forward.
+        let mut store_events = store_events;
+        if let Some(time_to_live) = time_to_live
+            && chunk.timelines().contains_key(&time_to_live.timeline)
+            && self
+                .last_time_to_live_gc_at
+                .is_none_or(|last_gc_at| last_gc_at.elapsed() >= TIME_TO_LIVE_GC_INTERVAL)
+        {
+            self.last_time_to_live_gc_at = Some(web_time::Instant::now());
+            store_events.extend(self.gc(&GarbageCollectionOptions {
+                target: time_to_live.into(),
+                protect_latest: 0,
+                time_budget: DEFAULT_GC_TIME_BUDGET,
+                protected_time_ranges: Default::default(),
+            }));
+        }
+
         Ok(store_events)
     }
 
@@ -704,7 +735,7 @@ impl EntityDb {
         let engine = engine.downgrade();
         let entity_paths_with_deletions = store_events
             .iter()
-            .filter(|event| event.kind == ChunkStoreDiffKind::Deletion)
+            .filter(|event| event.kind == ChunkStoreDiffKind::Deletion && !event.evicted)
             .map(|event| event.chunk.entity_path().clone())
             .collect();
         tree.on_store_deletions(&engine, &entity_paths_with_deletions, store_events);
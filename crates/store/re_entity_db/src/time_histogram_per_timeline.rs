@@ -142,6 +142,11 @@ impl ChunkStoreSubscriber for TimeHistogramPerTimeline {
         re_tracing::profile_function!();
 
         for event in events {
+            if event.evicted {
+                // The data is still there, it just lives on disk now.
+                continue;
+            }
+
             let times = event
                 .chunk
                 .timelines()
@@ -77,6 +77,11 @@ impl ChunkStoreSubscriber for TimesPerTimeline {
         re_tracing::profile_function!(format!("num_events={}", events.len()));
 
         for event in events {
+            if event.evicted {
+                // The data is still there, it just lives on disk now.
+                continue;
+            }
+
             for (&timeline, time_column) in event.chunk.timelines() {
                 let stats = self
                     .0
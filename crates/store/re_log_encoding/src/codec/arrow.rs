@@ -77,6 +77,19 @@ pub(crate) fn encode_arrow(
             let _span = tracing::trace_span!("lz4::compress").entered();
             lz4_flex::block::compress(&uncompressed)
         }
+        #[cfg(not(target_arch = "wasm32"))]
+        crate::Compression::Zstd { level } => {
+            re_tracing::profile_scope!("zstd::compress");
+            let _span = tracing::trace_span!("zstd::compress").entered();
+            zstd::bulk::compress(&uncompressed, level).map_err(crate::encoder::EncodeError::Zstd)?
+        }
+        #[cfg(target_arch = "wasm32")]
+        crate::Compression::Zstd { .. } => {
+            return Err(crate::encoder::EncodeError::Zstd(std::io::Error::new(
+                std::io::ErrorKind::Unsupported,
+                "zstd compression is not available on the web",
+            )));
+        }
     };
 
     Ok(Payload {
@@ -106,6 +119,12 @@ pub(crate) fn decode_arrow(
                 lz4_flex::block::decompress_into(data, &mut uncompressed)?;
                 uncompressed.as_slice()
             }
+            crate::Compression::Zstd { .. } => {
+                re_tracing::profile_scope!("zstd-decompress");
+                let _span = tracing::trace_span!("zstd::decompress").entered();
+                decompress_zstd_into(data, uncompressed_size, &mut uncompressed)?;
+                uncompressed.as_slice()
+            }
         };
 
         Ok(read_arrow_from_bytes(&mut &data[..])?)
@@ -127,9 +146,47 @@ pub(crate) fn decode_arrow(
                     lz4_flex::block::decompress_into(data, uncompressed)?;
                     uncompressed.as_slice()
                 }
+                crate::Compression::Zstd { .. } => {
+                    let _span = tracing::trace_span!("zstd::decompress").entered();
+                    decompress_zstd_into(data, uncompressed_size, uncompressed)?;
+                    uncompressed.as_slice()
+                }
             };
 
             Ok(read_arrow_from_bytes(&mut &data[..])?)
         })
     }
 }
+
+/// Decompresses a single zstd frame into `uncompressed`, reusing its allocation.
+///
+/// The web uses the pure Rust `ruzstd`, since the C library can't easily be built for it.
+#[cfg(feature = "decoder")]
+fn decompress_zstd_into(
+    data: &[u8],
+    uncompressed_size: usize,
+    uncompressed: &mut Vec<u8>,
+) -> Result<(), crate::decoder::DecodeError> {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        uncompressed.resize(uncompressed_size, 0);
+        let num_bytes = zstd::bulk::decompress_to_buffer(data, uncompressed.as_mut_slice())
+            .map_err(crate::decoder::DecodeError::Zstd)?;
+        uncompressed.truncate(num_bytes);
+        Ok(())
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    {
+        use std::io::Read as _;
+
+        let mut decoder = ruzstd::decoding::StreamingDecoder::new(data)
+            .map_err(|err| crate::decoder::DecodeError::Zstd(std::io::Error::other(err)))?;
+        uncompressed.clear();
+        uncompressed.reserve(uncompressed_size);
+        decoder
+            .read_to_end(uncompressed)
+            .map_err(crate::decoder::DecodeError::Zstd)?;
+        Ok(())
+    }
+}
@@ -1,5 +1,6 @@
 //! Decoding [`LogMsg`]:es from `.rrd` files/streams.
 
+pub mod seekable;
 pub mod stream;
 
 #[cfg(feature = "decoder")]
@@ -81,6 +82,9 @@ pub enum DecodeError {
     #[error("lz4 error: {0}")]
     Lz4(#[from] lz4_flex::block::DecompressError),
 
+    #[error("zstd error: {0}")]
+    Zstd(std::io::Error),
+
     #[error("Protobuf error: {0}")]
     Protobuf(#[from] re_protos::external::prost::DecodeError),
 
@@ -617,6 +621,14 @@ mod tests {
                 compression: Compression::LZ4,
                 serializer: Serializer::Protobuf,
             },
+            EncodingOptions {
+                compression: Compression::ZSTD,
+                serializer: Serializer::Protobuf,
+            },
+            EncodingOptions {
+                compression: Compression::Zstd { level: 19 },
+                serializer: Serializer::Protobuf,
+            },
         ];
 
         for options in options {
@@ -633,6 +645,37 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_compression_options() {
+        for compression in [Compression::Off, Compression::LZ4, Compression::ZSTD] {
+            let options = EncodingOptions {
+                compression,
+                serializer: Serializer::Protobuf,
+            };
+            assert_eq!(
+                EncodingOptions::from_bytes(options.to_bytes()).unwrap(),
+                options
+            );
+        }
+
+        // The level is an encoder-only setting, it never makes it into the stream.
+        let options = EncodingOptions {
+            compression: Compression::Zstd { level: 19 },
+            serializer: Serializer::Protobuf,
+        };
+        assert_eq!(
+            EncodingOptions::from_bytes(options.to_bytes()).unwrap(),
+            EncodingOptions::PROTOBUF_ZSTD
+        );
+
+        // Readers must cleanly reject compression formats they don't know about.
+        let err = EncodingOptions::from_bytes([42, Serializer::Protobuf as u8, 0, 0]).unwrap_err();
+        assert!(
+            matches!(err, crate::OptionsError::UnknownCompression(42)),
+            "Expected OptionsError::UnknownCompression; got {err:?}"
+        );
+    }
+
     /// Test that legacy messages (aka `StoreId` without an application id) are properly decoded.
     #[test]
     fn test_decode_legacy() {
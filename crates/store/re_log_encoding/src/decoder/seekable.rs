@@ -0,0 +1,407 @@
+//! Random access into `.rrd` files that have a footer.
+//!
+//! See [`crate::footer`] for more information.
+
+use std::io::{Read, Seek, SeekFrom};
+
+use re_build_info::CrateVersion;
+use re_chunk::Span;
+use re_log_types::LogMsg;
+
+use crate::{
+    FileHeader,
+    app_id_injector::CachingApplicationIdInjector,
+    codec::file::{self, MessageHeader, MessageKind},
+    footer::{RRD_FOOTER_MAGIC, RRD_FOOTER_TRAILER_SIZE, RrdFooter, RrdFooterQuery},
+};
+
+use super::{DecodeError, read_options};
+
+// ---
+
+/// Reads the [`RrdFooter`] at the end of an `.rrd` file, if any.
+///
+/// Returns `Ok(None)` if the file doesn't have a footer, e.g. because it was written by an older
+/// version of Rerun, or because it is still being written to.
+///
+/// Footers of concatenated files only cover the last file in the stream, so they are ignored too.
+pub fn read_footer(read: &mut (impl Read + Seek)) -> Result<Option<RrdFooter>, DecodeError> {
+    re_tracing::profile_function!();
+
+    use re_protos::external::prost::Message as _;
+
+    let file_len = read.seek(SeekFrom::End(0))?;
+    let min_len = (FileHeader::SIZE + MessageHeader::SIZE_BYTES + RRD_FOOTER_TRAILER_SIZE) as u64;
+    if file_len < min_len {
+        return Ok(None);
+    }
+
+    let mut trailer = [0_u8; RRD_FOOTER_TRAILER_SIZE];
+    read.seek(SeekFrom::End(-(RRD_FOOTER_TRAILER_SIZE as i64)))?;
+    read.read_exact(&mut trailer)?;
+
+    #[allow(clippy::unwrap_used)] // cannot fail
+    let footer_len = u64::from_le_bytes(trailer[..8].try_into().unwrap());
+    if &trailer[8..] != RRD_FOOTER_MAGIC {
+        return Ok(None);
+    }
+
+    // The footer is the payload of the end-of-stream marker, which should sit right before it.
+    // The length comes from the file, so it may be anything.
+    let Some(payload_len) = footer_len.checked_add(RRD_FOOTER_TRAILER_SIZE as u64) else {
+        return Ok(None);
+    };
+    let Some(end_marker_offset) = payload_len
+        .checked_add(MessageHeader::SIZE_BYTES as u64)
+        .and_then(|len| file_len.checked_sub(len))
+    else {
+        return Ok(None);
+    };
+    if end_marker_offset < FileHeader::SIZE as u64 {
+        return Ok(None);
+    }
+
+    read.seek(SeekFrom::Start(end_marker_offset))?;
+    let end_marker = MessageHeader::decode(read)?;
+    if end_marker.kind != MessageKind::End || end_marker.len != payload_len {
+        return Ok(None);
+    }
+
+    let mut buf = vec![0; footer_len as usize];
+    read.read_exact(&mut buf)?;
+    let footer: RrdFooter =
+        re_protos::log_msg::v1alpha1::RrdFooter::decode(buf.as_slice())?.try_into()?;
+
+    // Offsets are relative to the start of the file the footer was written for: if the messages
+    // don't end right where the end-of-stream marker starts, we're looking at concatenated files.
+    let messages_end = footer
+        .entries
+        .last()
+        .map_or(FileHeader::SIZE as u64, |entry| entry.byte_span.end());
+    if messages_end != end_marker_offset {
+        re_log::debug!("Ignoring footer of concatenated .rrd stream");
+        return Ok(None);
+    }
+
+    Ok(Some(footer))
+}
+
+/// Decodes [`LogMsg`]s from an `.rrd` file in any order, using its [`RrdFooter`].
+///
+/// Use this to only load the parts of a large recording that you're interested in, see
+/// [`Self::read_query`].
+pub struct SeekableDecoder<R: Read + Seek> {
+    version: CrateVersion,
+    footer: RrdFooter,
+    read: R,
+
+    /// The application id cache used for migrating old data.
+    app_id_cache: CachingApplicationIdInjector,
+}
+
+impl<R: Read + Seek> SeekableDecoder<R> {
+    /// Instantiates a new seekable decoder.
+    ///
+    /// Returns `Ok(None)` if the file doesn't have a footer, in which case you should fall back to
+    /// a regular [`super::Decoder`].
+    pub fn new(mut read: R) -> Result<Option<Self>, DecodeError> {
+        re_tracing::profile_function!();
+
+        read.seek(SeekFrom::Start(0))?;
+        let (version, _options) = read_options(&mut read)?;
+
+        let Some(footer) = read_footer(&mut read)? else {
+            return Ok(None);
+        };
+
+        Ok(Some(Self {
+            version,
+            footer,
+            read,
+            app_id_cache: CachingApplicationIdInjector::default(),
+        }))
+    }
+
+    /// Returns the Rerun version that was used to encode the data in the first place.
+    #[inline]
+    pub fn version(&self) -> CrateVersion {
+        self.version
+    }
+
+    /// The index of all the messages in the file.
+    #[inline]
+    pub fn footer(&self) -> &RrdFooter {
+        &self.footer
+    }
+
+    /// Decodes the message at the given location in the file.
+    ///
+    /// `byte_span` must come from an entry of [`Self::footer`].
+    pub fn read_at(&mut self, byte_span: Span<u64>) -> Result<LogMsg, DecodeError> {
+        re_tracing::profile_function!();
+
+        self.read.seek(SeekFrom::Start(byte_span.start))?;
+        let (read_bytes, msg) =
+            file::decoder::decode_to_app(&mut self.app_id_cache, &mut self.read)?;
+
+        if read_bytes != byte_span.len {
+            return Err(DecodeError::Codec(
+                crate::codec::CodecError::UnexpectedStreamState,
+            ));
+        }
+
+        // `None` would mean we've been pointed at the end-of-stream marker.
+        msg.ok_or(DecodeError::Codec(
+            crate::codec::CodecError::UnexpectedStreamState,
+        ))
+    }
+
+    /// Decodes all the messages relevant to the given query, in file order.
+    ///
+    /// Only the selected chunks are ever read from disk. Messages that aren't chunks
+    /// (e.g. `SetStoreInfo`) are always included, see [`RrdFooter::select`].
+    pub fn read_query(
+        &mut self,
+        query: &RrdFooterQuery,
+    ) -> impl Iterator<Item = Result<LogMsg, DecodeError>> + '_ {
+        let byte_spans: Vec<_> = self
+            .footer
+            .select(query)
+            .map(|entry| entry.byte_span)
+            .collect();
+
+        byte_spans
+            .into_iter()
+            .map(move |byte_span| self.read_at(byte_span))
+    }
+}
+
+#[cfg(all(test, feature = "encoder"))]
+mod tests {
+    #![allow(clippy::unwrap_used)] // acceptable for tests
+
+    use std::io::Cursor;
+
+    use re_chunk::{Chunk, RowId, TimeInt, TimePoint, Timeline};
+    use re_log_types::{
+        AbsoluteTimeRange, EntityPathFilter, SetStoreInfo, StoreId, StoreInfo, StoreKind,
+        StoreSource,
+    };
+
+    use super::*;
+    use crate::EncodingOptions;
+    use crate::encoder::DroppableEncoder;
+
+    fn fake_log_messages() -> Vec<LogMsg> {
+        let store_id = StoreId::random(StoreKind::Recording, "test_app");
+        let timeline = Timeline::new_sequence("frame");
+
+        let chunk = |entity_path: &str, frame: Option<i64>| {
+            let timepoint = frame.map_or_else(TimePoint::default, |frame| {
+                TimePoint::default().with(timeline, TimeInt::new_temporal(frame))
+            });
+
+            let chunk = Chunk::builder(entity_path)
+                .with_archetype(
+                    RowId::new(),
+                    timepoint,
+                    &re_types::archetypes::Points3D::new([(1.0, 2.0, 3.0)]),
+                )
+                .build()
+                .unwrap();
+
+            LogMsg::ArrowMsg(store_id.clone(), chunk.to_arrow_msg().unwrap())
+        };
+
+        vec![
+            LogMsg::SetStoreInfo(SetStoreInfo {
+                row_id: *RowId::new(),
+                info: StoreInfo {
+                    store_id: store_id.clone(),
+                    cloned_from: None,
+                    store_source: StoreSource::Unknown,
+                    store_version: Some(CrateVersion::LOCAL),
+                },
+            }),
+            chunk("/static", None),
+            chunk("/a", Some(1)),
+            chunk("/b", Some(1)),
+            chunk("/a", Some(10)),
+            chunk("/b", Some(10)),
+        ]
+    }
+
+    fn encode(messages: &[LogMsg], with_footer: bool) -> Vec<u8> {
+        let mut data = Vec::new();
+        {
+            let mut encoder = DroppableEncoder::new(
+                CrateVersion::LOCAL,
+                EncodingOptions::PROTOBUF_COMPRESSED,
+                &mut data,
+            )
+            .unwrap();
+            if with_footer {
+                encoder = encoder.with_footer();
+            }
+            for msg in messages {
+                encoder.append(msg).unwrap();
+            }
+            encoder.finish().unwrap();
+        }
+        data
+    }
+
+    fn entity_paths(messages: &[LogMsg]) -> Vec<String> {
+        messages
+            .iter()
+            .filter_map(|msg| match msg {
+                LogMsg::ArrowMsg(_, arrow_msg) => Some(
+                    Chunk::from_arrow_msg(arrow_msg)
+                        .unwrap()
+                        .entity_path()
+                        .to_string(),
+                ),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_footer_is_backwards_compatible() {
+        let messages = fake_log_messages();
+        let data = encode(&messages, true);
+
+        let decoded: Vec<_> = crate::decoder::Decoder::new(data.as_slice())
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        similar_asserts::assert_eq!(messages, decoded);
+    }
+
+    #[test]
+    fn test_read_footer() {
+        let messages = fake_log_messages();
+
+        let footer = read_footer(&mut Cursor::new(encode(&messages, true)))
+            .unwrap()
+            .unwrap();
+        assert_eq!(footer.entries.len(), messages.len());
+        assert_eq!(footer.num_chunks(), messages.len() - 1);
+
+        assert!(
+            read_footer(&mut Cursor::new(encode(&messages, false)))
+                .unwrap()
+                .is_none()
+        );
+
+        // A corrupt footer length must not overflow.
+        for footer_len in [u64::MAX, u64::MAX - RRD_FOOTER_TRAILER_SIZE as u64] {
+            let mut corrupt = encode(&messages, true);
+            let trailer_offset = corrupt.len() - RRD_FOOTER_TRAILER_SIZE;
+            corrupt[trailer_offset..trailer_offset + 8].copy_from_slice(&footer_len.to_le_bytes());
+            assert!(read_footer(&mut Cursor::new(corrupt)).unwrap().is_none());
+        }
+
+        // The offsets of a concatenated stream don't match the footer of the last file anymore.
+        let mut concatenated = encode(&messages, true);
+        concatenated.extend(encode(&messages, true));
+        assert!(
+            read_footer(&mut Cursor::new(concatenated))
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_read_query() {
+        let messages = fake_log_messages();
+        let mut decoder = SeekableDecoder::new(Cursor::new(encode(&messages, true)))
+            .unwrap()
+            .unwrap();
+
+        let everything: Vec<_> = decoder
+            .read_query(&RrdFooterQuery::default())
+            .collect::<Result<_, _>>()
+            .unwrap();
+        similar_asserts::assert_eq!(messages, everything);
+
+        let query = RrdFooterQuery {
+            entity_path_filter: Some(
+                EntityPathFilter::parse_forgiving("+ /a").resolve_without_substitutions(),
+            ),
+            time_range: None,
+        };
+        let decoded: Vec<_> = decoder
+            .read_query(&query)
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert!(matches!(decoded[0], LogMsg::SetStoreInfo(_)));
+        assert_eq!(entity_paths(&decoded), vec!["/a", "/a"]);
+
+        let query = RrdFooterQuery {
+            entity_path_filter: None,
+            time_range: Some(("frame".into(), AbsoluteTimeRange::new(5, 20))),
+        };
+        let decoded: Vec<_> = decoder
+            .read_query(&query)
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(entity_paths(&decoded), vec!["/static", "/a", "/b"]);
+
+        let query = RrdFooterQuery {
+            entity_path_filter: None,
+            time_range: Some(("other_timeline".into(), AbsoluteTimeRange::EVERYTHING)),
+        };
+        let decoded: Vec<_> = decoder
+            .read_query(&query)
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(entity_paths(&decoded), vec!["/static"]);
+    }
+
+    #[test]
+    fn test_read_query_keeps_blueprint() {
+        let blueprint_id = StoreId::random(StoreKind::Blueprint, "test_app");
+        let blueprint_chunk = Chunk::builder("/viewport")
+            .with_archetype(
+                RowId::new(),
+                TimePoint::default().with(
+                    Timeline::new_sequence("blueprint"),
+                    TimeInt::new_temporal(1),
+                ),
+                &re_types::archetypes::Points3D::new([(1.0, 2.0, 3.0)]),
+            )
+            .build()
+            .unwrap();
+
+        let mut messages = fake_log_messages();
+        messages.push(LogMsg::ArrowMsg(
+            blueprint_id,
+            blueprint_chunk.to_arrow_msg().unwrap(),
+        ));
+
+        let mut decoder = SeekableDecoder::new(Cursor::new(encode(&messages, true)))
+            .unwrap()
+            .unwrap();
+
+        let query = RrdFooterQuery {
+            entity_path_filter: Some(
+                EntityPathFilter::parse_forgiving("+ /a").resolve_without_substitutions(),
+            ),
+            time_range: Some(("frame".into(), AbsoluteTimeRange::new(5, 20))),
+        };
+
+        let decoded: Vec<_> = decoder
+            .read_query(&query)
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(entity_paths(&decoded), vec!["/a", "/viewport"]);
+
+        // Same thing for streams without a footer.
+        let filtered: Vec<_> = messages
+            .into_iter()
+            .filter(|msg| query.matches_msg(msg))
+            .collect();
+        assert_eq!(entity_paths(&filtered), vec!["/a", "/viewport"]);
+    }
+}
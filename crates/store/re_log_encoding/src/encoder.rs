@@ -4,9 +4,10 @@ use crate::FileHeader;
 use crate::Serializer;
 use crate::codec;
 use crate::codec::file::{self, encoder};
+use crate::footer::{RRD_FOOTER_MAGIC, RRD_FOOTER_TRAILER_SIZE, RrdFooterChunk, RrdFooterEntry};
 use crate::{Compression, EncodingOptions};
 use re_build_info::CrateVersion;
-use re_chunk::{ChunkError, ChunkResult};
+use re_chunk::{ChunkError, ChunkResult, Span};
 use re_log_types::LogMsg;
 use re_protos::log_msg::v1alpha1::LogMsg as LogMsgProto;
 
@@ -21,6 +22,9 @@ pub enum EncodeError {
     #[error("lz4 error: {0}")]
     Lz4(#[from] lz4_flex::block::CompressError),
 
+    #[error("zstd error: {0}")]
+    Zstd(std::io::Error),
+
     #[error("Protobuf error: {0}")]
     Protobuf(#[from] re_protos::external::prost::EncodeError),
 
@@ -97,6 +101,13 @@ impl<W: std::io::Write> DroppableEncoder<W> {
         })
     }
 
+    /// See [`Encoder::with_footer`].
+    #[inline]
+    pub fn with_footer(mut self) -> Self {
+        self.encoder.footer = Some(FooterBuilder::default());
+        self
+    }
+
     /// Returns the size in bytes of the encoded data.
     #[inline]
     pub fn append(&mut self, message: &LogMsg) -> Result<u64, EncodeError> {
@@ -124,6 +135,12 @@ impl<W: std::io::Write> DroppableEncoder<W> {
     pub fn flush_blocking(&mut self) -> std::io::Result<()> {
         self.encoder.flush_blocking()
     }
+
+    /// See [`Encoder::get_mut`].
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut W {
+        self.encoder.get_mut()
+    }
 }
 
 impl<W: std::io::Write> std::ops::Drop for DroppableEncoder<W> {
@@ -145,6 +162,14 @@ pub struct Encoder<W: std::io::Write> {
     compression: Compression,
     write: W,
     scratch: Vec<u8>,
+
+    /// The number of bytes written so far, i.e. where the next message will start.
+    num_bytes_written: u64,
+
+    /// The index of all the messages written so far, if we were asked to write a footer.
+    ///
+    /// See [`Self::with_footer`].
+    footer: Option<FooterBuilder>,
 }
 
 impl<W: std::io::Write> Encoder<W> {
@@ -165,24 +190,84 @@ impl<W: std::io::Write> Encoder<W> {
             compression: options.compression,
             write,
             scratch: Vec::new(),
+            num_bytes_written: FileHeader::SIZE as _,
+            footer: None,
         })
     }
 
+    /// Index all the messages written from now on, and write that index as an
+    /// [`crate::footer::RrdFooter`] when the stream is finished.
+    ///
+    /// The footer allows readers to only decode the chunks they are interested in, see
+    /// [`crate::decoder::seekable::SeekableDecoder`].
+    /// It is ignored by readers that don't know about it.
+    ///
+    /// The index is kept in its encoded form, and moved to a temporary file once it grows large,
+    /// so that long-running sessions don't accumulate it in memory.
+    ///
+    /// Messages appended with [`Self::append_proto`] cannot be indexed, and will disable the footer.
+    #[inline]
+    pub fn with_footer(mut self) -> Self {
+        self.footer = Some(FooterBuilder::default());
+        self
+    }
+
     /// Returns the size in bytes of the encoded data.
     pub fn append(&mut self, message: &LogMsg) -> Result<u64, EncodeError> {
         re_tracing::profile_function!();
 
+        // Only the schema and time columns of chunks are read, as this runs for every message.
+        let footer_entry = if self.footer.is_some() {
+            match message {
+                LogMsg::SetStoreInfo(msg) => Some((msg.info.store_id.clone(), None)),
+                LogMsg::ArrowMsg(store_id, msg) => match RrdFooterChunk::from_arrow_msg(msg) {
+                    Ok(chunk) => Some((store_id.clone(), Some(chunk))),
+                    Err(err) => {
+                        // The message can still be written: rather than failing the stream,
+                        // give up on the footer.
+                        re_log::warn_once!(
+                            "Failed to index message, no footer will be written: {err}"
+                        );
+                        self.footer = None;
+                        None
+                    }
+                },
+                LogMsg::BlueprintActivationCommand(cmd) => Some((cmd.blueprint_id.clone(), None)),
+            }
+        } else {
+            None
+        };
+
         self.scratch.clear();
         match self.serializer {
             Serializer::Protobuf => {
                 encoder::encode(&mut self.scratch, message, self.compression)?;
-
-                self.write
-                    .write_all(&self.scratch)
-                    .map(|_| self.scratch.len() as _)
-                    .map_err(EncodeError::Write)
             }
         }
+
+        self.write
+            .write_all(&self.scratch)
+            .map_err(EncodeError::Write)?;
+
+        let byte_span = Span {
+            start: self.num_bytes_written,
+            len: self.scratch.len() as u64,
+        };
+        self.num_bytes_written += byte_span.len;
+
+        if let (Some(footer), Some((store_id, chunk))) = (&mut self.footer, footer_entry)
+            && let Err(err) = footer.push(RrdFooterEntry {
+                byte_span,
+                store_id,
+                chunk,
+            })
+        {
+            // The message is already out: rather than failing the stream, give up on the footer.
+            re_log::warn_once!("Failed to index message, no footer will be written: {err}");
+            self.footer = None;
+        }
+
+        Ok(byte_span.len)
     }
 
     /// Returns the size in bytes of the encoded data.
@@ -193,28 +278,52 @@ impl<W: std::io::Write> Encoder<W> {
         match self.serializer {
             Serializer::Protobuf => {
                 encoder::encode_proto(&mut self.scratch, message)?;
-
-                self.write
-                    .write_all(&self.scratch)
-                    .map(|_| self.scratch.len() as _)
-                    .map_err(EncodeError::Write)
             }
         }
+
+        if self.footer.take().is_some() {
+            re_log::warn_once!(
+                "Raw Protobuf messages cannot be indexed: no footer will be written"
+            );
+        }
+
+        self.write
+            .write_all(&self.scratch)
+            .map_err(EncodeError::Write)?;
+        self.num_bytes_written += self.scratch.len() as u64;
+
+        Ok(self.scratch.len() as _)
     }
 
     // NOTE: This cannot be done in a `Drop` implementation because of `Self::into_inner` which
     // does a partial move.
     #[inline]
     pub fn finish(&mut self) -> Result<(), EncodeError> {
+        // The footer is the payload of the end-of-stream marker, see [`crate::footer`].
+        let footer = self.footer.take();
+        let payload_len = footer.as_ref().map_or(0, |footer| {
+            footer.num_bytes() + RRD_FOOTER_TRAILER_SIZE as u64
+        });
+
         match self.serializer {
             Serializer::Protobuf => {
                 file::MessageHeader {
                     kind: file::MessageKind::End,
-                    len: 0,
+                    len: payload_len,
                 }
                 .encode(&mut self.write)?;
             }
         }
+
+        if let Some(footer) = footer {
+            let footer_len = footer.num_bytes();
+            footer.write_to(&mut self.write)?;
+            self.write.write_all(&footer_len.to_le_bytes())?;
+            self.write.write_all(RRD_FOOTER_MAGIC)?;
+        }
+
+        self.num_bytes_written += file::MessageHeader::SIZE_BYTES as u64 + payload_len;
+
         Ok(())
     }
 
@@ -223,12 +332,141 @@ impl<W: std::io::Write> Encoder<W> {
         self.write.flush()
     }
 
+    /// Gets a mutable reference to the underlying writer.
+    ///
+    /// Writing to it directly will corrupt the stream.
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.write
+    }
+
     #[inline]
     pub fn into_inner(self) -> W {
         self.write
     }
 }
 
+/// Accumulates the entries of an [`crate::footer::RrdFooter`] while the stream is being written.
+///
+/// Entries are stored already encoded: a Protobuf message made of a single repeated field is just
+/// the concatenation of its encoded elements, so the footer can be written out as-is at the end.
+/// Past [`Self::MAX_IN_MEMORY_BYTES`], they are moved to an anonymous temporary file.
+struct FooterBuilder {
+    /// See [`Self::MAX_IN_MEMORY_BYTES`].
+    max_in_memory_bytes: usize,
+
+    /// The most recent encoded entries, not yet spilled to disk.
+    encoded: Vec<u8>,
+
+    /// The older encoded entries, and their total size.
+    #[cfg(not(target_arch = "wasm32"))]
+    spilled: Option<(std::fs::File, u64)>,
+}
+
+impl Default for FooterBuilder {
+    fn default() -> Self {
+        Self {
+            max_in_memory_bytes: Self::MAX_IN_MEMORY_BYTES,
+            encoded: Vec::new(),
+            #[cfg(not(target_arch = "wasm32"))]
+            spilled: None,
+        }
+    }
+}
+
+impl FooterBuilder {
+    const MAX_IN_MEMORY_BYTES: usize = 4 * 1024 * 1024;
+
+    fn push(&mut self, entry: RrdFooterEntry) -> Result<(), EncodeError> {
+        use re_protos::external::prost::Message as _;
+
+        re_protos::log_msg::v1alpha1::RrdFooter {
+            entries: vec![entry.into()],
+        }
+        .encode(&mut self.encoded)?;
+
+        #[cfg(not(target_arch = "wasm32"))]
+        if self.encoded.len() > self.max_in_memory_bytes {
+            use std::io::Write as _;
+
+            let (file, num_bytes) = match &mut self.spilled {
+                Some(spilled) => spilled,
+                None => self.spilled.insert((tempfile::tempfile()?, 0)),
+            };
+            file.write_all(&self.encoded)?;
+            *num_bytes += self.encoded.len() as u64;
+            self.encoded.clear();
+        }
+
+        Ok(())
+    }
+
+    /// The size of the encoded footer, trailer excluded.
+    fn num_bytes(&self) -> u64 {
+        #[cfg(not(target_arch = "wasm32"))]
+        let num_bytes_spilled = self.spilled.as_ref().map_or(0, |(_, num_bytes)| *num_bytes);
+        #[cfg(target_arch = "wasm32")]
+        let num_bytes_spilled = 0;
+
+        num_bytes_spilled + self.encoded.len() as u64
+    }
+
+    /// Writes out the encoded footer, trailer excluded.
+    fn write_to(self, write: &mut impl std::io::Write) -> Result<(), EncodeError> {
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some((mut file, num_bytes)) = self.spilled {
+            use std::io::Seek as _;
+
+            file.seek(std::io::SeekFrom::Start(0))?;
+            let num_bytes_copied = std::io::copy(&mut file, write)?;
+            debug_assert_eq!(num_bytes, num_bytes_copied);
+        }
+
+        write.write_all(&self.encoded)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[test]
+fn test_footer_builder_spills_to_disk() {
+    use re_protos::external::prost::Message as _;
+
+    let store_id = re_log_types::StoreId::random(re_log_types::StoreKind::Recording, "test_app");
+    let entries: Vec<_> = (0..100)
+        .map(|i| RrdFooterEntry {
+            byte_span: Span {
+                start: i * 10,
+                len: 10,
+            },
+            store_id: store_id.clone(),
+            chunk: None,
+        })
+        .collect();
+
+    let mut builder = FooterBuilder {
+        max_in_memory_bytes: 256,
+        ..Default::default()
+    };
+    for entry in &entries {
+        builder.push(entry.clone()).unwrap();
+    }
+    assert!(builder.spilled.is_some());
+
+    let num_bytes = builder.num_bytes();
+    let mut encoded = Vec::new();
+    builder.write_to(&mut encoded).unwrap();
+    assert_eq!(num_bytes, encoded.len() as u64);
+
+    let footer: crate::footer::RrdFooter =
+        re_protos::log_msg::v1alpha1::RrdFooter::decode(encoded.as_slice())
+            .unwrap()
+            .try_into()
+            .unwrap();
+    assert_eq!(entries, footer.entries);
+}
+
 /// Returns the size in bytes of the encoded data.
 pub fn encode(
     version: CrateVersion,
@@ -245,6 +483,24 @@ pub fn encode(
     Ok(size_bytes)
 }
 
+/// Like [`encode`], but also writes an [`crate::footer::RrdFooter`] at the end of the stream.
+///
+/// See [`Encoder::with_footer`].
+pub fn encode_with_footer(
+    version: CrateVersion,
+    options: EncodingOptions,
+    messages: impl Iterator<Item = ChunkResult<LogMsg>>,
+    write: &mut impl std::io::Write,
+) -> Result<u64, EncodeError> {
+    re_tracing::profile_function!();
+    let mut encoder = DroppableEncoder::new(version, options, write)?.with_footer();
+    let mut size_bytes = 0;
+    for message in messages {
+        size_bytes += encoder.append(&message?)?;
+    }
+    Ok(size_bytes)
+}
+
 /// Returns the size in bytes of the encoded data.
 pub fn encode_ref<'a>(
     version: CrateVersion,
@@ -61,7 +61,23 @@ impl FileSink {
     /// Start writing log messages to a file at the given path.
     pub fn new(path: impl Into<std::path::PathBuf>) -> Result<Self, FileSinkError> {
         // We always compress on disk
-        let encoding_options = crate::EncodingOptions::PROTOBUF_COMPRESSED;
+        Self::new_with_compression(
+            path,
+            crate::EncodingOptions::PROTOBUF_COMPRESSED.compression,
+        )
+    }
+
+    /// Start writing log messages to a file at the given path, using the given compression.
+    ///
+    /// Use [`crate::Compression::Zstd`] for smaller files at the cost of some encoding speed.
+    pub fn new_with_compression(
+        path: impl Into<std::path::PathBuf>,
+        compression: crate::Compression,
+    ) -> Result<Self, FileSinkError> {
+        let encoding_options = crate::EncodingOptions {
+            compression,
+            serializer: crate::Serializer::Protobuf,
+        };
 
         let (tx, rx) = std::sync::mpsc::channel();
 
@@ -79,7 +95,9 @@ impl FileSink {
             re_build_info::CrateVersion::LOCAL,
             encoding_options,
             file,
-        )?;
+        )?
+        // Files on disk are indexed, so that they can be loaded partially.
+        .with_footer();
         let join_handle = spawn_and_stream(Some(&path), encoder, rx)?;
 
         Ok(Self {
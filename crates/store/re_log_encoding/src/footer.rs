@@ -0,0 +1,383 @@
+//! The optional index written at the end of `.rrd` files.
+//!
+//! The footer lists every message in the file along with its byte offset, and for chunks, their
+//! entity path and time ranges. This allows readers to seek straight to the data they are
+//! interested in, rather than streaming the whole file from the start.
+//!
+//! The footer is stored as the payload of the end-of-stream marker, which is how it stays
+//! invisible to readers that don't know about it. The last [`RRD_FOOTER_TRAILER_SIZE`] bytes of the
+//! file are the little-endian length of the encoded footer, followed by [`RRD_FOOTER_MAGIC`].
+
+use std::collections::BTreeMap;
+
+use re_chunk::{
+    Chunk, ChunkError, ChunkId, ChunkResult, EntityPath, Span, TimeColumn, TimelineName,
+};
+use re_log_types::{AbsoluteTimeRange, ArrowMsg, LogMsg, ResolvedEntityPathFilter, StoreId};
+
+/// The magic bytes found at the very end of an `.rrd` file that has a footer.
+pub const RRD_FOOTER_MAGIC: &[u8; 8] = b"RRFOOTER";
+
+/// Size of the trailer: the length of the encoded footer (`u64`), followed by [`RRD_FOOTER_MAGIC`].
+pub const RRD_FOOTER_TRAILER_SIZE: usize = 16;
+
+/// An index of all the messages in an `.rrd` file.
+///
+/// See the [module-level docs](self) for more information.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RrdFooter {
+    /// All the messages in the file, in the order they were written.
+    pub entries: Vec<RrdFooterEntry>,
+}
+
+/// Where to find a single message in an `.rrd` file, and what it contains.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RrdFooterEntry {
+    /// Where the message is in the file, in bytes, header included.
+    pub byte_span: Span<u64>,
+
+    /// The store this message belongs to.
+    pub store_id: StoreId,
+
+    /// Only set if the message is a chunk (i.e. an `ArrowMsg`).
+    pub chunk: Option<RrdFooterChunk>,
+}
+
+/// Metadata about a chunk stored in an `.rrd` file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RrdFooterChunk {
+    pub chunk_id: ChunkId,
+
+    pub entity_path: EntityPath,
+
+    /// Static chunks are relevant at all times, on all timelines.
+    pub is_static: bool,
+
+    /// The time range covered by the chunk on each of its timelines.
+    pub time_ranges: BTreeMap<TimelineName, AbsoluteTimeRange>,
+}
+
+impl RrdFooterChunk {
+    pub fn from_chunk(chunk: &Chunk) -> Self {
+        Self {
+            chunk_id: chunk.id(),
+            entity_path: chunk.entity_path().clone(),
+            is_static: chunk.is_static(),
+            time_ranges: chunk
+                .timelines()
+                .iter()
+                .map(|(timeline, time_column)| (*timeline, time_column.time_range()))
+                .collect(),
+        }
+    }
+
+    /// Same as [`Self::from_chunk`], but only reads the schema and time columns of the message,
+    /// without decoding any of its components.
+    pub fn from_arrow_msg(msg: &ArrowMsg) -> ChunkResult<Self> {
+        re_tracing::profile_function!();
+
+        let batch = re_sorbet::ChunkBatch::try_from(&msg.batch)?;
+
+        let time_ranges = batch
+            .index_columns()
+            .map(|(descr, column)| {
+                let timeline = descr.timeline();
+                let times =
+                    TimeColumn::read_array(column).map_err(|err| ChunkError::Malformed {
+                        reason: format!("Bad time column '{}': {err}", descr.column_name()),
+                    })?;
+                let time_column =
+                    TimeColumn::new(descr.is_sorted().then_some(true), timeline, times);
+                Ok((*timeline.name(), time_column.time_range()))
+            })
+            .collect::<ChunkResult<_>>()?;
+
+        Ok(Self {
+            chunk_id: batch.chunk_id(),
+            entity_path: batch.entity_path().clone(),
+            is_static: batch.is_static(),
+            time_ranges,
+        })
+    }
+}
+
+/// Selects a subset of the chunks of an `.rrd` file, see [`RrdFooter::select`].
+#[derive(Debug, Clone, Default)]
+pub struct RrdFooterQuery {
+    /// Only keep chunks whose entity path matches this filter.
+    ///
+    /// Keeps everything if `None`.
+    pub entity_path_filter: Option<ResolvedEntityPathFilter>,
+
+    /// Only keep chunks that overlap with this time range, on this timeline.
+    ///
+    /// Static chunks are always kept, as they are relevant at all times.
+    /// Temporal chunks that don't have any data on this timeline are dropped.
+    ///
+    /// Keeps everything if `None`.
+    pub time_range: Option<(TimelineName, AbsoluteTimeRange)>,
+}
+
+impl RrdFooterQuery {
+    /// Does this query keep every chunk?
+    pub fn is_everything(&self) -> bool {
+        self.entity_path_filter.is_none() && self.time_range.is_none()
+    }
+
+    /// Does the given chunk, which belongs to the given store, match this query?
+    ///
+    /// Blueprint chunks always match: the query is about the data, and a recording without its
+    /// blueprint would silently lose its layout.
+    pub fn matches(&self, store_id: &StoreId, chunk: &RrdFooterChunk) -> bool {
+        let Self {
+            entity_path_filter,
+            time_range,
+        } = self;
+
+        if store_id.is_blueprint() {
+            return true;
+        }
+
+        if let Some(filter) = entity_path_filter
+            && !filter.matches(&chunk.entity_path)
+        {
+            return false;
+        }
+
+        if let Some((timeline, time_range)) = time_range
+            && !chunk.is_static
+        {
+            return chunk
+                .time_ranges
+                .get(timeline)
+                .is_some_and(|chunk_range| chunk_range.intersects(*time_range));
+        }
+
+        true
+    }
+
+    /// Does the given message pass this query?
+    ///
+    /// This is how streams that don't have a footer get filtered: it has to read every chunk.
+    /// Messages that aren't valid chunks always pass.
+    pub fn matches_msg(&self, msg: &LogMsg) -> bool {
+        if self.is_everything() {
+            return true;
+        }
+
+        match msg {
+            LogMsg::ArrowMsg(store_id, _) if store_id.is_blueprint() => true,
+
+            LogMsg::ArrowMsg(store_id, arrow_msg) => {
+                match RrdFooterChunk::from_arrow_msg(arrow_msg) {
+                    Ok(chunk) => self.matches(store_id, &chunk),
+                    Err(_) => true, // let the caller deal with the corrupt chunk
+                }
+            }
+
+            LogMsg::SetStoreInfo(_) | LogMsg::BlueprintActivationCommand(_) => true,
+        }
+    }
+}
+
+impl RrdFooter {
+    /// Returns the entries that are relevant for the given query, in file order.
+    ///
+    /// Messages that aren't chunks (e.g. `SetStoreInfo`) are always returned, so that the
+    /// resulting set of messages can always be loaded on its own.
+    pub fn select<'a>(
+        &'a self,
+        query: &'a RrdFooterQuery,
+    ) -> impl Iterator<Item = &'a RrdFooterEntry> + 'a {
+        self.entries.iter().filter(|entry| {
+            entry
+                .chunk
+                .as_ref()
+                .is_none_or(|chunk| query.matches(&entry.store_id, chunk))
+        })
+    }
+
+    /// Total number of chunks in the file.
+    pub fn num_chunks(&self) -> usize {
+        self.entries
+            .iter()
+            .filter(|entry| entry.chunk.is_some())
+            .count()
+    }
+}
+
+// ---
+
+impl From<RrdFooter> for re_protos::log_msg::v1alpha1::RrdFooter {
+    fn from(value: RrdFooter) -> Self {
+        Self {
+            entries: value.entries.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl From<RrdFooterEntry> for re_protos::log_msg::v1alpha1::RrdFooterEntry {
+    fn from(value: RrdFooterEntry) -> Self {
+        Self {
+            byte_offset: value.byte_span.start,
+            byte_len: value.byte_span.len,
+            store_id: Some(value.store_id.into()),
+            chunk: value.chunk.map(Into::into),
+        }
+    }
+}
+
+impl From<RrdFooterChunk> for re_protos::log_msg::v1alpha1::RrdFooterChunk {
+    fn from(value: RrdFooterChunk) -> Self {
+        Self {
+            chunk_id: Some(value.chunk_id.as_tuid().into()),
+            entity_path: Some(value.entity_path.into()),
+            is_static: value.is_static,
+            time_ranges: value
+                .time_ranges
+                .into_iter()
+                .map(
+                    |(timeline, time_range)| re_protos::log_msg::v1alpha1::RrdFooterTimeRange {
+                        timeline: Some(timeline.into()),
+                        time_range: Some(time_range.into()),
+                    },
+                )
+                .collect(),
+        }
+    }
+}
+
+impl TryFrom<re_protos::log_msg::v1alpha1::RrdFooter> for RrdFooter {
+    type Error = re_protos::TypeConversionError;
+
+    fn try_from(value: re_protos::log_msg::v1alpha1::RrdFooter) -> Result<Self, Self::Error> {
+        Ok(Self {
+            entries: value
+                .entries
+                .into_iter()
+                .map(TryInto::try_into)
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+impl TryFrom<re_protos::log_msg::v1alpha1::RrdFooterEntry> for RrdFooterEntry {
+    type Error = re_protos::TypeConversionError;
+
+    fn try_from(value: re_protos::log_msg::v1alpha1::RrdFooterEntry) -> Result<Self, Self::Error> {
+        use re_protos::missing_field;
+
+        let store_id = value
+            .store_id
+            .ok_or_else(|| {
+                missing_field!(re_protos::log_msg::v1alpha1::RrdFooterEntry, "store_id")
+            })?
+            .try_into()
+            .map_err(
+                |err: re_protos::common::v1alpha1::ext::StoreIdMissingApplicationIdError| {
+                    err.into_type_conversion_error("footer entries must have an application id")
+                },
+            )?;
+
+        Ok(Self {
+            byte_span: Span {
+                start: value.byte_offset,
+                len: value.byte_len,
+            },
+            store_id,
+            chunk: value.chunk.map(TryInto::try_into).transpose()?,
+        })
+    }
+}
+
+impl TryFrom<re_protos::log_msg::v1alpha1::RrdFooterChunk> for RrdFooterChunk {
+    type Error = re_protos::TypeConversionError;
+
+    fn try_from(value: re_protos::log_msg::v1alpha1::RrdFooterChunk) -> Result<Self, Self::Error> {
+        use re_protos::missing_field;
+
+        let chunk_id = ChunkId::from_tuid(
+            value
+                .chunk_id
+                .ok_or_else(|| {
+                    missing_field!(re_protos::log_msg::v1alpha1::RrdFooterChunk, "chunk_id")
+                })?
+                .try_into()?,
+        );
+
+        let entity_path = value
+            .entity_path
+            .ok_or_else(|| {
+                missing_field!(re_protos::log_msg::v1alpha1::RrdFooterChunk, "entity_path")
+            })?
+            .try_into()?;
+
+        let time_ranges = value
+            .time_ranges
+            .into_iter()
+            .map(|time_range| {
+                let timeline = time_range.timeline.ok_or_else(|| {
+                    missing_field!(re_protos::log_msg::v1alpha1::RrdFooterTimeRange, "timeline")
+                })?;
+                let range = time_range.time_range.ok_or_else(|| {
+                    missing_field!(
+                        re_protos::log_msg::v1alpha1::RrdFooterTimeRange,
+                        "time_range"
+                    )
+                })?;
+                Ok((timeline.into(), range.into()))
+            })
+            .collect::<Result<_, re_protos::TypeConversionError>>()?;
+
+        Ok(Self {
+            chunk_id,
+            entity_path,
+            is_static: value.is_static,
+            time_ranges,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)] // acceptable for tests
+
+    use re_chunk::{RowId, TimeInt, TimePoint, Timeline};
+
+    use super::*;
+
+    #[test]
+    fn footer_chunk_from_arrow_msg() {
+        let frame = Timeline::new_sequence("frame");
+        let tick = Timeline::new_sequence("tick");
+
+        let chunk = [3, 1, 2]
+            .into_iter()
+            .fold(Chunk::builder("/points"), |builder, i| {
+                builder.with_archetype(
+                    RowId::new(),
+                    TimePoint::default()
+                        .with(frame, TimeInt::new_temporal(i))
+                        .with(tick, TimeInt::new_temporal(i * 10)),
+                    &re_types::archetypes::Points3D::new([(1.0, 2.0, 3.0)]),
+                )
+            })
+            .build()
+            .unwrap();
+        let static_chunk = Chunk::builder("/static")
+            .with_archetype(
+                RowId::new(),
+                TimePoint::default(),
+                &re_types::archetypes::Points3D::new([(1.0, 2.0, 3.0)]),
+            )
+            .build()
+            .unwrap();
+
+        for chunk in [chunk, static_chunk] {
+            assert_eq!(
+                RrdFooterChunk::from_arrow_msg(&chunk.to_arrow_msg().unwrap()).unwrap(),
+                RrdFooterChunk::from_chunk(&chunk),
+            );
+        }
+    }
+}
@@ -8,6 +8,7 @@ pub mod encoder;
 
 mod app_id_injector;
 pub mod codec;
+pub mod footer;
 pub mod protobuf_conversions;
 
 #[cfg(feature = "encoder")]
@@ -20,6 +21,9 @@ pub mod stream_rrd_from_http;
 pub mod external {
     #[cfg(feature = "decoder")]
     pub use lz4_flex;
+
+    #[cfg(all(feature = "decoder", not(target_arch = "wasm32")))]
+    pub use zstd;
 }
 
 // ---------------------------------------------------------------------
@@ -44,12 +48,79 @@ const OLD_RRD_HEADERS: &[[u8; 4]] = &[*b"RRF0", *b"RRF1"];
 
 /// Compression format used.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
-#[repr(u8)]
 pub enum Compression {
-    Off = 0,
+    Off,
 
     /// Very fast compression and decompression, but not very good compression ratio.
-    LZ4 = 1,
+    LZ4,
+
+    /// Slower than [`Self::LZ4`], but with a much better compression ratio,
+    /// especially on large point clouds and tensors.
+    ///
+    /// The `level` only affects encoding: it is not stored in the stream, since
+    /// decompression doesn't depend on it.
+    /// Decoded streams always report [`Self::ZSTD_DEFAULT_LEVEL`].
+    Zstd {
+        level: i32,
+    },
+}
+
+impl Compression {
+    /// The default zstd compression level, a good trade-off between speed and ratio.
+    pub const ZSTD_DEFAULT_LEVEL: i32 = 3;
+
+    /// Zstd compression at [`Self::ZSTD_DEFAULT_LEVEL`].
+    pub const ZSTD: Self = Self::Zstd {
+        level: Self::ZSTD_DEFAULT_LEVEL,
+    };
+
+    const OFF_BYTE: u8 = 0;
+    const LZ4_BYTE: u8 = 1;
+    const ZSTD_BYTE: u8 = 2;
+
+    /// The byte used to identify this compression format in the stream header.
+    pub fn to_byte(self) -> u8 {
+        match self {
+            Self::Off => Self::OFF_BYTE,
+            Self::LZ4 => Self::LZ4_BYTE,
+            Self::Zstd { .. } => Self::ZSTD_BYTE,
+        }
+    }
+
+    /// Inverse of [`Self::to_byte`].
+    pub fn from_byte(byte: u8) -> Result<Self, OptionsError> {
+        match byte {
+            Self::OFF_BYTE => Ok(Self::Off),
+            Self::LZ4_BYTE => Ok(Self::LZ4),
+            Self::ZSTD_BYTE => Ok(Self::ZSTD),
+            _ => Err(OptionsError::UnknownCompression(byte)),
+        }
+    }
+}
+
+impl std::str::FromStr for Compression {
+    type Err = String;
+
+    /// Parses `off`, `lz4`, `zstd` or `zstd:<level>` (e.g. `zstd:19`).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "off" | "none" => Ok(Self::Off),
+            "lz4" => Ok(Self::LZ4),
+            "zstd" => Ok(Self::ZSTD),
+            other => {
+                if let Some(level) = other.strip_prefix("zstd:") {
+                    let level = level
+                        .parse()
+                        .map_err(|err| format!("invalid zstd level {level:?}: {err}"))?;
+                    Ok(Self::Zstd { level })
+                } else {
+                    Err(format!(
+                        "unknown compression {s:?}, expected one of: off, lz4, zstd, zstd:<level>"
+                    ))
+                }
+            }
+        }
+    }
 }
 
 /// How we serialize the data
@@ -74,15 +145,15 @@ impl EncodingOptions {
         compression: Compression::Off,
         serializer: Serializer::Protobuf,
     };
+    pub const PROTOBUF_ZSTD: Self = Self {
+        compression: Compression::ZSTD,
+        serializer: Serializer::Protobuf,
+    };
 
     pub fn from_bytes(bytes: [u8; 4]) -> Result<Self, OptionsError> {
         match bytes {
             [compression, serializer, 0, 0] => {
-                let compression = match compression {
-                    0 => Compression::Off,
-                    1 => Compression::LZ4,
-                    _ => return Err(OptionsError::UnknownCompression(compression)),
-                };
+                let compression = Compression::from_byte(compression)?;
                 let serializer = match serializer {
                     1 => return Err(OptionsError::RemovedMsgPackSerializer),
                     2 => Serializer::Protobuf,
@@ -99,7 +170,7 @@ impl EncodingOptions {
 
     pub fn to_bytes(self) -> [u8; 4] {
         [
-            self.compression as u8,
+            self.compression.to_byte(),
             self.serializer as u8,
             0, // reserved
             0, // reserved
@@ -12,6 +12,7 @@ impl From<re_protos::log_msg::v1alpha1::Compression> for crate::Compression {
             re_protos::log_msg::v1alpha1::Compression::Unspecified
             | re_protos::log_msg::v1alpha1::Compression::None => Self::Off,
             re_protos::log_msg::v1alpha1::Compression::Lz4 => Self::LZ4,
+            re_protos::log_msg::v1alpha1::Compression::Zstd => Self::ZSTD,
         }
     }
 }
@@ -21,6 +22,7 @@ impl From<crate::Compression> for re_protos::log_msg::v1alpha1::Compression {
         match value {
             crate::Compression::Off => Self::None,
             crate::Compression::LZ4 => Self::Lz4,
+            crate::Compression::Zstd { .. } => Self::Zstd,
         }
     }
 }
@@ -198,10 +200,7 @@ pub fn arrow_msg_to_proto(
     Ok(ProtoArrowMsg {
         store_id: Some(store_id.into()),
         chunk_id: Some(arrow_msg.chunk_id.into()),
-        compression: match compression {
-            crate::Compression::Off => re_protos::log_msg::v1alpha1::Compression::None as i32,
-            crate::Compression::LZ4 => re_protos::log_msg::v1alpha1::Compression::Lz4 as i32,
-        },
+        compression: re_protos::log_msg::v1alpha1::Compression::from(compression) as i32,
         uncompressed_size: payload.uncompressed_size as i32,
         encoding: re_protos::log_msg::v1alpha1::Encoding::ArrowIpc as i32,
         payload: payload.data.into(),
@@ -252,6 +252,93 @@ impl ::prost::Name for StoreVersion {
         "/rerun.log_msg.v1alpha1.StoreVersion".into()
     }
 }
+/// An index of all the messages in an RRD file.
+///
+/// It is written as the payload of the end-of-stream marker, so that readers that don't know about it
+/// simply skip over it. The last 16 bytes of such a file are the little-endian length of the encoded
+/// `RrdFooter`, followed by the `RRFOOTER` magic bytes.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RrdFooter {
+    #[prost(message, repeated, tag = "1")]
+    pub entries: ::prost::alloc::vec::Vec<RrdFooterEntry>,
+}
+impl ::prost::Name for RrdFooter {
+    const NAME: &'static str = "RrdFooter";
+    const PACKAGE: &'static str = "rerun.log_msg.v1alpha1";
+    fn full_name() -> ::prost::alloc::string::String {
+        "rerun.log_msg.v1alpha1.RrdFooter".into()
+    }
+    fn type_url() -> ::prost::alloc::string::String {
+        "/rerun.log_msg.v1alpha1.RrdFooter".into()
+    }
+}
+/// Where to find a single message in an RRD file, and what it contains.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RrdFooterEntry {
+    /// Offset of the message since the start of the file, in bytes, header included.
+    #[prost(uint64, tag = "1")]
+    pub byte_offset: u64,
+    /// Length of the message, in bytes, header included.
+    #[prost(uint64, tag = "2")]
+    pub byte_len: u64,
+    /// The store this message belongs to.
+    #[prost(message, optional, tag = "3")]
+    pub store_id: ::core::option::Option<super::super::common::v1alpha1::StoreId>,
+    /// Only set if the message is an `ArrowMsg`.
+    #[prost(message, optional, tag = "4")]
+    pub chunk: ::core::option::Option<RrdFooterChunk>,
+}
+impl ::prost::Name for RrdFooterEntry {
+    const NAME: &'static str = "RrdFooterEntry";
+    const PACKAGE: &'static str = "rerun.log_msg.v1alpha1";
+    fn full_name() -> ::prost::alloc::string::String {
+        "rerun.log_msg.v1alpha1.RrdFooterEntry".into()
+    }
+    fn type_url() -> ::prost::alloc::string::String {
+        "/rerun.log_msg.v1alpha1.RrdFooterEntry".into()
+    }
+}
+/// Metadata about a chunk stored in an RRD file.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RrdFooterChunk {
+    #[prost(message, optional, tag = "1")]
+    pub chunk_id: ::core::option::Option<super::super::common::v1alpha1::Tuid>,
+    #[prost(message, optional, tag = "2")]
+    pub entity_path: ::core::option::Option<super::super::common::v1alpha1::EntityPath>,
+    /// Static chunks are relevant at all times, on all timelines.
+    #[prost(bool, tag = "3")]
+    pub is_static: bool,
+    /// The time range covered by the chunk on each of its timelines.
+    #[prost(message, repeated, tag = "4")]
+    pub time_ranges: ::prost::alloc::vec::Vec<RrdFooterTimeRange>,
+}
+impl ::prost::Name for RrdFooterChunk {
+    const NAME: &'static str = "RrdFooterChunk";
+    const PACKAGE: &'static str = "rerun.log_msg.v1alpha1";
+    fn full_name() -> ::prost::alloc::string::String {
+        "rerun.log_msg.v1alpha1.RrdFooterChunk".into()
+    }
+    fn type_url() -> ::prost::alloc::string::String {
+        "/rerun.log_msg.v1alpha1.RrdFooterChunk".into()
+    }
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RrdFooterTimeRange {
+    #[prost(message, optional, tag = "1")]
+    pub timeline: ::core::option::Option<super::super::common::v1alpha1::Timeline>,
+    #[prost(message, optional, tag = "2")]
+    pub time_range: ::core::option::Option<super::super::common::v1alpha1::TimeRange>,
+}
+impl ::prost::Name for RrdFooterTimeRange {
+    const NAME: &'static str = "RrdFooterTimeRange";
+    const PACKAGE: &'static str = "rerun.log_msg.v1alpha1";
+    fn full_name() -> ::prost::alloc::string::String {
+        "rerun.log_msg.v1alpha1.RrdFooterTimeRange".into()
+    }
+    fn type_url() -> ::prost::alloc::string::String {
+        "/rerun.log_msg.v1alpha1.RrdFooterTimeRange".into()
+    }
+}
 /// The type of compression used on the payload.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
 #[repr(i32)]
@@ -261,6 +348,8 @@ pub enum Compression {
     None = 1,
     /// LZ4 block compression.
     Lz4 = 2,
+    /// Zstandard compression.
+    Zstd = 3,
 }
 impl Compression {
     /// String value of the enum field names used in the ProtoBuf definition.
@@ -272,6 +361,7 @@ impl Compression {
             Self::Unspecified => "COMPRESSION_UNSPECIFIED",
             Self::None => "COMPRESSION_NONE",
             Self::Lz4 => "COMPRESSION_LZ4",
+            Self::Zstd => "COMPRESSION_ZSTD",
         }
     }
     /// Creates an enum from field names used in the ProtoBuf definition.
@@ -280,6 +370,7 @@ impl Compression {
             "COMPRESSION_UNSPECIFIED" => Some(Self::Unspecified),
             "COMPRESSION_NONE" => Some(Self::None),
             "COMPRESSION_LZ4" => Some(Self::Lz4),
+            "COMPRESSION_ZSTD" => Some(Self::Zstd),
             _ => None,
         }
     }
@@ -309,6 +309,7 @@ impl ChunkStoreSubscriber for QueryCache {
                 kind: _, // Don't care: both additions and deletions invalidate query results.
                 chunk,
                 compacted,
+                evicted: _, // Don't care: evicted chunks must be reloaded from the cold tier.
             } = diff;
 
             {
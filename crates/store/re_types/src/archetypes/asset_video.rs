@@ -21,7 +21,7 @@ use ::re_types_core::{DeserializationError, DeserializationResult};
 
 /// **Archetype**: A video binary.
 ///
-/// Only MP4 containers are currently supported.
+/// Supported containers are MP4, Matroska (including `WebM`) and MPEG transport streams.
 ///
 /// See <https://rerun.io/docs/reference/video> for codec support and more general information.
 ///
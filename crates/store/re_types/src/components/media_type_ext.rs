@@ -53,6 +53,19 @@ impl MediaType {
     ///
     /// <https://www.iana.org/assignments/media-types/video/mp4>
     pub const MP4: &'static str = "video/mp4";
+
+    /// [Matroska video](https://en.wikipedia.org/wiki/Matroska): `video/x-matroska`.
+    pub const MKV: &'static str = "video/x-matroska";
+
+    /// [`WebM` video](https://en.wikipedia.org/wiki/WebM): `video/webm`.
+    ///
+    /// <https://www.iana.org/assignments/media-types/video/webm>
+    pub const WEBM: &'static str = "video/webm";
+
+    /// [MPEG transport stream](https://en.wikipedia.org/wiki/MPEG_transport_stream): `video/mp2t`.
+    ///
+    /// <https://www.iana.org/assignments/media-types/video/MP2T>
+    pub const MPEG_TS: &'static str = "video/mp2t";
 }
 
 impl MediaType {
@@ -118,6 +131,24 @@ impl MediaType {
     pub fn mp4() -> Self {
         Self(Self::MP4.into())
     }
+
+    /// `video/x-matroska`
+    #[inline]
+    pub fn mkv() -> Self {
+        Self(Self::MKV.into())
+    }
+
+    /// `video/webm`
+    #[inline]
+    pub fn webm() -> Self {
+        Self(Self::WEBM.into())
+    }
+
+    /// `video/mp2t`
+    #[inline]
+    pub fn mpeg_ts() -> Self {
+        Self(Self::MPEG_TS.into())
+    }
 }
 
 impl MediaType {
@@ -146,6 +177,10 @@ impl MediaType {
             Some("stl") => {
                 return Some(Self::stl());
             }
+            // `mime_guess2` considers `.ts` to be `video/vnd.dlna.mpeg-tts`.
+            Some("ts" | "m2ts" | "mts") => {
+                return Some(Self::mpeg_ts());
+            }
             _ => {}
         }
 
@@ -174,6 +209,11 @@ impl MediaType {
             // https://en.wikipedia.org/wiki/STL_(file_format)#Binary
         }
 
+        #[cfg(feature = "video")]
+        fn mpeg_ts_matcher(buf: &[u8]) -> bool {
+            re_video::is_mpeg_ts(buf)
+        }
+
         // NOTE:
         // - gltf is simply json, so no magic byte
         //   (also most gltf files contain file:// links, so not much point in sending that to
@@ -183,6 +223,9 @@ impl MediaType {
         let mut inferer = infer::Infer::new();
         inferer.add(Self::GLB, "glb", glb_matcher);
         inferer.add(Self::STL, "stl", stl_matcher);
+        // Transport streams have no magic bytes, only sync bytes at the start of every packet.
+        #[cfg(feature = "video")]
+        inferer.add(Self::MPEG_TS, "ts", mpeg_ts_matcher);
 
         inferer
             .get(data)
@@ -212,6 +255,8 @@ impl MediaType {
             // Special-case some where there are multiple extensions:
             Self::JPEG => Some("jpg"),
             Self::MARKDOWN => Some("md"),
+            Self::MKV => Some("mkv"),
+            Self::MPEG_TS => Some("ts"),
             Self::STL => Some("stl"),
             Self::TEXT => Some("txt"),
 
@@ -257,6 +302,9 @@ fn test_media_type_extension() {
     assert_eq!(MediaType::gltf().file_extension(), Some("gltf"));
     assert_eq!(MediaType::jpeg().file_extension(), Some("jpg"));
     assert_eq!(MediaType::mp4().file_extension(), Some("mp4"));
+    assert_eq!(MediaType::mkv().file_extension(), Some("mkv"));
+    assert_eq!(MediaType::webm().file_extension(), Some("webm"));
+    assert_eq!(MediaType::mpeg_ts().file_extension(), Some("ts"));
     assert_eq!(MediaType::markdown().file_extension(), Some("md"));
     assert_eq!(MediaType::plain_text().file_extension(), Some("txt"));
     assert_eq!(MediaType::png().file_extension(), Some("png"));
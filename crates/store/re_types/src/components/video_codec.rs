@@ -40,6 +40,30 @@ pub enum VideoCodec {
     ///
     /// Enum value is the fourcc for 'avc1' (the WebCodec string assigned to this codec) in big endian.
     H264 = 0x61766331,
+
+    /// High Efficiency Video Coding (HEVC/H.265)
+    ///
+    /// See <https://en.wikipedia.org/wiki/High_Efficiency_Video_Coding>
+    ///
+    /// [`components::VideoSample`][crate::components::VideoSample]s using this codec should be formatted according to Annex B specification.
+    /// (Note that this is different from AVCC format found in MP4 files.
+    /// To learn more about Annex B, check for instance <https://membrane.stream/learn/h264/3>)
+    /// Key frames (IRAP) require inclusion of a SPS (Sequence Parameter Set), as well as the
+    /// VPS (Video Parameter Set) and PPS (Picture Parameter Set).
+    ///
+    /// Enum value is the fourcc for 'hev1' (the WebCodec string assigned to this codec) in big endian.
+    H265 = 0x68657631,
+
+    /// AOMedia Video 1 (AV1)
+    ///
+    /// See <https://en.wikipedia.org/wiki/AV1>
+    ///
+    /// [`components::VideoSample`][crate::components::VideoSample]s using this codec should be formatted as a sequence of OBUs
+    /// (Open Bitstream Units) in the low overhead bitstream format, i.e. every OBU has a size field.
+    /// Key frames require inclusion of a sequence header OBU.
+    ///
+    /// Enum value is the fourcc for 'av01' (the WebCodec string assigned to this codec) in big endian.
+    AV1 = 0x61763031,
 }
 
 impl ::re_types_core::Component for VideoCodec {
@@ -115,6 +139,8 @@ impl ::re_types_core::Loggable for VideoCodec {
             .into_iter()
             .map(|typ| match typ {
                 Some(1635148593) => Ok(Some(Self::H264)),
+                Some(1751479857) => Ok(Some(Self::H265)),
+                Some(1635135537) => Ok(Some(Self::AV1)),
                 None => Ok(None),
                 Some(invalid) => Err(DeserializationError::missing_union_arm(
                     Self::arrow_datatype(),
@@ -131,6 +157,8 @@ impl std::fmt::Display for VideoCodec {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Self::H264 => write!(f, "H264"),
+            Self::H265 => write!(f, "H265"),
+            Self::AV1 => write!(f, "AV1"),
         }
     }
 }
@@ -138,7 +166,7 @@ impl std::fmt::Display for VideoCodec {
 impl ::re_types_core::reflection::Enum for VideoCodec {
     #[inline]
     fn variants() -> &'static [Self] {
-        &[Self::H264]
+        &[Self::H264, Self::H265, Self::AV1]
     }
 
     #[inline]
@@ -147,6 +175,12 @@ impl ::re_types_core::reflection::Enum for VideoCodec {
             Self::H264 => {
                 "Advanced Video Coding (AVC/H.264)\n\nSee <https://en.wikipedia.org/wiki/Advanced_Video_Coding>\n\n[`components.VideoSample`](https://rerun.io/docs/reference/types/components/video_sample)s using this codec should be formatted according to Annex B specification.\n(Note that this is different from AVCC format found in MP4 files.\nTo learn more about Annex B, check for instance <https://membrane.stream/learn/h264/3>)\nKey frames (IDR) require inclusion of a SPS (Sequence Parameter Set)\n\nEnum value is the fourcc for 'avc1' (the WebCodec string assigned to this codec) in big endian."
             }
+            Self::H265 => {
+                "High Efficiency Video Coding (HEVC/H.265)\n\nSee <https://en.wikipedia.org/wiki/High_Efficiency_Video_Coding>\n\n[`components.VideoSample`](https://rerun.io/docs/reference/types/components/video_sample)s using this codec should be formatted according to Annex B specification.\n(Note that this is different from AVCC format found in MP4 files.\nTo learn more about Annex B, check for instance <https://membrane.stream/learn/h264/3>)\nKey frames (IRAP) require inclusion of a SPS (Sequence Parameter Set), as well as the\nVPS (Video Parameter Set) and PPS (Picture Parameter Set).\n\nEnum value is the fourcc for 'hev1' (the WebCodec string assigned to this codec) in big endian."
+            }
+            Self::AV1 => {
+                "AOMedia Video 1 (AV1)\n\nSee <https://en.wikipedia.org/wiki/AV1>\n\n[`components.VideoSample`](https://rerun.io/docs/reference/types/components/video_sample)s using this codec should be formatted as a sequence of OBUs\n(Open Bitstream Units) in the low overhead bitstream format, i.e. every OBU has a size field.\nKey frames require inclusion of a sequence header OBU.\n\nEnum value is the fourcc for 'av01' (the WebCodec string assigned to this codec) in big endian."
+            }
         }
     }
 }
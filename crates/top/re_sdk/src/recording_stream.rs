@@ -902,6 +902,11 @@ struct RecordingStreamInner {
     /// See [`RecordingStream::log_file_from_path`] and [`RecordingStream::log_file_from_contents`].
     dataloader_handles: Mutex<Vec<std::thread::JoinHandle<()>>>,
 
+    /// The robots loaded by the `DataLoader`s of this `RecordingStream`, so that files logged
+    /// separately can animate each other's robots.
+    #[cfg(feature = "data_loaders")]
+    robots: re_data_loader::robots::Robots,
+
     pid_at_creation: u32,
 }
 
@@ -1018,6 +1023,8 @@ impl RecordingStreamInner {
             batcher_to_sink_handle: Some(batcher_to_sink_handle),
             sink_dependent_batcher_config,
             dataloader_handles: Mutex::new(Vec::new()),
+            #[cfg(feature = "data_loaders")]
+            robots: Default::default(),
             pid_at_creation: std::process::id(),
         })
     }
@@ -1452,6 +1459,9 @@ impl RecordingStream {
                 })
                 .unwrap_or_default()
             }),
+            rrd_query: Default::default(),
+            robots: self.with(|inner| inner.robots.clone()).unwrap_or_default(),
+            deferred_stores: None,
         };
 
         if prefer_current_recording {
@@ -2285,6 +2295,8 @@ impl fmt::Debug for RecordingStream {
                 batcher_to_sink_handle: _,
                 sink_dependent_batcher_config,
                 dataloader_handles,
+                #[cfg(feature = "data_loaders")]
+                    robots: _,
                 pid_at_creation,
             } = inner;
 
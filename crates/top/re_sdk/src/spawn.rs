@@ -37,6 +37,13 @@ pub struct SpawnOptions {
     /// Defaults to `0B`.
     pub server_memory_limit: String,
 
+    /// Only keep a sliding window of data in the Rerun Viewer: anything that falls behind the
+    /// latest time on the given timeline by more than the given duration gets dropped.
+    /// Example: `log_time=30s` or `frame_nr=1000`.
+    ///
+    /// Defaults to `None` (keep everything, up to [`Self::memory_limit`]).
+    pub time_to_live: Option<String>,
+
     /// Specifies the name of the Rerun executable.
     ///
     /// You can omit the `.exe` suffix on Windows.
@@ -73,6 +80,7 @@ impl Default for SpawnOptions {
             wait_for_bind: false,
             memory_limit: "75%".into(),
             server_memory_limit: "0B".into(),
+            time_to_live: None,
             executable_name: RERUN_BINARY.into(),
             executable_path: None,
             extra_args: Vec::new(),
@@ -301,6 +309,11 @@ pub fn spawn(opts: &SpawnOptions) -> Result<(), SpawnError> {
         rerun_bin.arg("--hide-welcome-screen");
     }
 
+    if let Some(time_to_live) = &opts.time_to_live {
+        // NOTE: See `re_chunk_store::ChunkStoreConfig::ENV_STORE_TIME_TO_LIVE`.
+        rerun_bin.env("RERUN_STORE_TIME_TO_LIVE", time_to_live);
+    }
+
     rerun_bin.args(opts.extra_args.clone());
     rerun_bin.envs(opts.extra_env.clone());
 
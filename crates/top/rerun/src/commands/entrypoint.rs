@@ -229,6 +229,22 @@ When persisted, the state will be stored at the following locations:
 If no arguments are given, a server will be hosted which a Rerun SDK can connect to.")]
     url_or_paths: Vec<String>,
 
+    /// Only load the entities of local `.rrd` files that match this entity path filter
+    /// expression, e.g. `"+ /robot/** - /robot/debug/**"`.
+    ///
+    /// Can be specified multiple times, in which case all the rules are combined.
+    /// Files that have a footer are only partially read from disk.
+    #[clap(long = "entity", value_name = "EXPR", allow_hyphen_values = true)]
+    entity_path_filters: Vec<String>,
+
+    /// Only load the data of local `.rrd` files that is within this range of the given timeline,
+    /// e.g. `frame_nr=100..200`.
+    ///
+    /// See `rerun rrd filter --help` for the accepted syntax. Static data is always loaded.
+    /// Files that have a footer are only partially read from disk.
+    #[clap(long = "time-range", value_name = "TIMELINE=MIN..MAX")]
+    time_range: Option<crate::commands::rrd::TimeRangeArg>,
+
     /// Print version and quit.
     #[clap(long)]
     version: bool,
@@ -870,6 +886,20 @@ fn run_impl(
             }
         });
 
+        let rrd_query = re_log_encoding::footer::RrdFooterQuery {
+            entity_path_filter: (!args.entity_path_filters.is_empty()).then(|| {
+                re_log_types::EntityPathFilter::parse_forgiving(args.entity_path_filters.join(" "))
+                    .resolve_without_substitutions()
+            }),
+            time_range: args
+                .time_range
+                .clone()
+                .map(|time_range| (time_range.timeline, time_range.range)),
+        };
+
+        // Robots loaded from one of the files are animated by the joint positions of the others.
+        let robots = re_data_loader::robots::Robots::default();
+
         #[allow(unused_mut)]
         let mut rxs_table = Vec::new();
         #[allow(unused_mut)]
@@ -879,7 +909,19 @@ fn run_impl(
                 // TODO(#10093): this is problematic because the connection registry's token have
                 // not yet been deserialized from persistence (this is done later by `App`. So if
                 // this requires such a token, it will fail even though it'd succeed later.
-                match data_source.stream(&connection_registry, on_cmd.clone(), None) {
+                let settings = re_data_loader::DataLoaderSettings {
+                    rrd_query: rrd_query.clone(),
+                    robots: robots.clone(),
+                    ..re_data_loader::DataLoaderSettings::recommended(
+                        re_log_types::RecordingId::random(),
+                    )
+                };
+                match data_source.stream_with_settings(
+                    &connection_registry,
+                    on_cmd.clone(),
+                    None,
+                    settings,
+                ) {
                     Ok(re_data_source::StreamSource::LogMessages(rx)) => Some(Ok(rx)),
 
                     Ok(re_data_source::StreamSource::CatalogUri(uri)) => {
@@ -1292,7 +1334,8 @@ fn stream_to_rrd_on_disk(
         re_build_info::CrateVersion::LOCAL,
         encoding_options,
         file,
-    )?;
+    )?
+    .with_footer();
 
     loop {
         if let Ok(msg) = rx.recv() {
@@ -1309,3 +1352,28 @@ fn stream_to_rrd_on_disk(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser as _;
+
+    use super::*;
+
+    #[test]
+    fn parse_entity_path_filters() {
+        let args = Args::try_parse_from([
+            "rerun",
+            "--entity",
+            "+ /robot/**",
+            "--entity",
+            "- /robot/debug/**",
+            "recording.rrd",
+        ])
+        .unwrap();
+        assert_eq!(
+            args.entity_path_filters,
+            ["+ /robot/**", "- /robot/debug/**"]
+        );
+        assert_eq!(args.url_or_paths, ["recording.rrd"]);
+    }
+}
@@ -0,0 +1,106 @@
+use std::collections::BTreeMap;
+
+use anyhow::Context as _;
+use itertools::Itertools as _;
+
+use re_log_types::{Duration, Timestamp};
+use re_sdk::external::re_data_loader::mcap::util::read_summary;
+
+// ---
+
+#[derive(Debug, Clone, clap::Parser)]
+pub struct InfoCommand {
+    /// Path to the .mcap file to read from.
+    path_to_input_mcap: String,
+}
+
+impl InfoCommand {
+    pub fn run(&self) -> anyhow::Result<()> {
+        let Self { path_to_input_mcap } = self;
+
+        let file = std::fs::File::open(path_to_input_mcap)
+            .with_context(|| format!("{path_to_input_mcap:?}"))?;
+        let size_bytes = file.metadata().map_or(0, |metadata| metadata.len());
+
+        let summary = read_summary(std::io::BufReader::new(file))
+            .with_context(|| format!("couldn't read summary of {path_to_input_mcap:?}"))?
+            .with_context(|| format!("{path_to_input_mcap:?} does not contain a summary"))?;
+
+        println!(
+            "File:       {path_to_input_mcap} ({})",
+            re_format::format_bytes(size_bytes as _)
+        );
+
+        let channel_message_counts = if let Some(stats) = &summary.stats {
+            println!(
+                "Messages:   {}",
+                re_format::format_uint(stats.message_count)
+            );
+
+            if stats.message_count > 0 {
+                let start = stats.message_start_time as i64;
+                let end = stats.message_end_time as i64;
+                println!(
+                    "Time range: {} – {} ({})",
+                    Timestamp::from_nanos_since_epoch(start).format_iso(),
+                    Timestamp::from_nanos_since_epoch(end).format_iso(),
+                    Duration::from_nanos(end - start),
+                );
+            }
+
+            Some(&stats.channel_message_counts)
+        } else {
+            println!("Messages:   <unknown, the file has no statistics>");
+            None
+        };
+
+        let compressions = summary
+            .chunk_indexes
+            .iter()
+            .map(|chunk| {
+                if chunk.compression.is_empty() {
+                    "none"
+                } else {
+                    chunk.compression.as_str()
+                }
+            })
+            .unique()
+            .join(", ");
+        println!(
+            "Chunks:     {} (compression: {compressions})",
+            re_format::format_uint(summary.chunk_indexes.len())
+        );
+
+        println!();
+        println!("Schemas:");
+        let schemas: BTreeMap<_, _> = summary.schemas.iter().collect();
+        for (id, schema) in schemas {
+            println!("  {id:>5}  {} ({})", schema.name, schema.encoding);
+        }
+
+        println!();
+        println!("Channels:");
+        let channels: BTreeMap<_, _> = summary.channels.iter().collect();
+        let topic_width = channels
+            .values()
+            .map(|channel| channel.topic.len())
+            .max()
+            .unwrap_or(0);
+        for (id, channel) in channels {
+            let num_messages = channel_message_counts
+                .and_then(|counts| counts.get(id))
+                .map_or_else(|| "?".to_owned(), |count| re_format::format_uint(*count));
+            let schema = channel
+                .schema
+                .as_ref()
+                .map_or("<schemaless>", |schema| schema.name.as_str());
+
+            println!(
+                "  {id:>5}  {:<topic_width$}  {num_messages:>12} msgs  {schema} ({})",
+                channel.topic, channel.message_encoding,
+            );
+        }
+
+        Ok(())
+    }
+}
@@ -1,16 +1,25 @@
+mod info;
+
 use std::{collections::BTreeSet, fs::File, io::BufWriter, sync::mpsc::Receiver};
 
+use anyhow::Context as _;
 use clap::Subcommand;
 use re_log_encoding::encoder::DroppableEncoder;
 use re_log_types::{LogMsg, RecordingId};
 use re_sdk::{
     ApplicationId, DataLoader, DataLoaderSettings, LoadedData,
     external::re_data_loader::{
-        McapLoader,
-        mcap::layers::{LayerIdentifier, SelectedLayers},
+        McapLoader, UrdfDataLoader, UrdfTree,
+        mcap::{
+            filter::McapFilter,
+            layers::{LayerIdentifier, SelectedLayers},
+            util::read_summary,
+        },
     },
 };
 
+use self::info::InfoCommand;
+
 #[derive(Debug, Clone, clap::Parser)]
 pub struct ConvertCommand {
     /// Paths to read from. Reads from standard input if none are specified.
@@ -35,6 +44,91 @@ pub struct ConvertCommand {
     /// output.
     #[clap(long = "recording-id")]
     recording_id: Option<String>,
+
+    /// Only convert the topics matching this glob pattern, e.g. `/camera/*`.
+    ///
+    /// Can be specified multiple times. Converts all topics if unspecified.
+    #[clap(long = "topic", value_name = "GLOB")]
+    include_topics: Vec<String>,
+
+    /// Never convert the topics matching this glob pattern, e.g. `/debug/*`.
+    ///
+    /// Can be specified multiple times. Takes precedence over `--topic`.
+    #[clap(long = "exclude-topic", value_name = "GLOB")]
+    exclude_topics: Vec<String>,
+
+    /// Only convert the channels whose schema name matches this glob pattern, e.g. `sensor_msgs/msg/*`.
+    ///
+    /// Can be specified multiple times. Converts all channels if unspecified.
+    #[clap(long = "schema", value_name = "GLOB")]
+    schemas: Vec<String>,
+
+    /// Only convert the messages logged at or after this time.
+    ///
+    /// Either an integer (nanoseconds), an RFC 3339 timestamp (e.g. `2025-06-01T12:00:00Z`) or a
+    /// duration relative to the first message of the file (e.g. `30s`).
+    #[clap(long = "start", value_name = "TIME")]
+    start: Option<LogTimeBound>,
+
+    /// Only convert the messages logged at or before this time.
+    ///
+    /// Same format as `--start`.
+    #[clap(long = "end", value_name = "TIME")]
+    end: Option<LogTimeBound>,
+
+    /// Log this robot description along with the data, and animate its joints.
+    ///
+    /// The joints are driven by the positions of `sensor_msgs/JointState` messages, matched by
+    /// joint name.
+    #[clap(long = "urdf", value_name = "robot.urdf")]
+    path_to_urdf: Option<String>,
+}
+
+/// See [`ConvertCommand::start`].
+#[derive(Debug, Clone, Copy)]
+enum LogTimeBound {
+    /// Nanoseconds, usually since the Unix epoch.
+    Absolute(u64),
+
+    /// Nanoseconds since the first message of the file.
+    SinceStart(u64),
+}
+
+impl std::str::FromStr for LogTimeBound {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if let Some(time) = re_format::parse_i64(s) {
+            Ok(Self::Absolute(time.max(0) as u64))
+        } else if let Ok(timestamp) = s.parse::<re_log_types::Timestamp>() {
+            Ok(Self::Absolute(timestamp.nanos_since_epoch().max(0) as u64))
+        } else if let Ok(duration) = s.parse::<re_log_types::Duration>() {
+            Ok(Self::SinceStart(duration.as_nanos().max(0) as u64))
+        } else {
+            Err(format!(
+                "expected an integer, a timestamp or a duration, got {s:?}"
+            ))
+        }
+    }
+}
+
+impl LogTimeBound {
+    fn resolve(self, first_log_time: u64) -> u64 {
+        match self {
+            Self::Absolute(time) => time,
+            Self::SinceStart(duration) => first_log_time.saturating_add(duration),
+        }
+    }
+}
+
+fn parse_globs(patterns: &[String]) -> anyhow::Result<Vec<glob::Pattern>> {
+    patterns
+        .iter()
+        .map(|pattern| {
+            glob::Pattern::new(pattern).with_context(|| format!("invalid glob {pattern:?}"))
+        })
+        .collect()
 }
 
 impl ConvertCommand {
@@ -45,6 +139,12 @@ impl ConvertCommand {
             application_id,
             recording_id,
             selected_layers,
+            include_topics,
+            exclude_topics,
+            schemas,
+            start,
+            end,
+            path_to_urdf,
         } = self;
 
         let start_time = std::time::Instant::now();
@@ -71,31 +171,72 @@ impl ConvertCommand {
             )
         };
 
-        let loader: &dyn DataLoader = &McapLoader::new(selected_layers);
+        let filter = McapFilter {
+            include_topics: parse_globs(include_topics)?,
+            exclude_topics: parse_globs(exclude_topics)?,
+            schemas: parse_globs(schemas)?,
+            start: None,
+            end: None,
+        };
+
+        let filter = if start.is_some() || end.is_some() {
+            let first_log_time = if matches!(start, Some(LogTimeBound::SinceStart(_)))
+                || matches!(end, Some(LogTimeBound::SinceStart(_)))
+            {
+                let file = File::open(path_to_input_mcap)
+                    .with_context(|| format!("{path_to_input_mcap:?}"))?;
+                read_summary(std::io::BufReader::new(file))?
+                    .as_ref()
+                    .and_then(first_log_time)
+                    .context("relative time bounds require an MCAP file with a summary")?
+            } else {
+                0
+            };
+
+            McapFilter {
+                start: start.map(|start| start.resolve(first_log_time)),
+                end: end.map(|end| end.resolve(first_log_time)),
+                ..filter
+            }
+        } else {
+            filter
+        };
+
+        let settings = DataLoaderSettings {
+            application_id: Some(application_id),
+            recording_id,
+            opened_store_id: None,
+            force_store_info: false,
+            entity_path_prefix: None,
+            timepoint: None,
+            rrd_query: Default::default(),
+            robots: Default::default(),
+            deferred_stores: None,
+        };
 
-        // TODO(#10862): This currently loads the entire file into memory.
         let (tx, rx) = std::sync::mpsc::channel::<LoadedData>();
-        loader.load_from_path(
-            &DataLoaderSettings {
-                application_id: Some(application_id),
-                recording_id,
-                opened_store_id: None,
-                force_store_info: false,
-                entity_path_prefix: None,
-                timepoint: None,
-            },
-            path_to_input_mcap.into(),
-            tx,
-        )?;
+
+        let urdf_tree = if let Some(path_to_urdf) = path_to_urdf {
+            UrdfDataLoader.load_from_path(&settings, path_to_urdf.into(), tx.clone())?;
+            Some(
+                UrdfTree::from_file_path(path_to_urdf)
+                    .with_context(|| format!("{path_to_urdf:?}"))?,
+            )
+        } else {
+            None
+        };
+
+        let loader: &dyn DataLoader = &McapLoader::new(selected_layers).with_filter(filter);
+        loader.load_from_path(&settings, path_to_input_mcap.into(), tx)?;
 
         if let Some(path) = path_to_output_rrd {
             let writer = BufWriter::new(File::create(path)?);
-            process_mcap(writer, &rx)?;
+            process_mcap(writer, &rx, urdf_tree.as_ref())?;
         } else {
             let stdout = std::io::stdout();
             let lock = stdout.lock();
             let writer = BufWriter::new(lock);
-            process_mcap(writer, &rx)?;
+            process_mcap(writer, &rx, urdf_tree.as_ref())?;
         }
 
         re_log::info!("Processing took {}s", start_time.elapsed().as_secs());
@@ -104,17 +245,50 @@ impl ConvertCommand {
     }
 }
 
+/// The `log_time` of the first message of the file, according to its summary.
+fn first_log_time(summary: &re_sdk::external::mcap::Summary) -> Option<u64> {
+    if let Some(stats) = &summary.stats {
+        return Some(stats.message_start_time);
+    }
+
+    summary
+        .chunk_indexes
+        .iter()
+        .map(|chunk| chunk.message_start_time)
+        .min()
+}
+
 /// Manipulate the contents of .mcap files.
 #[derive(Debug, Clone, Subcommand)]
 pub enum McapCommands {
     /// Convert an .mcap file to an .rrd
-    Convert(ConvertCommand),
+    ///
+    /// Topic, schema and time filters are applied on the chunk index of the file, so that
+    /// the parts of the file that aren't selected are never decompressed.
+    ///
+    /// Examples:
+    ///
+    /// * `rerun mcap convert input.mcap -o output.rrd`
+    ///
+    /// * `rerun mcap convert input.mcap --topic "/camera/*" --exclude-topic "*/camera_info" -o output.rrd`
+    ///
+    /// * `rerun mcap convert input.mcap --schema "sensor_msgs/msg/Imu" --start 10s --end 20s -o output.rrd`
+    ///
+    /// * `rerun mcap convert input.mcap --urdf robot.urdf -o output.rrd`
+    Convert(Box<ConvertCommand>),
+
+    /// Print the channels, schemas, message counts and time range of an .mcap file.
+    ///
+    /// This only reads the summary section at the end of the file, and is therefore fast even
+    /// for very large files.
+    Info(InfoCommand),
 }
 
 impl McapCommands {
     pub fn run(&self) -> anyhow::Result<()> {
         match self {
             Self::Convert(cmd) => cmd.run(),
+            Self::Info(cmd) => cmd.run(),
         }
     }
 }
@@ -122,6 +296,7 @@ impl McapCommands {
 fn process_mcap<W: std::io::Write>(
     writer: W,
     receiver: &Receiver<LoadedData>,
+    urdf_tree: Option<&UrdfTree>,
 ) -> anyhow::Result<()> {
     let mut num_total_msgs = 0;
     let mut topics = BTreeSet::new();
@@ -136,6 +311,14 @@ fn process_mcap<W: std::io::Write>(
             LoadedData::LogMsg(_, log_msg) => log_msg,
             LoadedData::Chunk(_, store_id, chunk) => {
                 topics.insert(chunk.entity_path().clone());
+
+                if let Some(urdf_tree) = urdf_tree {
+                    for joint_chunk in urdf_tree.joint_transform_chunks(&chunk)? {
+                        let arrow_msg = joint_chunk.to_arrow_msg()?;
+                        encoder.append(&LogMsg::ArrowMsg(store_id.clone(), arrow_msg))?;
+                    }
+                }
+
                 let arrow_msg = chunk.to_arrow_msg()?;
                 LogMsg::ArrowMsg(store_id, arrow_msg)
             }
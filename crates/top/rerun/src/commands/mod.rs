@@ -39,6 +39,7 @@ pub use self::mcap::McapCommands;
 pub use self::rrd::RrdCommands;
 pub use self::stdio::{
     read_raw_rrd_streams_from_file_or_stdin, read_rrd_streams_from_file_or_stdin,
+    read_rrd_streams_from_file_or_stdin_with_query,
 };
 
 #[cfg(feature = "analytics")]
@@ -0,0 +1,506 @@
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use anyhow::Context as _;
+use arrow::{
+    array::{ArrayRef as ArrowArrayRef, RecordBatch as ArrowRecordBatch, StringBuilder},
+    datatypes::{DataType as ArrowDataType, Field as ArrowField, Schema as ArrowSchema},
+    util::display::{ArrayFormatter, FormatOptions},
+};
+
+use re_chunk_store::{ChunkStore, ChunkStoreConfig, ChunkStoreHandle};
+use re_dataframe::{EntityPathFilter, QueryEngine, QueryExpression, SparseFillStrategy};
+use re_log_encoding::footer::RrdFooterQuery;
+use re_sdk::external::arrow;
+use re_sorbet::{ColumnSelector, ComponentColumnSelector, TimeColumnSelector};
+
+use super::filter::TimeRangeArg;
+
+// ---
+
+#[derive(Debug, Clone, clap::Parser)]
+pub struct ExportCommand {
+    /// Path to the .rrd file to read from.
+    path_to_input_rrd: String,
+
+    /// Path to write to.
+    #[arg(short = 'o', long = "output", value_name = "dst.(parquet|csv|arrow)")]
+    path_to_output: String,
+
+    /// Output format. Inferred from the extension of the output path if unspecified.
+    #[clap(long, value_enum)]
+    format: Option<ExportFormat>,
+
+    /// The recording to export, if the file contains more than one.
+    #[clap(long = "recording-id")]
+    recording_id: Option<String>,
+
+    /// The timeline to use as the index of the dataframe, e.g. `log_time` or `frame_nr`.
+    ///
+    /// If unspecified, the timeline of `--time-range` is used if any, otherwise only static data
+    /// is returned.
+    #[clap(long = "index", value_name = "TIMELINE")]
+    index: Option<String>,
+
+    /// Only export the rows within this range of the index, e.g. `frame_nr=100..200`.
+    ///
+    /// Bounds are inclusive, and either of them can be omitted (e.g. `frame_nr=100..`).
+    /// They can be integers (in the native unit of the timeline), RFC 3339 timestamps
+    /// (e.g. `2025-06-01T12:00:00Z`) or, on duration timelines, durations (e.g. `30s`).
+    #[clap(long = "time-range", value_name = "TIMELINE=MIN..MAX")]
+    time_range: Option<TimeRangeArg>,
+
+    /// Entity path filter expression selecting the entities to export, e.g. `"+ /robot/** - /robot/debug/**"`.
+    ///
+    /// Can be specified multiple times, in which case all the rules are combined.
+    /// Exports all entities if unspecified.
+    #[clap(long = "entity", value_name = "EXPR", allow_hyphen_values = true)]
+    entity_path_filters: Vec<String>,
+
+    /// The columns to export, in order, e.g. `log_time` or `/points:Points3D:positions`.
+    ///
+    /// Index columns are selected by timeline name, component columns by `ENTITY_PATH:COMPONENT`.
+    /// Can be specified multiple times. Exports all columns if unspecified.
+    #[clap(long = "column", value_name = "COLUMN")]
+    columns: Vec<String>,
+
+    /// How to fill the gaps in columns that have no data at a given index value.
+    #[clap(long = "sparse-fill", value_enum, default_value_t = SparseFill::None)]
+    sparse_fill: SparseFill,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+enum ExportFormat {
+    /// Apache Parquet.
+    Parquet,
+
+    /// Comma-separated values.
+    ///
+    /// Nested values (lists, structs, …) are written out as their string representation.
+    Csv,
+
+    /// Arrow IPC file format (a.k.a. Feather v2).
+    Arrow,
+}
+
+impl ExportFormat {
+    fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_lowercase();
+        match extension.as_str() {
+            "parquet" | "pq" => Some(Self::Parquet),
+            "csv" => Some(Self::Csv),
+            "arrow" | "ipc" | "feather" => Some(Self::Arrow),
+            _ => None,
+        }
+    }
+}
+
+/// See [`SparseFillStrategy`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+enum SparseFill {
+    /// Leave the gaps empty.
+    None,
+
+    /// Fill the gaps with the latest known value, as of the current index value.
+    LatestAtGlobal,
+
+    /// Same as `latest-at-global`, but never uses values from outside of `--time-range`.
+    LatestAtView,
+}
+
+impl From<SparseFill> for SparseFillStrategy {
+    fn from(value: SparseFill) -> Self {
+        match value {
+            SparseFill::None => Self::None,
+            SparseFill::LatestAtGlobal => Self::LatestAtGlobal,
+            SparseFill::LatestAtView => Self::LatestAtView,
+        }
+    }
+}
+
+impl ExportCommand {
+    pub fn run(&self) -> anyhow::Result<()> {
+        let Self {
+            path_to_input_rrd,
+            path_to_output,
+            format,
+            recording_id,
+            index,
+            time_range,
+            entity_path_filters,
+            columns,
+            sparse_fill,
+        } = self;
+
+        let path_to_output = PathBuf::from(path_to_output);
+        let Some(format) = format.or_else(|| ExportFormat::from_path(&path_to_output)) else {
+            anyhow::bail!(
+                "cannot infer the output format from {path_to_output:?}, please specify --format"
+            );
+        };
+
+        let index = match (index, time_range) {
+            (Some(index), Some(time_range)) => {
+                anyhow::ensure!(
+                    time_range.timeline.as_str() == index,
+                    "--time-range must be on the --index timeline ({index:?}), got {:?}",
+                    time_range.timeline,
+                );
+                Some(index.as_str().into())
+            }
+            (Some(index), None) => Some(index.as_str().into()),
+            (None, Some(time_range)) => Some(time_range.timeline),
+            (None, None) => None,
+        };
+
+        let entity_path_filter = if entity_path_filters.is_empty() {
+            EntityPathFilter::all()
+        } else {
+            EntityPathFilter::parse_forgiving(entity_path_filters.join(" "))
+        };
+
+        let now = std::time::Instant::now();
+        re_log::info!(src = ?path_to_input_rrd, dst = ?path_to_output, ?format, "export started");
+
+        // Files that have a footer are only partially loaded.
+        // Data outside of the time range might still be needed for global sparse filling, in which
+        // case we have to load everything.
+        let footer_query = RrdFooterQuery {
+            entity_path_filter: Some(entity_path_filter.clone().resolve_without_substitutions()),
+            time_range: (*sparse_fill != SparseFill::LatestAtGlobal)
+                .then(|| {
+                    time_range
+                        .clone()
+                        .map(|time_range| (time_range.timeline, time_range.range))
+                })
+                .flatten(),
+        };
+
+        let stores = ChunkStore::from_rrd_filepath_with_query(
+            &ChunkStoreConfig::from_env().unwrap_or_default(),
+            path_to_input_rrd,
+            &footer_query,
+        )
+        .with_context(|| format!("couldn't load {path_to_input_rrd:?}"))?;
+
+        let mut recordings = stores
+            .into_iter()
+            .filter(|(store_id, _)| store_id.is_recording())
+            .filter(|(store_id, _)| {
+                recording_id
+                    .as_ref()
+                    .is_none_or(|recording_id| store_id.recording_id().as_str() == recording_id)
+            });
+
+        let Some((store_id, store)) = recordings.next() else {
+            anyhow::bail!("no matching recording found in {path_to_input_rrd:?}");
+        };
+        if recordings.next().is_some() {
+            anyhow::bail!(
+                "{path_to_input_rrd:?} contains more than one recording, please specify --recording-id"
+            );
+        }
+
+        let engine = QueryEngine::from_store(ChunkStoreHandle::new(store));
+
+        let query = QueryExpression {
+            view_contents: Some(
+                engine
+                    .iter_entity_paths_sorted(&entity_path_filter)
+                    .map(|entity_path| (entity_path, None))
+                    .collect(),
+            ),
+            filtered_index: index,
+            filtered_index_range: time_range.as_ref().map(|time_range| time_range.range),
+            sparse_fill_strategy: (*sparse_fill).into(),
+            selection: (!columns.is_empty())
+                .then(|| columns.iter().map(|column| parse_column(column)).collect())
+                .transpose()?,
+            ..Default::default()
+        };
+
+        let query_handle = engine.query(query);
+        let schema = query_handle.schema().clone();
+
+        let num_rows = match format {
+            ExportFormat::Parquet => {
+                write_parquet(&path_to_output, schema, query_handle.into_batch_iter())?
+            }
+            ExportFormat::Csv => write_csv(&path_to_output, query_handle.into_batch_iter())?,
+            ExportFormat::Arrow => {
+                write_arrow_ipc(&path_to_output, &schema, query_handle.into_batch_iter())?
+            }
+        };
+
+        let size_bytes = std::fs::metadata(&path_to_output).map_or(0, |metadata| metadata.len());
+        re_log::info!(
+            recording_id = %store_id.recording_id(),
+            num_rows,
+            time = ?now.elapsed(),
+            dst = ?path_to_output,
+            dst_size_bytes = %re_format::format_bytes(size_bytes as _),
+            "export finished"
+        );
+
+        Ok(())
+    }
+}
+
+/// Parses either a timeline name (e.g. `log_time`) or a component column (e.g. `/points:Points3D:positions`).
+fn parse_column(column: &str) -> anyhow::Result<ColumnSelector> {
+    if column.contains(':') {
+        let selector: ComponentColumnSelector = column
+            .parse()
+            .with_context(|| format!("invalid column {column:?}"))?;
+        Ok(ColumnSelector::Component(selector))
+    } else {
+        anyhow::ensure!(!column.is_empty(), "invalid column: empty string");
+        Ok(ColumnSelector::Time(TimeColumnSelector::from(column)))
+    }
+}
+
+// ---
+
+fn create_file(path: &Path) -> anyhow::Result<std::io::BufWriter<std::fs::File>> {
+    let file = std::fs::File::create(path).with_context(|| format!("{path:?}"))?;
+    Ok(std::io::BufWriter::new(file))
+}
+
+fn write_parquet(
+    path: &Path,
+    schema: Arc<ArrowSchema>,
+    batches: impl Iterator<Item = ArrowRecordBatch>,
+) -> anyhow::Result<u64> {
+    let properties = parquet::file::properties::WriterProperties::builder()
+        .set_compression(parquet::basic::Compression::SNAPPY)
+        .build();
+    let mut writer =
+        parquet::arrow::ArrowWriter::try_new(create_file(path)?, schema, Some(properties))
+            .context("couldn't init parquet writer")?;
+
+    let mut num_rows = 0;
+    for batch in batches {
+        num_rows += batch.num_rows() as u64;
+        writer.write(&batch).context("couldn't write parquet")?;
+    }
+
+    writer.close().context("couldn't finish parquet")?;
+
+    Ok(num_rows)
+}
+
+fn write_csv(path: &Path, batches: impl Iterator<Item = ArrowRecordBatch>) -> anyhow::Result<u64> {
+    let mut writer = arrow::csv::Writer::new(create_file(path)?);
+
+    let mut num_rows = 0;
+    for batch in batches {
+        num_rows += batch.num_rows() as u64;
+        let batch = stringify_nested_columns(&batch)?;
+        writer.write(&batch).context("couldn't write csv")?;
+    }
+
+    writer
+        .into_inner()
+        .into_inner()
+        .context("couldn't flush csv")?;
+
+    Ok(num_rows)
+}
+
+fn write_arrow_ipc(
+    path: &Path,
+    schema: &ArrowSchema,
+    batches: impl Iterator<Item = ArrowRecordBatch>,
+) -> anyhow::Result<u64> {
+    let mut writer = arrow::ipc::writer::FileWriter::try_new(create_file(path)?, schema)
+        .context("couldn't init arrow writer")?;
+
+    let mut num_rows = 0;
+    for batch in batches {
+        num_rows += batch.num_rows() as u64;
+        writer.write(&batch).context("couldn't write arrow")?;
+    }
+
+    writer.finish().context("couldn't finish arrow")?;
+
+    Ok(num_rows)
+}
+
+/// CSV has no notion of nested data, so we replace these columns with their string representation.
+fn stringify_nested_columns(batch: &ArrowRecordBatch) -> anyhow::Result<ArrowRecordBatch> {
+    let options = FormatOptions::default();
+
+    let schema = batch.schema();
+    let (fields, columns): (Vec<_>, Vec<_>) = schema
+        .fields()
+        .iter()
+        .zip(batch.columns())
+        .map(|(field, column)| -> anyhow::Result<_> {
+            if !field.data_type().is_nested() {
+                return Ok((field.clone(), column.clone()));
+            }
+
+            let formatter = ArrayFormatter::try_new(column, &options)?;
+            let mut builder = StringBuilder::new();
+            for i in 0..column.len() {
+                if column.is_null(i) {
+                    builder.append_null();
+                } else {
+                    builder.append_value(formatter.value(i).to_string());
+                }
+            }
+
+            let field = ArrowField::new(field.name(), ArrowDataType::Utf8, true);
+            let column: ArrowArrayRef = Arc::new(builder.finish());
+            Ok((Arc::new(field), column))
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?
+        .into_iter()
+        .unzip();
+
+    Ok(ArrowRecordBatch::try_new(
+        Arc::new(ArrowSchema::new_with_metadata(
+            fields,
+            schema.metadata().clone(),
+        )),
+        columns,
+    )?)
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser as _;
+    use re_chunk::{Chunk, RowId, TimePoint};
+    use re_log_types::build_frame_nr;
+    use re_types::archetypes::Points3D;
+
+    use super::*;
+    use crate::commands::rrd::test_util;
+
+    /// A recording with one point per frame, for frames 0 through 4.
+    fn write_rrd(path: &Path) {
+        let chunk = (0..5)
+            .fold(Chunk::builder("points"), |builder, frame| {
+                builder.with_archetype(
+                    RowId::new(),
+                    TimePoint::from([build_frame_nr(frame)]),
+                    &Points3D::new([[frame as f32, 0.0, 0.0]]),
+                )
+            })
+            .build()
+            .unwrap();
+        test_util::write_rrd(path, [chunk]);
+    }
+
+    /// Runs `rerun rrd export` on a fresh recording, and returns the path of the output.
+    fn export(dir: &Path, output: &str, args: &[&str]) -> PathBuf {
+        let path_to_input_rrd = dir.join("input.rrd");
+        write_rrd(&path_to_input_rrd);
+
+        let path_to_output = dir.join(output);
+        let mut argv = vec![
+            "export".to_owned(),
+            path_to_input_rrd.to_string_lossy().into_owned(),
+            "-o".to_owned(),
+            path_to_output.to_string_lossy().into_owned(),
+        ];
+        argv.extend(args.iter().map(|arg| (*arg).to_owned()));
+
+        ExportCommand::try_parse_from(argv).unwrap().run().unwrap();
+
+        path_to_output
+    }
+
+    #[test]
+    fn export_csv() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = export(
+            dir.path(),
+            "out.csv",
+            &[
+                "--time-range",
+                "frame_nr=1..3",
+                "--column",
+                "frame_nr",
+                "--column",
+                "/points:Points3D:positions",
+            ],
+        );
+
+        let csv = std::fs::read_to_string(path).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 4, "{csv}"); // header + frames 1 through 3
+        assert!(lines[1].starts_with("1,"), "{csv}");
+        assert!(lines[3].starts_with("3,"), "{csv}");
+    }
+
+    #[test]
+    fn export_parquet() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = export(dir.path(), "out.parquet", &["--index", "frame_nr"]);
+
+        let reader = parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder::try_new(
+            std::fs::File::open(path).unwrap(),
+        )
+        .unwrap()
+        .build()
+        .unwrap();
+        let num_rows: usize = reader.map(|batch| batch.unwrap().num_rows()).sum();
+        assert_eq!(num_rows, 5);
+    }
+
+    #[test]
+    fn export_arrow_ipc() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = export(
+            dir.path(),
+            "out.data",
+            &["--index", "frame_nr", "--format", "arrow"],
+        );
+
+        let reader =
+            arrow::ipc::reader::FileReader::try_new(std::fs::File::open(path).unwrap(), None)
+                .unwrap();
+        let num_rows: usize = reader.map(|batch| batch.unwrap().num_rows()).sum();
+        assert_eq!(num_rows, 5);
+    }
+
+    #[test]
+    fn parse_entity_path_filters() {
+        let command = ExportCommand::try_parse_from([
+            "export",
+            "input.rrd",
+            "-o",
+            "out.csv",
+            "--entity",
+            "+ /**",
+            "--entity",
+            "- /points/debug/**",
+        ])
+        .unwrap();
+        assert_eq!(command.entity_path_filters, ["+ /**", "- /points/debug/**"]);
+    }
+
+    #[test]
+    fn output_format_and_columns() {
+        assert_eq!(
+            ExportFormat::from_path(Path::new("out.PARQUET")),
+            Some(ExportFormat::Parquet)
+        );
+        assert_eq!(
+            ExportFormat::from_path(Path::new("out.feather")),
+            Some(ExportFormat::Arrow)
+        );
+        assert_eq!(ExportFormat::from_path(Path::new("out")), None);
+
+        assert!(matches!(
+            parse_column("frame_nr").unwrap(),
+            ColumnSelector::Time(_)
+        ));
+        assert!(matches!(
+            parse_column("/points:Points3D:positions").unwrap(),
+            ColumnSelector::Component(_)
+        ));
+        assert!(parse_column("").is_err());
+    }
+}
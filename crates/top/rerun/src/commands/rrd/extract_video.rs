@@ -0,0 +1,887 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use anyhow::Context as _;
+
+use re_chunk::{LatestAtQuery, RangeQuery, TimeInt, UnitChunkShared};
+use re_chunk_store::{ChunkStore, ChunkStoreConfig};
+use re_log_encoding::footer::RrdFooterQuery;
+use re_log_types::{AbsoluteTimeRange, EntityPath, EntityPathFilter, TimeType, TimelineName};
+use re_types::{
+    ComponentDescriptor,
+    archetypes::{AssetVideo, VideoFrameReference, VideoStream},
+    components,
+};
+use re_video::{StableIndexDeque, VideoDataDescription};
+
+use super::filter::TimeRangeArg;
+
+/// How long to wait for the decoder to produce another frame before giving up.
+const DECODE_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(30);
+
+// ---
+
+#[derive(Debug, Clone, clap::Parser)]
+pub struct ExtractVideoCommand {
+    /// Path to the .rrd file to read from.
+    path_to_input_rrd: String,
+
+    /// Path to write to: an .mp4 file, or a directory for PNG frames.
+    #[arg(short = 'o', long = "output", value_name = "dst.mp4|dst_dir")]
+    path_to_output: String,
+
+    /// Output format. Inferred from the output path if unspecified: paths ending in `.mp4` are
+    /// written as MP4, anything else as a directory of PNG frames.
+    #[clap(long, value_enum)]
+    format: Option<ExtractVideoFormat>,
+
+    /// The entity holding the video, either as a `VideoStream` or as an `AssetVideo`.
+    ///
+    /// For an `AssetVideo`, the frames are selected by the `VideoFrameReference`s logged to the
+    /// same entity.
+    #[clap(long = "entity", value_name = "ENTITY_PATH")]
+    entity_path: String,
+
+    /// The recording to extract from, if the file contains more than one.
+    #[clap(long = "recording-id")]
+    recording_id: Option<String>,
+
+    /// The range of frames to extract, e.g. `log_time=2025-06-01T12:00:10Z..2025-06-01T12:00:20Z`
+    /// or `frame_nr=100..200`.
+    ///
+    /// Bounds are inclusive, and either of them can be omitted (e.g. `frame_nr=100..`).
+    /// They can be integers (in the native unit of the timeline), RFC 3339 timestamps
+    /// (e.g. `2025-06-01T12:00:00Z`) or, on duration timelines, durations (e.g. `30s`).
+    #[clap(long = "time-range", value_name = "TIMELINE=MIN..MAX")]
+    time_range: TimeRangeArg,
+
+    /// The frame rate of video streams logged on a sequence timeline.
+    ///
+    /// Every step of the timeline is assumed to be one frame at this rate.
+    #[clap(long = "fps", default_value_t = 30, value_parser = clap::value_parser!(u64).range(1..))]
+    fps: u64,
+
+    /// Path to the `ffmpeg` binary used to decode H.264 & H.265 videos to PNG frames.
+    ///
+    /// Uses the `ffmpeg` found on the `PATH` if unspecified.
+    #[clap(long = "ffmpeg-path")]
+    ffmpeg_path: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+enum ExtractVideoFormat {
+    /// The encoded samples, remuxed into an MP4 file without re-encoding.
+    ///
+    /// The clip is cut at the boundaries of the groups of pictures covering the time range, so it
+    /// may start a little before and end a little after it.
+    Mp4,
+
+    /// Every frame in the time range is decoded and written as a PNG file, named after its time.
+    Png,
+}
+
+impl ExtractVideoFormat {
+    fn from_path(path: &Path) -> Self {
+        let is_mp4 = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .is_some_and(|extension| extension.eq_ignore_ascii_case("mp4"));
+        if is_mp4 { Self::Mp4 } else { Self::Png }
+    }
+}
+
+impl ExtractVideoCommand {
+    pub fn run(&self) -> anyhow::Result<()> {
+        let Self {
+            path_to_input_rrd,
+            path_to_output,
+            format,
+            entity_path,
+            recording_id,
+            time_range,
+            fps,
+            ffmpeg_path,
+        } = self;
+
+        let path_to_output = PathBuf::from(path_to_output);
+        let format = format.unwrap_or_else(|| ExtractVideoFormat::from_path(&path_to_output));
+        let entity_path = EntityPath::parse_forgiving(entity_path);
+
+        let now = std::time::Instant::now();
+        re_log::info!(src = ?path_to_input_rrd, dst = ?path_to_output, ?format, "extraction started");
+
+        // The whole video is needed, since the range may start in the middle of a group of pictures.
+        let footer_query = RrdFooterQuery {
+            entity_path_filter: Some(
+                EntityPathFilter::parse_forgiving(entity_path.to_string())
+                    .resolve_without_substitutions(),
+            ),
+            time_range: None,
+        };
+
+        let stores = ChunkStore::from_rrd_filepath_with_query(
+            &ChunkStoreConfig::from_env().unwrap_or_default(),
+            path_to_input_rrd,
+            &footer_query,
+        )
+        .with_context(|| format!("couldn't load {path_to_input_rrd:?}"))?;
+
+        let mut recordings = stores
+            .into_iter()
+            .filter(|(store_id, _)| store_id.is_recording())
+            .filter(|(store_id, _)| {
+                recording_id
+                    .as_ref()
+                    .is_none_or(|recording_id| store_id.recording_id().as_str() == recording_id)
+            });
+
+        let Some((store_id, store)) = recordings.next() else {
+            anyhow::bail!("no matching recording found in {path_to_input_rrd:?}");
+        };
+        if recordings.next().is_some() {
+            anyhow::bail!(
+                "{path_to_input_rrd:?} contains more than one recording, please specify --recording-id"
+            );
+        }
+
+        // Checked first: only the chunks of the entity are loaded, so there are no timelines if it
+        // doesn't exist.
+        let is_video_stream =
+            store.entity_has_component(&entity_path, &VideoStream::descriptor_sample());
+        if !is_video_stream
+            && !store.entity_has_component(&entity_path, &AssetVideo::descriptor_blob())
+        {
+            anyhow::bail!("{entity_path} has neither a VideoStream nor an AssetVideo");
+        }
+
+        let TimeRangeArg {
+            timeline,
+            range,
+            has_duration_bound,
+        } = time_range;
+        let Some(time_type) = store
+            .timelines()
+            .get(timeline)
+            .map(|timeline| timeline.typ())
+        else {
+            anyhow::bail!("unknown timeline {timeline:?}");
+        };
+        if time_type == TimeType::TimestampNs && *has_duration_bound {
+            anyhow::bail!(
+                "{timeline:?} is a timestamp timeline: its range must be given as RFC 3339 timestamps \
+                 (e.g. `{timeline}=2025-06-01T12:00:10Z..2025-06-01T12:00:20Z`), not as durations"
+            );
+        }
+
+        let source = if is_video_stream {
+            let timescale = match time_type {
+                TimeType::Sequence => re_video::Timescale::new(*fps),
+                TimeType::DurationNs | TimeType::TimestampNs => re_video::Timescale::NANOSECOND,
+            };
+            load_video_stream(&store, &entity_path, *timeline, *range, timescale)?
+        } else {
+            load_asset_video(&store, &entity_path, *timeline, *range)?
+        };
+
+        let VideoSource {
+            video,
+            buffers,
+            frames,
+        } = &source;
+        let buffers = buffers
+            .iter()
+            .map(Vec::as_slice)
+            .collect::<StableIndexDeque<_>>();
+
+        let presentation_timestamps = frames.iter().map(|(_, timestamp)| *timestamp);
+        let (Some(start), Some(end)) = (
+            presentation_timestamps.clone().min(),
+            presentation_timestamps.max(),
+        ) else {
+            anyhow::bail!("{entity_path} has no video frames in the given time range");
+        };
+        let Some(sample_range) = video.gop_aligned_sample_range(start, end) else {
+            anyhow::bail!("{entity_path} has no decodable video samples in the given time range");
+        };
+
+        let num_written = match format {
+            ExtractVideoFormat::Mp4 => {
+                let mut file = std::io::BufWriter::new(
+                    std::fs::File::create(&path_to_output)
+                        .with_context(|| format!("{path_to_output:?}"))?,
+                );
+                re_video::write_mp4(video, &buffers, sample_range.clone(), &mut file)
+                    .context("couldn't write mp4")?;
+                std::io::Write::flush(&mut file).with_context(|| format!("{path_to_output:?}"))?;
+                sample_range.len()
+            }
+
+            ExtractVideoFormat::Png => {
+                std::fs::create_dir_all(&path_to_output)
+                    .with_context(|| format!("{path_to_output:?}"))?;
+
+                let frame_times =
+                    frame_times_by_presentation_timestamp(video, sample_range.clone(), frames);
+                let num_before_first_sample =
+                    frames.len() - frame_times.values().map(Vec::len).sum::<usize>();
+                if num_before_first_sample > 0 {
+                    re_log::warn!(
+                        "{num_before_first_sample} frames on {timeline} come before the first video sample"
+                    );
+                }
+
+                decode_frames(
+                    &entity_path.to_string(),
+                    video,
+                    &buffers,
+                    sample_range,
+                    ffmpeg_path.clone(),
+                    frame_times,
+                    |time, content| {
+                        let path =
+                            path_to_output.join(format!("{timeline}_{:019}.png", time.as_i64()));
+                        rgb_image_from_frame(content)?
+                            .save(&path)
+                            .with_context(|| format!("{path:?}"))
+                    },
+                )?
+            }
+        };
+
+        re_log::info!(
+            recording_id = %store_id.recording_id(),
+            num_written,
+            time = ?now.elapsed(),
+            dst = ?path_to_output,
+            "extraction finished"
+        );
+
+        Ok(())
+    }
+}
+
+// ---
+
+/// A video, along with the frames to extract from it.
+struct VideoSource {
+    video: VideoDataDescription,
+
+    /// The buffers the samples of `video` refer to.
+    buffers: Vec<Vec<u8>>,
+
+    /// The times of the frames to extract, along with the presentation timestamp of the video
+    /// at each of them, in ascending order.
+    frames: Vec<(TimeInt, re_video::Time)>,
+}
+
+/// Collects the samples of a `VideoStream`, each of which is a frame of its own.
+fn load_video_stream(
+    store: &ChunkStore,
+    entity_path: &EntityPath,
+    timeline: TimelineName,
+    range: AbsoluteTimeRange,
+    timescale: re_video::Timescale,
+) -> anyhow::Result<VideoSource> {
+    re_tracing::profile_function!();
+
+    let codec_descr = VideoStream::descriptor_codec();
+    let codec = latest_at_unit(
+        store,
+        &LatestAtQuery::latest(timeline),
+        entity_path,
+        &codec_descr,
+    )
+    .and_then(|unit| unit.component_instance::<components::VideoCodec>(&codec_descr, 0))
+    .context("video stream has no codec")??;
+    let codec = match codec {
+        components::VideoCodec::H264 => re_video::VideoCodec::H264,
+        components::VideoCodec::H265 => re_video::VideoCodec::H265,
+        components::VideoCodec::AV1 => re_video::VideoCodec::AV1,
+    };
+
+    let sample_descr = VideoStream::descriptor_sample();
+    let mut samples = Vec::new();
+    for chunk in store.range_relevant_chunks(
+        &RangeQuery::new(timeline, AbsoluteTimeRange::EVERYTHING),
+        entity_path,
+        &sample_descr,
+    ) {
+        let chunk = chunk.sorted_by_timeline_if_unsorted(&timeline);
+        samples.extend(
+            chunk
+                .iter_component_indices(&timeline, &sample_descr)
+                .zip(chunk.iter_slices::<&[u8]>(sample_descr.clone()))
+                .filter_map(|((time, _row_id), blobs)| {
+                    let blob = blobs.into_iter().next()?;
+                    (!blob.is_empty()).then(|| (time, blob.to_vec()))
+                }),
+        );
+    }
+    samples.sort_by_key(|(time, _)| *time);
+
+    let mut video = VideoDataDescription {
+        codec,
+        encoding_details: None,
+        timescale: Some(timescale),
+        duration: None,
+        gops: StableIndexDeque::new(),
+        samples: StableIndexDeque::with_capacity(samples.len()),
+        samples_statistics: re_video::SamplesStatistics::NO_BFRAMES,
+        last_time_updated_samples: None,
+        mp4_tracks: Default::default(),
+        sample_layout: re_video::SampleLayout::Contiguous,
+    };
+
+    let mut buffers = Vec::with_capacity(samples.len());
+    let mut frames = Vec::new();
+    for (sample_idx, (time, data)) in samples.into_iter().enumerate() {
+        let is_sync = match re_video::detect_gop_start(&data, codec) {
+            Ok(re_video::GopStartDetection::StartOfGop(encoding_details)) => {
+                video.encoding_details.get_or_insert(encoding_details);
+                true
+            }
+            Ok(re_video::GopStartDetection::NotStartOfGop) => false,
+            Err(err) => {
+                re_log::warn_once!("Failed to detect GOP for video sample: {err}");
+                false
+            }
+        };
+
+        if is_sync {
+            video.gops.push_back(re_video::GroupOfPictures {
+                sample_range: sample_idx..sample_idx + 1,
+            });
+        } else if let Some(gop) = video.gops.back_mut() {
+            gop.sample_range.end = sample_idx + 1;
+        }
+
+        let timestamp = re_video::Time(time.as_i64());
+        if range.contains(time) {
+            frames.push((time, timestamp));
+        }
+
+        video.samples.push_back(re_video::SampleMetadata {
+            is_sync,
+            frame_nr: sample_idx as u32,
+            decode_timestamp: timestamp,
+            presentation_timestamp: timestamp,
+            duration: None,
+            buffer_index: sample_idx,
+            byte_span: re_video::Span {
+                start: 0,
+                len: u32::try_from(data.len()).context("video sample too large")?,
+            },
+        });
+        buffers.push(data);
+    }
+
+    Ok(VideoSource {
+        video,
+        buffers,
+        frames,
+    })
+}
+
+/// Loads an `AssetVideo`, with the frames referenced by the `VideoFrameReference`s on the same entity.
+fn load_asset_video(
+    store: &ChunkStore,
+    entity_path: &EntityPath,
+    timeline: TimelineName,
+    range: AbsoluteTimeRange,
+) -> anyhow::Result<VideoSource> {
+    re_tracing::profile_function!();
+
+    let query = LatestAtQuery::new(timeline, range.max());
+
+    let blob_descr = AssetVideo::descriptor_blob();
+    let blob = latest_at_unit(store, &query, entity_path, &blob_descr)
+        .and_then(|unit| unit.component_instance::<components::Blob>(&blob_descr, 0))
+        .context("asset video has no blob")??;
+    let blob: &[u8] = &blob;
+
+    let media_type_descr = AssetVideo::descriptor_media_type();
+    let media_type = latest_at_unit(store, &query, entity_path, &media_type_descr)
+        .and_then(|unit| unit.component_instance::<components::MediaType>(&media_type_descr, 0))
+        .transpose()?
+        .or_else(|| components::MediaType::guess_from_data(blob))
+        .context("couldn't determine the media type of the asset video")?;
+
+    let video = VideoDataDescription::load_from_bytes(
+        blob,
+        media_type.as_str(),
+        entity_path.to_string().as_str(),
+    )
+    .context("couldn't load asset video")?;
+    let timescale = video.timescale.context("asset video has no timescale")?;
+
+    let timestamp_descr = VideoFrameReference::descriptor_timestamp();
+    let mut frames = Vec::new();
+    for chunk in store.range_relevant_chunks(
+        &RangeQuery::new(timeline, range),
+        entity_path,
+        &timestamp_descr,
+    ) {
+        let chunk = chunk.sorted_by_timeline_if_unsorted(&timeline);
+        frames.extend(
+            chunk
+                .iter_component_indices(&timeline, &timestamp_descr)
+                .zip(chunk.iter_slices::<i64>(timestamp_descr.clone()))
+                .filter(|((time, _row_id), _)| range.contains(*time))
+                .filter_map(|((time, _row_id), nanos)| {
+                    let nanos = *nanos.first()?;
+                    Some((time, re_video::Time::from_nanos(nanos, timescale)))
+                }),
+        );
+    }
+    frames.sort_by_key(|(time, _)| *time);
+
+    Ok(VideoSource {
+        video,
+        buffers: vec![blob.to_vec()],
+        frames,
+    })
+}
+
+/// The most recent single-row chunk holding the given component, as of the query time.
+fn latest_at_unit(
+    store: &ChunkStore,
+    query: &LatestAtQuery,
+    entity_path: &EntityPath,
+    component_descr: &ComponentDescriptor,
+) -> Option<UnitChunkShared> {
+    store
+        .latest_at_relevant_chunks(query, entity_path, component_descr)
+        .into_iter()
+        .filter_map(|chunk| chunk.latest_at(query, component_descr).into_unit())
+        .max_by_key(|unit| unit.index(&query.timeline()))
+}
+
+// ---
+
+/// Groups the times of the frames to extract by the presentation timestamp of the sample shown at
+/// each of them, i.e. the latest one presented at or before it.
+///
+/// Times before the first sample of the range are left out.
+fn frame_times_by_presentation_timestamp(
+    video: &VideoDataDescription,
+    sample_range: std::ops::Range<re_video::SampleIndex>,
+    frames: &[(TimeInt, re_video::Time)],
+) -> BTreeMap<re_video::Time, Vec<TimeInt>> {
+    let mut presentation_timestamps = video
+        .samples
+        .iter_index_range_clamped(&sample_range)
+        .map(|(_, sample)| sample.presentation_timestamp)
+        .collect::<Vec<_>>();
+    presentation_timestamps.sort();
+    presentation_timestamps.dedup();
+
+    let mut frame_times = BTreeMap::<_, Vec<_>>::new();
+    for (time, presentation_timestamp) in frames {
+        let num_presented = presentation_timestamps
+            .partition_point(|timestamp| timestamp <= presentation_timestamp);
+        if let Some(timestamp) = num_presented
+            .checked_sub(1)
+            .map(|idx| presentation_timestamps[idx])
+        {
+            frame_times.entry(timestamp).or_default().push(*time);
+        }
+    }
+    frame_times
+}
+
+/// Decodes the given samples, handing each decoded frame to `write_frame` as soon as it arrives,
+/// once for every time it is shown at.
+///
+/// `frame_times` maps presentation timestamps to the times they are shown at, see
+/// [`frame_times_by_presentation_timestamp`]. Returns the number of frames written.
+fn decode_frames(
+    debug_name: &str,
+    video: &VideoDataDescription,
+    buffers: &StableIndexDeque<&[u8]>,
+    sample_range: std::ops::Range<re_video::SampleIndex>,
+    ffmpeg_path: Option<PathBuf>,
+    mut frame_times: BTreeMap<re_video::Time, Vec<TimeInt>>,
+    mut write_frame: impl FnMut(TimeInt, &re_video::FrameContent) -> anyhow::Result<()>,
+) -> anyhow::Result<usize> {
+    re_tracing::profile_function!();
+
+    let (tx, rx) = crossbeam::channel::unbounded();
+    let decode_settings = re_video::DecodeSettings {
+        hw_acceleration: re_video::DecodeHardwareAcceleration::PreferSoftware,
+        ffmpeg_path,
+    };
+    let mut decoder = re_video::new_decoder(debug_name, video, &decode_settings, move |frame| {
+        tx.send(frame).ok();
+    })
+    .context("couldn't create video decoder")?;
+
+    decoder.reset(video)?;
+    let mut num_written = 0;
+    for (sample_idx, sample) in video.samples.iter_index_range_clamped(&sample_range) {
+        let chunk = sample
+            .get(video.sample_layout, buffers, sample_idx)
+            .with_context(|| format!("missing data of video sample {sample_idx}"))?;
+        decoder.submit_chunk(chunk)?;
+
+        // Write the frames decoded so far, so that they don't pile up while submitting.
+        for frame in rx.try_iter() {
+            num_written += write_decoded_frame(frame, &mut frame_times, &mut write_frame)?;
+        }
+    }
+    decoder.end_of_video()?;
+
+    while !frame_times.is_empty() {
+        let frame = match rx.recv_timeout(DECODE_TIMEOUT) {
+            Ok(frame) => frame,
+            Err(crossbeam::channel::RecvTimeoutError::Timeout) => {
+                re_log::warn!("timed out waiting for the video decoder");
+                break;
+            }
+            Err(crossbeam::channel::RecvTimeoutError::Disconnected) => break,
+        };
+        num_written += write_decoded_frame(frame, &mut frame_times, &mut write_frame)?;
+    }
+
+    for time in frame_times.into_values().flatten() {
+        re_log::warn!("no decoded frame for time {}", time.as_i64());
+    }
+
+    Ok(num_written)
+}
+
+/// Hands a decoded frame to `write_frame` for every time it is shown at, and returns how many
+/// times that was.
+fn write_decoded_frame(
+    frame: re_video::DecodeResult<re_video::Frame>,
+    frame_times: &mut BTreeMap<re_video::Time, Vec<TimeInt>>,
+    write_frame: &mut impl FnMut(TimeInt, &re_video::FrameContent) -> anyhow::Result<()>,
+) -> anyhow::Result<usize> {
+    let frame = match frame {
+        Ok(frame) => frame,
+        Err(err) => {
+            re_log::warn!("failed to decode video frame: {err}");
+            return Ok(0);
+        }
+    };
+
+    let times = frame_times
+        .remove(&frame.info.presentation_timestamp)
+        .unwrap_or_default();
+    for time in &times {
+        write_frame(*time, &frame.content)?;
+    }
+    Ok(times.len())
+}
+
+/// Converts a decoded frame to RGB, on the CPU.
+fn rgb_image_from_frame(content: &re_video::FrameContent) -> anyhow::Result<image::RgbImage> {
+    let re_video::FrameContent {
+        data,
+        width,
+        height,
+        format,
+    } = content;
+    let (width, height) = (*width as usize, *height as usize);
+
+    let rgb = match format {
+        re_video::PixelFormat::Rgb8Unorm => data.clone(),
+
+        re_video::PixelFormat::Rgba8Unorm => data
+            .chunks_exact(4)
+            .flat_map(|rgba| [rgba[0], rgba[1], rgba[2]])
+            .collect(),
+
+        re_video::PixelFormat::Yuv {
+            layout,
+            range,
+            coefficients,
+        } => {
+            // The planes are stored one after another: Y, then U & V with subsampling applied.
+            let (shift_x, shift_y) = match layout {
+                re_video::YuvPixelLayout::Y_U_V444 | re_video::YuvPixelLayout::Y400 => (0, 0),
+                re_video::YuvPixelLayout::Y_U_V422 => (1, 0),
+                re_video::YuvPixelLayout::Y_U_V420 => (1, 1),
+            };
+            let chroma_width = width.div_ceil(1 << shift_x);
+            let chroma_height = height.div_ceil(1 << shift_y);
+            let chroma_plane_len = if *layout == re_video::YuvPixelLayout::Y400 {
+                0
+            } else {
+                chroma_width * chroma_height
+            };
+            anyhow::ensure!(
+                data.len() >= width * height + 2 * chroma_plane_len,
+                "decoded frame is smaller than expected for {width}x{height} {format:?}"
+            );
+            let (y_plane, chroma_planes) = data.split_at(width * height);
+            let (u_plane, v_plane) = chroma_planes.split_at(chroma_plane_len);
+
+            let limited_range = matches!(range, re_video::YuvRange::Limited);
+            let mut rgb = Vec::with_capacity(width * height * 3);
+            for row in 0..height {
+                for column in 0..width {
+                    let y = y_plane[row * width + column];
+                    let (u, v) = if chroma_plane_len == 0 {
+                        (128, 128)
+                    } else {
+                        let idx = (row >> shift_y) * chroma_width + (column >> shift_x);
+                        (u_plane[idx], v_plane[idx])
+                    };
+                    rgb.extend_from_slice(&match coefficients {
+                        re_video::YuvMatrixCoefficients::Identity => [v, y, u],
+                        re_video::YuvMatrixCoefficients::Bt601 => re_types::image::rgb_from_yuv(
+                            y,
+                            u,
+                            v,
+                            limited_range,
+                            re_types::image::YuvMatrixCoefficients::Bt601,
+                        ),
+                        re_video::YuvMatrixCoefficients::Bt709 => re_types::image::rgb_from_yuv(
+                            y,
+                            u,
+                            v,
+                            limited_range,
+                            re_types::image::YuvMatrixCoefficients::Bt709,
+                        ),
+                    });
+                }
+            }
+            rgb
+        }
+    };
+
+    image::RgbImage::from_raw(width as u32, height as u32, rgb)
+        .context("decoded frame doesn't match its dimensions")
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser as _;
+    use re_chunk::{Chunk, RowId, TimePoint};
+    use re_log_types::{Timestamp, build_frame_nr, build_log_time};
+
+    use super::*;
+    use crate::commands::rrd::test_util;
+
+    /// SPS, PPS & IDR frame of a 64x64 H.264 stream, see `test_detect_h264_annexb_gop`.
+    const KEY_FRAME: &[u8] = &[
+        0x00, 0x00, 0x00, 0x01, 0x67, 0x64, 0x00, 0x0A, 0xAC, 0x72, 0x84, 0x44, 0x26, 0x84, 0x00,
+        0x00, 0x03, 0x00, 0x04, 0x00, 0x00, 0x03, 0x00, 0xCA, 0x3C, 0x48, 0x96, 0x11, 0x80, 0x00,
+        0x00, 0x00, 0x01, 0x68, 0xEE, 0x3C, 0x80, 0x00, 0x00, 0x00, 0x01, 0x65, 0x88, 0x84, 0x21,
+        0x43,
+    ];
+    const DELTA_FRAME: &[u8] = &[0x00, 0x00, 0x00, 0x01, 0x41, 0x9A, 0x02, 0x03];
+
+    /// A recording with an H.264 video stream of two groups of pictures, on frames 0 through 5.
+    ///
+    /// The frames are also logged on `log_time`, one second apart.
+    fn write_rrd(path: &Path) {
+        let chunk = [
+            KEY_FRAME,
+            DELTA_FRAME,
+            DELTA_FRAME,
+            KEY_FRAME,
+            DELTA_FRAME,
+            DELTA_FRAME,
+        ]
+        .into_iter()
+        .enumerate()
+        .fold(Chunk::builder("video"), |builder, (frame, sample)| {
+            builder.with_archetype(
+                RowId::new(),
+                TimePoint::from([
+                    build_frame_nr(frame as i64),
+                    build_log_time(Timestamp::from_nanos_since_epoch(
+                        frame as i64 * 1_000_000_000,
+                    )),
+                ]),
+                &VideoStream::new(components::VideoCodec::H264).with_sample(sample.to_vec()),
+            )
+        })
+        .build()
+        .unwrap();
+
+        test_util::write_rrd(path, [chunk]);
+    }
+
+    #[test]
+    fn extract_video_stream_to_mp4() {
+        let dir = tempfile::tempdir().unwrap();
+        let path_to_input_rrd = dir.path().join("input.rrd");
+        write_rrd(&path_to_input_rrd);
+
+        let path_to_output = dir.path().join("out.mp4");
+        ExtractVideoCommand::try_parse_from([
+            "extract-video",
+            path_to_input_rrd.to_str().unwrap(),
+            "-o",
+            path_to_output.to_str().unwrap(),
+            "--entity",
+            "video",
+            "--time-range",
+            "frame_nr=4..5",
+        ])
+        .unwrap()
+        .run()
+        .unwrap();
+
+        // The clip starts at the key frame of the group of pictures containing frame 4.
+        let mp4 = std::fs::read(&path_to_output).unwrap();
+        let video = VideoDataDescription::load_mp4(&mp4, "out.mp4").unwrap();
+        assert_eq!(video.codec, re_video::VideoCodec::H264);
+        assert_eq!(
+            video
+                .samples
+                .iter()
+                .map(|sample| sample.is_sync)
+                .collect::<Vec<_>>(),
+            [true, false, false]
+        );
+    }
+
+    #[test]
+    fn extract_video_from_missing_entity() {
+        let dir = tempfile::tempdir().unwrap();
+        let path_to_input_rrd = dir.path().join("input.rrd");
+        write_rrd(&path_to_input_rrd);
+
+        let err = ExtractVideoCommand::try_parse_from([
+            "extract-video",
+            path_to_input_rrd.to_str().unwrap(),
+            "-o",
+            dir.path().join("out.mp4").to_str().unwrap(),
+            "--entity",
+            "not_a_video",
+            "--time-range",
+            "frame_nr=0..5",
+        ])
+        .unwrap()
+        .run()
+        .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("neither a VideoStream nor an AssetVideo")
+        );
+    }
+
+    #[test]
+    fn extract_video_rejects_durations_on_timestamp_timelines() {
+        let dir = tempfile::tempdir().unwrap();
+        let path_to_input_rrd = dir.path().join("input.rrd");
+        write_rrd(&path_to_input_rrd);
+
+        let err = ExtractVideoCommand::try_parse_from([
+            "extract-video",
+            path_to_input_rrd.to_str().unwrap(),
+            "-o",
+            dir.path().join("out.mp4").to_str().unwrap(),
+            "--entity",
+            "video",
+            "--time-range",
+            "log_time=1s..3s",
+        ])
+        .unwrap()
+        .run()
+        .unwrap_err();
+        assert!(err.to_string().contains("is a timestamp timeline"), "{err}");
+    }
+
+    #[test]
+    fn frame_times_are_grouped_by_shown_sample() {
+        // Samples presented out of decode order, as with B-frames.
+        let samples = [0, 30, 10, 20]
+            .into_iter()
+            .enumerate()
+            .map(|(idx, pts)| re_video::SampleMetadata {
+                is_sync: idx == 0,
+                frame_nr: idx as u32,
+                decode_timestamp: re_video::Time::new(idx as i64 * 10 - 10),
+                presentation_timestamp: re_video::Time::new(pts),
+                duration: Some(re_video::Time::new(10)),
+                buffer_index: 0,
+                byte_span: Default::default(),
+            })
+            .collect::<StableIndexDeque<_>>();
+        let video = VideoDataDescription {
+            codec: re_video::VideoCodec::H264,
+            encoding_details: None,
+            timescale: Some(re_video::Timescale::new(10)),
+            duration: None,
+            gops: std::iter::once(re_video::GroupOfPictures { sample_range: 0..4 }).collect(),
+            samples_statistics: re_video::SamplesStatistics::new(&samples),
+            samples,
+            last_time_updated_samples: None,
+            mp4_tracks: Default::default(),
+            sample_layout: re_video::SampleLayout::Contiguous,
+        };
+
+        let frames = [-5, 0, 5, 10, 25, 30, 100]
+            .map(|pts| (TimeInt::new_temporal(pts), re_video::Time::new(pts)));
+        let frame_times = frame_times_by_presentation_timestamp(&video, 0..4, &frames);
+
+        let time = TimeInt::new_temporal;
+        assert_eq!(
+            frame_times.into_iter().collect::<Vec<_>>(),
+            [
+                (re_video::Time::new(0), vec![time(0), time(5)]),
+                (re_video::Time::new(10), vec![time(10)]),
+                (re_video::Time::new(20), vec![time(25)]),
+                (re_video::Time::new(30), vec![time(30), time(100)]),
+            ]
+        );
+    }
+
+    #[test]
+    fn rgb_image_from_rgba_frame() {
+        let content = re_video::FrameContent {
+            data: vec![1, 2, 3, 255, 4, 5, 6, 128],
+            width: 2,
+            height: 1,
+            format: re_video::PixelFormat::Rgba8Unorm,
+        };
+        let image = rgb_image_from_frame(&content).unwrap();
+        assert_eq!(image.dimensions(), (2, 1));
+        assert_eq!(image.into_raw(), [1, 2, 3, 4, 5, 6]);
+    }
+
+    #[test]
+    fn rgb_image_from_yuv_frame() {
+        let yuv_format = |layout| re_video::PixelFormat::Yuv {
+            layout,
+            range: re_video::YuvRange::Full,
+            coefficients: re_video::YuvMatrixCoefficients::Bt709,
+        };
+
+        // A 2x2 4:2:0 frame has a single chroma sample, shared by all pixels.
+        let content = re_video::FrameContent {
+            data: vec![0, 255, 255, 0, 128, 128],
+            width: 2,
+            height: 2,
+            format: yuv_format(re_video::YuvPixelLayout::Y_U_V420),
+        };
+        let image = rgb_image_from_frame(&content).unwrap();
+        assert_eq!(
+            image.pixels().map(|pixel| pixel.0).collect::<Vec<_>>(),
+            [[0, 0, 0], [255, 255, 255], [255, 255, 255], [0, 0, 0]]
+        );
+
+        // Monochrome frames have no chroma planes.
+        let content = re_video::FrameContent {
+            data: vec![0, 255],
+            width: 2,
+            height: 1,
+            format: yuv_format(re_video::YuvPixelLayout::Y400),
+        };
+        let image = rgb_image_from_frame(&content).unwrap();
+        assert_eq!(image.into_raw(), [0, 0, 0, 255, 255, 255]);
+
+        // Missing chroma planes are an error.
+        let content = re_video::FrameContent {
+            data: vec![0; 4],
+            width: 2,
+            height: 2,
+            format: yuv_format(re_video::YuvPixelLayout::Y_U_V420),
+        };
+        assert!(rgb_image_from_frame(&content).is_err());
+    }
+}
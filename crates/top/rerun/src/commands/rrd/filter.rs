@@ -8,10 +8,12 @@ use arrow::{
 use itertools::Either;
 
 use re_build_info::CrateVersion;
-use re_chunk::external::crossbeam;
+use re_chunk::{Chunk, TimeInt, TimelineName, external::crossbeam};
+use re_log_encoding::footer::RrdFooterQuery;
+use re_log_types::{AbsoluteTimeRange, EntityPathFilter};
 use re_sdk::{EntityPath, external::arrow};
 
-use crate::commands::read_rrd_streams_from_file_or_stdin;
+use crate::commands::read_rrd_streams_from_file_or_stdin_with_query;
 
 // ---
 
@@ -32,6 +34,29 @@ pub struct FilterCommand {
     #[clap(long = "drop-entity")]
     dropped_entity_paths: Vec<String>,
 
+    /// Entity path filter expression selecting the entities to keep, e.g. `"+ /robot/** - /robot/debug/**"`.
+    ///
+    /// Can be specified multiple times, in which case all the rules are combined.
+    /// Keeps all entities if unspecified.
+    #[clap(long = "entity", value_name = "EXPR", allow_hyphen_values = true)]
+    entity_path_filters: Vec<String>,
+
+    /// Only keep the data within this range of the given timeline, e.g. `frame_nr=100..200`.
+    ///
+    /// Bounds are inclusive, and either of them can be omitted (e.g. `frame_nr=100..`).
+    /// They can be integers (in the native unit of the timeline), RFC 3339 timestamps
+    /// (e.g. `2025-06-01T12:00:00Z`) or durations (e.g. `30s`).
+    ///
+    /// Chunks are cut accordingly. Static data is always kept, whereas temporal data that isn't
+    /// on that timeline is dropped.
+    #[clap(long = "time-range", value_name = "TIMELINE=MIN..MAX")]
+    time_range: Option<TimeRangeArg>,
+
+    /// Components to be filtered out, either by identifier (e.g. `Points3D:colors`) or by
+    /// type (e.g. `rerun.components.Color`).
+    #[clap(long = "drop-component")]
+    dropped_components: Vec<String>,
+
     /// If set, will try to proceed even in the face of IO and/or decoding errors in the input data.
     #[clap(long = "continue-on-error", default_value_t = false)]
     continue_on_error: bool,
@@ -44,6 +69,9 @@ impl FilterCommand {
             path_to_output_rrd,
             dropped_timelines,
             dropped_entity_paths,
+            entity_path_filters,
+            time_range,
+            dropped_components,
             continue_on_error,
         } = self;
 
@@ -59,12 +87,27 @@ impl FilterCommand {
         re_log::info!(srcs = ?path_to_input_rrds, ?dropped_timelines, "filter started");
 
         let dropped_timelines: HashSet<_> = dropped_timelines.iter().cloned().collect();
+        let dropped_components: HashSet<_> = dropped_components.iter().cloned().collect();
+        let time_range = time_range
+            .clone()
+            .map(|time_range| (time_range.timeline, time_range.range));
+
         let dropped_entity_paths: HashSet<EntityPath> = dropped_entity_paths
             .iter()
             .map(|s| EntityPath::parse_forgiving(s))
             .collect();
 
-        let (rx_decoder, rx_size_bytes) = read_rrd_streams_from_file_or_stdin(path_to_input_rrds);
+        // Filtered out entities and time ranges are never even read from files that have a footer.
+        let query = RrdFooterQuery {
+            entity_path_filter: (!entity_path_filters.is_empty()).then(|| {
+                EntityPathFilter::parse_forgiving(entity_path_filters.join(" "))
+                    .resolve_without_substitutions()
+            }),
+            time_range,
+        };
+
+        let (rx_decoder, rx_size_bytes) =
+            read_rrd_streams_from_file_or_stdin_with_query(path_to_input_rrds, query.clone());
 
         // TODO(cmc): might want to make this configurable at some point.
         let (tx_encoder, rx_encoder) = crossbeam::channel::bounded(100);
@@ -88,6 +131,7 @@ impl FilterCommand {
                     let options = re_log_encoding::EncodingOptions::PROTOBUF_COMPRESSED;
                     re_log_encoding::encoder::DroppableEncoder::new(version, options, &mut rrd_out)
                         .context("couldn't init encoder")?
+                        .with_footer()
                 };
 
                 let mut size_bytes = 0;
@@ -107,39 +151,27 @@ impl FilterCommand {
             match res {
                 Ok(msg) => {
                     let msg = match msg {
-                        re_log_types::LogMsg::ArrowMsg(store_id, mut msg) => {
-                            match re_sorbet::ChunkBatch::try_from(&msg.batch) {
-                                Ok(batch) => {
-                                    if !dropped_entity_paths.contains(batch.entity_path()) {
-                                        None
-                                    } else {
-                                        let (fields, columns): (Vec<_>, Vec<_>) = itertools::izip!(
-                                            &batch.schema().fields,
-                                            batch.columns()
-                                        )
-                                        .filter(|(field, _col)| {
-                                            !is_field_timeline_of(field, &dropped_timelines)
-                                        })
-                                        .map(|(field, col)| (field.clone(), col.clone()))
-                                        .unzip();
-
-                                        if let Ok(new_batch) = ArrowRecordBatch::try_new(
-                                            ArrowSchema::new_with_metadata(
-                                                fields,
-                                                batch.schema().metadata().clone(),
-                                            )
-                                            .into(),
-                                            columns,
-                                        ) {
-                                            msg.batch = new_batch;
-                                            Some(re_log_types::LogMsg::ArrowMsg(store_id, msg))
-                                        } else {
-                                            None // Probably failed because we filtered out everything
-                                        }
-                                    }
-                                }
+                        re_log_types::LogMsg::ArrowMsg(store_id, msg) => {
+                            // Like the footer query, the time range only applies to the data,
+                            // never to the blueprint.
+                            let time_range = if store_id.is_blueprint() {
+                                None
+                            } else {
+                                query.time_range.as_ref()
+                            };
+
+                            match time_sliced(msg, time_range) {
+                                Ok(Some(msg)) => filtered(
+                                    store_id,
+                                    msg,
+                                    &dropped_entity_paths,
+                                    &dropped_timelines,
+                                    &dropped_components,
+                                ),
+                                Ok(None) => None, // nothing left in that time range
                                 Err(err) => {
-                                    re_log::warn_once!("Failed to parse chunk schema: {err}");
+                                    re_log::error!("Failed to slice chunk: {err:#}");
+                                    is_success = false;
                                     None
                                 }
                             }
@@ -205,9 +237,339 @@ impl FilterCommand {
 
 // ---
 
+/// Drops the timelines and components of the chunk that are filtered out, or the whole chunk if
+/// its entity is.
+fn filtered(
+    store_id: re_log_types::StoreId,
+    mut msg: re_log_types::ArrowMsg,
+    dropped_entity_paths: &HashSet<EntityPath>,
+    dropped_timelines: &HashSet<String>,
+    dropped_components: &HashSet<String>,
+) -> Option<re_log_types::LogMsg> {
+    let batch = match re_sorbet::ChunkBatch::try_from(&msg.batch) {
+        Ok(batch) => batch,
+        Err(err) => {
+            re_log::warn_once!("Failed to parse chunk schema: {err}");
+            return None;
+        }
+    };
+
+    if dropped_entity_paths.contains(batch.entity_path()) {
+        return None;
+    }
+
+    let (fields, columns): (Vec<_>, Vec<_>) =
+        itertools::izip!(&batch.schema().fields, batch.columns())
+            .filter(|(field, _col)| {
+                !is_field_timeline_of(field, dropped_timelines)
+                    && !is_field_component_of(batch.entity_path(), field, dropped_components)
+            })
+            .map(|(field, col)| (field.clone(), col.clone()))
+            .unzip();
+
+    // Probably fails because we filtered out everything.
+    let new_batch = ArrowRecordBatch::try_new(
+        ArrowSchema::new_with_metadata(fields, batch.schema().metadata().clone()).into(),
+        columns,
+    )
+    .ok()?;
+
+    msg.batch = new_batch;
+    Some(re_log_types::LogMsg::ArrowMsg(store_id, msg))
+}
+
 // Does the given field represent a timeline that is in the given set?
 fn is_field_timeline_of(field: &ArrowField, dropped_timelines: &HashSet<String>) -> bool {
     re_sorbet::IndexColumnDescriptor::try_from(field)
         .ok()
         .is_some_and(|schema| dropped_timelines.contains(schema.column_name()))
 }
+
+// Does the given field represent a component that is in the given set?
+fn is_field_component_of(
+    entity_path: &EntityPath,
+    field: &ArrowField,
+    dropped_components: &HashSet<String>,
+) -> bool {
+    if dropped_components.is_empty()
+        || re_sorbet::ColumnKind::try_from(field).ok() != Some(re_sorbet::ColumnKind::Component)
+    {
+        return false;
+    }
+
+    let descr = re_sorbet::ComponentColumnDescriptor::from_arrow_field(Some(entity_path), field);
+    dropped_components.contains(descr.component.as_str())
+        || descr
+            .component_type
+            .is_some_and(|component_type| dropped_components.contains(component_type.as_str()))
+}
+
+/// Only keeps the rows of the chunk that are within the given time range, if any.
+///
+/// Returns `None` if nothing is left.
+///
+/// Unlike [`Chunk::timeline_sliced`], which only keeps the given timeline, this cuts the chunk
+/// row-wise: all timelines and components of the remaining rows are kept as is.
+fn time_sliced(
+    msg: re_log_types::ArrowMsg,
+    time_range: Option<&(TimelineName, AbsoluteTimeRange)>,
+) -> anyhow::Result<Option<re_log_types::ArrowMsg>> {
+    let Some((timeline, time_range)) = time_range else {
+        return Ok(Some(msg));
+    };
+
+    let chunk = Chunk::from_arrow_msg(&msg)?;
+    if chunk.is_static() {
+        return Ok(Some(msg));
+    }
+
+    let Some(time_column) = chunk.timelines().get(timeline) else {
+        return Ok(None);
+    };
+
+    if time_range.contains_range(time_column.time_range()) {
+        return Ok(Some(msg)); // nothing to cut
+    }
+
+    // Once sorted, the rows within the time range are contiguous, so the chunk can be sliced
+    // without copying any data.
+    let chunk = chunk.sorted_by_timeline_if_unsorted(timeline);
+    let Some(times) = chunk
+        .timelines()
+        .get(timeline)
+        .map(|time_column| time_column.times_raw())
+    else {
+        return Ok(None);
+    };
+    let start_index = times.partition_point(|&time| time < time_range.min().as_i64());
+    let end_index = times.partition_point(|&time| time <= time_range.max().as_i64());
+
+    if start_index >= end_index {
+        return Ok(None);
+    }
+
+    Ok(Some(
+        chunk
+            .row_sliced(start_index, end_index - start_index)
+            .with_id(re_chunk::ChunkId::new())
+            .to_arrow_msg()?,
+    ))
+}
+
+/// See [`FilterCommand::time_range`].
+#[derive(Debug, Clone)]
+pub(crate) struct TimeRangeArg {
+    pub(crate) timeline: TimelineName,
+    pub(crate) range: AbsoluteTimeRange,
+
+    /// Whether any of the bounds was given as a duration, which is only meaningful on timelines
+    /// of durations: on timestamp timelines, it ends up relative to the Unix epoch.
+    pub(crate) has_duration_bound: bool,
+}
+
+impl std::str::FromStr for TimeRangeArg {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        /// Returns the bound, and whether it is a duration.
+        fn parse_bound(s: &str, unbounded: TimeInt) -> Result<(TimeInt, bool), String> {
+            let s = s.trim();
+            if s.is_empty() {
+                Ok((unbounded, false))
+            } else if let Some(time) = re_format::parse_i64(s) {
+                Ok((TimeInt::saturated_temporal_i64(time), false))
+            } else if let Ok(timestamp) = s.parse::<re_log_types::Timestamp>() {
+                Ok((
+                    TimeInt::saturated_temporal_i64(timestamp.nanos_since_epoch()),
+                    false,
+                ))
+            } else if let Ok(duration) = s.parse::<re_log_types::Duration>() {
+                Ok((TimeInt::saturated_temporal_i64(duration.as_nanos()), true))
+            } else {
+                Err(format!(
+                    "expected an integer, a timestamp or a duration, got {s:?}"
+                ))
+            }
+        }
+
+        let Some((timeline, range)) = s.split_once('=') else {
+            return Err(format!("expected `TIMELINE=MIN..MAX`, got {s:?}"));
+        };
+        let Some((min, max)) = range.split_once("..") else {
+            return Err(format!("expected `TIMELINE=MIN..MAX`, got {s:?}"));
+        };
+
+        let (min, is_min_duration) = parse_bound(min, TimeInt::MIN)?;
+        let (max, is_max_duration) = parse_bound(max, TimeInt::MAX)?;
+        let range = AbsoluteTimeRange::new(min, max);
+        if range.min() > range.max() {
+            return Err(format!("empty time range: {s:?}"));
+        }
+
+        Ok(Self {
+            timeline: timeline.into(),
+            range,
+            has_duration_bound: is_min_duration || is_max_duration,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser as _;
+    use re_chunk::{RowId, TimePoint, Timeline};
+    use re_log_types::build_frame_nr;
+    use re_types::archetypes::Points3D;
+
+    use super::*;
+    use crate::commands::rrd::test_util;
+
+    #[test]
+    fn parse_time_range() {
+        let parse = |s: &str| {
+            s.parse::<TimeRangeArg>()
+                .map(|arg| (arg.timeline, arg.range.min(), arg.range.max()))
+        };
+        let time = TimeInt::new_temporal;
+
+        assert_eq!(
+            parse("frame_nr=10..20"),
+            Ok((TimelineName::new("frame_nr"), time(10), time(20)))
+        );
+        assert_eq!(
+            parse("frame_nr=..20"),
+            Ok((TimelineName::new("frame_nr"), TimeInt::MIN, time(20)))
+        );
+        assert_eq!(
+            parse("log_time=30s.."),
+            Ok((
+                TimelineName::new("log_time"),
+                time(30_000_000_000),
+                TimeInt::MAX
+            ))
+        );
+        assert_eq!(
+            parse("log_time=2025-06-01T12:00:00Z..2025-06-01T12:00:01Z"),
+            Ok((
+                TimelineName::new("log_time"),
+                time(1_748_779_200_000_000_000),
+                time(1_748_779_201_000_000_000)
+            ))
+        );
+
+        let has_duration_bound = |s: &str| s.parse::<TimeRangeArg>().unwrap().has_duration_bound;
+        assert!(has_duration_bound("log_time=30s.."));
+        assert!(has_duration_bound("log_time=..30s"));
+        assert!(!has_duration_bound("log_time=2025-06-01T12:00:00Z.."));
+        assert!(!has_duration_bound("frame_nr=10..20"));
+
+        assert!(parse("frame_nr").is_err());
+        assert!(parse("frame_nr=10").is_err());
+        assert!(parse("frame_nr=twenty..30").is_err());
+        assert!(parse("frame_nr=20..10").is_err());
+    }
+
+    /// Points with colors on frames 0 through 4, and ticks 0 through 40.
+    fn points(entity_path: &str) -> Chunk {
+        (0..5)
+            .fold(Chunk::builder(entity_path), |builder, frame| {
+                builder.with_archetype(
+                    RowId::new(),
+                    TimePoint::from([
+                        build_frame_nr(frame),
+                        (
+                            Timeline::new_sequence("tick"),
+                            TimeInt::new_temporal(frame * 10),
+                        ),
+                    ]),
+                    &Points3D::new([[frame as f32, 0.0, 0.0]]).with_colors([0xFF0000FF]),
+                )
+            })
+            .build()
+            .unwrap()
+    }
+
+    /// Runs `rerun rrd filter` on a fresh recording, and returns the chunks of the output.
+    fn filter(args: &[&str]) -> Vec<Chunk> {
+        let dir = tempfile::tempdir().unwrap();
+        let path_to_input_rrd = dir.path().join("input.rrd");
+        test_util::write_rrd(
+            &path_to_input_rrd,
+            ["/robot/arm", "/robot/debug", "/world"].map(points),
+        );
+
+        let path_to_output_rrd = dir.path().join("output.rrd");
+        let mut argv = vec![
+            "filter".to_owned(),
+            path_to_input_rrd.to_string_lossy().into_owned(),
+            "-o".to_owned(),
+            path_to_output_rrd.to_string_lossy().into_owned(),
+        ];
+        argv.extend(args.iter().map(|arg| (*arg).to_owned()));
+
+        FilterCommand::try_parse_from(argv).unwrap().run().unwrap();
+
+        test_util::read_chunks(&path_to_output_rrd)
+    }
+
+    fn entity_paths(chunks: &[Chunk]) -> Vec<String> {
+        chunks
+            .iter()
+            .map(|chunk| chunk.entity_path().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn filter_nothing() {
+        let chunks = filter(&[]);
+        assert_eq!(
+            entity_paths(&chunks),
+            ["/robot/arm", "/robot/debug", "/world"]
+        );
+        assert!(chunks.iter().all(|chunk| chunk.num_rows() == 5));
+    }
+
+    #[test]
+    fn filter_entities() {
+        let chunks = filter(&["--entity", "+ /robot/** - /robot/debug/**"]);
+        assert_eq!(entity_paths(&chunks), ["/robot/arm"]);
+
+        // Rules of several `--entity` are combined.
+        let chunks = filter(&["--entity", "+ /robot/**", "--entity", "- /robot/arm"]);
+        assert_eq!(entity_paths(&chunks), ["/robot/debug"]);
+
+        let chunks = filter(&["--drop-entity", "/world"]);
+        assert_eq!(entity_paths(&chunks), ["/robot/arm", "/robot/debug"]);
+    }
+
+    #[test]
+    fn filter_time_range() {
+        let chunks = filter(&["--time-range", "frame_nr=1..3"]);
+        assert_eq!(chunks.len(), 3);
+        for chunk in &chunks {
+            assert_eq!(chunk.num_rows(), 3);
+
+            // Other timelines are kept.
+            let ticks = chunk.timelines()[&TimelineName::new("tick")].times_raw();
+            assert_eq!(ticks, [10, 20, 30]);
+        }
+
+        assert!(filter(&["--time-range", "frame_nr=10.."]).is_empty());
+    }
+
+    #[test]
+    fn filter_components() {
+        let colors = Points3D::descriptor_colors();
+        let positions = Points3D::descriptor_positions();
+
+        // By identifier, or by type.
+        for component in ["Points3D:colors", "rerun.components.Color"] {
+            let chunks = filter(&["--drop-component", component]);
+            assert_eq!(chunks.len(), 3);
+            for chunk in &chunks {
+                assert!(!chunk.components().contains_component(&colors));
+                assert!(chunk.components().contains_component(&positions));
+            }
+        }
+    }
+}
@@ -52,6 +52,7 @@ impl MergeCommand {
             num_passes,
             *continue_on_error,
             &store_config,
+            re_log_encoding::EncodingOptions::PROTOBUF_COMPRESSED.compression,
             path_to_input_rrds,
             path_to_output_rrd.as_ref(),
         )
@@ -107,6 +108,12 @@ pub struct CompactCommand {
     #[arg(long = "num-pass", default_value_t = 50)]
     num_extra_passes: u32,
 
+    /// How to compress the output: `off`, `lz4`, `zstd` or `zstd:<level>` (e.g. `zstd:19`).
+    ///
+    /// Zstd yields much smaller files than LZ4, at the cost of slower encoding.
+    #[arg(long = "compression", default_value = "lz4")]
+    compression: re_log_encoding::Compression,
+
     /// If set, will try to proceed even in the face of IO and/or decoding errors in the input data.
     #[clap(long = "continue-on-error", default_value_t = false)]
     continue_on_error: bool,
@@ -121,6 +128,7 @@ impl CompactCommand {
             max_rows,
             max_rows_if_unsorted,
             num_extra_passes,
+            compression,
             continue_on_error,
         } = self;
 
@@ -150,6 +158,7 @@ impl CompactCommand {
             *num_extra_passes,
             *continue_on_error,
             &store_config,
+            *compression,
             path_to_input_rrds,
             path_to_output_rrd.as_ref(),
         )
@@ -160,6 +169,7 @@ fn merge_and_compact(
     num_passes: u32,
     continue_on_error: bool,
     store_config: &ChunkStoreConfig,
+    compression: re_log_encoding::Compression,
     path_to_input_rrds: &[String],
     path_to_output_rrd: Option<&String>,
 ) -> anyhow::Result<()> {
@@ -300,7 +310,10 @@ fn merge_and_compact(
         });
 
     // TODO(cmc): encoding options should match the original.
-    let encoding_options = re_log_encoding::EncodingOptions::PROTOBUF_COMPRESSED;
+    let encoding_options = re_log_encoding::EncodingOptions {
+        compression,
+        serializer: re_log_encoding::Serializer::Protobuf,
+    };
     let version = entity_dbs
         .values()
         .next()
@@ -309,7 +322,7 @@ fn merge_and_compact(
         .unwrap_or(re_build_info::CrateVersion::LOCAL);
 
     re_log::info!("encoding…");
-    let rrd_out_size = re_log_encoding::encoder::encode(
+    let rrd_out_size = re_log_encoding::encoder::encode_with_footer(
         version,
         encoding_options,
         // NOTE: We want to make sure all blueprints come first, so that the viewer can immediately
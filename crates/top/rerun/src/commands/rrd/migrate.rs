@@ -151,7 +151,7 @@ fn migrate_from_to(from_path: &Utf8PathBuf, to_path: &Utf8PathBuf) -> anyhow::Re
 
     let mut buffered_writer = std::io::BufWriter::new(new_file);
 
-    re_log_encoding::encoder::encode(
+    re_log_encoding::encoder::encode_with_footer(
         CrateVersion::LOCAL,
         EncodingOptions::PROTOBUF_COMPRESSED,
         messages,
@@ -1,10 +1,18 @@
 mod compare;
+#[cfg(feature = "rrd_export")]
+mod export;
+mod extract_video;
 mod filter;
 mod merge_compact;
 mod migrate;
 mod print;
 mod route;
+mod split;
 mod stats;
+#[cfg(test)]
+mod test_util;
+mod to_lerobot;
+mod to_mcap;
 mod verify;
 
 use self::{
@@ -14,10 +22,20 @@ use self::{
     migrate::MigrateCommand,
     print::PrintCommand,
     route::RouteCommand,
+    split::SplitCommand,
     stats::StatsCommand,
     verify::VerifyCommand,
 };
 
+pub(crate) use self::filter::TimeRangeArg;
+
+#[cfg(feature = "rrd_export")]
+use self::export::ExportCommand;
+
+use self::{
+    extract_video::ExtractVideoCommand, to_lerobot::ToLeRobotCommand, to_mcap::ToMcapCommand,
+};
+
 // ---
 
 use anyhow::Context as _;
@@ -44,6 +62,8 @@ pub enum RrdCommands {
     /// * `RERUN_CHUNK_MAX_ROWS=4096 RERUN_CHUNK_MAX_BYTES=1048576 rerun rrd compact /my/recordings/*.rrd -o output.rrd`
     ///
     /// * `rerun rrd compact --max-rows 4096 --max-bytes=1048576 /my/recordings/*.rrd > output.rrd`
+    ///
+    /// * `rerun rrd compact --compression zstd:19 /my/recordings/*.rrd -o output.rrd`
     Compact(CompactCommand),
 
     /// Compares the data between 2 .rrd files, returning a successful shell exit code if they
@@ -52,13 +72,47 @@ pub enum RrdCommands {
     /// This ignores the `log_time` timeline.
     Compare(CompareCommand),
 
+    /// Exports the contents of an .rrd file as a dataframe, in Parquet, CSV or Arrow IPC format.
+    ///
+    /// The dataframe is the result of a query: one row per value of the `--index` timeline, with
+    /// one column per component of every selected entity.
+    ///
+    /// Examples:
+    ///
+    /// * `rerun rrd export recording.rrd --index frame_nr -o recording.parquet`
+    ///
+    /// * `rerun rrd export recording.rrd --time-range frame_nr=..300 --entity "/robot/**" --sparse-fill latest-at-global -o robot.csv`
+    ///
+    /// * `rerun rrd export recording.rrd --index frame_nr --column frame_nr --column /points:Points3D:positions -o points.arrow`
+    #[cfg(feature = "rrd_export")]
+    Export(ExportCommand),
+
+    /// Extracts a clip of a `VideoStream` or `AssetVideo` entity from an .rrd file.
+    ///
+    /// The clip is either remuxed into an MP4 file without re-encoding, cut at the boundaries of
+    /// the groups of pictures covering the time range, or decoded into a directory of PNG frames
+    /// named after their time.
+    ///
+    /// Examples:
+    ///
+    /// * `rerun rrd extract-video recording.rrd --entity /camera --time-range log_time=2025-06-01T12:00:10Z..2025-06-01T12:00:20Z -o clip.mp4`
+    ///
+    /// * `rerun rrd extract-video recording.rrd --entity /camera --time-range frame_nr=100..200 -o frames/`
+    #[command(name = "extract-video")]
+    ExtractVideo(ExtractVideoCommand),
+
     /// Filters out data from .rrd/.rbl files/streams, and writes the result to standard output.
     ///
     /// Reads from standard input if no paths are specified.
     ///
-    /// This will not affect the chunking of the data in any way.
+    /// This will not affect the chunking of the data in any way, except for chunks that need to be
+    /// cut when filtering by time range.
+    ///
+    /// Examples:
     ///
-    /// Example: `rerun rrd filter --drop-timeline log_tick /my/recordings/*.rrd > output.rrd`
+    /// * `rerun rrd filter --drop-timeline log_tick /my/recordings/*.rrd > output.rrd`
+    ///
+    /// * `rerun rrd filter --entity "+ /robot/** - /robot/debug/**" --time-range log_time=2025-06-01T12:00:00Z..2025-06-01T12:00:30Z recording.rrd -o incident.rrd`
     Filter(FilterCommand),
 
     /// Merges the contents of multiple .rrd/.rbl files/streams, and writes the result to standard output.
@@ -91,6 +145,23 @@ pub enum RrdCommands {
     /// Note: Because the payload of the messages is never decoded, no migration or verification will performed.
     Route(RouteCommand),
 
+    /// Splits the contents of one or more .rrd/.rbl files/streams into multiple .rrd files.
+    ///
+    /// Reads from standard input if no paths are specified.
+    ///
+    /// The data can be split by time window, by size, or by entity. Store infos, blueprints and
+    /// (except when splitting by entity) static data are copied into every shard, so that each
+    /// of them can be opened on its own.
+    ///
+    /// Examples:
+    ///
+    /// * `rerun rrd split --time-window log_time=60s /my/recordings/*.rrd -o output.rrd`
+    ///
+    /// * `rerun rrd split --max-bytes 1GiB /my/recordings/*.rrd -o output.rrd`
+    ///
+    /// * `rerun rrd split --entity /camera --entity /lidar /my/recordings/*.rrd -o output.rrd`
+    Split(SplitCommand),
+
     /// Compute important statistics for one or more .rrd/.rbl files/streams.
     ///
     /// Reads from standard input if no paths are specified.
@@ -98,6 +169,34 @@ pub enum RrdCommands {
     /// Example: `rerun rrd stats /my/recordings/*.rrd`
     Stats(StatsCommand),
 
+    /// Converts .rrd files/streams to a `LeRobot` dataset, with one episode per recording.
+    ///
+    /// Reads from standard input if no paths are specified.
+    ///
+    /// Every recording is resampled at a fixed frame rate, and the selected component columns
+    /// are written as the features of the dataset. Video features are encoded to MP4 using
+    /// `ffmpeg`, which must be available on the `PATH`.
+    ///
+    /// Example:
+    ///
+    /// * `rerun rrd to-lerobot /my/recordings/*.rrd --fps 30 --feature /robot/joints:Scalars:scalars=observation.state --feature /robot/camera:EncodedImage:blob=observation.images.front --task "pick up the cube" -o my_dataset`
+    #[command(name = "to-lerobot")]
+    ToLeRobot(ToLeRobotCommand),
+
+    /// Converts the contents of .rrd files/streams to an .mcap file.
+    ///
+    /// Reads from standard input if no paths are specified.
+    ///
+    /// Images, point clouds, transforms and text logs are written using the Foxglove protobuf
+    /// schemas. Every other component column becomes its own channel of Arrow IPC-encoded messages.
+    ///
+    /// Examples:
+    ///
+    /// * `rerun rrd to-mcap recording.rrd -o recording.mcap`
+    ///
+    /// * `rerun rrd to-mcap recording.rrd --timeline sensor_time -o recording.mcap`
+    ToMcap(ToMcapCommand),
+
     /// Verify the that the .rrd file can be loaded and correctly interpreted.
     ///
     /// Can be used to ensure that the current Rerun version can load the data.
@@ -113,12 +212,18 @@ impl RrdCommands {
                     .with_context(|| format!("current directory {:?}", std::env::current_dir()))
             }
             Self::Compact(cmd) => cmd.run(),
+            #[cfg(feature = "rrd_export")]
+            Self::Export(cmd) => cmd.run(),
+            Self::ExtractVideo(cmd) => cmd.run(),
             Self::Filter(cmd) => cmd.run(),
             Self::Merge(cmd) => cmd.run(),
             Self::Migrate(cmd) => cmd.run(),
             Self::Print(cmd) => cmd.run(),
             Self::Route(cmd) => cmd.run(),
+            Self::Split(cmd) => cmd.run(),
             Self::Stats(cmd) => cmd.run(),
+            Self::ToLeRobot(cmd) => cmd.run(),
+            Self::ToMcap(cmd) => cmd.run(),
             Self::Verify(cmd) => cmd.run(),
         }
     }
@@ -6,7 +6,7 @@ use re_byte_size::SizeBytes as _;
 use re_log_types::{LogMsg, SetStoreInfo};
 use re_sdk::EntityPath;
 
-use crate::commands::read_rrd_streams_from_file_or_stdin;
+use crate::commands::read_rrd_streams_from_file_or_stdin_with_query;
 
 // ---
 
@@ -87,7 +87,16 @@ impl PrintCommand {
             );
         }
 
-        let (rx, _) = read_rrd_streams_from_file_or_stdin(&path_to_input_rrds);
+        // Files that have a footer are only partially read when looking at a single entity.
+        let query = re_log_encoding::footer::RrdFooterQuery {
+            entity_path_filter: options.entity.as_ref().map(|entity| {
+                re_log_types::EntityPathFilter::single_entity_filter(entity)
+                    .resolve_without_substitutions()
+            }),
+            time_range: None,
+        };
+
+        let (rx, _) = read_rrd_streams_from_file_or_stdin_with_query(&path_to_input_rrds, query);
 
         for (_source, res) in rx {
             let mut is_success = true;
@@ -0,0 +1,733 @@
+use std::collections::{BTreeMap, VecDeque};
+use std::io::{Seek as _, SeekFrom, Write as _};
+use std::path::{Path, PathBuf};
+
+use anyhow::Context as _;
+use arrow::array::BooleanArray as ArrowBooleanArray;
+use itertools::Itertools as _;
+
+use re_build_info::CrateVersion;
+use re_chunk::{Chunk, ChunkId, TimelineName};
+use re_log_types::{EntityPath, LogMsg};
+use re_sdk::external::arrow;
+
+use crate::commands::read_rrd_streams_from_file_or_stdin;
+
+// ---
+
+#[derive(Debug, Clone, clap::Parser)]
+#[clap(group(
+    clap::ArgGroup::new("strategy")
+        .required(true)
+        .args(["time_window", "max_bytes", "entities"]),
+))]
+pub struct SplitCommand {
+    /// Paths to read from. Reads from standard input if none are specified.
+    path_to_input_rrds: Vec<String>,
+
+    /// Where to write the shards.
+    ///
+    /// Shards are numbered in the order they are first needed, e.g. `-o out.rrd` results in
+    /// `out_0000.rrd`, `out_0001.rrd`, etc.
+    #[arg(short = 'o', long = "output", value_name = "dst.rrd")]
+    path_to_output_rrd: String,
+
+    /// Split along fixed-size windows of the given timeline, e.g. `log_time=60s` or `frame_nr=1000`.
+    ///
+    /// Durations (`ms`, `s`, `m`, `h`) only make sense for temporal timelines. Plain integers are in
+    /// the native unit of the timeline (nanoseconds or sequence numbers).
+    ///
+    /// Chunks that span multiple windows are cut accordingly.
+    /// Temporal data that isn't on that timeline at all ends up in the first shard.
+    #[arg(long = "time-window", value_name = "TIMELINE=SIZE")]
+    time_window: Option<TimeWindow>,
+
+    /// Start a new shard whenever the current one grows beyond this many bytes, e.g. `500MB` or `2GiB`.
+    ///
+    /// This is a best-effort budget: chunks are never cut, and static data counts towards the
+    /// size of every shard.
+    #[arg(long = "max-bytes", value_parser = parse_max_bytes)]
+    max_bytes: Option<u64>,
+
+    /// Split by entity: each of these subtrees gets its own shard, and everything else ends up in
+    /// one last shard.
+    ///
+    /// Can be specified multiple times. The first matching subtree wins.
+    #[arg(long = "entity", value_name = "ENTITY_PATH")]
+    entities: Vec<String>,
+
+    /// If set, will try to proceed even in the face of IO and/or decoding errors in the input data.
+    #[clap(long = "continue-on-error", default_value_t = false)]
+    continue_on_error: bool,
+}
+
+/// See [`SplitCommand::time_window`].
+#[derive(Debug, Clone)]
+struct TimeWindow {
+    timeline: TimelineName,
+    size: i64,
+}
+
+impl std::str::FromStr for TimeWindow {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let Some((timeline, size)) = s.split_once('=') else {
+            return Err(format!("expected `TIMELINE=SIZE`, got {s:?}"));
+        };
+
+        let size = if let Some(size) = re_format::parse_i64(size) {
+            size
+        } else {
+            let secs = re_format::parse_duration(size)?;
+            (secs as f64 * 1e9).round() as i64
+        };
+
+        if size <= 0 {
+            return Err(format!("window size must be strictly positive, got {s:?}"));
+        }
+
+        Ok(Self {
+            timeline: timeline.into(),
+            size,
+        })
+    }
+}
+
+fn parse_max_bytes(s: &str) -> Result<u64, String> {
+    re_format::parse_bytes(s)
+        .and_then(|bytes| u64::try_from(bytes).ok())
+        .filter(|bytes| *bytes > 0)
+        .ok_or_else(|| format!("expected a strictly positive number of bytes, got {s:?}"))
+}
+
+/// How messages are assigned to shards.
+enum Strategy {
+    TimeWindow(TimeWindow),
+    MaxBytes(u64),
+    Entities(Vec<EntityPath>),
+}
+
+impl SplitCommand {
+    pub fn run(&self) -> anyhow::Result<()> {
+        let Self {
+            path_to_input_rrds,
+            path_to_output_rrd,
+            time_window,
+            max_bytes,
+            entities,
+            continue_on_error,
+        } = self;
+
+        let strategy = if let Some(time_window) = time_window {
+            Strategy::TimeWindow(time_window.clone())
+        } else if let Some(max_bytes) = max_bytes {
+            Strategy::MaxBytes(*max_bytes)
+        } else {
+            Strategy::Entities(
+                entities
+                    .iter()
+                    .map(|entity| EntityPath::parse_forgiving(entity))
+                    .collect(),
+            )
+        };
+
+        let now = std::time::Instant::now();
+        re_log::info!(srcs = ?path_to_input_rrds, "split started");
+
+        let (rx, rx_size_bytes) = read_rrd_streams_from_file_or_stdin(path_to_input_rrds);
+
+        let mut shards = Shards::new(Path::new(path_to_output_rrd))?;
+        let mut num_size_shards = 0;
+
+        for (_source, res) in rx {
+            let mut is_success = true;
+
+            match res {
+                Ok(msg) => {
+                    if let Err(err) = split_msg(&strategy, &mut shards, &mut num_size_shards, &msg)
+                    {
+                        re_log::error!(err = re_error::format(err));
+                        is_success = false;
+                    }
+                }
+
+                Err(err) => {
+                    re_log::error!(err = re_error::format(err));
+                    is_success = false;
+                }
+            }
+
+            if !*continue_on_error && !is_success {
+                anyhow::bail!(
+                    "one or more IO and/or decoding failures in the input stream (check logs)"
+                )
+            }
+        }
+
+        let shards = shards.finish()?;
+
+        for (path, size_bytes) in &shards {
+            re_log::info!(
+                dst = ?path,
+                dst_size_bytes = %re_format::format_bytes(*size_bytes as _),
+                "shard written"
+            );
+        }
+
+        let rrds_in_size = rx_size_bytes.recv().ok();
+        re_log::info!(
+            num_shards = shards.len(),
+            time = ?now.elapsed(),
+            srcs = ?path_to_input_rrds,
+            srcs_size_bytes = %rrds_in_size.map_or_else(
+                || "<unknown>".to_owned(),
+                |size| re_format::format_bytes(size as _),
+            ),
+            "split finished"
+        );
+
+        Ok(())
+    }
+}
+
+fn split_msg(
+    strategy: &Strategy,
+    shards: &mut Shards,
+    num_size_shards: &mut i64,
+    msg: &LogMsg,
+) -> anyhow::Result<()> {
+    let LogMsg::ArrowMsg(store_id, arrow_msg) = msg else {
+        // Store infos and blueprint activations are needed for every shard to be usable on its own.
+        return shards.broadcast(msg);
+    };
+
+    if store_id.is_blueprint() {
+        return shards.broadcast(msg);
+    }
+
+    let chunk = Chunk::from_arrow_msg(arrow_msg).context("couldn't decode chunk")?;
+
+    match strategy {
+        Strategy::TimeWindow(TimeWindow { timeline, size }) => {
+            if chunk.is_static() {
+                return shards.broadcast(msg);
+            }
+
+            let Some(time_column) = chunk.timelines().get(timeline) else {
+                let shard = shards.first_or_open()?;
+                return shards.append(shard, msg);
+            };
+
+            let windows: Vec<i64> = time_column
+                .times_raw()
+                .iter()
+                .map(|time| time.div_euclid(*size))
+                .collect();
+
+            let Some((first_window, last_window)) = windows.iter().copied().minmax().into_option()
+            else {
+                return Ok(()); // empty chunk
+            };
+
+            if first_window == last_window {
+                let shard = shards.get_or_open(first_window)?;
+                return shards.append(shard, msg);
+            }
+
+            let distinct_windows: std::collections::BTreeSet<i64> =
+                windows.iter().copied().collect();
+            for window in distinct_windows {
+                let filter: ArrowBooleanArray =
+                    windows.iter().map(|w| Some(*w == window)).collect();
+                let Some(sliced) = chunk.filtered(&filter) else {
+                    continue;
+                };
+
+                let sliced = sliced.with_id(ChunkId::new());
+                let sliced_msg = LogMsg::ArrowMsg(
+                    store_id.clone(),
+                    sliced.to_arrow_msg().context("couldn't encode chunk")?,
+                );
+
+                let shard = shards.get_or_open(window)?;
+                shards.append(shard, &sliced_msg)?;
+            }
+
+            Ok(())
+        }
+
+        Strategy::MaxBytes(max_bytes) => {
+            if chunk.is_static() {
+                return shards.broadcast(msg);
+            }
+
+            let mut shard = shards.get_or_open(*num_size_shards)?;
+            if shards.size_bytes(shard) >= *max_bytes {
+                *num_size_shards += 1;
+                shard = shards.get_or_open(*num_size_shards)?;
+            }
+
+            shards.append(shard, msg)
+        }
+
+        Strategy::Entities(entities) => {
+            let index = entities
+                .iter()
+                .position(|entity| chunk.entity_path().starts_with(entity))
+                .unwrap_or(entities.len());
+
+            let shard = shards.get_or_open(index as i64)?;
+            shards.append(shard, msg)
+        }
+    }
+}
+
+// ---
+
+/// The set of output files.
+///
+/// Only the [`Self::max_open_shards`] most recently used shards keep their file open: splitting
+/// along small windows can produce many more shards than a process is allowed to keep open files.
+/// When the input is sorted, shards are thus closed once their window has passed.
+struct Shards {
+    path_to_output_rrd: PathBuf,
+
+    /// Messages that every shard must start with in order to be usable on its own, in order.
+    preamble: Preamble,
+
+    /// Maps the keys given by the split strategy to indices in [`Self::shards`].
+    keys: BTreeMap<i64, usize>,
+
+    shards: Vec<Shard>,
+
+    /// Indices of the shards whose file is currently open, least recently used first.
+    ///
+    /// These are always up-to-date with the preamble.
+    open_shards: VecDeque<usize>,
+
+    max_open_shards: usize,
+}
+
+struct Shard {
+    path: PathBuf,
+    encoder: re_log_encoding::encoder::DroppableEncoder<ShardFile>,
+    size_bytes: u64,
+
+    /// How many messages of the preamble have been written to this shard so far.
+    preamble_len: usize,
+}
+
+impl Shards {
+    /// Comfortably below the usual limit of 1024 open files per process.
+    const MAX_OPEN_SHARDS: usize = 256;
+
+    fn new(path_to_output_rrd: &Path) -> anyhow::Result<Self> {
+        Ok(Self {
+            path_to_output_rrd: path_to_output_rrd.to_owned(),
+            preamble: Preamble::new()?,
+            keys: BTreeMap::new(),
+            shards: Vec::new(),
+            open_shards: VecDeque::new(),
+            max_open_shards: Self::MAX_OPEN_SHARDS,
+        })
+    }
+
+    fn shard_path(&self, index: usize) -> PathBuf {
+        let stem = self
+            .path_to_output_rrd
+            .file_stem()
+            .map_or_else(|| "split".into(), |stem| stem.to_string_lossy());
+        let extension = self
+            .path_to_output_rrd
+            .extension()
+            .map_or_else(|| "rrd".into(), |ext| ext.to_string_lossy());
+
+        self.path_to_output_rrd
+            .with_file_name(format!("{stem}_{index:04}.{extension}"))
+    }
+
+    /// Returns the index of the shard for the given key, creating it if needed.
+    fn get_or_open(&mut self, key: i64) -> anyhow::Result<usize> {
+        if let Some(index) = self.keys.get(&key) {
+            return Ok(*index);
+        }
+
+        let index = self.shards.len();
+        let path = self.shard_path(index);
+
+        let file = std::fs::File::create(&path).with_context(|| format!("{path:?}"))?;
+        let encoder = re_log_encoding::encoder::DroppableEncoder::new(
+            CrateVersion::LOCAL,
+            re_log_encoding::EncodingOptions::PROTOBUF_COMPRESSED,
+            ShardFile {
+                path: path.clone(),
+                file: Some(std::io::BufWriter::new(file)),
+            },
+        )
+        .context("couldn't init encoder")?
+        .with_footer();
+
+        self.shards.push(Shard {
+            path,
+            encoder,
+            size_bytes: 0,
+            preamble_len: 0,
+        });
+        self.keys.insert(key, index);
+
+        self.open(index)?;
+
+        Ok(index)
+    }
+
+    /// Returns the index of the first shard, creating it if needed.
+    fn first_or_open(&mut self) -> anyhow::Result<usize> {
+        if self.shards.is_empty() {
+            self.get_or_open(i64::MIN)
+        } else {
+            Ok(0)
+        }
+    }
+
+    fn size_bytes(&self, index: usize) -> u64 {
+        self.shards[index].size_bytes
+    }
+
+    fn append(&mut self, index: usize, msg: &LogMsg) -> anyhow::Result<()> {
+        self.open(index)?;
+        self.shards[index].append(msg)
+    }
+
+    /// Writes the message to all shards, including those that don't exist yet.
+    fn broadcast(&mut self, msg: &LogMsg) -> anyhow::Result<()> {
+        self.preamble.push(msg)?;
+
+        // Closed shards will catch up once they are opened again.
+        for &index in &self.open_shards {
+            let shard = &mut self.shards[index];
+            shard.append(msg)?;
+            shard.preamble_len += 1;
+        }
+
+        Ok(())
+    }
+
+    /// Marks the shard as most recently used, opening it and closing the least recently used
+    /// one if needed.
+    fn open(&mut self, index: usize) -> anyhow::Result<()> {
+        if let Some(pos) = self.open_shards.iter().position(|open| *open == index) {
+            self.open_shards.remove(pos);
+            self.open_shards.push_back(index);
+            return Ok(());
+        }
+
+        while self.open_shards.len() >= self.max_open_shards {
+            let Some(lru) = self.open_shards.pop_front() else {
+                break;
+            };
+            let shard = &mut self.shards[lru];
+            shard
+                .encoder
+                .get_mut()
+                .close()
+                .with_context(|| format!("couldn't close {:?}", shard.path))?;
+        }
+
+        // The file itself is reopened on the next write.
+        self.shards[index].catch_up(&mut self.preamble)?;
+        self.open_shards.push_back(index);
+
+        Ok(())
+    }
+
+    /// Returns the path and size of every shard.
+    fn finish(self) -> anyhow::Result<Vec<(PathBuf, u64)>> {
+        let Self {
+            mut preamble,
+            shards,
+            ..
+        } = self;
+
+        shards
+            .into_iter()
+            .map(|mut shard| {
+                shard.catch_up(&mut preamble)?;
+                shard
+                    .encoder
+                    .finish()
+                    .with_context(|| format!("couldn't finish {:?}", shard.path))?;
+                shard
+                    .encoder
+                    .get_mut()
+                    .close()
+                    .with_context(|| format!("couldn't flush {:?}", shard.path))?;
+                Ok((shard.path, shard.size_bytes))
+            })
+            .collect()
+    }
+}
+
+impl Shard {
+    fn append(&mut self, msg: &LogMsg) -> anyhow::Result<()> {
+        self.size_bytes += self
+            .encoder
+            .append(msg)
+            .with_context(|| format!("couldn't encode message into {:?}", self.path))?;
+        Ok(())
+    }
+
+    /// Writes the messages of the preamble that this shard missed while it was closed.
+    fn catch_up(&mut self, preamble: &mut Preamble) -> anyhow::Result<()> {
+        preamble.replay(self.preamble_len, |msg| self.append(msg))?;
+        self.preamble_len = preamble.len();
+        Ok(())
+    }
+}
+
+/// The file of a [`Shard`], which is transparently reopened when written to after being closed.
+struct ShardFile {
+    path: PathBuf,
+    file: Option<std::io::BufWriter<std::fs::File>>,
+}
+
+impl ShardFile {
+    fn file(&mut self) -> std::io::Result<&mut std::io::BufWriter<std::fs::File>> {
+        let file = match self.file.take() {
+            Some(file) => file,
+            None => {
+                std::io::BufWriter::new(std::fs::OpenOptions::new().append(true).open(&self.path)?)
+            }
+        };
+        Ok(self.file.insert(file))
+    }
+
+    fn close(&mut self) -> std::io::Result<()> {
+        if let Some(mut file) = self.file.take() {
+            file.flush()?;
+        }
+        Ok(())
+    }
+}
+
+impl std::io::Write for ShardFile {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.file()?.write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        match &mut self.file {
+            Some(file) => file.flush(),
+            None => Ok(()), // flushed when closed
+        }
+    }
+}
+
+/// The messages that every shard must start with, see [`Shards::broadcast`].
+///
+/// Static data can be large, so these are kept in a temporary file rather than in memory.
+struct Preamble {
+    encoder: re_log_encoding::encoder::Encoder<std::io::BufWriter<std::fs::File>>,
+
+    /// Where each message starts in the file.
+    offsets: Vec<u64>,
+
+    /// Where the next message will start in the file.
+    end_offset: u64,
+}
+
+impl Preamble {
+    const OPTIONS: re_log_encoding::EncodingOptions =
+        re_log_encoding::EncodingOptions::PROTOBUF_UNCOMPRESSED;
+
+    fn new() -> anyhow::Result<Self> {
+        let file = tempfile::tempfile().context("couldn't create temporary file")?;
+        let mut encoder = re_log_encoding::encoder::Encoder::new(
+            CrateVersion::LOCAL,
+            Self::OPTIONS,
+            std::io::BufWriter::new(file),
+        )
+        .context("couldn't init encoder")?;
+
+        encoder.flush_blocking()?;
+        let end_offset = encoder.get_mut().get_mut().stream_position()?;
+
+        Ok(Self {
+            encoder,
+            offsets: Vec::new(),
+            end_offset,
+        })
+    }
+
+    fn len(&self) -> usize {
+        self.offsets.len()
+    }
+
+    fn push(&mut self, msg: &LogMsg) -> anyhow::Result<()> {
+        let num_bytes = self
+            .encoder
+            .append(msg)
+            .context("couldn't write to temporary file")?;
+        self.offsets.push(self.end_offset);
+        self.end_offset += num_bytes;
+        Ok(())
+    }
+
+    /// Calls `on_msg` for every message from the `start`-th one onwards, in order.
+    fn replay(
+        &mut self,
+        start: usize,
+        mut on_msg: impl FnMut(&LogMsg) -> anyhow::Result<()>,
+    ) -> anyhow::Result<()> {
+        let Some(&offset) = self.offsets.get(start) else {
+            return Ok(());
+        };
+
+        self.encoder.flush_blocking()?;
+        let file = self.encoder.get_mut().get_mut();
+        file.seek(SeekFrom::Start(offset))?;
+
+        let decoder = re_log_encoding::decoder::Decoder::new_with_options(
+            Self::OPTIONS,
+            CrateVersion::LOCAL,
+            std::io::BufReader::new(&mut *file),
+        );
+        for msg in decoder.take(self.offsets.len() - start) {
+            on_msg(&msg.context("couldn't read temporary file")?)?;
+        }
+
+        // Back to appending.
+        file.seek(SeekFrom::End(0))?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use re_chunk::{RowId, TimePoint};
+    use re_log_types::{StoreId, StoreKind, build_frame_nr};
+    use re_types::archetypes::Points3D;
+
+    use super::*;
+
+    fn chunk_msg(store_id: &StoreId, timepoints: &[TimePoint]) -> (ChunkId, LogMsg) {
+        let chunk = timepoints
+            .iter()
+            .fold(Chunk::builder("points"), |builder, timepoint| {
+                builder.with_archetype(
+                    RowId::new(),
+                    timepoint.clone(),
+                    &Points3D::new([[1.0, 2.0, 3.0]]),
+                )
+            })
+            .build()
+            .unwrap();
+
+        (
+            chunk.id(),
+            LogMsg::ArrowMsg(store_id.clone(), chunk.to_arrow_msg().unwrap()),
+        )
+    }
+
+    /// The ids of the chunks in the given shard, in order.
+    fn chunk_ids(path: &Path) -> Vec<ChunkId> {
+        let file = std::fs::File::open(path).unwrap();
+        re_log_encoding::decoder::Decoder::new(std::io::BufReader::new(file))
+            .unwrap()
+            .map(|msg| match msg.unwrap() {
+                LogMsg::ArrowMsg(_, arrow_msg) => Chunk::from_arrow_msg(&arrow_msg).unwrap().id(),
+                msg => panic!("unexpected message: {msg:?}"),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn shards_are_closed_and_reopened() {
+        let dir = tempfile::tempdir().unwrap();
+        let store_id = StoreId::random(StoreKind::Recording, "test_app");
+
+        let mut shards = Shards::new(&dir.path().join("out.rrd")).unwrap();
+        shards.max_open_shards = 2;
+
+        let (static_1, msg) = chunk_msg(&store_id, &[TimePoint::default()]);
+        shards.broadcast(&msg).unwrap();
+
+        let mut temporal = Vec::new();
+        for key in 0..4 {
+            let (chunk_id, msg) = chunk_msg(&store_id, &[TimePoint::from([build_frame_nr(key)])]);
+            let shard = shards.get_or_open(key).unwrap();
+            shards.append(shard, &msg).unwrap();
+            temporal.push(chunk_id);
+        }
+        assert_eq!(shards.open_shards, [2, 3]);
+
+        // Shards 0 and 1 are closed at that point, and only get it once reopened.
+        let (static_2, msg) = chunk_msg(&store_id, &[TimePoint::default()]);
+        shards.broadcast(&msg).unwrap();
+
+        let (late, msg) = chunk_msg(&store_id, &[TimePoint::from([build_frame_nr(0)])]);
+        let shard = shards.get_or_open(0).unwrap();
+        shards.append(shard, &msg).unwrap();
+        assert_eq!(shards.open_shards, [3, 0]);
+
+        let shards = shards.finish().unwrap();
+        assert_eq!(shards.len(), 4);
+
+        assert_eq!(
+            chunk_ids(&shards[0].0),
+            [static_1, temporal[0], static_2, late]
+        );
+        for (index, (path, size_bytes)) in shards.iter().enumerate().skip(1) {
+            assert_eq!(chunk_ids(path), [static_1, temporal[index], static_2]);
+            assert!(*size_bytes > 0);
+        }
+    }
+
+    #[test]
+    fn split_by_time_window() {
+        let dir = tempfile::tempdir().unwrap();
+        let store_id = StoreId::random(StoreKind::Recording, "test_app");
+        let strategy = Strategy::TimeWindow("frame_nr=10".parse().unwrap());
+
+        let mut shards = Shards::new(&dir.path().join("out.rrd")).unwrap();
+        let mut num_size_shards = 0;
+
+        let (static_chunk, msg) = chunk_msg(&store_id, &[TimePoint::default()]);
+        split_msg(&strategy, &mut shards, &mut num_size_shards, &msg).unwrap();
+
+        // Spans three windows, and gets cut accordingly.
+        let (_, msg) = chunk_msg(
+            &store_id,
+            &[5, 15, 16, 25].map(|frame| TimePoint::from([build_frame_nr(frame)])),
+        );
+        split_msg(&strategy, &mut shards, &mut num_size_shards, &msg).unwrap();
+
+        let shards = shards.finish().unwrap();
+        assert_eq!(shards.len(), 3);
+
+        for ((path, _), expected_frames) in shards.iter().zip([vec![5], vec![15, 16], vec![25]]) {
+            let file = std::fs::File::open(path).unwrap();
+            let chunks: Vec<Chunk> =
+                re_log_encoding::decoder::Decoder::new(std::io::BufReader::new(file))
+                    .unwrap()
+                    .map(|msg| match msg.unwrap() {
+                        LogMsg::ArrowMsg(_, arrow_msg) => {
+                            Chunk::from_arrow_msg(&arrow_msg).unwrap()
+                        }
+                        msg => panic!("unexpected message: {msg:?}"),
+                    })
+                    .collect();
+
+            assert_eq!(chunks.len(), 2);
+            assert_eq!(chunks[0].id(), static_chunk);
+            let frames = chunks[1]
+                .timelines()
+                .get(&TimelineName::new("frame_nr"))
+                .unwrap()
+                .times_raw()
+                .to_vec();
+            assert_eq!(frames, expected_frames);
+        }
+    }
+}
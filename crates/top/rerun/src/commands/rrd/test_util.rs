@@ -0,0 +1,53 @@
+//! Helpers shared by the tests of the `rrd` subcommands.
+
+use std::path::Path;
+
+use re_build_info::CrateVersion;
+use re_chunk::{Chunk, RowId};
+use re_log_encoding::{EncodingOptions, encoder::DroppableEncoder};
+use re_log_types::{LogMsg, SetStoreInfo, StoreId, StoreInfo, StoreKind, StoreSource};
+
+/// Writes a recording made of the given chunks to `path`, with a footer.
+pub(super) fn write_rrd(path: &Path, chunks: impl IntoIterator<Item = Chunk>) {
+    let store_id = StoreId::random(StoreKind::Recording, "test_app");
+
+    let mut encoder = DroppableEncoder::new(
+        CrateVersion::LOCAL,
+        EncodingOptions::PROTOBUF_COMPRESSED,
+        std::fs::File::create(path).unwrap(),
+    )
+    .unwrap()
+    .with_footer();
+    encoder
+        .append(&LogMsg::SetStoreInfo(SetStoreInfo {
+            row_id: *RowId::new(),
+            info: StoreInfo {
+                store_id: store_id.clone(),
+                cloned_from: None,
+                store_source: StoreSource::Unknown,
+                store_version: Some(CrateVersion::LOCAL),
+            },
+        }))
+        .unwrap();
+    for chunk in chunks {
+        encoder
+            .append(&LogMsg::ArrowMsg(
+                store_id.clone(),
+                chunk.to_arrow_msg().unwrap(),
+            ))
+            .unwrap();
+    }
+    encoder.finish().unwrap();
+}
+
+/// Reads all the chunks of the recording at `path`.
+pub(super) fn read_chunks(path: &Path) -> Vec<Chunk> {
+    let file = std::fs::File::open(path).unwrap();
+    re_log_encoding::decoder::Decoder::new(std::io::BufReader::new(file))
+        .unwrap()
+        .filter_map(|msg| match msg.unwrap() {
+            LogMsg::ArrowMsg(_, arrow_msg) => Some(Chunk::from_arrow_msg(&arrow_msg).unwrap()),
+            _ => None,
+        })
+        .collect()
+}
@@ -0,0 +1,209 @@
+use std::sync::Arc;
+
+use anyhow::Context as _;
+
+use re_chunk::{Chunk, TimelineName};
+use re_data_loader::lerobot::export::{FeatureMapping, LeRobotDatasetWriter};
+use re_log_types::{LogMsg, StoreId};
+
+use crate::commands::read_rrd_streams_from_file_or_stdin;
+
+// ---
+
+#[derive(Debug, Clone, clap::Parser)]
+pub struct ToLeRobotCommand {
+    /// Paths to read from. Reads from standard input if none are specified.
+    path_to_input_rrds: Vec<String>,
+
+    /// The directory to write the dataset to.
+    #[arg(short = 'o', long = "output", value_name = "dst_dir")]
+    path_to_output_dataset: String,
+
+    /// Maps a component column onto a feature of the dataset, e.g. `/robot/joints:Scalars:scalars=observation.state`.
+    ///
+    /// The dtype of the feature can be appended, e.g. `=observation.state:float64`. It defaults to
+    /// `video` for `EncodedImage:blob` and `Image:buffer` columns, and to `float32` otherwise.
+    ///
+    /// Can be specified multiple times.
+    #[clap(
+        long = "feature",
+        value_name = "ENTITY_PATH:COMPONENT=FEATURE[:DTYPE]",
+        required = true
+    )]
+    features: Vec<FeatureMapping>,
+
+    /// The timeline to resample the recordings on.
+    #[clap(long = "timeline", default_value = "log_time")]
+    timeline: String,
+
+    /// The frame rate of the dataset.
+    ///
+    /// Temporal timelines are resampled at this rate, sequence timelines are sampled at every
+    /// integer.
+    #[clap(
+        long = "fps",
+        default_value_t = 30,
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..)
+    )]
+    fps: usize,
+
+    /// The task performed in every episode. Defaults to the application id of each recording.
+    #[clap(long = "task")]
+    task: Option<String>,
+
+    /// The type of robot, as recorded in the dataset metadata.
+    #[clap(long = "robot-type")]
+    robot_type: Option<String>,
+
+    /// If set, will try to proceed even in the face of IO and/or decoding errors in the input data.
+    #[clap(long = "continue-on-error", default_value_t = false)]
+    continue_on_error: bool,
+}
+
+impl ToLeRobotCommand {
+    pub fn run(&self) -> anyhow::Result<()> {
+        let Self {
+            path_to_input_rrds,
+            path_to_output_dataset,
+            features,
+            timeline,
+            fps,
+            task,
+            robot_type,
+            continue_on_error,
+        } = self;
+
+        let now = std::time::Instant::now();
+        re_log::info!(srcs = ?path_to_input_rrds, dst = ?path_to_output_dataset, "conversion to LeRobot started");
+
+        let (rx, _) = read_rrd_streams_from_file_or_stdin(path_to_input_rrds);
+
+        // Every recording becomes an episode, in the order they appear in the input.
+        let mut recordings: Vec<(StoreId, Vec<Arc<Chunk>>)> = Vec::new();
+
+        for (_source, res) in rx {
+            let mut is_success = true;
+
+            match res {
+                Ok(msg) => {
+                    let LogMsg::ArrowMsg(store_id, arrow_msg) = &msg else {
+                        continue;
+                    };
+
+                    if !store_id.is_recording() {
+                        continue;
+                    }
+
+                    match Chunk::from_arrow_msg(arrow_msg).context("couldn't decode chunk") {
+                        Ok(chunk) => {
+                            let chunks = if let Some((_, chunks)) =
+                                recordings.iter_mut().find(|(id, _)| id == store_id)
+                            {
+                                chunks
+                            } else {
+                                recordings.push((store_id.clone(), Vec::new()));
+                                #[allow(clippy::unwrap_used)] // we just pushed it
+                                &mut recordings.last_mut().unwrap().1
+                            };
+                            chunks.push(Arc::new(chunk));
+                        }
+
+                        Err(err) => {
+                            re_log::error!(err = re_error::format(err));
+                            is_success = false;
+                        }
+                    }
+                }
+
+                Err(err) => {
+                    re_log::error!(err = re_error::format(err));
+                    is_success = false;
+                }
+            }
+
+            if !*continue_on_error && !is_success {
+                anyhow::bail!(
+                    "one or more IO and/or decoding failures in the input stream (check logs)"
+                )
+            }
+        }
+
+        let mut writer = LeRobotDatasetWriter::new(
+            path_to_output_dataset,
+            TimelineName::new(timeline),
+            *fps,
+            features.clone(),
+        )
+        .context("couldn't create dataset")?;
+        if let Some(robot_type) = robot_type {
+            writer = writer.with_robot_type(robot_type);
+        }
+
+        for (store_id, chunks) in &recordings {
+            let task = task
+                .clone()
+                .unwrap_or_else(|| store_id.application_id().to_string());
+
+            match writer.write_episode(chunks, &task) {
+                Ok(num_frames) => {
+                    re_log::info!(
+                        recording_id = %store_id.recording_id(),
+                        num_frames,
+                        "episode written"
+                    );
+                }
+
+                Err(err) => {
+                    let err = anyhow::Error::from(err).context(format!(
+                        "couldn't write recording {} as an episode",
+                        store_id.recording_id()
+                    ));
+                    if *continue_on_error {
+                        re_log::error!(err = re_error::format(err));
+                    } else {
+                        return Err(err);
+                    }
+                }
+            }
+        }
+
+        let info = writer.finish().context("couldn't write dataset metadata")?;
+
+        re_log::info!(
+            num_episodes = info.total_episodes,
+            num_frames = info.total_frames,
+            time = ?now.elapsed(),
+            dst = ?path_to_output_dataset,
+            "conversion to LeRobot finished"
+        );
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser as _;
+
+    use super::*;
+
+    #[test]
+    fn parse_fps() {
+        let args = |fps| {
+            [
+                "to-lerobot",
+                "-o",
+                "dataset",
+                "--feature",
+                "/robot/joints:Scalars:scalars=observation.state",
+                "--fps",
+                fps,
+            ]
+        };
+
+        let cmd = ToLeRobotCommand::try_parse_from(args("10")).unwrap();
+        assert_eq!(cmd.fps, 10);
+
+        assert!(ToLeRobotCommand::try_parse_from(args("0")).is_err());
+    }
+}
@@ -0,0 +1,142 @@
+use std::io::BufWriter;
+
+use anyhow::Context as _;
+
+use re_chunk::{Chunk, TimelineName};
+use re_data_loader::mcap::export::McapExporter;
+use re_log_types::{LogMsg, StoreId};
+
+use crate::commands::read_rrd_streams_from_file_or_stdin;
+
+// ---
+
+#[derive(Debug, Clone, clap::Parser)]
+pub struct ToMcapCommand {
+    /// Paths to read from. Reads from standard input if none are specified.
+    path_to_input_rrds: Vec<String>,
+
+    /// Path to write to.
+    #[arg(short = 'o', long = "output", value_name = "dst.mcap")]
+    path_to_output_mcap: String,
+
+    /// The timeline whose values are used as the timestamps of the MCAP messages, in nanoseconds.
+    ///
+    /// Data that isn't on this timeline is skipped, static data is written at time zero.
+    #[clap(long = "timeline", default_value = "log_time")]
+    timeline: String,
+
+    /// The recording to export. Defaults to the first recording in the input.
+    #[clap(long = "recording-id")]
+    recording_id: Option<String>,
+
+    /// If set, will try to proceed even in the face of IO and/or decoding errors in the input data.
+    #[clap(long = "continue-on-error", default_value_t = false)]
+    continue_on_error: bool,
+}
+
+impl ToMcapCommand {
+    pub fn run(&self) -> anyhow::Result<()> {
+        let Self {
+            path_to_input_rrds,
+            path_to_output_mcap,
+            timeline,
+            recording_id,
+            continue_on_error,
+        } = self;
+
+        let now = std::time::Instant::now();
+        re_log::info!(srcs = ?path_to_input_rrds, dst = ?path_to_output_mcap, "conversion to MCAP started");
+
+        let file = std::fs::File::create(path_to_output_mcap)
+            .with_context(|| format!("{path_to_output_mcap:?}"))?;
+        let mut exporter = McapExporter::new(BufWriter::new(file), TimelineName::new(timeline))
+            .context("couldn't init MCAP writer")?;
+
+        let (rx, rx_size_bytes) = read_rrd_streams_from_file_or_stdin(path_to_input_rrds);
+
+        let mut exported_store_id: Option<StoreId> = None;
+        let mut num_chunks = 0;
+
+        for (_source, res) in rx {
+            let mut is_success = true;
+
+            match res {
+                Ok(msg) => {
+                    let LogMsg::ArrowMsg(store_id, arrow_msg) = &msg else {
+                        continue;
+                    };
+
+                    if !store_id.is_recording()
+                        || recording_id.as_ref().is_some_and(|recording_id| {
+                            store_id.recording_id().as_str() != recording_id
+                        })
+                    {
+                        continue;
+                    }
+
+                    let exported_store_id =
+                        exported_store_id.get_or_insert_with(|| store_id.clone());
+                    if exported_store_id != store_id {
+                        re_log::warn_once!(
+                            "Input contains more than one recording, only exporting {:?} (use --recording-id to pick another one)",
+                            exported_store_id.recording_id()
+                        );
+                        continue;
+                    }
+
+                    if let Err(err) = Chunk::from_arrow_msg(arrow_msg)
+                        .context("couldn't decode chunk")
+                        .and_then(|chunk| {
+                            exporter
+                                .add_chunk(&chunk)
+                                .context("couldn't write chunk to MCAP")
+                        })
+                    {
+                        re_log::error!(err = re_error::format(err));
+                        is_success = false;
+                    } else {
+                        num_chunks += 1;
+                    }
+                }
+
+                Err(err) => {
+                    re_log::error!(err = re_error::format(err));
+                    is_success = false;
+                }
+            }
+
+            if !*continue_on_error && !is_success {
+                anyhow::bail!(
+                    "one or more IO and/or decoding failures in the input stream (check logs)"
+                )
+            }
+        }
+
+        if exported_store_id.is_none() {
+            re_log::warn!("no matching recording found in the input");
+        }
+
+        exporter
+            .finish()
+            .context("couldn't finish MCAP")?
+            .into_inner()
+            .context("couldn't flush MCAP")?;
+
+        let mcap_size_bytes = std::fs::metadata(path_to_output_mcap).map_or(0, |m| m.len());
+        let rrds_in_size = rx_size_bytes.recv().ok();
+        re_log::info!(
+            num_chunks,
+            time = ?now.elapsed(),
+            srcs = ?path_to_input_rrds,
+            srcs_size_bytes = %rrds_in_size.map_or_else(
+                || "<unknown>".to_owned(),
+                |size| re_format::format_bytes(size as _),
+            ),
+            dst = ?path_to_output_mcap,
+            dst_size_bytes = %re_format::format_bytes(mcap_size_bytes as _),
+            "conversion to MCAP finished"
+        );
+
+        Ok(())
+    }
+}
@@ -5,6 +5,7 @@ use crossbeam::channel;
 use itertools::Itertools as _;
 
 use re_chunk::external::crossbeam;
+use re_log_encoding::footer::RrdFooterQuery;
 use re_log_types::LogMsg;
 
 // ---
@@ -42,6 +43,24 @@ pub fn read_rrd_streams_from_file_or_stdin(
 ) -> (
     channel::Receiver<(InputSource, anyhow::Result<LogMsg>)>,
     channel::Receiver<u64>,
+) {
+    read_rrd_streams_from_file_or_stdin_with_query(paths, RrdFooterQuery::default())
+}
+
+/// Same as [`read_rrd_streams_from_file_or_stdin`], but only yields the chunks that match the
+/// given `query`.
+///
+/// Files that were written with a footer are read lazily: only the matching chunks are ever read
+/// from disk. All other inputs (including standard input) are decoded in full, and filtered on
+/// the fly.
+///
+/// Messages that aren't chunks (e.g. `SetStoreInfo`) are always yielded.
+pub fn read_rrd_streams_from_file_or_stdin_with_query(
+    paths: &[String],
+    query: RrdFooterQuery,
+) -> (
+    channel::Receiver<(InputSource, anyhow::Result<LogMsg>)>,
+    channel::Receiver<u64>,
 ) {
     let path_to_input_rrds = paths
         .iter()
@@ -74,6 +93,11 @@ pub fn read_rrd_streams_from_file_or_stdin(
                 };
 
                 for res in &mut decoder {
+                    if let Ok(msg) = &res
+                        && !query.matches_msg(msg)
+                    {
+                        continue;
+                    }
                     let res = res.context("couldn't decode message from stdin -- skipping");
                     tx.send((InputSource::Stdin, res)).ok();
                 }
@@ -83,7 +107,7 @@ pub fn read_rrd_streams_from_file_or_stdin(
                 // file(s)
 
                 for rrd_path in path_to_input_rrds {
-                    let rrd_file = match std::fs::File::open(&rrd_path)
+                    let mut rrd_file = match std::fs::File::open(&rrd_path)
                         .with_context(|| format!("couldn't open {rrd_path:?} -- skipping"))
                     {
                         Ok(file) => file,
@@ -94,6 +118,27 @@ pub fn read_rrd_streams_from_file_or_stdin(
                         }
                     };
 
+                    if !query.is_everything() {
+                        match read_rrd_file_with_footer(&rrd_path, &mut rrd_file, &query, &tx) {
+                            Ok(Some(read_bytes)) => {
+                                size_bytes += read_bytes;
+                                continue;
+                            }
+
+                            Ok(None) => {
+                                re_log::debug!(
+                                    "{rrd_path:?} has no footer, falling back to a full scan"
+                                );
+                            }
+
+                            Err(err) => {
+                                tx.send((InputSource::File(rrd_path.clone()), Err(err)))
+                                    .ok();
+                                continue;
+                            }
+                        }
+                    }
+
                     let mut decoder = match re_log_encoding::decoder::Decoder::new(rrd_file)
                         .with_context(|| format!("couldn't decode {rrd_path:?} -- skipping"))
                     {
@@ -106,6 +151,11 @@ pub fn read_rrd_streams_from_file_or_stdin(
                     };
 
                     for res in &mut decoder {
+                        if let Ok(msg) = &res
+                            && !query.matches_msg(msg)
+                        {
+                            continue;
+                        }
                         let res = res.context("decode rrd message").with_context(|| {
                             format!("couldn't decode message {rrd_path:?} -- skipping")
                         });
@@ -122,6 +172,44 @@ pub fn read_rrd_streams_from_file_or_stdin(
     (rx, rx_size_bytes)
 }
 
+/// Reads the chunks of `rrd_file` that match `query`, using its footer.
+///
+/// Returns the number of bytes read, or `None` if the file doesn't have a footer, in which case
+/// nothing was sent and the file is rewound to its start.
+fn read_rrd_file_with_footer(
+    rrd_path: &PathBuf,
+    rrd_file: &mut std::fs::File,
+    query: &RrdFooterQuery,
+    tx: &channel::Sender<(InputSource, anyhow::Result<LogMsg>)>,
+) -> anyhow::Result<Option<u64>> {
+    use std::io::Seek as _;
+
+    let decoder = re_log_encoding::decoder::seekable::SeekableDecoder::new(&*rrd_file)
+        .with_context(|| format!("couldn't decode {rrd_path:?} -- skipping"))?;
+
+    let Some(mut decoder) = decoder else {
+        rrd_file
+            .rewind()
+            .with_context(|| format!("couldn't rewind {rrd_path:?} -- skipping"))?;
+        return Ok(None);
+    };
+
+    let read_bytes = decoder
+        .footer()
+        .select(query)
+        .map(|entry| entry.byte_span.len)
+        .sum();
+
+    for res in decoder.read_query(query) {
+        let res = res
+            .context("decode rrd message")
+            .with_context(|| format!("couldn't decode message {rrd_path:?} -- skipping"));
+        tx.send((InputSource::File(rrd_path.clone()), res)).ok();
+    }
+
+    Ok(Some(read_bytes))
+}
+
 /// Asynchronously decodes potentially multiplexed RRD streams from the given `paths`, or standard
 /// input if none are specified.
 ///
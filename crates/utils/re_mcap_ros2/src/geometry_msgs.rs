@@ -4,6 +4,8 @@
 //!
 use serde::{Deserialize, Serialize};
 
+use crate::std_msgs::Header;
+
 /// This represents a vector in free space.
 ///
 /// This is semantically different than a point.
@@ -39,3 +41,26 @@ pub struct Pose {
     pub position: Point,
     pub orientation: Quaternion,
 }
+
+/// This represents the transform between two coordinate frames in free space.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Transform {
+    pub translation: Vector3,
+    pub rotation: Quaternion,
+}
+
+/// This expresses a transform from coordinate frame `header.frame_id`
+/// to the coordinate frame `child_frame_id` at the time of `header.stamp`.
+///
+/// This message is mostly used by the [tf2](https://docs.ros.org/en/rolling/p/tf2/) package.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TransformStamped {
+    /// The frame id in the header is used as the reference frame of this transform.
+    pub header: Header,
+
+    /// The frame id of the child frame to which this transform points.
+    pub child_frame_id: String,
+
+    /// Translation and rotation in 3-dimensions of `child_frame_id` from `header.frame_id`.
+    pub transform: Transform,
+}
@@ -8,8 +8,12 @@
 //!
 //! - [`builtin_interfaces`]: Time and duration representations.
 //! - [`std_msgs`]: Common standard messages like [`std_msgs::Header`] and [`std_msgs::ColorRGBA`].
+//! - [`tf2_msgs`]: The messages making up the transform tree, i.e. [`tf2_msgs::TFMessage`].
+//! - [`visualization_msgs`]: `RViz` markers, i.e. [`visualization_msgs::Marker`] and [`visualization_msgs::MarkerArray`].
 
 pub mod builtin_interfaces;
 pub mod geometry_msgs;
 pub mod sensor_msgs;
 pub mod std_msgs;
+pub mod tf2_msgs;
+pub mod visualization_msgs;
@@ -0,0 +1,136 @@
+//! Definitions for the ROS2 `visualization_msgs` package.
+//!
+//! Based on definitions taken from <https://github.com/ros2/common_interfaces/tree/rolling/visualization_msgs>
+
+use std::borrow::Cow;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    builtin_interfaces::Duration,
+    geometry_msgs::{Point, Pose, Vector3},
+    sensor_msgs::CompressedImage,
+    std_msgs::{ColorRGBA, Header},
+};
+
+/// Texture coordinates of a single vertex of a [`Marker::TRIANGLE_LIST`] marker.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct UVCoordinate {
+    pub u: f32,
+    pub v: f32,
+}
+
+/// A mesh file, embedded in a [`Marker::MESH_RESOURCE`] marker.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MeshFile<'a> {
+    /// The filename is used for both debug purposes and to provide a file extension
+    /// for whatever parser is used.
+    pub filename: String,
+
+    #[serde(with = "serde_bytes")]
+    #[serde(borrow)]
+    /// The contents of the file.
+    pub data: Cow<'a, [u8]>,
+}
+
+/// A geometric primitive to be displayed in `RViz`.
+///
+/// See <http://www.ros.org/wiki/rviz/DisplayTypes/Marker> for more information.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Marker<'a> {
+    /// Header for timestamp and frame id.
+    pub header: Header,
+
+    /// Namespace in which to place the object.
+    ///
+    /// Used in conjunction with `id` to create a unique name for the object.
+    pub ns: String,
+
+    /// Object ID used in conjunction with the namespace for manipulating and deleting the object later.
+    pub id: i32,
+
+    /// Type of object, e.g. [`Self::CUBE`].
+    pub r#type: i32,
+
+    /// Action to take, e.g. [`Self::ADD`].
+    pub action: i32,
+
+    /// Pose of the object with respect to the frame / time specified in the header.
+    pub pose: Pose,
+
+    /// Scale of the object; `[1, 1, 1]` means default (usually 1 meter square).
+    pub scale: Vector3,
+
+    /// Color, in the range `[0, 1]`.
+    pub color: ColorRGBA,
+
+    /// How long the object should last before being automatically deleted. 0 means forever.
+    pub lifetime: Duration,
+
+    /// If this marker should be frame-locked, i.e. retransformed into its frame every timestep.
+    pub frame_locked: bool,
+
+    /// Only used if the type specified has some use for them (eg. `POINTS`, `LINE_STRIP`, …).
+    pub points: Vec<Point>,
+
+    /// Only used if the type specified has some use for them (eg. `POINTS`, `LINE_STRIP`, …).
+    ///
+    /// The number of colors provided must either be 0 or equal to the number of points provided.
+    /// NOTE: alpha is not yet used.
+    pub colors: Vec<ColorRGBA>,
+
+    /// Texture resource is a special URI that can either reference a texture file in
+    /// a format acceptable to (resource retriever) or an embedded texture via a string
+    /// matching the format: `"embedded://texture_name"`.
+    pub texture_resource: String,
+
+    /// An image to be loaded into the rendering engine as the texture for this marker.
+    ///
+    /// This will be used iff `texture_resource` is set to embedded.
+    #[serde(borrow)]
+    pub texture: CompressedImage<'a>,
+
+    /// Location of each vertex within the texture; in the range: `[0.0-1.0]`.
+    pub uv_coordinates: Vec<UVCoordinate>,
+
+    /// Only used for text markers.
+    pub text: String,
+
+    /// Only used for `MESH_RESOURCE` markers.
+    ///
+    /// Similar to texture resource, mesh resource uses resource retriever to load a mesh.
+    /// Optionally, a mesh file can be sent in-message via the `mesh_file` field.
+    pub mesh_resource: String,
+
+    #[serde(borrow)]
+    pub mesh_file: MeshFile<'a>,
+
+    pub mesh_use_embedded_materials: bool,
+}
+
+impl Marker<'_> {
+    pub const ARROW: i32 = 0;
+    pub const CUBE: i32 = 1;
+    pub const SPHERE: i32 = 2;
+    pub const CYLINDER: i32 = 3;
+    pub const LINE_STRIP: i32 = 4;
+    pub const LINE_LIST: i32 = 5;
+    pub const CUBE_LIST: i32 = 6;
+    pub const SPHERE_LIST: i32 = 7;
+    pub const POINTS: i32 = 8;
+    pub const TEXT_VIEW_FACING: i32 = 9;
+    pub const MESH_RESOURCE: i32 = 10;
+    pub const TRIANGLE_LIST: i32 = 11;
+
+    pub const ADD: i32 = 0;
+    pub const MODIFY: i32 = 0;
+    pub const DELETE: i32 = 2;
+    pub const DELETEALL: i32 = 3;
+}
+
+/// A batch of [`Marker`]s, all of which are processed in order.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MarkerArray<'a> {
+    #[serde(borrow)]
+    pub markers: Vec<Marker<'a>>,
+}
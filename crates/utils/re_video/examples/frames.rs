@@ -68,7 +68,9 @@ fn main() {
     let start = Instant::now();
     let video_buffers = std::iter::once(video_blob.as_ref()).collect();
     for (sample_idx, sample) in video.samples.iter_indexed() {
-        let chunk = sample.get(&video_buffers, sample_idx).unwrap();
+        let chunk = sample
+            .get(video.sample_layout, &video_buffers, sample_idx)
+            .unwrap();
         decoder.submit_chunk(chunk).expect("Failed to submit chunk");
     }
 
@@ -0,0 +1,281 @@
+//! General AV1 utilities.
+//!
+//! See the [AV1 bitstream specification](https://aomediacodec.github.io/av1-spec/av1-spec.pdf).
+
+use h264_reader::rbsp::{BitRead as _, BitReader, BitReaderError};
+
+use crate::{ChromaSubsamplingModes, VideoEncodingDetails};
+
+/// AV1 OBU (Open Bitstream Unit) types, see section 6.2.2 of the AV1 spec.
+pub mod obu_type {
+    pub const SEQUENCE_HEADER: u8 = 1;
+    pub const FRAME_HEADER: u8 = 3;
+    pub const FRAME: u8 = 6;
+}
+
+/// A single OBU of an AV1 bitstream.
+pub struct Obu<'a> {
+    pub obu_type: u8,
+
+    /// The payload of the OBU, without its header and size field.
+    pub payload: &'a [u8],
+}
+
+/// Iterates over the OBUs of a sample in the low overhead bitstream format (section 5.2).
+///
+/// Stops at the first malformed OBU.
+pub fn obus(mut data: &[u8]) -> impl Iterator<Item = Obu<'_>> {
+    std::iter::from_fn(move || {
+        let (&header, rest) = data.split_first()?;
+        let obu_type = (header >> 3) & 0x0F;
+        let has_extension = header & 0b100 != 0;
+        let has_size_field = header & 0b10 != 0;
+
+        let rest = if has_extension { rest.get(1..)? } else { rest };
+
+        let (payload, rest) = if has_size_field {
+            let (size, rest) = read_leb128(rest)?;
+            let size = usize::try_from(size).ok()?;
+            if rest.len() < size {
+                return None;
+            }
+            rest.split_at(size)
+        } else {
+            // Without a size field, the OBU spans the rest of the data.
+            (rest, &[][..])
+        };
+
+        data = rest;
+        Some(Obu { obu_type, payload })
+    })
+}
+
+/// Reads an unsigned LEB128 value (section 4.10.5), returning it and the remaining data.
+fn read_leb128(data: &[u8]) -> Option<(u64, &[u8])> {
+    let mut value = 0u64;
+    for (i, byte) in data.iter().take(8).enumerate() {
+        value |= u64::from(byte & 0x7F) << (i * 7);
+        if byte & 0x80 == 0 {
+            return Some((value, &data[i + 1..]));
+        }
+    }
+    None
+}
+
+/// The parts of an AV1 sequence header (section 5.5) we're interested in.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SequenceHeader {
+    pub seq_profile: u8,
+    pub seq_level_idx: u8,
+    pub seq_tier: u8,
+    pub reduced_still_picture_header: bool,
+    pub max_frame_width: u32,
+    pub max_frame_height: u32,
+    pub bit_depth: u8,
+    pub chroma_subsampling: Option<ChromaSubsamplingModes>,
+}
+
+impl SequenceHeader {
+    /// Parses a sequence header OBU payload.
+    pub fn parse(payload: &[u8]) -> Result<Self, BitReaderError> {
+        let mut r = BitReader::new(payload);
+
+        let seq_profile: u8 = r.read(3, "seq_profile")?;
+        r.skip(1, "still_picture")?;
+        let reduced_still_picture_header = r.read_bool("reduced_still_picture_header")?;
+
+        let mut seq_level_idx = 0;
+        let mut seq_tier = 0;
+
+        if reduced_still_picture_header {
+            seq_level_idx = r.read(5, "seq_level_idx")?;
+        } else {
+            let mut buffer_delay_length = 0;
+            let timing_info_present = r.read_bool("timing_info_present_flag")?;
+            let mut decoder_model_info_present = false;
+            if timing_info_present {
+                r.skip(64, "num_units_in_display_tick, time_scale")?;
+                if r.read_bool("equal_picture_interval")? {
+                    read_uvlc(&mut r)?;
+                }
+                decoder_model_info_present = r.read_bool("decoder_model_info_present_flag")?;
+                if decoder_model_info_present {
+                    buffer_delay_length = r.read::<u32>(5, "buffer_delay_length_minus_1")? + 1;
+                    r.skip(32 + 5 + 5, "decoder_model_info")?;
+                }
+            }
+            let initial_display_delay_present = r.read_bool("initial_display_delay_present")?;
+
+            let operating_points_cnt: u8 = r.read::<u8>(5, "operating_points_cnt_minus_1")? + 1;
+            for i in 0..operating_points_cnt {
+                r.skip(12, "operating_point_idc")?;
+                let level: u8 = r.read(5, "seq_level_idx")?;
+                let tier = if level > 7 { r.read(1, "seq_tier")? } else { 0 };
+                if decoder_model_info_present && r.read_bool("decoder_model_present_for_this_op")? {
+                    r.skip(2 * buffer_delay_length + 1, "operating_parameters_info")?;
+                }
+                if initial_display_delay_present
+                    && r.read_bool("initial_display_delay_present_for_this_op")?
+                {
+                    r.skip(4, "initial_display_delay_minus_1")?;
+                }
+
+                // The first operating point is the one for the full stream.
+                if i == 0 {
+                    seq_level_idx = level;
+                    seq_tier = tier;
+                }
+            }
+        }
+
+        let frame_width_bits: u32 = r.read::<u32>(4, "frame_width_bits_minus_1")? + 1;
+        let frame_height_bits: u32 = r.read::<u32>(4, "frame_height_bits_minus_1")? + 1;
+        let max_frame_width = r.read::<u32>(frame_width_bits, "max_frame_width_minus_1")? + 1;
+        let max_frame_height = r.read::<u32>(frame_height_bits, "max_frame_height_minus_1")? + 1;
+
+        if !reduced_still_picture_header && r.read_bool("frame_id_numbers_present_flag")? {
+            r.skip(4 + 3, "frame_id_length")?;
+        }
+        r.skip(
+            3,
+            "use_128x128_superblock, enable_filter_intra, enable_intra_edge",
+        )?;
+
+        if !reduced_still_picture_header {
+            r.skip(
+                4,
+                "enable_interintra_compound, enable_masked_compound, enable_warped_motion, enable_dual_filter",
+            )?;
+            let enable_order_hint = r.read_bool("enable_order_hint")?;
+            if enable_order_hint {
+                r.skip(2, "enable_jnt_comp, enable_ref_frame_mvs")?;
+            }
+            let seq_force_screen_content_tools =
+                if r.read_bool("seq_choose_screen_content_tools")? {
+                    2 // SELECT_SCREEN_CONTENT_TOOLS
+                } else {
+                    r.read::<u8>(1, "seq_force_screen_content_tools")?
+                };
+            if seq_force_screen_content_tools > 0 && !r.read_bool("seq_choose_integer_mv")? {
+                r.skip(1, "seq_force_integer_mv")?;
+            }
+            if enable_order_hint {
+                r.skip(3, "order_hint_bits_minus_1")?;
+            }
+        }
+
+        r.skip(3, "enable_superres, enable_cdef, enable_restoration")?;
+
+        // color_config()
+        let high_bitdepth = r.read_bool("high_bitdepth")?;
+        let bit_depth = if seq_profile == 2 && high_bitdepth {
+            if r.read_bool("twelve_bit")? { 12 } else { 10 }
+        } else if high_bitdepth {
+            10
+        } else {
+            8
+        };
+        let mono_chrome = seq_profile != 1 && r.read_bool("mono_chrome")?;
+
+        let chroma_subsampling = if mono_chrome {
+            Some(ChromaSubsamplingModes::Monochrome)
+        } else {
+            let mut color_primaries = 2; // CP_UNSPECIFIED
+            let mut transfer_characteristics = 2; // TC_UNSPECIFIED
+            let mut matrix_coefficients = 2; // MC_UNSPECIFIED
+            if r.read_bool("color_description_present_flag")? {
+                color_primaries = r.read::<u8>(8, "color_primaries")?;
+                transfer_characteristics = r.read::<u8>(8, "transfer_characteristics")?;
+                matrix_coefficients = r.read::<u8>(8, "matrix_coefficients")?;
+            }
+
+            // sRGB (CP_BT_709, TC_SRGB, MC_IDENTITY) is always 4:4:4.
+            if color_primaries == 1 && transfer_characteristics == 13 && matrix_coefficients == 0 {
+                Some(ChromaSubsamplingModes::Yuv444)
+            } else {
+                r.skip(1, "color_range")?;
+                match seq_profile {
+                    0 => Some(ChromaSubsamplingModes::Yuv420),
+                    1 => Some(ChromaSubsamplingModes::Yuv444),
+                    _ if bit_depth == 12 => {
+                        let subsampling_x = r.read_bool("subsampling_x")?;
+                        let subsampling_y = subsampling_x && r.read_bool("subsampling_y")?;
+                        match (subsampling_x, subsampling_y) {
+                            (true, true) => Some(ChromaSubsamplingModes::Yuv420),
+                            (true, false) => Some(ChromaSubsamplingModes::Yuv422),
+                            (false, _) => Some(ChromaSubsamplingModes::Yuv444),
+                        }
+                    }
+                    _ => Some(ChromaSubsamplingModes::Yuv422),
+                }
+            }
+        };
+
+        Ok(Self {
+            seq_profile,
+            seq_level_idx,
+            seq_tier,
+            reduced_still_picture_header,
+            max_frame_width,
+            max_frame_height,
+            bit_depth,
+            chroma_subsampling,
+        })
+    }
+
+    /// The [`VideoEncodingDetails`] described by this sequence header.
+    pub fn encoding_details(&self) -> VideoEncodingDetails {
+        let Self {
+            seq_profile,
+            seq_level_idx,
+            seq_tier,
+            reduced_still_picture_header: _,
+            max_frame_width,
+            max_frame_height,
+            bit_depth,
+            chroma_subsampling,
+        } = self;
+
+        // See https://aomediacodec.github.io/av1-isobmff/#codecsparam
+        let tier = if *seq_tier == 0 { 'M' } else { 'H' };
+        let codec_string = format!("av01.{seq_profile}.{seq_level_idx:02}{tier}.{bit_depth:02}");
+
+        VideoEncodingDetails {
+            codec_string,
+            coded_dimensions: [*max_frame_width as _, *max_frame_height as _],
+            bit_depth: Some(*bit_depth),
+            chroma_subsampling: *chroma_subsampling,
+            stsd: None,
+        }
+    }
+}
+
+/// Whether a frame header (or frame) OBU payload starts a key frame (section 5.9.2).
+pub fn is_key_frame(
+    frame_header: &[u8],
+    sequence_header: &SequenceHeader,
+) -> Result<bool, BitReaderError> {
+    if sequence_header.reduced_still_picture_header {
+        return Ok(true);
+    }
+
+    let mut r = BitReader::new(frame_header);
+    if r.read_bool("show_existing_frame")? {
+        return Ok(false);
+    }
+    let frame_type: u8 = r.read(2, "frame_type")?;
+    Ok(frame_type == 0) // KEY_FRAME
+}
+
+/// Reads a variable length unsigned value (section 4.10.3).
+fn read_uvlc(r: &mut BitReader<&[u8]>) -> Result<u32, BitReaderError> {
+    let mut leading_zeros = 0;
+    while !r.read_bool("uvlc")? {
+        leading_zeros += 1;
+    }
+    match leading_zeros {
+        0 => Ok(0),
+        32.. => Ok(u32::MAX),
+        _ => r.read(leading_zeros, "uvlc"),
+    }
+}
@@ -19,10 +19,10 @@ use h264_reader::nal::UnitType;
 use parking_lot::Mutex;
 
 use crate::{
-    PixelFormat, Time, VideoDataDescription, VideoEncodingDetails,
+    PixelFormat, Time, VideoCodec, VideoDataDescription, VideoEncodingDetails,
     decode::{
         AsyncDecoder, Chunk, DecodeError, Frame, FrameContent, FrameInfo, OutputCallback,
-        ffmpeg_h264::{FFMPEG_MINIMUM_VERSION_MAJOR, FFMPEG_MINIMUM_VERSION_MINOR, FFmpegVersion},
+        ffmpeg_cli::{FFMPEG_MINIMUM_VERSION_MAJOR, FFMPEG_MINIMUM_VERSION_MINOR, FFmpegVersion},
     },
     demux::ChromaSubsamplingModes,
 };
@@ -86,6 +86,9 @@ pub enum Error {
 
     #[error("Failed to parse sequence parameter set.")]
     SpsParsing,
+
+    #[error("FFmpeg decoding is not supported for {0:?}")]
+    UnsupportedCodec(VideoCodec),
 }
 
 impl Error {
@@ -207,12 +210,21 @@ struct FFmpegProcessAndListener {
 impl FFmpegProcessAndListener {
     fn new(
         debug_name: &str,
+        codec: VideoCodec,
         on_output: Arc<OutputCallback>,
         encoding_details: &Option<VideoEncodingDetails>,
         ffmpeg_path: Option<&std::path::Path>,
     ) -> Result<Self, Error> {
         re_tracing::profile_function!();
 
+        let input_format = match codec {
+            VideoCodec::H264 => "h264",
+            VideoCodec::H265 => "hevc",
+            VideoCodec::AV1 | VideoCodec::VP8 | VideoCodec::VP9 => {
+                return Err(Error::UnsupportedCodec(codec));
+            }
+        };
+
         // TODO(andreas): should get SPS also without AVCC from ongoing stream.
 
         let (pixel_format, ffmpeg_pix_fmt) = if let Some(chroma_subsampling) =
@@ -271,10 +283,10 @@ impl FFmpegProcessAndListener {
                 "0",
             ])
             // Keep in mind that all arguments that are about the input, need to go before!
-            .format("h264") // TODO(andreas): should we check ahead of time whether this is available?
+            .format(input_format) // TODO(andreas): should we check ahead of time whether this is available?
             //.fps_mode("0")
             .input("-") // stdin is our input!
-            // h264 bitstreams doesn't have timestamp information. Whatever ffmpeg tries to make up about timing & framerates is wrong!
+            // h264/h265 bitstreams doesn't have timestamp information. Whatever ffmpeg tries to make up about timing & framerates is wrong!
             // If we don't tell it to just pass the frames through, variable framerate (VFR) video will just not play at all.
             .fps_mode("passthrough")
             .pix_fmt(ffmpeg_pix_fmt)
@@ -326,7 +338,9 @@ impl FFmpegProcessAndListener {
             })
             .expect("Failed to spawn ffmpeg listener thread");
 
-        let avcc = encoding_details.as_ref().and_then(|e| e.avcc()).cloned();
+        let annexb_conversion = encoding_details
+            .as_ref()
+            .and_then(AnnexBConversion::from_encoding_details);
 
         // Writes video data to the ffmpeg process:
         let write_thread = std::thread::Builder::new()
@@ -343,7 +357,8 @@ impl FFmpegProcessAndListener {
                         &mut ffmpeg_stdin,
                         &frame_data_rx,
                         on_output.as_ref(),
-                        &avcc,
+                        codec,
+                        annexb_conversion.as_ref(),
                     );
                 }
             })
@@ -464,7 +479,8 @@ fn write_ffmpeg_input(
     ffmpeg_stdin: &mut dyn std::io::Write,
     frame_data_rx: &Receiver<FFmpegFrameData>,
     on_output: &Mutex<Option<Arc<OutputCallback>>>,
-    avcc: &Option<re_mp4::Avc1Box>,
+    codec: VideoCodec,
+    annexb_conversion: Option<&AnnexBConversion>,
 ) {
     let mut state = NaluStreamState::default();
 
@@ -474,13 +490,23 @@ fn write_ffmpeg_input(
             FFmpegFrameData::Quit => {
                 // Try to flush out the last frames from ffmpeg with an EndSequence/EndStream NAL units.
                 // Unfortunatelt this doesn't help, at least not for https://github.com/rerun-io/rerun/issues/8073
-                let end_nals: Vec<u8> = [
-                    ANNEXB_NAL_START_CODE,
-                    &[UnitType::EndOfSeq.id()],
-                    ANNEXB_NAL_START_CODE,
-                    &[UnitType::EndOfStream.id()],
-                ]
-                .concat();
+                let end_nals: Vec<u8> = if codec == VideoCodec::H265 {
+                    [
+                        ANNEXB_NAL_START_CODE,
+                        &H265_END_OF_SEQUENCE_NAL,
+                        ANNEXB_NAL_START_CODE,
+                        &H265_END_OF_BITSTREAM_NAL,
+                    ]
+                    .concat()
+                } else {
+                    [
+                        ANNEXB_NAL_START_CODE,
+                        &[UnitType::EndOfSeq.id()],
+                        ANNEXB_NAL_START_CODE,
+                        &[UnitType::EndOfStream.id()],
+                    ]
+                    .concat()
+                };
                 write_bytes(ffmpeg_stdin, &end_nals).ok();
 
                 // NOTE(emilk): I've also tried writing `NalUnitType::AccessUnitDelimiter` here, but to no avail.
@@ -491,10 +517,15 @@ fn write_ffmpeg_input(
             }
         };
 
-        let write_result = if let Some(avcc) = avcc {
-            write_avc_chunk_to_nalu_stream(avcc, ffmpeg_stdin, &chunk, &mut state)
+        let write_result = if let Some(annexb_conversion) = annexb_conversion {
+            write_length_prefixed_chunk_to_nalu_stream(
+                annexb_conversion,
+                ffmpeg_stdin,
+                &chunk,
+                &mut state,
+            )
         } else {
-            // If there was no AVCC box, we assume the data is already in Annex B format.
+            // If there was no AVCC/HVCC box, we assume the data is already in Annex B format.
             // TODO(andreas): feels a bit implicit, would be nice to make this more clear.
             write_bytes(ffmpeg_stdin, &chunk.data)
         };
@@ -828,18 +859,20 @@ fn read_ffmpeg_output(
     Some(())
 }
 
-/// Decode H.264 video via ffmpeg over CLI
-pub struct FFmpegCliH264Decoder {
+/// Decode H.264 or H.265 video via ffmpeg over CLI
+pub struct FFmpegCliDecoder {
     debug_name: String,
+    codec: VideoCodec,
     // Restarted on reset
     ffmpeg: FFmpegProcessAndListener,
     on_output: Arc<OutputCallback>,
     ffmpeg_path: Option<std::path::PathBuf>,
 }
 
-impl FFmpegCliH264Decoder {
+impl FFmpegCliDecoder {
     pub fn new(
         debug_name: String,
+        codec: VideoCodec,
         encoding_details: &Option<VideoEncodingDetails>,
         on_output: impl Fn(crate::decode::Result<Frame>) + Send + Sync + 'static,
         ffmpeg_path: Option<std::path::PathBuf>,
@@ -858,6 +891,7 @@ impl FFmpegCliH264Decoder {
         let on_output = Arc::new(on_output);
         let ffmpeg = FFmpegProcessAndListener::new(
             &debug_name,
+            codec,
             on_output.clone(),
             encoding_details,
             ffmpeg_path.as_deref(),
@@ -865,6 +899,7 @@ impl FFmpegCliH264Decoder {
 
         Ok(Self {
             debug_name,
+            codec,
             ffmpeg,
             on_output,
             ffmpeg_path,
@@ -900,7 +935,7 @@ fn check_ffmpeg_version(
     }
 }
 
-impl AsyncDecoder for FFmpegCliH264Decoder {
+impl AsyncDecoder for FFmpegCliDecoder {
     fn submit_chunk(&mut self, chunk: Chunk) -> crate::decode::Result<()> {
         re_tracing::profile_function!();
 
@@ -927,6 +962,7 @@ impl AsyncDecoder for FFmpegCliH264Decoder {
         re_log::trace!("Resetting ffmpeg decoder {}", self.debug_name);
         self.ffmpeg = FFmpegProcessAndListener::new(
             &self.debug_name,
+            self.codec,
             self.on_output.clone(),
             &video_descr.encoding_details,
             self.ffmpeg_path.as_deref(),
@@ -948,32 +984,76 @@ struct NaluStreamState {
     previous_frame_was_idr: bool,
 }
 
+/// H.265 end of sequence NAL unit (type 36, see table 7-1 of the H.265 spec).
+const H265_END_OF_SEQUENCE_NAL: [u8; 2] = [36 << 1, 1];
+
+/// H.265 end of bitstream NAL unit (type 37, see table 7-1 of the H.265 spec).
+const H265_END_OF_BITSTREAM_NAL: [u8; 2] = [37 << 1, 1];
+
+/// Everything needed to convert length prefixed samples (as found in MP4) to an Annex B stream.
+struct AnnexBConversion {
+    /// Size of the length prefix of every NAL unit in a sample.
+    length_prefix_size: usize,
+
+    /// Parameter set NAL units (VPS, SPS & PPS) that have to precede every IDR frame.
+    parameter_sets: Vec<Vec<u8>>,
+}
+
+impl AnnexBConversion {
+    /// Returns `None` if the samples are not length prefixed, i.e. there's no AVCC/HVCC box.
+    fn from_encoding_details(encoding_details: &VideoEncodingDetails) -> Option<Self> {
+        match &encoding_details.stsd.as_ref()?.contents {
+            re_mp4::StsdBoxContent::Avc1(avc1) => {
+                let avcc = &avc1.avcc;
+                Some(Self {
+                    length_prefix_size: avcc.length_size_minus_one as usize + 1,
+                    parameter_sets: avcc
+                        .sequence_parameter_sets
+                        .iter()
+                        .chain(&avcc.picture_parameter_sets)
+                        .map(|parameter_set| parameter_set.bytes.clone())
+                        .collect(),
+                })
+            }
+
+            re_mp4::StsdBoxContent::Hvc1(hevc) | re_mp4::StsdBoxContent::Hev1(hevc) => {
+                let hvcc = &hevc.hvcc;
+                Some(Self {
+                    length_prefix_size: hvcc.length_size_minus_one as usize + 1,
+                    parameter_sets: hvcc
+                        .arrays
+                        .iter()
+                        .flat_map(|array| &array.nalus)
+                        .map(|nalu| nalu.data.clone())
+                        .collect(),
+                })
+            }
+
+            _ => None,
+        }
+    }
+}
+
 fn write_bytes(stream: &mut dyn std::io::Write, data: &[u8]) -> Result<(), Error> {
     stream.write_all(data).map_err(Error::FailedToWriteToFfmpeg)
 }
 
-fn write_avc_chunk_to_nalu_stream(
-    avcc: &re_mp4::Avc1Box,
+fn write_length_prefixed_chunk_to_nalu_stream(
+    annexb_conversion: &AnnexBConversion,
     nalu_stream: &mut dyn std::io::Write,
     chunk: &Chunk,
     state: &mut NaluStreamState,
 ) -> Result<(), Error> {
     re_tracing::profile_function!();
 
-    let avcc = &avcc.avcc;
-
     // We expect the stream of chunks to not have any SPS (Sequence Parameter Set) & PPS (Picture Parameter Set)
     // just as it is the case with MP4 data.
     // In order to have every IDR frame be able to be fully re-entrant, we need to prepend the SPS & PPS NAL units.
     // Otherwise the decoder is not able to get the necessary information about how the video stream is encoded.
     if chunk.is_sync && !state.previous_frame_was_idr {
-        for sps in &avcc.sequence_parameter_sets {
-            write_bytes(nalu_stream, ANNEXB_NAL_START_CODE)?;
-            write_bytes(nalu_stream, &sps.bytes)?;
-        }
-        for pps in &avcc.picture_parameter_sets {
+        for parameter_set in &annexb_conversion.parameter_sets {
             write_bytes(nalu_stream, ANNEXB_NAL_START_CODE)?;
-            write_bytes(nalu_stream, &pps.bytes)?;
+            write_bytes(nalu_stream, parameter_set)?;
         }
         state.previous_frame_was_idr = true;
     } else {
@@ -989,7 +1069,7 @@ fn write_avc_chunk_to_nalu_stream(
 
         // Each NAL unit in mp4 is prefixed with a length prefix.
         // In Annex B this doesn't exist.
-        let length_prefix_size = avcc.length_size_minus_one as usize + 1;
+        let length_prefix_size = annexb_conversion.length_prefix_size;
 
         if sample_end < buffer_offset + length_prefix_size {
             return Err(Error::BadVideoData(
@@ -4,7 +4,7 @@ use h264_reader::{
     push::NalInterest,
 };
 
-use crate::{VideoCodec, VideoEncodingDetails, h264::encoding_details_from_h264_sps};
+use crate::{VideoCodec, VideoEncodingDetails, av1, h264::encoding_details_from_h264_sps, h265};
 
 /// Failure reason for [`detect_gop_start`].
 #[derive(thiserror::Error, Debug)]
@@ -62,13 +62,11 @@ pub fn detect_gop_start(
     sample_data: &[u8],
     codec: VideoCodec,
 ) -> Result<GopStartDetection, DetectGopStartError> {
-    #[expect(clippy::match_same_arms)]
     match codec {
         VideoCodec::H264 => detect_h264_annexb_gop(sample_data),
-        VideoCodec::H265 => Err(DetectGopStartError::UnsupportedCodec(codec)),
-        VideoCodec::AV1 => Err(DetectGopStartError::UnsupportedCodec(codec)),
-        VideoCodec::VP8 => Err(DetectGopStartError::UnsupportedCodec(codec)),
-        VideoCodec::VP9 => Err(DetectGopStartError::UnsupportedCodec(codec)),
+        VideoCodec::H265 => detect_h265_annexb_gop(sample_data),
+        VideoCodec::AV1 => detect_av1_gop(sample_data),
+        VideoCodec::VP8 | VideoCodec::VP9 => Err(DetectGopStartError::UnsupportedCodec(codec)),
     }
 }
 
@@ -140,9 +138,106 @@ fn detect_h264_annexb_gop(
     }
 
     let handler = reader.into_nal_handler();
-    match handler.coding_details_from_sps {
+    gop_start_detection(handler.coding_details_from_sps, handler.idr_frame_found)
+}
+
+/// Try to determine whether a frame chunk is the start of a GOP in an h265 Annex B encoded stream.
+///
+/// Unlike for h264, any IRAP (intra random access point) picture starts a GOP, not just IDR pictures.
+fn detect_h265_annexb_gop(sample_data: &[u8]) -> Result<GopStartDetection, DetectGopStartError> {
+    let mut coding_details_from_sps = None;
+    let mut irap_frame_found = false;
+
+    for nal in annexb_nal_units(sample_data) {
+        let Some(nal_unit_type) = h265::nal_unit_type(nal) else {
+            continue;
+        };
+
+        if nal_unit_type == h265::nal_unit_type::SPS {
+            // Note that if we find several SPS, we'll always use the latest one.
+            coding_details_from_sps = Some(
+                h265::encoding_details_from_h265_sps(nal)
+                    .map_err(|sps_error| format!("Failed reading SPS: {sps_error:?}")), // h264_reader errors don't implement display
+            );
+        } else if h265::is_irap(nal_unit_type) {
+            irap_frame_found = true;
+        }
+    }
+
+    gop_start_detection(coding_details_from_sps, irap_frame_found)
+}
+
+/// Try to determine whether a frame chunk is the start of a GOP in an AV1 stream of OBUs.
+///
+/// A GOP starts with a key frame, which we can only make sense of with a sequence header in the same sample.
+fn detect_av1_gop(sample_data: &[u8]) -> Result<GopStartDetection, DetectGopStartError> {
+    let mut sequence_header = None;
+    let mut key_frame_found = false;
+
+    for obu in av1::obus(sample_data) {
+        match obu.obu_type {
+            av1::obu_type::SEQUENCE_HEADER => {
+                sequence_header = Some(
+                    av1::SequenceHeader::parse(obu.payload)
+                        .map_err(|err| format!("Failed reading sequence header: {err:?}")),
+                );
+            }
+
+            av1::obu_type::FRAME_HEADER | av1::obu_type::FRAME => {
+                if let Some(Ok(sequence_header)) = &sequence_header {
+                    key_frame_found |=
+                        av1::is_key_frame(obu.payload, sequence_header).unwrap_or(false);
+                }
+
+                // Only the first frame of a sample decides whether it is a key frame.
+                break;
+            }
+
+            _ => {}
+        }
+    }
+
+    gop_start_detection(
+        sequence_header.map(|header| header.map(|header| header.encoding_details())),
+        key_frame_found,
+    )
+}
+
+/// Iterates over the NAL units of an Annex B byte stream, without their start codes.
+pub(crate) fn annexb_nal_units(mut data: &[u8]) -> impl Iterator<Item = &[u8]> {
+    fn find_start_code(data: &[u8]) -> Option<usize> {
+        data.windows(3).position(|window| window == [0, 0, 1])
+    }
+
+    std::iter::from_fn(move || {
+        loop {
+            let start = find_start_code(data)? + 3;
+            data = &data[start..];
+
+            let end = find_start_code(data).unwrap_or(data.len());
+            let mut nal = &data[..end];
+            data = &data[end..];
+
+            // Zeros before the next start code are either trailing zero bytes, or part of a 4 byte start code.
+            while let [rest @ .., 0] = nal {
+                nal = rest;
+            }
+
+            if !nal.is_empty() {
+                return Some(nal);
+            }
+        }
+    })
+}
+
+/// Combines the encoding details found in a sample with whether it contains a keyframe.
+fn gop_start_detection(
+    coding_details: Option<Result<VideoEncodingDetails, String>>,
+    keyframe_found: bool,
+) -> Result<GopStartDetection, DetectGopStartError> {
+    match coding_details {
         Some(Ok(decoding_details)) => {
-            if handler.idr_frame_found {
+            if keyframe_found {
                 Ok(GopStartDetection::StartOfGop(decoding_details))
             } else {
                 // In theory it could happen that we got an SPS but no IDR frame.
@@ -161,7 +256,9 @@ fn detect_h264_annexb_gop(
 
 #[cfg(test)]
 mod test {
-    use super::{GopStartDetection, detect_h264_annexb_gop};
+    use super::{
+        GopStartDetection, detect_av1_gop, detect_h264_annexb_gop, detect_h265_annexb_gop,
+    };
     use crate::{ChromaSubsamplingModes, DetectGopStartError, VideoEncodingDetails};
 
     #[test]
@@ -218,4 +315,166 @@ mod test {
         let result = detect_h264_annexb_gop(sample_data);
         assert_eq!(result, Ok(GopStartDetection::NotStartOfGop));
     }
+
+    #[test]
+    fn test_detect_h265_annexb_gop() {
+        // SPS of a 1920x1080 Main profile stream, coded as 1920x1088 with a conformance window.
+        let sps = [
+            0x00, 0x00, 0x00, 0x01, 0x42, 0x01, 0x01, 0x01, 0x60, 0x00, 0x00, 0x03, 0x00, 0x90,
+            0x00, 0x00, 0x03, 0x00, 0x00, 0x03, 0x00, 0x78, 0xA0, 0x03, 0xC0, 0x80, 0x11, 0x07,
+            0xCB, 0xC0,
+        ];
+        // Start of an IDR_W_RADL slice, followed by a TRAIL_R slice.
+        let idr_slice = [0x00, 0x00, 0x00, 0x01, 0x26, 0x01, 0xAF, 0x09, 0x40, 0xF3];
+        let trail_slice = [0x00, 0x00, 0x01, 0x02, 0x01, 0xD0, 0x09, 0x7E, 0x10];
+
+        let result = detect_h265_annexb_gop(&[sps.as_slice(), &idr_slice].concat());
+        assert_eq!(
+            result,
+            Ok(GopStartDetection::StartOfGop(VideoEncodingDetails {
+                codec_string: "hev1.1.6.L120.90".to_owned(),
+                coded_dimensions: [1920, 1080],
+                bit_depth: Some(8),
+                chroma_subsampling: Some(ChromaSubsamplingModes::Yuv420),
+                stsd: None,
+            }))
+        );
+
+        // Truncated SPS.
+        let result = detect_h265_annexb_gop(&[&sps[..12], &idr_slice].concat());
+        assert!(matches!(
+            result,
+            Err(DetectGopStartError::FailedToExtractEncodingDetails(_))
+        ));
+
+        // Not an IRAP picture.
+        let result = detect_h265_annexb_gop(&[sps.as_slice(), &trail_slice].concat());
+        assert_eq!(result, Ok(GopStartDetection::NotStartOfGop));
+
+        // IRAP picture without SPS.
+        let result = detect_h265_annexb_gop(&idr_slice);
+        assert_eq!(result, Ok(GopStartDetection::NotStartOfGop));
+    }
+
+    /// Builds an H.265 SPS NAL unit (with start code) of a Main profile stream, with the given
+    /// values for the conformance window offsets and `bit_depth_luma_minus8`.
+    fn h265_sps(conf_win_offset: u32, bit_depth_luma_minus8: u32) -> Vec<u8> {
+        fn write(bits: &mut Vec<bool>, value: u64, num_bits: u32) {
+            bits.extend((0..num_bits).rev().map(|bit| (value >> bit) & 1 == 1));
+        }
+        fn write_ue(bits: &mut Vec<bool>, value: u32) {
+            let value = u64::from(value) + 1;
+            let num_bits = u64::BITS - value.leading_zeros();
+            write(bits, 0, num_bits - 1);
+            write(bits, value, num_bits);
+        }
+
+        let mut bits = Vec::new();
+        write(&mut bits, 0x4201, 16); // NAL header
+        write(&mut bits, 0, 4); // vps id
+        write(&mut bits, 0, 3); // max sub layers
+        write(&mut bits, 1, 1); // temporal id nesting
+        write(&mut bits, 0, 2); // profile space
+        write(&mut bits, 0, 1); // tier
+        write(&mut bits, 1, 5); // profile
+        write(&mut bits, 0x6000_0000, 32); // profile compatibility flags
+        write(&mut bits, 0x9000_0000_0000, 48); // constraint flags
+        write(&mut bits, 120, 8); // level
+        write_ue(&mut bits, 0); // sps id
+        write_ue(&mut bits, 1); // chroma format
+        write_ue(&mut bits, 1920);
+        write_ue(&mut bits, 1088);
+        write(&mut bits, 1, 1); // conformance window
+        for _ in 0..4 {
+            write_ue(&mut bits, conf_win_offset);
+        }
+        write_ue(&mut bits, bit_depth_luma_minus8);
+        write(&mut bits, 1, 1); // stop bit
+
+        let bytes: Vec<u8> = bits
+            .chunks(8)
+            .map(|byte| {
+                byte.iter()
+                    .chain(std::iter::repeat(&false))
+                    .take(8)
+                    .fold(0, |acc, bit| (acc << 1) | u8::from(*bit))
+            })
+            .collect();
+
+        // Insert emulation prevention bytes.
+        let mut nal = vec![0x00, 0x00, 0x00, 0x01];
+        let mut num_zeros = 0;
+        for byte in bytes {
+            if num_zeros >= 2 && byte <= 3 {
+                nal.push(0x03);
+                num_zeros = 0;
+            }
+            num_zeros = if byte == 0 { num_zeros + 1 } else { 0 };
+            nal.push(byte);
+        }
+        nal
+    }
+
+    #[test]
+    fn test_detect_h265_annexb_gop_malformed_sps() {
+        let idr_slice = [0x00, 0x00, 0x00, 0x01, 0x26, 0x01, 0xAF, 0x09, 0x40, 0xF3];
+
+        let details =
+            |sps: Vec<u8>| match detect_h265_annexb_gop(&[sps, idr_slice.to_vec()].concat()) {
+                Ok(GopStartDetection::StartOfGop(details)) => details,
+                result => panic!("unexpected result: {result:?}"),
+            };
+
+        let valid = details(h265_sps(4, 2));
+        assert_eq!(valid.coded_dimensions, [1904, 1072]);
+        assert_eq!(valid.bit_depth, Some(10));
+
+        // Out of range values must neither overflow nor be trusted.
+        let malformed = details(h265_sps(u32::MAX - 1, u32::MAX - 1));
+        assert_eq!(malformed.coded_dimensions, [0, 0]);
+        assert_eq!(malformed.bit_depth, None);
+
+        let malformed = details(h265_sps(0, 9));
+        assert_eq!(malformed.bit_depth, None);
+    }
+
+    #[test]
+    fn test_detect_av1_gop() {
+        let temporal_delimiter = [0x12, 0x00];
+        // Sequence header of a 1920x1080 8-bit 4:2:0 main profile stream.
+        let sequence_header = [
+            0x0A, 0x0B, 0x00, 0x00, 0x00, 0x42, 0xAB, 0xBF, 0xC3, 0x73, 0x09, 0xE6, 0x01,
+        ];
+        // Start of a frame OBU with `frame_type` set to key frame and inter frame respectively.
+        let key_frame = [0x32, 0x04, 0x10, 0x00, 0xB7, 0x42];
+        let inter_frame = [0x32, 0x04, 0x30, 0x01, 0x9C, 0x27];
+
+        let result =
+            detect_av1_gop(&[temporal_delimiter.as_slice(), &sequence_header, &key_frame].concat());
+        assert_eq!(
+            result,
+            Ok(GopStartDetection::StartOfGop(VideoEncodingDetails {
+                codec_string: "av01.0.08M.08".to_owned(),
+                coded_dimensions: [1920, 1080],
+                bit_depth: Some(8),
+                chroma_subsampling: Some(ChromaSubsamplingModes::Yuv420),
+                stsd: None,
+            }))
+        );
+
+        // Not a key frame.
+        let result = detect_av1_gop(
+            &[
+                temporal_delimiter.as_slice(),
+                &sequence_header,
+                &inter_frame,
+            ]
+            .concat(),
+        );
+        assert_eq!(result, Ok(GopStartDetection::NotStartOfGop));
+
+        // Key frame without sequence header.
+        let result = detect_av1_gop(&[temporal_delimiter.as_slice(), &key_frame].concat());
+        assert_eq!(result, Ok(GopStartDetection::NotStartOfGop));
+    }
 }
@@ -83,10 +83,10 @@ mod async_decoder_wrapper;
 mod av1;
 
 #[cfg(with_ffmpeg)]
-mod ffmpeg_h264;
+mod ffmpeg_cli;
 
 #[cfg(with_ffmpeg)]
-pub use ffmpeg_h264::{
+pub use ffmpeg_cli::{
     Error as FFmpegError, FFmpegVersion, FFmpegVersionParseError, ffmpeg_download_url,
 };
 
@@ -95,6 +95,7 @@ mod webcodecs;
 
 mod gop_detection;
 
+pub(crate) use gop_detection::annexb_nal_units;
 pub use gop_detection::{DetectGopStartError, GopStartDetection, detect_gop_start};
 
 use crate::{SampleIndex, Time, VideoDataDescription};
@@ -249,10 +250,11 @@ pub fn new_decoder(
         }
 
         #[cfg(with_ffmpeg)]
-        crate::VideoCodec::H264 => {
-            re_log::trace!("Decoding H.264…");
-            Ok(Box::new(ffmpeg_h264::FFmpegCliH264Decoder::new(
+        crate::VideoCodec::H264 | crate::VideoCodec::H265 => {
+            re_log::trace!("Decoding {:?}…", video.codec);
+            Ok(Box::new(ffmpeg_cli::FFmpegCliDecoder::new(
                 debug_name.to_owned(),
+                video.codec,
                 &video.encoding_details,
                 on_output,
                 decode_settings.ffmpeg_path.clone(),
@@ -0,0 +1,832 @@
+//! Matroska & `WebM` demuxing.
+//!
+//! See the [Matroska specification](https://www.matroska.org/technical/elements.html).
+//! `WebM` is a subset of Matroska, so both are handled by the same demuxer.
+
+use std::{collections::BTreeMap, ops::Range};
+
+use h264_reader::rbsp::{BitRead as _, BitReader};
+use re_mp4::ReadBox as _;
+use re_span::Span;
+
+use super::{
+    SampleLayout, SampleMetadata, SamplesStatistics, VideoDataDescription, VideoLoadError,
+    finalize_demuxed_samples, mp4::codec_details_from_stds,
+};
+
+use crate::{
+    ChromaSubsamplingModes, GopStartDetection, StableIndexDeque, Time, Timescale, TrackId,
+    TrackKind, VideoCodec, VideoEncodingDetails, detect_gop_start,
+};
+
+/// IDs of the elements we're interested in.
+mod element_id {
+    pub const EBML: u32 = 0x1A45_DFA3;
+    pub const DOC_TYPE: u32 = 0x4282;
+
+    pub const SEGMENT: u32 = 0x1853_8067;
+
+    pub const INFO: u32 = 0x1549_A966;
+    pub const TIMESTAMP_SCALE: u32 = 0x2A_D7B1;
+    pub const DURATION: u32 = 0x4489;
+
+    pub const TRACKS: u32 = 0x1654_AE6B;
+    pub const TRACK_ENTRY: u32 = 0xAE;
+    pub const TRACK_NUMBER: u32 = 0xD7;
+    pub const TRACK_TYPE: u32 = 0x83;
+    pub const CODEC_ID: u32 = 0x86;
+    pub const CODEC_PRIVATE: u32 = 0x63A2;
+    pub const DEFAULT_DURATION: u32 = 0x23_E383;
+    pub const VIDEO: u32 = 0xE0;
+    pub const PIXEL_WIDTH: u32 = 0xB0;
+    pub const PIXEL_HEIGHT: u32 = 0xBA;
+
+    pub const CLUSTER: u32 = 0x1F43_B675;
+    pub const CLUSTER_TIMESTAMP: u32 = 0xE7;
+    pub const CLUSTER_POSITION: u32 = 0xA7;
+    pub const CLUSTER_PREV_SIZE: u32 = 0xAB;
+    pub const SIMPLE_BLOCK: u32 = 0xA3;
+    pub const BLOCK_GROUP: u32 = 0xA0;
+    pub const BLOCK: u32 = 0xA1;
+    pub const BLOCK_DURATION: u32 = 0x9B;
+    pub const REFERENCE_BLOCK: u32 = 0xFB;
+
+    pub const CRC_32: u32 = 0xBF;
+    pub const VOID: u32 = 0xEC;
+}
+
+/// Track types as stored in the `TrackType` element.
+mod track_type {
+    pub const VIDEO: u64 = 1;
+    pub const AUDIO: u64 = 2;
+    pub const SUBTITLE: u64 = 17;
+}
+
+/// Default `TimestampScale`, i.e. timestamps are in milliseconds.
+const DEFAULT_TIMESTAMP_SCALE: u64 = 1_000_000;
+
+impl VideoDataDescription {
+    pub fn load_mkv(data: &[u8], debug_name: &str) -> Result<Self, VideoLoadError> {
+        re_tracing::profile_function!();
+
+        let mut reader = ElementReader::new(data, 0..data.len());
+
+        let ebml_header = reader
+            .next_element()?
+            .filter(|element| element.id == element_id::EBML)
+            .ok_or_else(|| err("Missing EBML header"))?;
+        let doc_type = ElementReader::new(data, ebml_header.data.clone())
+            .find(element_id::DOC_TYPE)?
+            .map(|element| read_string(&data[element.data]))
+            .unwrap_or_else(|| "matroska".to_owned());
+        if doc_type != "matroska" && doc_type != "webm" {
+            return Err(err(format!("Unsupported document type {doc_type:?}")));
+        }
+
+        let segment = reader
+            .find(element_id::SEGMENT)?
+            .ok_or_else(|| err("Missing segment"))?;
+
+        let mut timestamp_scale = DEFAULT_TIMESTAMP_SCALE;
+        let mut segment_duration = None;
+        let mut tracks = Vec::new();
+        let mut blocks = Vec::new();
+
+        let mut segment_reader = ElementReader::new(data, segment.data);
+        while let Some(element) = segment_reader.next_element()? {
+            match element.id {
+                element_id::INFO => {
+                    let mut info_reader = ElementReader::new(data, element.data);
+                    while let Some(element) = info_reader.next_element()? {
+                        match element.id {
+                            element_id::TIMESTAMP_SCALE => {
+                                timestamp_scale = read_uint(&data[element.data]).max(1);
+                            }
+                            element_id::DURATION => {
+                                segment_duration = read_float(&data[element.data]);
+                            }
+                            _ => {}
+                        }
+                    }
+                }
+
+                element_id::TRACKS => {
+                    let mut tracks_reader = ElementReader::new(data, element.data);
+                    while let Some(element) = tracks_reader.next_element()? {
+                        if element.id == element_id::TRACK_ENTRY {
+                            tracks.push(TrackEntry::parse(data, element.data)?);
+                        }
+                    }
+                }
+
+                element_id::CLUSTER => {
+                    let cluster_end = parse_cluster(data, &element, &mut blocks)?;
+                    // Clusters of unknown size end where the next non-cluster element starts.
+                    segment_reader.pos = cluster_end;
+                }
+
+                _ => {}
+            }
+        }
+
+        let mp4_tracks = tracks
+            .iter()
+            .filter_map(|track| {
+                let kind = match track.track_type {
+                    track_type::VIDEO => Some(TrackKind::Video),
+                    track_type::AUDIO => Some(TrackKind::Audio),
+                    track_type::SUBTITLE => Some(TrackKind::Subtitle),
+                    _ => None,
+                };
+                Some((TrackId::try_from(track.number).ok()?, kind))
+            })
+            .collect::<BTreeMap<_, _>>();
+
+        let track = tracks
+            .into_iter()
+            .find(|track| track.track_type == track_type::VIDEO)
+            .ok_or(VideoLoadError::NoVideoTrack)?;
+
+        let codec = match track.codec_id.as_str() {
+            "V_MPEG4/ISO/AVC" => VideoCodec::H264,
+            "V_MPEGH/ISO/HEVC" => VideoCodec::H265,
+            "V_AV1" => VideoCodec::AV1,
+            "V_VP8" => VideoCodec::VP8,
+            "V_VP9" => VideoCodec::VP9,
+            codec_id => return Err(VideoLoadError::UnsupportedCodecId(codec_id.to_owned())),
+        };
+
+        // Express time in `TimestampScale` units if possible, otherwise fall back to nanoseconds.
+        let (timescale, ticks_to_time) = if 1_000_000_000 % timestamp_scale == 0 {
+            (Timescale::new(1_000_000_000 / timestamp_scale), 1)
+        } else {
+            (Timescale::NANOSECOND, timestamp_scale as i64)
+        };
+        let default_duration = track
+            .default_duration_nanos
+            .map(|nanos| Time::from_nanos(i64::try_from(nanos).unwrap_or(i64::MAX), timescale));
+
+        let mut samples = StableIndexDeque::<SampleMetadata>::with_capacity(blocks.len());
+        for block in blocks
+            .iter()
+            .filter(|block| block.track_number == track.number)
+        {
+            let byte_span = Span {
+                start: u32::try_from(block.data.start)
+                    .map_err(|_err| err("Files larger than 4 GiB are not supported"))?,
+                len: (block.data.end - block.data.start) as u32,
+            };
+            let presentation_timestamp = Time(block.timestamp * ticks_to_time);
+
+            samples.push_back(SampleMetadata {
+                is_sync: block.is_keyframe,
+                frame_nr: 0, // filled in by `finalize_demuxed_samples`
+                decode_timestamp: presentation_timestamp, // fixed up below
+                presentation_timestamp,
+                duration: block
+                    .duration
+                    .map(|duration| Time(duration * ticks_to_time))
+                    .or(default_duration),
+                buffer_index: 0,
+                byte_span,
+            });
+        }
+
+        assign_decode_timestamps(&mut samples);
+        let gops = finalize_demuxed_samples(&mut samples);
+
+        let encoding_details = encoding_details(codec, &track, &samples, data)?;
+
+        let duration = segment_duration
+            .map(|duration| Time((duration * ticks_to_time as f64).round() as i64))
+            .or_else(|| {
+                samples
+                    .iter()
+                    .map(|sample| {
+                        sample.presentation_timestamp + sample.duration.unwrap_or_default()
+                    })
+                    .max()
+            });
+
+        let video_data_description = Self {
+            codec,
+            encoding_details,
+            timescale: Some(timescale),
+            duration,
+            samples_statistics: SamplesStatistics::new(&samples),
+            last_time_updated_samples: None,
+            gops,
+            samples,
+            mp4_tracks,
+            sample_layout: SampleLayout::Contiguous,
+        };
+
+        if cfg!(debug_assertions)
+            && let Err(err) = video_data_description.sanity_check()
+        {
+            panic!("VideoDataDescription sanity check for {debug_name} failed: {err}");
+        }
+
+        Ok(video_data_description)
+    }
+}
+
+fn err(msg: impl Into<String>) -> VideoLoadError {
+    VideoLoadError::ParseMatroska(msg.into())
+}
+
+/// The parts of a `TrackEntry` we're interested in.
+struct TrackEntry {
+    number: u64,
+    track_type: u64,
+    codec_id: String,
+    codec_private: Option<Vec<u8>>,
+    default_duration_nanos: Option<u64>,
+    pixel_width: u16,
+    pixel_height: u16,
+}
+
+impl TrackEntry {
+    fn parse(data: &[u8], range: Range<usize>) -> Result<Self, VideoLoadError> {
+        let mut track = Self {
+            number: 0,
+            track_type: 0,
+            codec_id: String::new(),
+            codec_private: None,
+            default_duration_nanos: None,
+            pixel_width: 0,
+            pixel_height: 0,
+        };
+
+        let mut reader = ElementReader::new(data, range);
+        while let Some(element) = reader.next_element()? {
+            let bytes = &data[element.data.clone()];
+            match element.id {
+                element_id::TRACK_NUMBER => track.number = read_uint(bytes),
+                element_id::TRACK_TYPE => track.track_type = read_uint(bytes),
+                element_id::CODEC_ID => track.codec_id = read_string(bytes),
+                element_id::CODEC_PRIVATE => track.codec_private = Some(bytes.to_vec()),
+                element_id::DEFAULT_DURATION => {
+                    track.default_duration_nanos = Some(read_uint(bytes));
+                }
+                element_id::VIDEO => {
+                    let mut video_reader = ElementReader::new(data, element.data);
+                    while let Some(element) = video_reader.next_element()? {
+                        let value = read_uint(&data[element.data]);
+                        match element.id {
+                            element_id::PIXEL_WIDTH => track.pixel_width = value as u16,
+                            element_id::PIXEL_HEIGHT => track.pixel_height = value as u16,
+                            _ => {}
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Ok(track)
+    }
+}
+
+/// A single (unlaced) block of any track.
+struct Block {
+    track_number: u64,
+
+    /// Absolute timestamp in `TimestampScale` units.
+    timestamp: i64,
+
+    /// Duration in `TimestampScale` units, if specified.
+    duration: Option<i64>,
+
+    is_keyframe: bool,
+
+    /// Range of the frame data in the file.
+    data: Range<usize>,
+}
+
+/// Parses all blocks of a cluster and returns the offset at which the cluster ends.
+fn parse_cluster(
+    data: &[u8],
+    cluster: &Element,
+    blocks: &mut Vec<Block>,
+) -> Result<usize, VideoLoadError> {
+    let mut cluster_timestamp = 0;
+    let mut reader = ElementReader::new(data, cluster.data.clone());
+
+    loop {
+        let element_start = reader.pos;
+        let Some(element) = reader.next_element()? else {
+            return Ok(cluster.data.end);
+        };
+
+        match element.id {
+            element_id::CLUSTER_TIMESTAMP => {
+                cluster_timestamp = read_uint(&data[element.data]) as i64;
+            }
+
+            element_id::SIMPLE_BLOCK => {
+                blocks.extend(parse_block(data, element.data, cluster_timestamp, None));
+            }
+
+            element_id::BLOCK_GROUP => {
+                let mut block = None;
+                let mut duration = None;
+                let mut has_references = false;
+
+                let mut group_reader = ElementReader::new(data, element.data);
+                while let Some(element) = group_reader.next_element()? {
+                    match element.id {
+                        element_id::BLOCK => block = Some(element.data),
+                        element_id::BLOCK_DURATION => {
+                            duration = Some(read_uint(&data[element.data]) as i64);
+                        }
+                        element_id::REFERENCE_BLOCK => has_references = true,
+                        _ => {}
+                    }
+                }
+
+                if let Some(block) = block {
+                    blocks.extend(
+                        parse_block(data, block, cluster_timestamp, Some(!has_references)).map(
+                            |block| Block {
+                                duration: duration.or(block.duration),
+                                ..block
+                            },
+                        ),
+                    );
+                }
+            }
+
+            element_id::CLUSTER_POSITION
+            | element_id::CLUSTER_PREV_SIZE
+            | element_id::CRC_32
+            | element_id::VOID => {}
+
+            _ => {
+                if cluster.unknown_size {
+                    // Any other element marks the end of a cluster with unknown size.
+                    return Ok(element_start);
+                }
+            }
+        }
+    }
+}
+
+/// Parses a `SimpleBlock` or `Block`.
+///
+/// For `Block`s, whether it is a keyframe is determined by its `BlockGroup`.
+fn parse_block(
+    data: &[u8],
+    range: Range<usize>,
+    cluster_timestamp: i64,
+    is_keyframe: Option<bool>,
+) -> Option<Block> {
+    let bytes = &data[range.clone()];
+    let (track_number, track_number_len) = read_vint(bytes)?;
+    let header = bytes.get(track_number_len..track_number_len + 3)?;
+    let relative_timestamp = i16::from_be_bytes([header[0], header[1]]);
+    let flags = header[2];
+
+    let lacing = (flags >> 1) & 0b11;
+    if lacing != 0 {
+        // Video is practically never laced, since lacing doesn't allow for per-frame timestamps.
+        re_log::warn_once!("Laced Matroska blocks are not supported, skipping them.");
+        return None;
+    }
+
+    Some(Block {
+        track_number,
+        timestamp: cluster_timestamp + relative_timestamp as i64,
+        duration: None,
+        is_keyframe: is_keyframe.unwrap_or(flags & 0x80 != 0),
+        data: (range.start + track_number_len + 3)..range.end,
+    })
+}
+
+/// Matroska only stores presentation timestamps, with blocks in decode order.
+///
+/// We derive decode timestamps by handing out the sorted presentation timestamps in decode order,
+/// shifted such that no sample is decoded after it is presented.
+/// Without frame reordering, this results in decode timestamps equal to presentation timestamps.
+fn assign_decode_timestamps(samples: &mut StableIndexDeque<SampleMetadata>) {
+    let mut sorted_pts = samples
+        .iter()
+        .map(|sample| sample.presentation_timestamp)
+        .collect::<Vec<_>>();
+    sorted_pts.sort();
+
+    let shift = samples
+        .iter()
+        .zip(&sorted_pts)
+        .map(|(sample, dts)| *dts - sample.presentation_timestamp)
+        .max()
+        .unwrap_or_default();
+
+    for (sample, dts) in samples.iter_mut().zip(sorted_pts) {
+        sample.decode_timestamp = dts - shift;
+    }
+}
+
+fn encoding_details(
+    codec: VideoCodec,
+    track: &TrackEntry,
+    samples: &StableIndexDeque<SampleMetadata>,
+    data: &[u8],
+) -> Result<Option<VideoEncodingDetails>, VideoLoadError> {
+    let coded_dimensions = [track.pixel_width, track.pixel_height];
+    let first_sync_sample = samples
+        .iter()
+        .find(|sample| sample.is_sync)
+        .map(|sample| &data[sample.byte_span.range_usize()]);
+
+    // Matroska stores the same codec configuration records as mp4 does.
+    let sample_entry = match codec {
+        VideoCodec::H264 => Some((b"avc1", b"avcC")),
+        VideoCodec::H265 => Some((b"hvc1", b"hvcC")),
+        VideoCodec::AV1 => Some((b"av01", b"av1C")),
+        VideoCodec::VP8 | VideoCodec::VP9 => None,
+    };
+    if let (Some((sample_entry_type, config_type)), Some(codec_private)) =
+        (sample_entry, &track.codec_private)
+    {
+        let stsd = stsd_box(
+            sample_entry_type,
+            config_type,
+            coded_dimensions,
+            codec_private,
+        )?;
+        return codec_details_from_stds(coded_dimensions, stsd).map(Some);
+    }
+
+    match codec {
+        VideoCodec::H264 | VideoCodec::H265 => Err(VideoLoadError::InvalidConfigFormat),
+
+        // The configuration is optional for AV1, but every key frame starts with a sequence header.
+        VideoCodec::AV1 => {
+            Ok(
+                first_sync_sample.and_then(|sample| match detect_gop_start(sample, codec) {
+                    Ok(GopStartDetection::StartOfGop(details)) => Some(details),
+                    Ok(GopStartDetection::NotStartOfGop) | Err(_) => None,
+                }),
+            )
+        }
+
+        VideoCodec::VP8 => Ok(Some(VideoEncodingDetails {
+            codec_string: "vp8".to_owned(),
+            coded_dimensions,
+            bit_depth: Some(8),
+            chroma_subsampling: Some(ChromaSubsamplingModes::Yuv420),
+            stsd: None,
+        })),
+
+        VideoCodec::VP9 => {
+            Ok(first_sync_sample.and_then(|sample| vp9_encoding_details(sample, coded_dimensions)))
+        }
+    }
+}
+
+/// Builds an mp4 stsd box with a single sample entry holding the given codec configuration record.
+fn stsd_box(
+    sample_entry_type: &[u8; 4],
+    config_type: &[u8; 4],
+    [width, height]: [u16; 2],
+    config: &[u8],
+) -> Result<re_mp4::StsdBox, VideoLoadError> {
+    let mut sample_entry = Vec::with_capacity(86 + config.len());
+    sample_entry.extend_from_slice(&[0; 6]); // reserved
+    sample_entry.extend_from_slice(&1u16.to_be_bytes()); // data_reference_index
+    sample_entry.extend_from_slice(&[0; 16]); // pre-defined & reserved
+    sample_entry.extend_from_slice(&width.to_be_bytes());
+    sample_entry.extend_from_slice(&height.to_be_bytes());
+    sample_entry.extend_from_slice(&0x0048_0000u32.to_be_bytes()); // 72 dpi
+    sample_entry.extend_from_slice(&0x0048_0000u32.to_be_bytes()); // 72 dpi
+    sample_entry.extend_from_slice(&[0; 4]); // reserved
+    sample_entry.extend_from_slice(&1u16.to_be_bytes()); // frame_count
+    sample_entry.extend_from_slice(&[0; 32]); // compressorname
+    sample_entry.extend_from_slice(&0x0018u16.to_be_bytes()); // depth
+    sample_entry.extend_from_slice(&(-1i16).to_be_bytes()); // pre-defined
+    write_box(&mut sample_entry, config_type, config);
+
+    let mut stsd_contents = Vec::with_capacity(8 + 8 + sample_entry.len());
+    stsd_contents.extend_from_slice(&[0; 4]); // version & flags
+    stsd_contents.extend_from_slice(&1u32.to_be_bytes()); // entry_count
+    write_box(&mut stsd_contents, sample_entry_type, &sample_entry);
+
+    let mut stsd = Vec::with_capacity(8 + stsd_contents.len());
+    write_box(&mut stsd, b"stsd", &stsd_contents);
+
+    let mut reader = std::io::Cursor::new(stsd.as_slice());
+    let header = re_mp4::BoxHeader::read(&mut reader)?;
+    Ok(re_mp4::StsdBox::read_box(&mut reader, header.size)?)
+}
+
+fn write_box(out: &mut Vec<u8>, box_type: &[u8; 4], contents: &[u8]) {
+    out.extend_from_slice(&(8 + contents.len() as u32).to_be_bytes());
+    out.extend_from_slice(box_type);
+    out.extend_from_slice(contents);
+}
+
+/// Determines encoding details from the uncompressed header of a VP9 key frame.
+///
+/// See section 6.2 of the VP9 bitstream specification.
+fn vp9_encoding_details(
+    key_frame: &[u8],
+    coded_dimensions: [u16; 2],
+) -> Option<VideoEncodingDetails> {
+    let mut r = BitReader::new(key_frame);
+
+    if r.read::<u8>(2, "frame_marker").ok()? != 2 {
+        return None;
+    }
+    let profile_low_bit: u8 = r.read(1, "profile_low_bit").ok()?;
+    let profile_high_bit: u8 = r.read(1, "profile_high_bit").ok()?;
+    let profile = (profile_high_bit << 1) | profile_low_bit;
+    if profile == 3 {
+        r.skip(1, "reserved_zero").ok()?;
+    }
+    if r.read_bool("show_existing_frame").ok()? || r.read_bool("frame_type").ok()? {
+        // Not a key frame.
+        return None;
+    }
+    r.skip(2, "show_frame, error_resilient_mode").ok()?;
+    if r.read::<u32>(24, "frame_sync_code").ok()? != 0x49_8342 {
+        return None;
+    }
+
+    // color_config()
+    let bit_depth = if profile >= 2 {
+        if r.read_bool("ten_or_twelve_bit").ok()? {
+            12
+        } else {
+            10
+        }
+    } else {
+        8
+    };
+    let color_space: u8 = r.read(3, "color_space").ok()?;
+    let chroma_subsampling = if color_space == 7 {
+        // CS_RGB
+        ChromaSubsamplingModes::Yuv444
+    } else {
+        r.skip(1, "color_range").ok()?;
+        if profile == 1 || profile == 3 {
+            match (
+                r.read_bool("subsampling_x").ok()?,
+                r.read_bool("subsampling_y").ok()?,
+            ) {
+                (true, true) => ChromaSubsamplingModes::Yuv420,
+                (true, false) => ChromaSubsamplingModes::Yuv422,
+                (false, _) => ChromaSubsamplingModes::Yuv444,
+            }
+        } else {
+            ChromaSubsamplingModes::Yuv420
+        }
+    };
+
+    // The level isn't part of the bitstream.
+    // We claim the highest one, so decoders don't reject the stream.
+    let level = 62;
+
+    Some(VideoEncodingDetails {
+        codec_string: format!("vp09.{profile:02}.{level}.{bit_depth:02}"),
+        coded_dimensions,
+        bit_depth: Some(bit_depth),
+        chroma_subsampling: Some(chroma_subsampling),
+        stsd: None,
+    })
+}
+
+// ----------------------------------------------------------------------------
+// EBML
+
+struct Element {
+    id: u32,
+
+    /// Range of the element's data in the file.
+    data: Range<usize>,
+
+    /// Whether the element's size is unknown, i.e. its data may end before the end of [`Self::data`].
+    unknown_size: bool,
+}
+
+/// Reads consecutive EBML elements within a range of the file.
+struct ElementReader<'a> {
+    file: &'a [u8],
+    pos: usize,
+    end: usize,
+}
+
+impl<'a> ElementReader<'a> {
+    fn new(file: &'a [u8], range: Range<usize>) -> Self {
+        Self {
+            file,
+            pos: range.start,
+            end: range.end.min(file.len()),
+        }
+    }
+
+    /// Reads the next element, returning `None` once the end of the range is reached.
+    ///
+    /// Elements that are cut off by the end of the range are truncated,
+    /// so that the readable parts of incomplete recordings can still be used.
+    fn next_element(&mut self) -> Result<Option<Element>, VideoLoadError> {
+        if self.pos >= self.end {
+            return Ok(None);
+        }
+
+        let bytes = &self.file[self.pos..self.end];
+        let (id, id_len) = read_element_id(bytes)
+            .ok_or_else(|| err(format!("Invalid element ID at offset {}", self.pos)))?;
+        let (size, size_len) = read_vint(&bytes[id_len..])
+            .ok_or_else(|| err(format!("Invalid element size at offset {}", self.pos)))?;
+
+        // All value bits set means the size is unknown.
+        let unknown_size = size == (1 << (7 * size_len)) - 1;
+
+        let data_start = self.pos + id_len + size_len;
+        let data_end = if unknown_size {
+            self.end
+        } else {
+            usize::try_from(size)
+                .ok()
+                .and_then(|size| data_start.checked_add(size))
+                .map_or(self.end, |data_end| data_end.min(self.end))
+        };
+
+        self.pos = data_end;
+
+        Ok(Some(Element {
+            id,
+            data: data_start..data_end,
+            unknown_size,
+        }))
+    }
+
+    /// Skips elements until one with the given ID is found.
+    fn find(&mut self, id: u32) -> Result<Option<Element>, VideoLoadError> {
+        while let Some(element) = self.next_element()? {
+            if element.id == id {
+                return Ok(Some(element));
+            }
+        }
+        Ok(None)
+    }
+}
+
+/// Reads an element ID, which unlike other variable size integers keeps its length marker.
+fn read_element_id(bytes: &[u8]) -> Option<(u32, usize)> {
+    let len = bytes.first()?.leading_zeros() as usize + 1;
+    if len > 4 {
+        return None;
+    }
+    let id = bytes
+        .get(..len)?
+        .iter()
+        .fold(0u32, |id, byte| (id << 8) | *byte as u32);
+    Some((id, len))
+}
+
+/// Reads a variable size integer, returning its value and its length in bytes.
+fn read_vint(bytes: &[u8]) -> Option<(u64, usize)> {
+    let first = *bytes.first()?;
+    let len = first.leading_zeros() as usize + 1;
+    if len > 8 {
+        return None;
+    }
+    let marker_mask = 0xFFu8.checked_shr(len as u32).unwrap_or(0);
+    let value = bytes
+        .get(1..len)?
+        .iter()
+        .fold((first & marker_mask) as u64, |value, byte| {
+            (value << 8) | *byte as u64
+        });
+    Some((value, len))
+}
+
+fn read_uint(bytes: &[u8]) -> u64 {
+    bytes
+        .iter()
+        .take(8)
+        .fold(0, |value, byte| (value << 8) | *byte as u64)
+}
+
+fn read_float(bytes: &[u8]) -> Option<f64> {
+    match bytes.len() {
+        4 => Some(f32::from_be_bytes(bytes.try_into().ok()?) as f64),
+        8 => Some(f64::from_be_bytes(bytes.try_into().ok()?)),
+        _ => None,
+    }
+}
+
+fn read_string(bytes: &[u8]) -> String {
+    String::from_utf8_lossy(bytes)
+        .trim_end_matches('\0')
+        .to_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn element(id: u32, data: &[u8]) -> Vec<u8> {
+        let id_bytes = id.to_be_bytes();
+        let id_start = id_bytes.iter().position(|byte| *byte != 0).unwrap_or(3);
+        let mut out = id_bytes[id_start..].to_vec();
+        // Always use 8 byte sizes for simplicity.
+        out.push(0x01);
+        out.extend_from_slice(&(data.len() as u64).to_be_bytes()[1..]);
+        out.extend_from_slice(data);
+        out
+    }
+
+    fn simple_block(relative_timestamp: i16, is_keyframe: bool, frame: &[u8]) -> Vec<u8> {
+        let mut data = vec![0x81]; // Track number 1.
+        data.extend_from_slice(&relative_timestamp.to_be_bytes());
+        data.push(if is_keyframe { 0x80 } else { 0x00 });
+        data.extend_from_slice(frame);
+        element(element_id::SIMPLE_BLOCK, &data)
+    }
+
+    #[test]
+    fn test_load_webm() {
+        let frames: [&[u8]; 4] = [b"key0", b"delta1", b"key2", b"delta3"];
+
+        let ebml_header = element(element_id::EBML, &element(element_id::DOC_TYPE, b"webm"));
+
+        let info = element(
+            element_id::INFO,
+            &element(element_id::TIMESTAMP_SCALE, &[0x0F, 0x42, 0x40]), // Milliseconds.
+        );
+        let video = [
+            element(element_id::PIXEL_WIDTH, &[64]),
+            element(element_id::PIXEL_HEIGHT, &[48]),
+        ]
+        .concat();
+        let track_entry = [
+            element(element_id::TRACK_NUMBER, &[1]),
+            element(element_id::TRACK_TYPE, &[track_type::VIDEO as u8]),
+            element(element_id::CODEC_ID, b"V_VP8"),
+            element(element_id::VIDEO, &video),
+        ]
+        .concat();
+        let tracks = element(
+            element_id::TRACKS,
+            &element(element_id::TRACK_ENTRY, &track_entry),
+        );
+
+        let clusters = [
+            element(
+                element_id::CLUSTER,
+                &[
+                    element(element_id::CLUSTER_TIMESTAMP, &[0]),
+                    simple_block(0, true, frames[0]),
+                    simple_block(33, false, frames[1]),
+                ]
+                .concat(),
+            ),
+            element(
+                element_id::CLUSTER,
+                &[
+                    element(element_id::CLUSTER_TIMESTAMP, &[66]),
+                    simple_block(0, true, frames[2]),
+                    simple_block(33, false, frames[3]),
+                ]
+                .concat(),
+            ),
+        ]
+        .concat();
+
+        let data = [
+            ebml_header,
+            element(element_id::SEGMENT, &[info, tracks, clusters].concat()),
+        ]
+        .concat();
+
+        let video = VideoDataDescription::load_mkv(&data, "test.webm").unwrap();
+
+        assert_eq!(video.codec, VideoCodec::VP8);
+        assert_eq!(video.timescale, Some(Timescale::new(1000)));
+        let encoding_details = video.encoding_details.as_ref().unwrap();
+        assert_eq!(encoding_details.codec_string, "vp8");
+        assert_eq!(encoding_details.coded_dimensions, [64, 48]);
+
+        assert_eq!(video.gops.num_elements(), 2);
+        assert_eq!(
+            video
+                .samples
+                .iter()
+                .map(|sample| (sample.presentation_timestamp.0, sample.is_sync))
+                .collect::<Vec<_>>(),
+            [(0, true), (33, false), (66, true), (99, false)]
+        );
+        assert_eq!(video.duration, Some(Time(132)));
+
+        let buffers = std::iter::once(data.as_slice()).collect();
+        for ((sample_idx, sample), frame) in video.samples.iter_indexed().zip(frames) {
+            let chunk = sample
+                .get(video.sample_layout, &buffers, sample_idx)
+                .unwrap();
+            assert_eq!(chunk.data, frame);
+        }
+    }
+}
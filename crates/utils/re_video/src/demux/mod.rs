@@ -5,7 +5,9 @@
 //! The entry point is [`VideoDataDescription::load_from_bytes`]
 //! which produces an instance of [`VideoDataDescription`] from any supported video container.
 
+pub mod mkv;
 pub mod mp4;
+pub mod mpeg_ts;
 
 use std::{collections::BTreeMap, ops::Range};
 
@@ -176,8 +178,12 @@ pub struct VideoDataDescription {
 
     /// All the tracks in the mp4; not just the video track.
     ///
+    /// For other containers, these are the tracks (or elementary streams) found in the container.
     /// Can be nice to show in a UI.
     pub mp4_tracks: BTreeMap<TrackId, Option<TrackKind>>,
+
+    /// How the samples are stored in the data buffers.
+    pub sample_layout: SampleLayout,
 }
 
 impl re_byte_size::SizeBytes for VideoDataDescription {
@@ -192,6 +198,7 @@ impl re_byte_size::SizeBytes for VideoDataDescription {
             samples_statistics,
             mp4_tracks,
             last_time_updated_samples: _,
+            sample_layout: _,
         } = self;
 
         gops.heap_size_bytes()
@@ -378,6 +385,24 @@ impl VideoEncodingDetails {
             _ => None,
         }
     }
+
+    /// Get the HVCC box from the stsd box if any.
+    pub fn hvcc(&self) -> Option<&re_mp4::HevcBox> {
+        let stsd = self.stsd.as_ref()?;
+        match &stsd.contents {
+            re_mp4::StsdBoxContent::Hvc1(hevc) | re_mp4::StsdBoxContent::Hev1(hevc) => Some(hevc),
+            _ => None,
+        }
+    }
+
+    /// Get the AV1C box from the stsd box if any.
+    pub fn av1c(&self) -> Option<&re_mp4::Av01Box> {
+        let stsd = self.stsd.as_ref()?;
+        match &stsd.contents {
+            re_mp4::StsdBoxContent::Av01(av01) => Some(av01),
+            _ => None,
+        }
+    }
 }
 
 /// Meta informationa about the video samples.
@@ -460,6 +485,8 @@ impl VideoDataDescription {
         re_tracing::profile_function!();
         match media_type {
             "video/mp4" => Self::load_mp4(data, debug_name),
+            "video/x-matroska" | "video/webm" => Self::load_mkv(data, debug_name),
+            "video/mp2t" => Self::load_mpeg_ts(data, debug_name),
 
             media_type => {
                 if media_type.starts_with("video/") {
@@ -663,6 +690,25 @@ impl VideoDataDescription {
             self.samples[requested_sample_index].decode_timestamp,
         )
     }
+
+    /// Returns the range of samples spanning all GOPs that present frames in the given presentation time range.
+    ///
+    /// The range starts at the beginning of a GOP, so it can be decoded on its own.
+    /// If `start` lies before the first presented frame, the range starts at the first GOP.
+    pub fn gop_aligned_sample_range(&self, start: Time, end: Time) -> Option<Range<SampleIndex>> {
+        let first_gop_idx = self
+            .gop_index_containing_presentation_timestamp(start)
+            .or_else(|| self.gops.iter_indexed().next().map(|(idx, _)| idx))?;
+        let last_gop_idx = self.gop_index_containing_presentation_timestamp(end)?;
+        if last_gop_idx < first_gop_idx {
+            return None;
+        }
+
+        Some(
+            self.gops.get(first_gop_idx)?.sample_range.start
+                ..self.gops.get(last_gop_idx)?.sample_range.end,
+        )
+    }
 }
 
 /// A Group of Pictures (GOP) always starts with an I(DR)-frame, followed by delta-frames.
@@ -767,11 +813,24 @@ impl SampleMetadata {
     /// For video streams, `data` refers to the currently available data
     /// which is described by the [`VideoDataDescription`].
     ///
+    /// `layout` is the [`VideoDataDescription::sample_layout`] of the video this sample belongs to.
+    ///
     /// Returns `None` if the sample is out of bounds, which can only happen
     /// if `data` is not the original video data.
-    pub fn get(&self, buffers: &StableIndexDeque<&[u8]>, sample_idx: SampleIndex) -> Option<Chunk> {
+    pub fn get(
+        &self,
+        layout: SampleLayout,
+        buffers: &StableIndexDeque<&[u8]>,
+        sample_idx: SampleIndex,
+    ) -> Option<Chunk> {
         let buffer = *buffers.get(self.buffer_index)?;
-        let data = buffer.get(self.byte_span.range_usize())?.to_vec();
+        let sample_bytes = buffer.get(self.byte_span.range_usize())?;
+        let data = match layout {
+            SampleLayout::Contiguous => sample_bytes.to_vec(),
+            SampleLayout::MpegTsPackets { pid, packet_size } => {
+                mpeg_ts::read_pes_payload(sample_bytes, pid, packet_size)?
+            }
+        };
 
         Some(Chunk {
             data,
@@ -785,6 +844,80 @@ impl SampleMetadata {
     }
 }
 
+/// How samples are stored in the data buffers they refer to.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SampleLayout {
+    /// The byte span of a sample contains exactly the sample's data.
+    ///
+    /// This is the case for mp4, Matroska/WebM and video streams.
+    #[default]
+    Contiguous,
+
+    /// The byte span of a sample covers the MPEG transport stream packets carrying it.
+    ///
+    /// The span may contain packets of other elementary streams as well, only the packets of
+    /// the given `pid` make up the sample.
+    MpegTsPackets {
+        /// Packet identifier of the video elementary stream.
+        pid: u16,
+
+        /// Size of each packet in bytes, i.e. 188, or 192 for transport streams with timecode prefixes.
+        packet_size: u8,
+    },
+}
+
+/// Fills in frame numbers and missing durations of demuxed samples and groups them into GOPs.
+///
+/// Samples are expected in decode order.
+/// Every GOP starts at a sync sample, samples before the first sync sample don't belong to any GOP.
+fn finalize_demuxed_samples(
+    samples: &mut StableIndexDeque<SampleMetadata>,
+) -> StableIndexDeque<GroupOfPictures> {
+    re_tracing::profile_function!();
+
+    {
+        let mut samples_sorted_by_pts = samples.iter_mut().collect::<Vec<_>>();
+        samples_sorted_by_pts.sort_by_key(|s| s.presentation_timestamp);
+
+        let mut last_duration = None;
+        for frame_nr in 0..samples_sorted_by_pts.len() {
+            let next_pts = samples_sorted_by_pts
+                .get(frame_nr + 1)
+                .map(|next| next.presentation_timestamp);
+
+            let sample = &mut *samples_sorted_by_pts[frame_nr];
+            sample.frame_nr = frame_nr as u32;
+            if sample.duration.is_none() {
+                // The last sample is assumed to be as long as the one before.
+                sample.duration = next_pts
+                    .map(|next_pts| next_pts - sample.presentation_timestamp)
+                    .or(last_duration);
+            }
+            last_duration = sample.duration;
+        }
+    }
+
+    let mut gops = StableIndexDeque::new();
+    let mut gop_start = None;
+    for (sample_idx, sample) in samples.iter_indexed() {
+        if sample.is_sync {
+            if let Some(start) = gop_start {
+                gops.push_back(GroupOfPictures {
+                    sample_range: start..sample_idx,
+                });
+            }
+            gop_start = Some(sample_idx);
+        }
+    }
+    if let Some(start) = gop_start {
+        gops.push_back(GroupOfPictures {
+            sample_range: start..samples.next_index(),
+        });
+    }
+
+    gops
+}
+
 /// Errors that can occur when loading a video.
 #[derive(thiserror::Error, Debug)]
 pub enum VideoLoadError {
@@ -828,6 +961,15 @@ pub enum VideoLoadError {
 
     #[error("Failed to parse H.264 SPS from mp4: {0:?}")]
     SpsParsingError(h264_reader::nal::sps::SpsError),
+
+    #[error("Failed to parse Matroska/WebM: {0}")]
+    ParseMatroska(String),
+
+    #[error("Failed to parse MPEG transport stream: {0}")]
+    ParseMpegTs(String),
+
+    #[error("Video track uses unsupported codec \"{0}\"")] // NOLINT
+    UnsupportedCodecId(String),
 }
 
 impl std::fmt::Debug for VideoDataDescription {
@@ -944,4 +1086,51 @@ mod tests {
         // (this is not the last element in the list since that one doesn't have the highest PTS)
         assert_eq!(Some(48), query_pts(Time(123123123123123123)));
     }
+
+    #[test]
+    fn test_gop_aligned_sample_range() {
+        // Eight samples, 10 time units apart, in groups of pictures of 3, 3 & 2 samples.
+        let samples = (0..8)
+            .map(|idx| SampleMetadata {
+                is_sync: [0, 3, 6].contains(&idx),
+                frame_nr: idx,
+                decode_timestamp: Time(idx as i64 * 10),
+                presentation_timestamp: Time(idx as i64 * 10),
+                duration: Some(Time(10)),
+                buffer_index: 0,
+                byte_span: Default::default(),
+            })
+            .collect::<StableIndexDeque<_>>();
+        let video = VideoDataDescription {
+            codec: VideoCodec::H264,
+            encoding_details: None,
+            timescale: Some(Timescale::new(10)),
+            duration: None,
+            gops: [0..3, 3..6, 6..8]
+                .into_iter()
+                .map(|sample_range| GroupOfPictures { sample_range })
+                .collect(),
+            samples_statistics: SamplesStatistics::new(&samples),
+            samples,
+            last_time_updated_samples: None,
+            mp4_tracks: Default::default(),
+            sample_layout: SampleLayout::Contiguous,
+        };
+        let range = |start, end| video.gop_aligned_sample_range(Time(start), Time(end));
+
+        // Within a single group of pictures.
+        assert_eq!(range(0, 5), Some(0..3));
+        assert_eq!(range(31, 39), Some(3..6));
+
+        // Spanning several groups of pictures, starting in the middle of one.
+        assert_eq!(range(15, 35), Some(0..6));
+        assert_eq!(range(45, 1000), Some(3..8));
+
+        // Starting before the first frame.
+        assert_eq!(range(-100, 5), Some(0..3));
+
+        // Ending before the first frame, or before the start.
+        assert_eq!(range(-100, -5), None);
+        assert_eq!(range(50, 20), None);
+    }
 }
@@ -2,7 +2,7 @@ use h264_reader::nal::{self, Nal as _};
 use itertools::Itertools as _;
 use re_span::Span;
 
-use super::{GroupOfPictures, SampleMetadata, VideoDataDescription, VideoLoadError};
+use super::{GroupOfPictures, SampleLayout, SampleMetadata, VideoDataDescription, VideoLoadError};
 
 use crate::{
     StableIndexDeque, Time, Timescale,
@@ -136,7 +136,7 @@ impl VideoDataDescription {
 
         let video_data_description = Self {
             codec,
-            encoding_details: Some(codec_details_from_stds(track, stsd)?),
+            encoding_details: Some(codec_details_from_stds([track.width, track.height], stsd)?),
             timescale: Some(timescale),
             duration: Some(duration),
             samples_statistics,
@@ -144,6 +144,7 @@ impl VideoDataDescription {
             gops,
             samples,
             mp4_tracks,
+            sample_layout: SampleLayout::Contiguous,
         };
 
         if cfg!(debug_assertions)
@@ -164,8 +165,11 @@ fn unknown_codec_fourcc(mp4: &re_mp4::Mp4, track: &re_mp4::Track) -> re_mp4::Fou
     }
 }
 
-fn codec_details_from_stds(
-    track: &re_mp4::Track,
+/// Determines the [`VideoEncodingDetails`] from an mp4 stsd box.
+///
+/// `coded_dimensions` are used unless they can be determined from the codec configuration.
+pub(super) fn codec_details_from_stds(
+    coded_dimensions: [u16; 2],
     stsd: re_mp4::StsdBox,
 ) -> Result<VideoEncodingDetails, VideoLoadError> {
     // For AVC we don't have to rely on the stsd box, since we can parse the SPS directly.
@@ -192,7 +196,7 @@ fn codec_details_from_stds(
             .contents
             .codec_string()
             .ok_or(VideoLoadError::UnableToDetermineCodecString)?,
-        coded_dimensions: [track.width, track.height],
+        coded_dimensions,
         bit_depth: stsd.contents.bit_depth(),
         chroma_subsampling: subsampling_mode(&stsd),
         stsd: Some(stsd),
@@ -225,9 +229,15 @@ fn subsampling_mode(stsd: &re_mp4::StsdBox) -> Option<ChromaSubsamplingModes> {
             None
         }
 
-        re_mp4::StsdBoxContent::Hvc1(_) | re_mp4::StsdBoxContent::Hev1(_) => {
-            // TODO(andreas): Parse HVC1/HEV1 SPS
-            None
+        re_mp4::StsdBoxContent::Hvc1(hevc_box) | re_mp4::StsdBoxContent::Hev1(hevc_box) => {
+            // Same as `chroma_format_idc` in the SPS.
+            match hevc_box.hvcc.chroma_format_idc {
+                0 => Some(ChromaSubsamplingModes::Monochrome),
+                1 => Some(ChromaSubsamplingModes::Yuv420),
+                2 => Some(ChromaSubsamplingModes::Yuv422),
+                3 => Some(ChromaSubsamplingModes::Yuv444),
+                _ => None,
+            }
         }
 
         re_mp4::StsdBoxContent::Vp08(vp08_box) => {
@@ -0,0 +1,629 @@
+//! MPEG transport stream demuxing.
+//!
+//! See ITU-T H.222.0 (ISO/IEC 13818-1).
+//!
+//! A transport stream interleaves small fixed size packets of several elementary streams,
+//! so samples aren't stored contiguously. Instead, every sample refers to the range of packets
+//! it is spread over, see [`SampleLayout::MpegTsPackets`].
+
+use std::collections::BTreeMap;
+
+use re_span::Span;
+
+use super::{
+    SampleLayout, SampleMetadata, SamplesStatistics, VideoDataDescription, VideoLoadError,
+    finalize_demuxed_samples,
+};
+
+use crate::{
+    GopStartDetection, StableIndexDeque, Time, Timescale, TrackId, TrackKind, VideoCodec,
+    detect_gop_start,
+};
+
+const SYNC_BYTE: u8 = 0x47;
+
+/// Size of a transport stream packet.
+///
+/// Some formats (e.g. M2TS/AVCHD) prefix every packet with a 4 byte timecode.
+const PACKET_SIZE: usize = 188;
+
+/// Timestamps in PES headers are in units of a 90 kHz clock.
+const PES_TIMESCALE: Timescale = Timescale::new(90_000);
+
+/// PES timestamps are 33 bit and wrap around after roughly 26.5 hours.
+const PES_TIMESTAMP_WRAP: i64 = 1 << 33;
+
+/// Packet identifier of the program association table.
+const PAT_PID: u16 = 0;
+
+/// Stream types as found in the program map table, see table 2-34 of H.222.0.
+mod stream_type {
+    pub const MPEG1_AUDIO: u8 = 0x03;
+    pub const MPEG2_AUDIO: u8 = 0x04;
+    pub const AAC: u8 = 0x0F;
+    pub const AAC_LATM: u8 = 0x11;
+    pub const H264: u8 = 0x1B;
+    pub const H265: u8 = 0x24;
+    pub const AC3: u8 = 0x81;
+    pub const EAC3: u8 = 0x87;
+}
+
+impl VideoDataDescription {
+    pub fn load_mpeg_ts(data: &[u8], debug_name: &str) -> Result<Self, VideoLoadError> {
+        re_tracing::profile_function!();
+
+        let packet_size = detect_packet_size(data).ok_or_else(|| {
+            VideoLoadError::ParseMpegTs("No transport stream packets found".to_owned())
+        })?;
+
+        let mut pmt_pids = Vec::new();
+        let mut streams = BTreeMap::<u16, u8>::new();
+        let mut video_stream = None;
+        let mut pes_packets = Vec::new();
+        let mut current_pes: Option<PesPacket> = None;
+
+        for (packet_idx, packet) in data.chunks_exact(packet_size).enumerate() {
+            let packet_offset = packet_idx * packet_size;
+            let Some(packet) = parse_packet(&packet[packet_size - PACKET_SIZE..]) else {
+                continue;
+            };
+
+            if packet.pid == PAT_PID {
+                if let Some(section) = psi_section(&packet) {
+                    pmt_pids = parse_pat(section);
+                }
+            } else if pmt_pids.contains(&packet.pid) {
+                if let Some(section) = psi_section(&packet) {
+                    let program_streams = parse_pmt(section);
+                    if video_stream.is_none() {
+                        video_stream =
+                            program_streams.iter().find_map(
+                                |(pid, stream_type)| match *stream_type {
+                                    stream_type::H264 => Some((*pid, VideoCodec::H264)),
+                                    stream_type::H265 => Some((*pid, VideoCodec::H265)),
+                                    _ => None,
+                                },
+                            );
+                    }
+                    streams.extend(program_streams);
+                }
+            } else if video_stream.is_some_and(|(pid, _)| pid == packet.pid) {
+                if packet.payload_unit_start {
+                    pes_packets.extend(current_pes.take());
+                    current_pes = parse_pes_header(packet.payload).map(|header| PesPacket {
+                        header,
+                        start: packet_offset,
+                        end: packet_offset + packet_size,
+                    });
+                } else if let Some(pes) = &mut current_pes {
+                    pes.end = packet_offset + packet_size;
+                }
+            }
+        }
+        pes_packets.extend(current_pes);
+
+        let mp4_tracks = streams
+            .iter()
+            .map(|(pid, stream_type)| {
+                let kind = match *stream_type {
+                    stream_type::H264 | stream_type::H265 => Some(TrackKind::Video),
+                    stream_type::MPEG1_AUDIO
+                    | stream_type::MPEG2_AUDIO
+                    | stream_type::AAC
+                    | stream_type::AAC_LATM
+                    | stream_type::AC3
+                    | stream_type::EAC3 => Some(TrackKind::Audio),
+                    _ => None,
+                };
+                (TrackId::from(*pid), kind)
+            })
+            .collect();
+
+        let Some((video_pid, codec)) = video_stream else {
+            return Err(if streams.is_empty() {
+                VideoLoadError::ParseMpegTs("No program map table found".to_owned())
+            } else {
+                VideoLoadError::NoVideoTrack
+            });
+        };
+        let sample_layout = SampleLayout::MpegTsPackets {
+            pid: video_pid,
+            packet_size: packet_size as u8,
+        };
+
+        let mut encoding_details = None;
+        let mut samples = StableIndexDeque::<SampleMetadata>::with_capacity(pes_packets.len());
+        let mut last_timestamp = None;
+
+        for pes in &pes_packets {
+            let Some(pts) = pes.header.pts else {
+                re_log::warn_once!(
+                    "Skipping video PES packets without presentation timestamp in {debug_name}"
+                );
+                continue;
+            };
+            let dts = pes.header.dts.unwrap_or(pts);
+
+            let decode_timestamp = unwrap_timestamp(dts, last_timestamp);
+            let presentation_timestamp = unwrap_timestamp(pts, Some(decode_timestamp));
+            last_timestamp = Some(decode_timestamp);
+
+            let byte_span = Span {
+                start: u32::try_from(pes.start).map_err(|_err| {
+                    VideoLoadError::ParseMpegTs(
+                        "Files larger than 4 GiB are not supported".to_owned(),
+                    )
+                })?,
+                len: (pes.end - pes.start) as u32,
+            };
+
+            // Transport streams carry no information about key frames that we could rely on,
+            // so we have to look at the bitstream itself.
+            let sample_data =
+                read_pes_payload(&data[byte_span.range_usize()], video_pid, packet_size as u8)
+                    .unwrap_or_default();
+            let is_sync = match detect_gop_start(&sample_data, codec) {
+                Ok(GopStartDetection::StartOfGop(details)) => {
+                    encoding_details.get_or_insert(details);
+                    true
+                }
+                Ok(GopStartDetection::NotStartOfGop) => false,
+                Err(err) => {
+                    re_log::warn_once!("Failed to detect key frames in {debug_name}: {err}");
+                    false
+                }
+            };
+
+            samples.push_back(SampleMetadata {
+                is_sync,
+                frame_nr: 0, // filled in by `finalize_demuxed_samples`
+                decode_timestamp: Time(decode_timestamp),
+                presentation_timestamp: Time(presentation_timestamp),
+                duration: None, // filled in by `finalize_demuxed_samples`
+                buffer_index: 0,
+                byte_span,
+            });
+        }
+
+        // Transport stream timestamps start at arbitrary values, make the first frame start at zero.
+        if let Some(first_pts) = samples.iter().map(|s| s.presentation_timestamp).min() {
+            for sample in samples.iter_mut() {
+                sample.presentation_timestamp = sample.presentation_timestamp - first_pts;
+                sample.decode_timestamp = sample.decode_timestamp - first_pts;
+            }
+        }
+
+        let gops = finalize_demuxed_samples(&mut samples);
+
+        let duration = samples
+            .iter()
+            .map(|sample| sample.presentation_timestamp + sample.duration.unwrap_or_default())
+            .max();
+
+        let video_data_description = Self {
+            codec,
+            encoding_details,
+            timescale: Some(PES_TIMESCALE),
+            duration,
+            samples_statistics: SamplesStatistics::new(&samples),
+            last_time_updated_samples: None,
+            gops,
+            samples,
+            mp4_tracks,
+            sample_layout,
+        };
+
+        if cfg!(debug_assertions)
+            && let Err(err) = video_data_description.sanity_check()
+        {
+            panic!("VideoDataDescription sanity check for {debug_name} failed: {err}");
+        }
+
+        Ok(video_data_description)
+    }
+}
+
+/// Reassembles the payload of the PES packet of the given stream spread over `packets`.
+///
+/// Returns `None` if the packets don't start with a valid PES packet.
+pub fn read_pes_payload(packets: &[u8], pid: u16, packet_size: u8) -> Option<Vec<u8>> {
+    let packet_size = packet_size as usize;
+    let mut payload = Vec::with_capacity(packets.len());
+
+    for packet in packets.chunks_exact(packet_size) {
+        let Some(packet) = parse_packet(&packet[packet_size - PACKET_SIZE..]) else {
+            continue;
+        };
+        if packet.pid != pid {
+            continue;
+        }
+
+        if packet.payload_unit_start {
+            if !payload.is_empty() {
+                // Start of the next PES packet.
+                break;
+            }
+            let header = parse_pes_header(packet.payload)?;
+            payload.extend_from_slice(packet.payload.get(header.payload_offset..)?);
+        } else {
+            payload.extend_from_slice(packet.payload);
+        }
+    }
+
+    Some(payload)
+}
+
+/// Number of consecutive packets that have to start with a sync byte for data to be considered a
+/// transport stream.
+///
+/// Any transport stream worth loading has at least a PAT, a PMT and a PES packet.
+const MIN_SYNC_PACKETS: usize = 3;
+
+/// Number of packets checked for sync bytes, if there are that many.
+const MAX_SYNC_PACKETS: usize = 5;
+
+/// Whether `data` looks like an MPEG transport stream, i.e. starts with several consecutive
+/// packets with sync bytes, with or without a 4 byte timecode in front of each packet.
+pub fn is_mpeg_ts(data: &[u8]) -> bool {
+    detect_packet_size(data).is_some()
+}
+
+/// Determines the packet size by looking for consecutive sync bytes.
+fn detect_packet_size(data: &[u8]) -> Option<usize> {
+    [PACKET_SIZE, PACKET_SIZE + 4]
+        .into_iter()
+        .find(|&packet_size| {
+            let sync_byte_offset = packet_size - PACKET_SIZE;
+            let num_packets = (data.len() / packet_size).min(MAX_SYNC_PACKETS);
+            num_packets >= MIN_SYNC_PACKETS
+                && (0..num_packets).all(|packet_idx| {
+                    data[packet_idx * packet_size + sync_byte_offset] == SYNC_BYTE
+                })
+        })
+}
+
+struct Packet<'a> {
+    pid: u16,
+    payload_unit_start: bool,
+    payload: &'a [u8],
+}
+
+/// Parses a single 188 byte transport stream packet.
+fn parse_packet(packet: &[u8]) -> Option<Packet<'_>> {
+    let [sync_byte, b1, b2, b3, ..] = *packet else {
+        return None;
+    };
+    let transport_error = b1 & 0x80 != 0;
+    if sync_byte != SYNC_BYTE || transport_error {
+        return None;
+    }
+
+    let payload_unit_start = b1 & 0x40 != 0;
+    let pid = (((b1 & 0x1F) as u16) << 8) | b2 as u16;
+
+    let adaptation_field_control = (b3 >> 4) & 0b11;
+    let has_adaptation_field = adaptation_field_control & 0b10 != 0;
+    let has_payload = adaptation_field_control & 0b01 != 0;
+
+    let payload = if !has_payload {
+        &[][..]
+    } else if has_adaptation_field {
+        let adaptation_field_length = *packet.get(4)? as usize;
+        packet.get(5 + adaptation_field_length..)?
+    } else {
+        &packet[4..]
+    };
+
+    Some(Packet {
+        pid,
+        payload_unit_start,
+        payload,
+    })
+}
+
+/// Returns the PSI section starting in this packet, if any.
+///
+/// Sections spanning several packets are not supported, but PAT & PMT practically always fit into one.
+fn psi_section<'a>(packet: &Packet<'a>) -> Option<&'a [u8]> {
+    if !packet.payload_unit_start {
+        return None;
+    }
+    let pointer_field = *packet.payload.first()? as usize;
+    let section = packet.payload.get(1 + pointer_field..)?;
+    let section_length = (((section.get(1)? & 0x0F) as usize) << 8) | *section.get(2)? as usize;
+    section.get(..3 + section_length)
+}
+
+/// Returns the PIDs of all program map tables listed in a program association table section.
+fn parse_pat(section: &[u8]) -> Vec<u16> {
+    const TABLE_ID: u8 = 0x00;
+    if section.first() != Some(&TABLE_ID) || section.len() < 12 {
+        return Vec::new();
+    }
+
+    // Skip the header and the trailing CRC.
+    section[8..section.len() - 4]
+        .chunks_exact(4)
+        .filter_map(|program| {
+            let program_number = u16::from_be_bytes([program[0], program[1]]);
+            let pid = (((program[2] & 0x1F) as u16) << 8) | program[3] as u16;
+            // Program number zero refers to the network information table instead.
+            (program_number != 0).then_some(pid)
+        })
+        .collect()
+}
+
+/// Returns the PIDs & stream types of all elementary streams listed in a program map table section.
+fn parse_pmt(section: &[u8]) -> Vec<(u16, u8)> {
+    const TABLE_ID: u8 = 0x02;
+    if section.first() != Some(&TABLE_ID) || section.len() < 16 {
+        return Vec::new();
+    }
+
+    let program_info_length = (((section[10] & 0x0F) as usize) << 8) | section[11] as usize;
+    let end = section.len() - 4; // Trailing CRC.
+
+    let mut streams = Vec::new();
+    let mut pos = 12 + program_info_length;
+    while pos + 5 <= end {
+        let stream_type = section[pos];
+        let pid = (((section[pos + 1] & 0x1F) as u16) << 8) | section[pos + 2] as u16;
+        let es_info_length =
+            (((section[pos + 3] & 0x0F) as usize) << 8) | section[pos + 4] as usize;
+        streams.push((pid, stream_type));
+        pos += 5 + es_info_length;
+    }
+    streams
+}
+
+struct PesHeader {
+    pts: Option<i64>,
+    dts: Option<i64>,
+
+    /// Offset of the elementary stream data within the PES packet.
+    payload_offset: usize,
+}
+
+/// A PES packet of the video stream, spread over the packets in `start..end`.
+struct PesPacket {
+    header: PesHeader,
+    start: usize,
+    end: usize,
+}
+
+/// Parses the header of a PES packet carrying video.
+fn parse_pes_header(payload: &[u8]) -> Option<PesHeader> {
+    if payload.get(..3)? != [0, 0, 1] || payload.len() < 9 {
+        return None;
+    }
+
+    let pts_dts_flags = payload[7] >> 6;
+    let header_data_length = payload[8] as usize;
+
+    let pts = if pts_dts_flags & 0b10 != 0 {
+        Some(read_pes_timestamp(payload.get(9..14)?))
+    } else {
+        None
+    };
+    let dts = if pts_dts_flags == 0b11 {
+        Some(read_pes_timestamp(payload.get(14..19)?))
+    } else {
+        None
+    };
+
+    Some(PesHeader {
+        pts,
+        dts,
+        payload_offset: 9 + header_data_length,
+    })
+}
+
+/// Reads a 33 bit timestamp spread over 5 bytes with marker bits.
+fn read_pes_timestamp(bytes: &[u8]) -> i64 {
+    ((((bytes[0] >> 1) & 0x07) as i64) << 30)
+        | ((bytes[1] as i64) << 22)
+        | (((bytes[2] >> 1) as i64) << 15)
+        | ((bytes[3] as i64) << 7)
+        | (bytes[4] >> 1) as i64
+}
+
+/// Undoes the wrap around of a 33 bit timestamp, by picking the value closest to `reference`.
+fn unwrap_timestamp(timestamp: i64, reference: Option<i64>) -> i64 {
+    let Some(reference) = reference else {
+        return timestamp;
+    };
+    let wraps = (reference - timestamp + PES_TIMESTAMP_WRAP / 2).div_euclid(PES_TIMESTAMP_WRAP);
+    timestamp + wraps * PES_TIMESTAMP_WRAP
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PMT_PID: u16 = 0x20;
+    const VIDEO_PID: u16 = 0x100;
+    const AUDIO_PID: u16 = 0x101;
+
+    fn packet(pid: u16, payload_unit_start: bool, payload: &[u8]) -> Vec<u8> {
+        assert!(payload.len() <= PACKET_SIZE - 4);
+        let mut packet = vec![
+            SYNC_BYTE,
+            ((payload_unit_start as u8) << 6) | (pid >> 8) as u8,
+            pid as u8,
+        ];
+        if payload.len() == PACKET_SIZE - 4 {
+            packet.push(0x10); // Payload only.
+        } else {
+            // Pad with an adaptation field.
+            let adaptation_field_length = PACKET_SIZE - 5 - payload.len();
+            packet.push(0x30);
+            packet.push(adaptation_field_length as u8);
+            if adaptation_field_length > 0 {
+                packet.push(0x00); // No flags.
+                packet.resize(6 + adaptation_field_length - 1, 0xFF);
+            }
+        }
+        packet.extend_from_slice(payload);
+        assert_eq!(packet.len(), PACKET_SIZE);
+        packet
+    }
+
+    fn psi_packet(pid: u16, table_id: u8, table_data: &[u8]) -> Vec<u8> {
+        let section_length = 5 + table_data.len() + 4;
+        let mut payload = vec![
+            0x00, // Pointer field.
+            table_id,
+            0xB0 | (section_length >> 8) as u8,
+            section_length as u8,
+            0x00,
+            0x01, // Table id extension.
+            0xC1, // Version & current/next indicator.
+            0x00, // Section number.
+            0x00, // Last section number.
+        ];
+        payload.extend_from_slice(table_data);
+        payload.extend_from_slice(&[0; 4]); // CRC, not checked.
+        packet(pid, true, &payload)
+    }
+
+    fn pes_timestamp(prefix: u8, timestamp: i64) -> [u8; 5] {
+        [
+            (prefix << 4) | (((timestamp >> 30) & 0x07) << 1) as u8 | 1,
+            (timestamp >> 22) as u8,
+            (((timestamp >> 15) & 0x7F) << 1) as u8 | 1,
+            (timestamp >> 7) as u8,
+            ((timestamp & 0x7F) << 1) as u8 | 1,
+        ]
+    }
+
+    /// Splits a PES packet with the given elementary stream data into transport stream packets.
+    fn pes_packets(pts: i64, es_data: &[u8]) -> Vec<Vec<u8>> {
+        let mut pes = vec![0x00, 0x00, 0x01, 0xE0, 0x00, 0x00, 0x80, 0x80, 0x05];
+        pes.extend_from_slice(&pes_timestamp(0b0010, pts));
+        pes.extend_from_slice(es_data);
+
+        pes.chunks(PACKET_SIZE - 4)
+            .enumerate()
+            .map(|(idx, chunk)| packet(VIDEO_PID, idx == 0, chunk))
+            .collect()
+    }
+
+    #[test]
+    fn test_is_mpeg_ts() {
+        let packets = [0, 1, 2].map(|_| packet(PAT_PID, false, &[]));
+        assert!(is_mpeg_ts(&packets.concat()));
+        assert!(is_mpeg_ts(
+            &packets
+                .iter()
+                .map(|packet| [&[0; 4][..], packet].concat())
+                .collect::<Vec<_>>()
+                .concat()
+        ));
+
+        // Too short to tell.
+        assert!(!is_mpeg_ts(&packets[..2].concat()));
+
+        // Text that happens to have a 'G' at the start of the first two packets.
+        let mut text = b"Good morning! ".repeat(50);
+        text[PACKET_SIZE] = b'G';
+        assert!(!is_mpeg_ts(&text));
+    }
+
+    #[test]
+    fn test_load_mpeg_ts() {
+        // SPS & IDR frame, see `test_detect_h264_annexb_gop`.
+        let mut key_frame = vec![
+            0x00, 0x00, 0x00, 0x01, 0x67, 0x64, 0x00, 0x0A, 0xAC, 0x72, 0x84, 0x44, 0x26, 0x84,
+            0x00, 0x00, 0x03, 0x00, 0x04, 0x00, 0x00, 0x03, 0x00, 0xCA, 0x3C, 0x48, 0x96, 0x11,
+            0x80, 0x00, 0x00, 0x00, 0x01, 0x65, 0x88, 0x84,
+        ];
+        // Make it span several packets.
+        key_frame.resize(300, 0x42);
+        let delta_frame = vec![0x00, 0x00, 0x00, 0x01, 0x41, 0x9A, 0x02, 0x03];
+
+        // Start right before the 33 bit timestamps wrap around.
+        let first_pts = PES_TIMESTAMP_WRAP - 3000;
+
+        let pat = psi_packet(PAT_PID, 0x00, &[0x00, 0x01, 0xE0, PMT_PID as u8]);
+        let pmt = psi_packet(
+            PMT_PID,
+            0x02,
+            &[
+                0xE1,
+                0x00, // PCR PID.
+                0xF0,
+                0x00, // Program info length.
+                stream_type::H264,
+                0xE1,
+                0x00,
+                0xF0,
+                0x00, // Video stream.
+                stream_type::AAC,
+                0xE1,
+                0x01,
+                0xF0,
+                0x00, // Audio stream.
+            ],
+        );
+        let audio = packet(AUDIO_PID, true, &[0xAA; 20]);
+
+        let mut packets = vec![pat, pmt];
+        let mut key_frame_packets = pes_packets(first_pts, &key_frame);
+        // Interleave audio in the middle of a video sample.
+        key_frame_packets.insert(1, audio.clone());
+        packets.extend(key_frame_packets);
+        packets.extend(pes_packets(first_pts + 3000, &delta_frame));
+        packets.push(audio);
+        packets.extend(pes_packets(first_pts + 6000, &key_frame));
+
+        for packet_prefix in [0, 4] {
+            let data = packets
+                .iter()
+                .flat_map(|packet| [&[0; 4][..packet_prefix], packet].concat())
+                .collect::<Vec<_>>();
+
+            let video = VideoDataDescription::load_mpeg_ts(&data, "test.ts").unwrap();
+
+            assert_eq!(video.codec, VideoCodec::H264);
+            assert_eq!(video.timescale, Some(PES_TIMESCALE));
+            assert_eq!(
+                video.encoding_details.as_ref().unwrap().codec_string,
+                "avc1.64000A"
+            );
+            assert_eq!(
+                video.sample_layout,
+                SampleLayout::MpegTsPackets {
+                    pid: VIDEO_PID,
+                    packet_size: (PACKET_SIZE + packet_prefix) as u8,
+                }
+            );
+            assert_eq!(
+                video.mp4_tracks.get(&TrackId::from(AUDIO_PID)),
+                Some(&Some(TrackKind::Audio))
+            );
+
+            assert_eq!(video.gops.num_elements(), 2);
+            assert_eq!(
+                video
+                    .samples
+                    .iter()
+                    .map(|sample| (sample.presentation_timestamp.0, sample.is_sync))
+                    .collect::<Vec<_>>(),
+                [(0, true), (3000, false), (6000, true)]
+            );
+
+            let buffers = std::iter::once(data.as_slice()).collect();
+            for ((sample_idx, sample), frame) in
+                video
+                    .samples
+                    .iter_indexed()
+                    .zip([&key_frame, &delta_frame, &key_frame])
+            {
+                let chunk = sample
+                    .get(video.sample_layout, &buffers, sample_idx)
+                    .unwrap();
+                assert_eq!(&chunk.data, frame);
+            }
+        }
+    }
+}
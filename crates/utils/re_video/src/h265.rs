@@ -0,0 +1,153 @@
+//! General H.265 utilities.
+
+use std::fmt::Write as _;
+use std::num::NonZeroUsize;
+
+use h264_reader::rbsp::{BitRead as _, BitReader, BitReaderError, ByteReader};
+
+use crate::{ChromaSubsamplingModes, VideoEncodingDetails};
+
+/// H.265 NAL unit types, see table 7-1 of the H.265 spec.
+pub mod nal_unit_type {
+    /// First IRAP (intra random access point) type, i.e. `BLA_W_LP`.
+    pub const IRAP_FIRST: u8 = 16;
+
+    /// Last IRAP type, i.e. `RSV_IRAP_VCL23`.
+    pub const IRAP_LAST: u8 = 23;
+
+    /// Sequence parameter set.
+    pub const SPS: u8 = 33;
+}
+
+/// Size of the header of every H.265 NAL unit.
+const NAL_HEADER_LEN: NonZeroUsize = NonZeroUsize::new(2).expect("non-zero");
+
+/// The type of an H.265 NAL unit, read from its (two byte) header.
+pub fn nal_unit_type(nal: &[u8]) -> Option<u8> {
+    nal.first().map(|header| (header >> 1) & 0x3F)
+}
+
+/// Whether the NAL unit type is an IRAP picture, i.e. a picture that decoding can start from.
+pub fn is_irap(nal_unit_type: u8) -> bool {
+    (nal_unit_type::IRAP_FIRST..=nal_unit_type::IRAP_LAST).contains(&nal_unit_type)
+}
+
+/// Retrieve [`VideoEncodingDetails`] from a H.265 SPS NAL unit, including its header.
+pub fn encoding_details_from_h265_sps(
+    sps_nal: &[u8],
+) -> Result<VideoEncodingDetails, BitReaderError> {
+    let mut r = BitReader::new(ByteReader::skipping_bytes(sps_nal, NAL_HEADER_LEN));
+
+    r.skip(4, "sps_video_parameter_set_id")?;
+    let max_sub_layers_minus1: u8 = r.read(3, "sps_max_sub_layers_minus1")?;
+    r.skip(1, "sps_temporal_id_nesting_flag")?;
+
+    // profile_tier_level( 1, sps_max_sub_layers_minus1 )
+    let general_profile_space: u8 = r.read(2, "general_profile_space")?;
+    let general_tier_flag = r.read_bool("general_tier_flag")?;
+    let general_profile_idc: u8 = r.read(5, "general_profile_idc")?;
+    let general_profile_compatibility_flags: u32 =
+        r.read(32, "general_profile_compatibility_flags")?;
+    let general_constraint_indicator_flags: u64 = r.read(48, "general_constraint_flags")?;
+    let general_level_idc: u8 = r.read(8, "general_level_idc")?;
+
+    let mut sub_layers_present = Vec::with_capacity(max_sub_layers_minus1 as usize);
+    for _ in 0..max_sub_layers_minus1 {
+        let profile_present = r.read_bool("sub_layer_profile_present_flag")?;
+        let level_present = r.read_bool("sub_layer_level_present_flag")?;
+        sub_layers_present.push((profile_present, level_present));
+    }
+    if max_sub_layers_minus1 > 0 {
+        for _ in max_sub_layers_minus1..8 {
+            r.skip(2, "reserved_zero_2bits")?;
+        }
+    }
+    for (profile_present, level_present) in sub_layers_present {
+        if profile_present {
+            r.skip(88, "sub_layer_profile")?;
+        }
+        if level_present {
+            r.skip(8, "sub_layer_level_idc")?;
+        }
+    }
+
+    r.read_ue("sps_seq_parameter_set_id")?;
+    let chroma_format_idc = r.read_ue("chroma_format_idc")?;
+    if chroma_format_idc == 3 {
+        r.skip(1, "separate_colour_plane_flag")?;
+    }
+
+    let mut width = r.read_ue("pic_width_in_luma_samples")?;
+    let mut height = r.read_ue("pic_height_in_luma_samples")?;
+
+    if r.read_bool("conformance_window_flag")? {
+        // Offsets are in chroma samples.
+        let (sub_width, sub_height) = match chroma_format_idc {
+            1 => (2, 2),
+            2 => (2, 1),
+            _ => (1, 1),
+        };
+        let left = r.read_ue("conf_win_left_offset")?;
+        let right = r.read_ue("conf_win_right_offset")?;
+        let top = r.read_ue("conf_win_top_offset")?;
+        let bottom = r.read_ue("conf_win_bottom_offset")?;
+
+        // Saturating, as malformed streams may have arbitrarily large offsets.
+        width = width.saturating_sub(left.saturating_add(right).saturating_mul(sub_width));
+        height = height.saturating_sub(top.saturating_add(bottom).saturating_mul(sub_height));
+    }
+
+    let bit_depth_luma_minus8 = r.read_ue("bit_depth_luma_minus8")?;
+    // The H.265 spec allows bit depths of 8 through 16.
+    let bit_depth = if bit_depth_luma_minus8 <= 8 {
+        Some(bit_depth_luma_minus8 as u8 + 8)
+    } else {
+        re_log::error_once!("Invalid luma bit depth in H265 SPS: {bit_depth_luma_minus8} + 8");
+        None
+    };
+
+    let chroma_subsampling = match chroma_format_idc {
+        0 => Some(ChromaSubsamplingModes::Monochrome),
+        1 => Some(ChromaSubsamplingModes::Yuv420),
+        2 => Some(ChromaSubsamplingModes::Yuv422),
+        3 => Some(ChromaSubsamplingModes::Yuv444),
+        _ => {
+            re_log::error_once!("Invalid chroma format in H265 SPS: {chroma_format_idc}");
+            None
+        }
+    };
+
+    // Codec string as defined in ISO/IEC 14496-15 Annex E.
+    // We use `hev1` since Annex B streams carry their parameter sets in-band.
+    let mut codec_string = "hev1.".to_owned();
+    match general_profile_space {
+        1 => codec_string.push('A'),
+        2 => codec_string.push('B'),
+        3 => codec_string.push('C'),
+        _ => {}
+    }
+    write!(
+        codec_string,
+        "{general_profile_idc}.{:X}.{}{general_level_idc}",
+        general_profile_compatibility_flags.reverse_bits(),
+        if general_tier_flag { 'H' } else { 'L' },
+    )
+    .ok();
+    // Six constraint bytes, trailing zero bytes are omitted.
+    let constraint_bytes = &general_constraint_indicator_flags.to_be_bytes()[2..];
+    let num_constraint_bytes = constraint_bytes
+        .iter()
+        .rposition(|byte| *byte != 0)
+        .map_or(0, |idx| idx + 1);
+    for byte in &constraint_bytes[..num_constraint_bytes] {
+        write!(codec_string, ".{byte:X}").ok();
+    }
+
+    Ok(VideoEncodingDetails {
+        codec_string,
+        coded_dimensions: [width as _, height as _],
+        bit_depth,
+        chroma_subsampling,
+        stsd: None,
+    })
+}
@@ -1,8 +1,11 @@
 //! Video decoding library.
 
+mod av1;
 mod decode;
 mod demux;
 mod h264;
+mod h265;
+mod mux;
 mod stable_index_deque;
 mod time;
 
@@ -14,9 +17,11 @@ pub use self::{
         new_decoder,
     },
     demux::{
-        ChromaSubsamplingModes, GopIndex, GroupOfPictures, SampleIndex, SampleMetadata,
-        SamplesStatistics, VideoCodec, VideoDataDescription, VideoEncodingDetails, VideoLoadError,
+        ChromaSubsamplingModes, GopIndex, GroupOfPictures, SampleIndex, SampleLayout,
+        SampleMetadata, SamplesStatistics, VideoCodec, VideoDataDescription, VideoEncodingDetails,
+        VideoLoadError, mpeg_ts::is_mpeg_ts,
     },
+    mux::{MuxError, mp4::write_mp4},
 };
 
 #[cfg(with_ffmpeg)]
@@ -0,0 +1,36 @@
+//! Writing video samples into containers, without re-encoding them.
+
+pub mod mp4;
+
+use crate::{SampleIndex, VideoCodec};
+
+/// Errors that can occur when writing a video.
+#[derive(thiserror::Error, Debug)]
+pub enum MuxError {
+    #[error("There are no samples to write")]
+    NoSamples,
+
+    #[error("The first sample is not a key frame, so the video couldn't be decoded")]
+    NoKeyFrameAtStart,
+
+    #[error("Video has no timescale, which is required to write frame timestamps in time units")]
+    NoTimescale,
+
+    #[error("Timestamps are out of the range supported by the container")]
+    TimestampOutOfRange,
+
+    #[error("Data of sample {0} is missing")]
+    MissingSampleData(SampleIndex),
+
+    #[error("Couldn't find the codec configuration of the {0:?} video")]
+    MissingCodecConfiguration(VideoCodec),
+
+    #[error("Video track uses unsupported codec \"{0}\"")] // NOLINT
+    UnsupportedCodec(re_mp4::FourCC),
+
+    #[error("Video is too large to be written")]
+    TooLarge,
+
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
@@ -0,0 +1,871 @@
+//! MP4 writing.
+//!
+//! See ISO/IEC 14496-12 (ISO base media file format) and ISO/IEC 14496-15 (carriage of NAL unit
+//! structured video) for the box layouts.
+
+use std::ops::Range;
+
+use super::MuxError;
+
+use crate::{
+    ChromaSubsamplingModes, Chunk, SampleIndex, StableIndexDeque, VideoCodec, VideoDataDescription,
+    VideoEncodingDetails, av1, decode::annexb_nal_units,
+};
+
+/// Size of the length prefix of NAL units converted from Annex B.
+const NAL_LENGTH_SIZE: u8 = 4;
+
+/// The one and only track we write.
+const TRACK_ID: u32 = 1;
+
+/// Unity transformation matrix, as used by `mvhd` and `tkhd`.
+const UNITY_MATRIX: [u32; 9] = [0x0001_0000, 0, 0, 0, 0x0001_0000, 0, 0, 0, 0x4000_0000];
+
+/// Writes the given range of samples as an MP4 file with a single video track, without re-encoding.
+///
+/// The range should start at a key frame, typically the start of a [`crate::GroupOfPictures`].
+/// Timestamps are shifted such that the first sample is decoded at zero.
+///
+/// Annex B H.264 & H.265 streams are converted to length prefixed NAL units,
+/// with the codec configuration taken from the parameter sets found in the samples.
+pub fn write_mp4(
+    video: &VideoDataDescription,
+    buffers: &StableIndexDeque<&[u8]>,
+    sample_range: Range<SampleIndex>,
+    out: &mut impl std::io::Write,
+) -> Result<(), MuxError> {
+    re_tracing::profile_function!();
+
+    let timescale = video.timescale.ok_or(MuxError::NoTimescale)?;
+    let timescale = u32::try_from(timescale.get()).map_err(|_err| MuxError::TimestampOutOfRange)?;
+
+    let mut samples = video
+        .samples
+        .iter_index_range_clamped(&sample_range)
+        .map(|(sample_idx, sample)| {
+            sample
+                .get(video.sample_layout, buffers, sample_idx)
+                .ok_or(MuxError::MissingSampleData(sample_idx))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let Some(first_sample) = samples.first() else {
+        return Err(MuxError::NoSamples);
+    };
+    if !first_sample.is_sync {
+        return Err(MuxError::NoKeyFrameAtStart);
+    }
+
+    let (sample_entry, is_annexb) = sample_entry(video, &samples)?;
+    if is_annexb {
+        for sample in &mut samples {
+            sample.data = length_prefixed_from_annexb(&sample.data);
+        }
+    }
+
+    let timing = SampleTiming::new(&samples)?;
+
+    let ftyp = {
+        let mut ftyp = Vec::new();
+        write_box(&mut ftyp, b"ftyp", |out| {
+            out.extend_from_slice(b"isom"); // major brand
+            out.extend_from_slice(&0x200u32.to_be_bytes()); // minor version
+            for brand in [b"isom", b"iso2", b"mp41"] {
+                out.extend_from_slice(brand);
+            }
+        });
+        ftyp
+    };
+
+    let mdat_data_len = samples
+        .iter()
+        .map(|sample| sample.data.len() as u64)
+        .sum::<u64>();
+    let mdat_header_len = if mdat_data_len + 8 > u32::MAX as u64 {
+        16
+    } else {
+        8
+    };
+
+    // The size of the `moov` box doesn't depend on the chunk offset, so we can determine it upfront.
+    let moov_len = moov_box(timescale, video, &samples, &timing, &sample_entry, 0)?.len();
+    let chunk_offset = u32::try_from(ftyp.len() + moov_len + mdat_header_len)
+        .map_err(|_err| MuxError::TooLarge)?;
+    let moov = moov_box(
+        timescale,
+        video,
+        &samples,
+        &timing,
+        &sample_entry,
+        chunk_offset,
+    )?;
+
+    out.write_all(&ftyp)?;
+    out.write_all(&moov)?;
+    if mdat_header_len == 16 {
+        out.write_all(&1u32.to_be_bytes())?;
+        out.write_all(b"mdat")?;
+        out.write_all(&(mdat_data_len + 16).to_be_bytes())?;
+    } else {
+        out.write_all(&(mdat_data_len as u32 + 8).to_be_bytes())?;
+        out.write_all(b"mdat")?;
+    }
+    for sample in &samples {
+        out.write_all(&sample.data)?;
+    }
+
+    Ok(())
+}
+
+/// Sample timing, relative to the decode timestamp of the first sample.
+struct SampleTiming {
+    /// Decode duration of each sample.
+    durations: Vec<u32>,
+
+    /// Presentation minus decode timestamp of each sample.
+    composition_offsets: Vec<i64>,
+
+    /// Sum of all sample durations.
+    media_duration: u64,
+
+    /// Earliest presentation timestamp.
+    presentation_start: i64,
+}
+
+impl SampleTiming {
+    fn new(samples: &[Chunk]) -> Result<Self, MuxError> {
+        let mut durations = Vec::with_capacity(samples.len());
+        for (idx, sample) in samples.iter().enumerate() {
+            let duration = if let Some(next_sample) = samples.get(idx + 1) {
+                (next_sample.decode_timestamp - sample.decode_timestamp).0
+            } else {
+                // The last sample is assumed to be as long as the one before if its duration is unknown.
+                sample
+                    .duration
+                    .map(|duration| duration.0)
+                    .or_else(|| durations.last().map(|duration: &u32| *duration as i64))
+                    .unwrap_or(1)
+            };
+            durations.push(u32::try_from(duration).map_err(|_err| MuxError::TimestampOutOfRange)?);
+        }
+
+        let composition_offsets = samples
+            .iter()
+            .map(|sample| (sample.presentation_timestamp - sample.decode_timestamp).0)
+            .collect::<Vec<_>>();
+        if composition_offsets
+            .iter()
+            .any(|offset| i32::try_from(*offset).is_err())
+        {
+            return Err(MuxError::TimestampOutOfRange);
+        }
+
+        let first_decode_timestamp = samples
+            .first()
+            .map_or(0, |sample| sample.decode_timestamp.0);
+        let presentation_start = samples
+            .iter()
+            .map(|sample| (sample.presentation_timestamp.0 - first_decode_timestamp))
+            .min()
+            .unwrap_or_default();
+
+        Ok(Self {
+            media_duration: durations.iter().map(|duration| *duration as u64).sum(),
+            durations,
+            composition_offsets,
+            presentation_start,
+        })
+    }
+}
+
+fn moov_box(
+    timescale: u32,
+    video: &VideoDataDescription,
+    samples: &[Chunk],
+    timing: &SampleTiming,
+    sample_entry: &[u8],
+    chunk_offset: u32,
+) -> Result<Vec<u8>, MuxError> {
+    let [width, height] = video
+        .encoding_details
+        .as_ref()
+        .map_or([0, 0], |details| details.coded_dimensions);
+    let num_samples = u32::try_from(samples.len()).map_err(|_err| MuxError::TooLarge)?;
+    let presentation_duration = timing
+        .media_duration
+        .saturating_sub(timing.presentation_start.max(0) as u64);
+
+    let mut moov = Vec::new();
+    write_box(&mut moov, b"moov", |out| {
+        write_full_box(out, b"mvhd", 1, 0, |out| {
+            out.extend_from_slice(&0u64.to_be_bytes()); // creation time
+            out.extend_from_slice(&0u64.to_be_bytes()); // modification time
+            out.extend_from_slice(&timescale.to_be_bytes());
+            out.extend_from_slice(&presentation_duration.to_be_bytes());
+            out.extend_from_slice(&0x0001_0000u32.to_be_bytes()); // rate
+            out.extend_from_slice(&0x0100u16.to_be_bytes()); // volume
+            out.extend_from_slice(&[0; 10]); // reserved
+            write_matrix(out);
+            out.extend_from_slice(&[0; 24]); // pre-defined
+            out.extend_from_slice(&(TRACK_ID + 1).to_be_bytes()); // next track id
+        });
+
+        write_box(out, b"trak", |out| {
+            const TRACK_ENABLED_IN_MOVIE: u32 = 0x3;
+            write_full_box(out, b"tkhd", 1, TRACK_ENABLED_IN_MOVIE, |out| {
+                out.extend_from_slice(&0u64.to_be_bytes()); // creation time
+                out.extend_from_slice(&0u64.to_be_bytes()); // modification time
+                out.extend_from_slice(&TRACK_ID.to_be_bytes());
+                out.extend_from_slice(&[0; 4]); // reserved
+                out.extend_from_slice(&presentation_duration.to_be_bytes());
+                out.extend_from_slice(&[0; 8]); // reserved
+                out.extend_from_slice(&0u16.to_be_bytes()); // layer
+                out.extend_from_slice(&0u16.to_be_bytes()); // alternate group
+                out.extend_from_slice(&0u16.to_be_bytes()); // volume
+                out.extend_from_slice(&[0; 2]); // reserved
+                write_matrix(out);
+                out.extend_from_slice(&((width as u32) << 16).to_be_bytes());
+                out.extend_from_slice(&((height as u32) << 16).to_be_bytes());
+            });
+
+            // Map the media time of the first presented frame to the start of the movie.
+            if timing.presentation_start != 0 {
+                write_box(out, b"edts", |out| {
+                    write_full_box(out, b"elst", 1, 0, |out| {
+                        out.extend_from_slice(&1u32.to_be_bytes()); // entry count
+                        out.extend_from_slice(&presentation_duration.to_be_bytes());
+                        out.extend_from_slice(&timing.presentation_start.to_be_bytes());
+                        out.extend_from_slice(&1u16.to_be_bytes()); // media rate integer
+                        out.extend_from_slice(&0u16.to_be_bytes()); // media rate fraction
+                    });
+                });
+            }
+
+            write_box(out, b"mdia", |out| {
+                write_full_box(out, b"mdhd", 1, 0, |out| {
+                    out.extend_from_slice(&0u64.to_be_bytes()); // creation time
+                    out.extend_from_slice(&0u64.to_be_bytes()); // modification time
+                    out.extend_from_slice(&timescale.to_be_bytes());
+                    out.extend_from_slice(&timing.media_duration.to_be_bytes());
+                    out.extend_from_slice(&0x55C4u16.to_be_bytes()); // language: "und"
+                    out.extend_from_slice(&0u16.to_be_bytes()); // pre-defined
+                });
+
+                write_full_box(out, b"hdlr", 0, 0, |out| {
+                    out.extend_from_slice(&0u32.to_be_bytes()); // pre-defined
+                    out.extend_from_slice(b"vide");
+                    out.extend_from_slice(&[0; 12]); // reserved
+                    out.extend_from_slice(b"VideoHandler\0");
+                });
+
+                write_box(out, b"minf", |out| {
+                    write_full_box(out, b"vmhd", 0, 1, |out| {
+                        out.extend_from_slice(&[0; 8]); // graphics mode & opcolor
+                    });
+
+                    write_box(out, b"dinf", |out| {
+                        write_full_box(out, b"dref", 0, 0, |out| {
+                            out.extend_from_slice(&1u32.to_be_bytes()); // entry count
+                            const SELF_CONTAINED: u32 = 0x1;
+                            write_full_box(out, b"url ", 0, SELF_CONTAINED, |_| {});
+                        });
+                    });
+
+                    write_sample_table(
+                        out,
+                        samples,
+                        timing,
+                        sample_entry,
+                        chunk_offset,
+                        num_samples,
+                    );
+                });
+            });
+        });
+    });
+
+    Ok(moov)
+}
+
+fn write_sample_table(
+    out: &mut Vec<u8>,
+    samples: &[Chunk],
+    timing: &SampleTiming,
+    sample_entry: &[u8],
+    chunk_offset: u32,
+    num_samples: u32,
+) {
+    write_box(out, b"stbl", |out| {
+        write_full_box(out, b"stsd", 0, 0, |out| {
+            out.extend_from_slice(&1u32.to_be_bytes()); // entry count
+            out.extend_from_slice(sample_entry);
+        });
+
+        let durations = run_length_encode(timing.durations.iter().copied());
+        write_full_box(out, b"stts", 0, 0, |out| {
+            out.extend_from_slice(&(durations.len() as u32).to_be_bytes());
+            for (count, duration) in durations {
+                out.extend_from_slice(&count.to_be_bytes());
+                out.extend_from_slice(&duration.to_be_bytes());
+            }
+        });
+
+        if timing.composition_offsets.iter().any(|offset| *offset != 0) {
+            // Version 1 allows for negative offsets.
+            let version = u8::from(timing.composition_offsets.iter().any(|offset| *offset < 0));
+            let offsets = run_length_encode(timing.composition_offsets.iter().copied());
+            write_full_box(out, b"ctts", version, 0, |out| {
+                out.extend_from_slice(&(offsets.len() as u32).to_be_bytes());
+                for (count, offset) in offsets {
+                    out.extend_from_slice(&count.to_be_bytes());
+                    out.extend_from_slice(&(offset as i32).to_be_bytes());
+                }
+            });
+        }
+
+        // Without a sync sample box, every sample is a sync sample.
+        if samples.iter().any(|sample| !sample.is_sync) {
+            let sync_sample_numbers = samples
+                .iter()
+                .enumerate()
+                .filter(|(_, sample)| sample.is_sync)
+                .map(|(idx, _)| idx as u32 + 1)
+                .collect::<Vec<_>>();
+            write_full_box(out, b"stss", 0, 0, |out| {
+                out.extend_from_slice(&(sync_sample_numbers.len() as u32).to_be_bytes());
+                for sample_number in sync_sample_numbers {
+                    out.extend_from_slice(&sample_number.to_be_bytes());
+                }
+            });
+        }
+
+        // All samples are stored in a single chunk.
+        write_full_box(out, b"stsc", 0, 0, |out| {
+            out.extend_from_slice(&1u32.to_be_bytes()); // entry count
+            out.extend_from_slice(&1u32.to_be_bytes()); // first chunk
+            out.extend_from_slice(&num_samples.to_be_bytes()); // samples per chunk
+            out.extend_from_slice(&1u32.to_be_bytes()); // sample description index
+        });
+
+        write_full_box(out, b"stsz", 0, 0, |out| {
+            out.extend_from_slice(&0u32.to_be_bytes()); // sample size, 0 means sizes vary
+            out.extend_from_slice(&num_samples.to_be_bytes());
+            for sample in samples {
+                out.extend_from_slice(&(sample.data.len() as u32).to_be_bytes());
+            }
+        });
+
+        write_full_box(out, b"stco", 0, 0, |out| {
+            out.extend_from_slice(&1u32.to_be_bytes()); // entry count
+            out.extend_from_slice(&chunk_offset.to_be_bytes());
+        });
+    });
+}
+
+/// Returns `(count, value)` pairs of consecutive equal values.
+fn run_length_encode<T: PartialEq + Copy>(values: impl Iterator<Item = T>) -> Vec<(u32, T)> {
+    let mut runs: Vec<(u32, T)> = Vec::new();
+    for value in values {
+        match runs.last_mut() {
+            Some((count, last_value)) if *last_value == value => *count += 1,
+            _ => runs.push((1, value)),
+        }
+    }
+    runs
+}
+
+// ---------------------------------------------------------------------------
+// Codec configuration
+
+/// Determines the sample entry of the video track, and whether samples are in Annex B format.
+fn sample_entry(
+    video: &VideoDataDescription,
+    samples: &[Chunk],
+) -> Result<(Vec<u8>, bool), MuxError> {
+    let details = video.encoding_details.as_ref();
+    let coded_dimensions = details.map_or([0, 0], |details| details.coded_dimensions);
+
+    // Videos from MP4 & Matroska files come with the configuration we need.
+    if let Some(stsd) = details.and_then(|details| details.stsd.as_ref()) {
+        let (sample_entry_type, config_type, config) = match &stsd.contents {
+            re_mp4::StsdBoxContent::Avc1(avc1) => (b"avc1", b"avcC", &avc1.avcc.raw),
+            re_mp4::StsdBoxContent::Hvc1(hvc1) => (b"hvc1", b"hvcC", &hvc1.hvcc.raw),
+            re_mp4::StsdBoxContent::Hev1(hev1) => (b"hev1", b"hvcC", &hev1.hvcc.raw),
+            re_mp4::StsdBoxContent::Av01(av01) => (b"av01", b"av1C", &av01.av1c.raw),
+            re_mp4::StsdBoxContent::Vp08(vp08) => (b"vp08", b"vpcC", &vp08.vpcc.raw),
+            re_mp4::StsdBoxContent::Vp09(vp09) => (b"vp09", b"vpcC", &vp09.vpcc.raw),
+            re_mp4::StsdBoxContent::Mp4a(_) => {
+                return Err(MuxError::UnsupportedCodec((*b"mp4a").into()));
+            }
+            re_mp4::StsdBoxContent::Tx3g(_) => {
+                return Err(MuxError::UnsupportedCodec((*b"tx3g").into()));
+            }
+            re_mp4::StsdBoxContent::Unknown(four_cc) => {
+                return Err(MuxError::UnsupportedCodec(*four_cc));
+            }
+        };
+        let sample_entry =
+            visual_sample_entry(sample_entry_type, coded_dimensions, config_type, config);
+        return Ok((sample_entry, false));
+    }
+
+    let missing_configuration = || MuxError::MissingCodecConfiguration(video.codec);
+
+    Ok(match video.codec {
+        VideoCodec::H264 => {
+            let config =
+                avc_decoder_configuration(samples, details).ok_or_else(missing_configuration)?;
+            (
+                visual_sample_entry(b"avc1", coded_dimensions, b"avcC", &config),
+                true,
+            )
+        }
+
+        // Parameter sets stay in-band, which is only allowed for `hev1`.
+        VideoCodec::H265 => {
+            let config =
+                hevc_decoder_configuration(samples, details).ok_or_else(missing_configuration)?;
+            (
+                visual_sample_entry(b"hev1", coded_dimensions, b"hvcC", &config),
+                true,
+            )
+        }
+
+        VideoCodec::AV1 => {
+            let config = av1_codec_configuration(samples).ok_or_else(missing_configuration)?;
+            (
+                visual_sample_entry(b"av01", coded_dimensions, b"av1C", &config),
+                false,
+            )
+        }
+
+        VideoCodec::VP8 | VideoCodec::VP9 => {
+            let config = vp_codec_configuration(details).ok_or_else(missing_configuration)?;
+            let sample_entry_type = if video.codec == VideoCodec::VP8 {
+                b"vp08"
+            } else {
+                b"vp09"
+            };
+            (
+                visual_sample_entry(sample_entry_type, coded_dimensions, b"vpcC", &config),
+                false,
+            )
+        }
+    })
+}
+
+/// Collects the distinct NAL units of the given types from all key frames.
+fn parameter_sets<'a>(
+    samples: &'a [Chunk],
+    nal_unit_type: impl Fn(&[u8]) -> Option<u8>,
+    wanted_types: &[u8],
+) -> Vec<Vec<&'a [u8]>> {
+    let mut parameter_sets = vec![Vec::new(); wanted_types.len()];
+    for sample in samples.iter().filter(|sample| sample.is_sync) {
+        for nal in annexb_nal_units(&sample.data) {
+            let Some(type_idx) = nal_unit_type(nal)
+                .and_then(|typ| wanted_types.iter().position(|wanted| *wanted == typ))
+            else {
+                continue;
+            };
+            if !parameter_sets[type_idx].contains(&nal) {
+                parameter_sets[type_idx].push(nal);
+            }
+        }
+    }
+    parameter_sets
+}
+
+/// Builds an `AVCDecoderConfigurationRecord` from the SPS & PPS found in Annex B samples.
+fn avc_decoder_configuration(
+    samples: &[Chunk],
+    details: Option<&VideoEncodingDetails>,
+) -> Option<Vec<u8>> {
+    const SPS: u8 = 7;
+    const PPS: u8 = 8;
+
+    let [sps, pps] = <[_; 2]>::try_from(parameter_sets(
+        samples,
+        |nal| nal.first().map(|header| header & 0x1F),
+        &[SPS, PPS],
+    ))
+    .ok()?;
+    let [_, profile_idc, constraint_flags, level_idc, ..] = **sps.first()? else {
+        return None;
+    };
+    if pps.is_empty() {
+        return None;
+    }
+
+    let mut config = vec![
+        1, // configuration version
+        profile_idc,
+        constraint_flags,
+        level_idc,
+        0xFC | (NAL_LENGTH_SIZE - 1),
+        0xE0 | sps.len() as u8,
+    ];
+    write_parameter_sets(&mut config, &sps);
+    config.push(pps.len() as u8);
+    write_parameter_sets(&mut config, &pps);
+
+    // High profiles carry additional information about the chroma format.
+    if matches!(profile_idc, 100 | 110 | 122 | 144) {
+        let bit_depth_minus8 = details
+            .and_then(|details| details.bit_depth)
+            .map_or(0, |bit_depth| bit_depth.saturating_sub(8));
+        config.extend_from_slice(&[
+            0xFC | chroma_format_idc(details),
+            0xF8 | bit_depth_minus8, // luma
+            0xF8 | bit_depth_minus8, // chroma
+            0,                       // no SPS extensions
+        ]);
+    }
+
+    Some(config)
+}
+
+/// Builds a `HEVCDecoderConfigurationRecord` from the VPS, SPS & PPS found in Annex B samples.
+fn hevc_decoder_configuration(
+    samples: &[Chunk],
+    details: Option<&VideoEncodingDetails>,
+) -> Option<Vec<u8>> {
+    const VPS: u8 = 32;
+    const SPS: u8 = 33;
+    const PPS: u8 = 34;
+
+    let parameter_sets = parameter_sets(samples, crate::h265::nal_unit_type, &[VPS, SPS, PPS]);
+    if parameter_sets.iter().any(|nals| nals.is_empty()) {
+        return None;
+    }
+
+    // Skip the NAL header, then the SPS starts with the general profile, tier & level.
+    let sps = rbsp_from_nal(parameter_sets[1].first()?);
+    let sub_layer_info = *sps.get(2)?;
+    let profile_tier_level = sps.get(3..15)?;
+    let num_temporal_layers = ((sub_layer_info >> 1) & 0x07) + 1;
+    let temporal_id_nested = sub_layer_info & 0x01;
+
+    let bit_depth_minus8 = details
+        .and_then(|details| details.bit_depth)
+        .map_or(0, |bit_depth| bit_depth.saturating_sub(8));
+
+    let mut config = vec![1]; // configuration version
+    config.extend_from_slice(profile_tier_level);
+    config.extend_from_slice(&[
+        0xF0,
+        0x00, // min spatial segmentation
+        0xFC, // parallelism type: unknown
+        0xFC | chroma_format_idc(details),
+        0xF8 | bit_depth_minus8, // luma
+        0xF8 | bit_depth_minus8, // chroma
+        0x00,
+        0x00, // average frame rate: unknown
+        (num_temporal_layers << 3) | (temporal_id_nested << 2) | (NAL_LENGTH_SIZE - 1),
+        parameter_sets.len() as u8,
+    ]);
+    for (nal_unit_type, nals) in [VPS, SPS, PPS].into_iter().zip(&parameter_sets) {
+        const ARRAY_COMPLETENESS: u8 = 0x80;
+        config.push(ARRAY_COMPLETENESS | nal_unit_type);
+        config.extend_from_slice(&(nals.len() as u16).to_be_bytes());
+        write_parameter_sets(&mut config, nals);
+    }
+
+    Some(config)
+}
+
+fn write_parameter_sets(config: &mut Vec<u8>, nals: &[&[u8]]) {
+    for nal in nals {
+        config.extend_from_slice(&(nal.len() as u16).to_be_bytes());
+        config.extend_from_slice(nal);
+    }
+}
+
+/// Chroma format as used by H.264 & H.265.
+fn chroma_format_idc(details: Option<&VideoEncodingDetails>) -> u8 {
+    match details.and_then(|details| details.chroma_subsampling) {
+        Some(ChromaSubsamplingModes::Monochrome) => 0,
+        Some(ChromaSubsamplingModes::Yuv420) | None => 1,
+        Some(ChromaSubsamplingModes::Yuv422) => 2,
+        Some(ChromaSubsamplingModes::Yuv444) => 3,
+    }
+}
+
+/// Removes emulation prevention bytes from a NAL unit.
+fn rbsp_from_nal(nal: &[u8]) -> Vec<u8> {
+    let mut rbsp = Vec::with_capacity(nal.len());
+    let mut num_zeros = 0;
+    for &byte in nal {
+        if num_zeros >= 2 && byte == 0x03 {
+            num_zeros = 0;
+            continue;
+        }
+        num_zeros = if byte == 0 { num_zeros + 1 } else { 0 };
+        rbsp.push(byte);
+    }
+    rbsp
+}
+
+/// Builds an `AV1CodecConfigurationRecord` from the first sequence header found in the samples.
+fn av1_codec_configuration(samples: &[Chunk]) -> Option<Vec<u8>> {
+    let sequence_header_payload = samples
+        .iter()
+        .filter(|sample| sample.is_sync)
+        .flat_map(|sample| av1::obus(&sample.data))
+        .find(|obu| obu.obu_type == av1::obu_type::SEQUENCE_HEADER)?
+        .payload;
+    let sequence_header = av1::SequenceHeader::parse(sequence_header_payload).ok()?;
+
+    let (monochrome, subsampling_x, subsampling_y) = match sequence_header.chroma_subsampling {
+        Some(ChromaSubsamplingModes::Monochrome) => (1, 1, 1),
+        Some(ChromaSubsamplingModes::Yuv420) | None => (0, 1, 1),
+        Some(ChromaSubsamplingModes::Yuv422) => (0, 1, 0),
+        Some(ChromaSubsamplingModes::Yuv444) => (0, 0, 0),
+    };
+    let high_bitdepth = u8::from(sequence_header.bit_depth > 8);
+    let twelve_bit = u8::from(sequence_header.bit_depth == 12);
+
+    let mut config = vec![
+        0x81, // marker & version
+        (sequence_header.seq_profile << 5) | (sequence_header.seq_level_idx & 0x1F),
+        (sequence_header.seq_tier << 7)
+            | (high_bitdepth << 6)
+            | (twelve_bit << 5)
+            | (monochrome << 4)
+            | (subsampling_x << 3)
+            | (subsampling_y << 2),
+        0, // no initial presentation delay
+    ];
+
+    // The sequence header OBU, with size field.
+    config.push((av1::obu_type::SEQUENCE_HEADER << 3) | 0b10);
+    write_leb128(&mut config, sequence_header_payload.len() as u64);
+    config.extend_from_slice(sequence_header_payload);
+
+    Some(config)
+}
+
+fn write_leb128(out: &mut Vec<u8>, mut value: u64) {
+    loop {
+        let byte = (value & 0x7F) as u8;
+        value >>= 7;
+        if value == 0 {
+            out.push(byte);
+            return;
+        }
+        out.push(byte | 0x80);
+    }
+}
+
+/// Builds a `VPCodecConfigurationRecord` (including version & flags) from the encoding details.
+fn vp_codec_configuration(details: Option<&VideoEncodingDetails>) -> Option<Vec<u8>> {
+    let details = details?;
+
+    // VP9 codec strings look like `vp09.PP.LL.DD`, VP8 ones are just `vp8`.
+    let mut codec_string_parts = details.codec_string.split('.').skip(1);
+    let profile = codec_string_parts
+        .next()
+        .and_then(|part| part.parse().ok())
+        .unwrap_or(0u8);
+    let level = codec_string_parts
+        .next()
+        .and_then(|part| part.parse().ok())
+        .unwrap_or(0u8);
+
+    let bit_depth = details.bit_depth.unwrap_or(8);
+    let chroma_subsampling = match details.chroma_subsampling {
+        Some(ChromaSubsamplingModes::Yuv420 | ChromaSubsamplingModes::Monochrome) | None => 1,
+        Some(ChromaSubsamplingModes::Yuv422) => 2,
+        Some(ChromaSubsamplingModes::Yuv444) => 3,
+    };
+
+    const UNSPECIFIED: u8 = 2;
+    Some(vec![
+        1,
+        0,
+        0,
+        0, // version & flags
+        profile,
+        level,
+        (bit_depth << 4) | (chroma_subsampling << 1),
+        UNSPECIFIED, // color primaries
+        UNSPECIFIED, // transfer characteristics
+        UNSPECIFIED, // matrix coefficients
+        0,
+        0, // no codec initialization data
+    ])
+}
+
+/// Converts an Annex B byte stream into NAL units prefixed with their length.
+fn length_prefixed_from_annexb(data: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(data.len() + 16);
+    for nal in annexb_nal_units(data) {
+        out.extend_from_slice(&(nal.len() as u32).to_be_bytes());
+        out.extend_from_slice(nal);
+    }
+    out
+}
+
+// ---------------------------------------------------------------------------
+// Box writing
+
+/// Writes a visual sample entry box holding the given codec configuration box.
+pub(crate) fn visual_sample_entry(
+    sample_entry_type: &[u8; 4],
+    [width, height]: [u16; 2],
+    config_type: &[u8; 4],
+    config: &[u8],
+) -> Vec<u8> {
+    let mut sample_entry = Vec::with_capacity(94 + config.len());
+    write_box(&mut sample_entry, sample_entry_type, |out| {
+        out.extend_from_slice(&[0; 6]); // reserved
+        out.extend_from_slice(&1u16.to_be_bytes()); // data reference index
+        out.extend_from_slice(&[0; 16]); // pre-defined & reserved
+        out.extend_from_slice(&width.to_be_bytes());
+        out.extend_from_slice(&height.to_be_bytes());
+        out.extend_from_slice(&0x0048_0000u32.to_be_bytes()); // 72 dpi
+        out.extend_from_slice(&0x0048_0000u32.to_be_bytes()); // 72 dpi
+        out.extend_from_slice(&[0; 4]); // reserved
+        out.extend_from_slice(&1u16.to_be_bytes()); // frame count
+        out.extend_from_slice(&[0; 32]); // compressor name
+        out.extend_from_slice(&0x0018u16.to_be_bytes()); // depth
+        out.extend_from_slice(&(-1i16).to_be_bytes()); // pre-defined
+        write_box(out, config_type, |out| out.extend_from_slice(config));
+    });
+    sample_entry
+}
+
+/// Writes a box, with its size determined by what `write_contents` writes.
+pub(crate) fn write_box(
+    out: &mut Vec<u8>,
+    box_type: &[u8; 4],
+    write_contents: impl FnOnce(&mut Vec<u8>),
+) {
+    let start = out.len();
+    out.extend_from_slice(&[0; 4]); // size, filled in below
+    out.extend_from_slice(box_type);
+    write_contents(out);
+    let size = (out.len() - start) as u32;
+    out[start..start + 4].copy_from_slice(&size.to_be_bytes());
+}
+
+fn write_full_box(
+    out: &mut Vec<u8>,
+    box_type: &[u8; 4],
+    version: u8,
+    flags: u32,
+    write_contents: impl FnOnce(&mut Vec<u8>),
+) {
+    write_box(out, box_type, |out| {
+        out.push(version);
+        out.extend_from_slice(&flags.to_be_bytes()[1..]);
+        write_contents(out);
+    });
+}
+
+fn write_matrix(out: &mut Vec<u8>) {
+    for value in UNITY_MATRIX {
+        out.extend_from_slice(&value.to_be_bytes());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{SampleLayout, SampleMetadata, SamplesStatistics, Time, Timescale};
+
+    #[test]
+    fn test_write_annexb_h264_mp4() {
+        // SPS, PPS & IDR frame, see `test_detect_h264_annexb_gop`.
+        let key_frame: &[u8] = &[
+            0x00, 0x00, 0x00, 0x01, 0x67, 0x64, 0x00, 0x0A, 0xAC, 0x72, 0x84, 0x44, 0x26, 0x84,
+            0x00, 0x00, 0x03, 0x00, 0x04, 0x00, 0x00, 0x03, 0x00, 0xCA, 0x3C, 0x48, 0x96, 0x11,
+            0x80, 0x00, 0x00, 0x00, 0x01, 0x68, 0xEE, 0x3C, 0x80, 0x00, 0x00, 0x00, 0x01, 0x65,
+            0x88, 0x84, 0x21, 0x43,
+        ];
+        let delta_frame: &[u8] = &[0x00, 0x00, 0x00, 0x01, 0x41, 0x9A, 0x02, 0x03];
+
+        let frames = [key_frame, delta_frame, delta_frame, key_frame, delta_frame];
+        let buffers = frames.iter().copied().collect::<StableIndexDeque<_>>();
+        let samples = frames
+            .iter()
+            .enumerate()
+            .map(|(idx, frame)| {
+                // Starts at an arbitrary time, which is expected to be shifted to zero.
+                let timestamp = Time::new(1000 + idx as i64 * 3000);
+                SampleMetadata {
+                    is_sync: frame[4] == 0x67,
+                    frame_nr: idx as u32,
+                    decode_timestamp: timestamp,
+                    presentation_timestamp: timestamp,
+                    duration: Some(Time::new(3000)),
+                    buffer_index: idx,
+                    byte_span: re_span::Span {
+                        start: 0,
+                        len: frame.len() as u32,
+                    },
+                }
+            })
+            .collect::<StableIndexDeque<_>>();
+
+        let video = VideoDataDescription {
+            codec: VideoCodec::H264,
+            encoding_details: Some(VideoEncodingDetails {
+                codec_string: "avc1.64000A".to_owned(),
+                coded_dimensions: [64, 64],
+                bit_depth: Some(8),
+                chroma_subsampling: Some(ChromaSubsamplingModes::Yuv420),
+                stsd: None,
+            }),
+            timescale: Some(Timescale::new(90_000)),
+            duration: None,
+            gops: [0..3, 3..5]
+                .into_iter()
+                .map(|sample_range| crate::GroupOfPictures { sample_range })
+                .collect(),
+            samples_statistics: SamplesStatistics::new(&samples),
+            samples,
+            last_time_updated_samples: None,
+            mp4_tracks: Default::default(),
+            sample_layout: SampleLayout::Contiguous,
+        };
+
+        // Starting at a delta frame is not allowed.
+        assert!(matches!(
+            write_mp4(&video, &buffers, 1..5, &mut Vec::new()),
+            Err(MuxError::NoKeyFrameAtStart)
+        ));
+
+        let sample_range = video
+            .gop_aligned_sample_range(Time::new(10_000), Time::new(14_000))
+            .unwrap();
+        assert_eq!(sample_range, 3..5);
+        let mut mp4 = Vec::new();
+        write_mp4(&video, &buffers, sample_range, &mut mp4).unwrap();
+
+        let loaded = VideoDataDescription::load_mp4(&mp4, "test.mp4").unwrap();
+        assert_eq!(loaded.codec, VideoCodec::H264);
+        assert_eq!(loaded.timescale, Some(Timescale::new(90_000)));
+        let details = loaded.encoding_details.as_ref().unwrap();
+        assert_eq!(details.codec_string, "avc1.64000A");
+        assert_eq!(details.coded_dimensions, [64, 64]);
+        assert_eq!(
+            loaded
+                .samples
+                .iter()
+                .map(|sample| (sample.decode_timestamp.0, sample.is_sync))
+                .collect::<Vec<_>>(),
+            [(0, true), (3000, false)]
+        );
+
+        // Samples are now length prefixed.
+        let loaded_buffers = std::iter::once(mp4.as_slice()).collect();
+        let delta_chunk = loaded.samples[1]
+            .get(loaded.sample_layout, &loaded_buffers, 1)
+            .unwrap();
+        assert_eq!(
+            delta_chunk.data,
+            [0x00, 0x00, 0x00, 0x04, 0x41, 0x9A, 0x02, 0x03]
+        );
+    }
+}
@@ -8,6 +8,12 @@ impl Timescale {
     pub const fn new(v: u64) -> Self {
         Self(v)
     }
+
+    /// The number of time units per second.
+    #[inline]
+    pub const fn get(self) -> u64 {
+        self.0
+    }
 }
 
 /// A value in time units.
@@ -135,6 +135,8 @@ impl crate::DataUi for EntityDb {
                     chunk_max_bytes,
                     chunk_max_rows,
                     chunk_max_rows_if_unsorted,
+                    time_to_live: _,
+                    cold_storage_dir: _,
                 } = self.storage_engine().store().config();
 
                 ui.grid_left_hand_label("Compaction");
@@ -182,6 +184,16 @@ impl crate::DataUi for EntityDb {
                 ui.end_row();
             }
 
+            if let Some(time_to_live) = self.storage_engine().store().config().time_to_live {
+                ui.grid_left_hand_label("Time to live");
+                ui.label(time_to_live.to_string()).on_hover_text(format!(
+                    "Data that falls behind the latest time on this timeline by more than this \
+                     duration is dropped.\nConfigured through the {} environment variable.",
+                    ChunkStoreConfig::ENV_STORE_TIME_TO_LIVE,
+                ));
+                ui.end_row();
+            }
+
             if let Some(data_source) = &self.data_source {
                 ui.grid_left_hand_label("Data source");
                 data_source_button_ui(ctx, ui, data_source);
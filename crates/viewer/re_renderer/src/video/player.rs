@@ -532,7 +532,7 @@ impl VideoPlayer {
             .iter_index_range_clamped(sample_range)
         {
             let chunk = sample
-                .get(video_buffers, sample_idx)
+                .get(video_description.sample_layout, video_buffers, sample_idx)
                 .ok_or(VideoPlayerError::BadData)?;
             self.sample_decoder.decode(chunk)?;
 
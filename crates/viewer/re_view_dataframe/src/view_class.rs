@@ -144,13 +144,14 @@ Configure in the selection panel:
             view_contents: Some(view_contents),
             filtered_index: Some(*timeline.name()),
             filtered_index_range: Some(view_query.filter_by_range()?),
-            filtered_is_not_null: view_query.filter_is_not_null()?,
+            filtered_is_not_null: view_query.filter_is_not_null()?.map(Into::into),
             sparse_fill_strategy,
             selection: None,
 
             // not yet unsupported by the dataframe view
             filtered_index_values: None,
             using_index_values: None,
+            interpolation_strategy: re_chunk_store::InterpolationStrategy::None,
             include_semantically_empty_columns: false,
             include_tombstone_columns: false,
             include_static_columns: re_chunk_store::StaticColumnSelection::Both,
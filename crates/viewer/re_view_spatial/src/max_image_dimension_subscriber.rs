@@ -254,6 +254,8 @@ fn try_size_from_video_stream_sample(
 ) -> Option<[u32; 2]> {
     let codec = match video_codec {
         components::VideoCodec::H264 => re_video::VideoCodec::H264,
+        components::VideoCodec::H265 => re_video::VideoCodec::H265,
+        components::VideoCodec::AV1 => re_video::VideoCodec::AV1,
     };
 
     match re_video::detect_gop_start(sample, codec).ok()? {
@@ -136,6 +136,12 @@ pub struct App {
 
     connection_registry: ConnectionRegistryHandle,
 
+    /// The robots of all the files opened in the viewer, see [`Self::data_loader_settings`].
+    robots: re_data_loader::robots::Robots,
+
+    /// Recordings that are only loaded once they become active, see [`Self::data_loader_settings`].
+    deferred_stores: re_data_loader::deferred::DeferredStores,
+
     /// The async runtime that should be used for all asynchronous operations.
     ///
     /// Using the global tokio runtime should be avoided since:
@@ -377,6 +383,8 @@ impl App {
             event_dispatcher,
 
             connection_registry,
+            robots: Default::default(),
+            deferred_stores: Default::default(),
             async_runtime: tokio_runtime,
         }
     }
@@ -390,6 +398,21 @@ impl App {
         &self.connection_registry
     }
 
+    /// The settings all files opened in the viewer are loaded with.
+    ///
+    /// Files share their robots, so that e.g. an URDF file and an MCAP file opened one after the
+    /// other end up as a single animated robot.
+    ///
+    /// Loaders may defer the loading of recordings, which we load once they become active, see
+    /// `App::update`.
+    fn data_loader_settings(&self) -> re_data_loader::DataLoaderSettings {
+        re_data_loader::DataLoaderSettings {
+            robots: self.robots.clone(),
+            deferred_stores: Some(self.deferred_stores.clone()),
+            ..re_data_loader::DataLoaderSettings::recommended(re_log_types::RecordingId::random())
+        }
+    }
+
     pub fn set_examples_manifest_url(&mut self, url: String) {
         re_log::info!("Using manifest_url={url:?}");
         self.state.set_examples_manifest_url(&self.egui_ctx, url);
@@ -535,6 +558,11 @@ impl App {
             }
 
             SystemCommand::CloseRecordingOrTable(entry) => {
+                if let RecordingOrTable::Recording { store_id } = &entry {
+                    self.robots.forget_store(store_id);
+                    self.deferred_stores.forget_store(store_id);
+                }
+
                 // TODO(#9464): Find a better successor here.
                 store_hub.remove(&entry);
                 update_web_address_bar(
@@ -546,6 +574,8 @@ impl App {
 
             SystemCommand::CloseAllEntries => {
                 store_hub.clear_entries();
+                self.robots.forget_all_stores();
+                self.deferred_stores.forget_all_stores();
 
                 // Stop receiving into the old recordings.
                 // This is most important when going back to the example screen by using the "Back"
@@ -916,10 +946,12 @@ impl App {
             })
         };
 
-        match data_source
-            .clone()
-            .stream(&self.connection_registry, on_cmd, Some(waker))
-        {
+        match data_source.clone().stream_with_settings(
+            &self.connection_registry,
+            on_cmd,
+            Some(waker),
+            self.data_loader_settings(),
+        ) {
             Ok(re_data_source::StreamSource::LogMessages(rx)) => self.add_log_receiver(rx),
 
             Ok(re_data_source::StreamSource::CatalogUri(uri)) => {
@@ -2658,6 +2690,14 @@ impl eframe::App for App {
         }
         self.run_pending_system_commands(&mut store_hub, egui_ctx);
 
+        // Some data loaders (e.g. for `LeRobot` datasets) only load a recording once it is shown.
+        if let Some(rx) = store_hub
+            .active_store_id()
+            .and_then(|store_id| self.deferred_stores.load(store_id))
+        {
+            self.add_log_receiver(rx);
+        }
+
         // Return the `StoreHub` to the Viewer so we have it on the next frame
         self.store_hub = Some(store_hub);
 
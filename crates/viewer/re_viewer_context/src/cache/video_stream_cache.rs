@@ -244,10 +244,10 @@ fn load_video_data_from_chunks(
         .map_err(|err| VideoStreamProcessingError::FailedReadingCodec(Box::new(err)))?;
     let codec = match last_codec {
         components::VideoCodec::H264 => re_video::VideoCodec::H264,
-        // components::VideoCodec::H265 => re_video::VideoCodec::H265,
+        components::VideoCodec::H265 => re_video::VideoCodec::H265,
+        components::VideoCodec::AV1 => re_video::VideoCodec::AV1,
         // components::VideoCodec::VP8 => re_video::VideoCodec::Vp8,
         // components::VideoCodec::VP9 => re_video::VideoCodec::Vp9,
-        // components::VideoCodec::AV1 => re_video::VideoCodec::Av1,
     };
 
     // Extract all video samples.
@@ -261,6 +261,7 @@ fn load_video_data_from_chunks(
         samples: StableIndexDeque::with_capacity(sample_chunks.len()), // Number of video chunks is minimum number of samples.
         samples_statistics: re_video::SamplesStatistics::NO_BFRAMES, // TODO(#10090): No b-frames for now.
         mp4_tracks: Default::default(),
+        sample_layout: re_video::SampleLayout::Contiguous,
         last_time_updated_samples: Some(Instant::now()),
     };
 
@@ -770,6 +771,7 @@ mod tests {
             samples,
             samples_statistics,
             mp4_tracks,
+            sample_layout,
             last_time_updated_samples: _,
         } = data_descr.clone();
 
@@ -778,6 +780,7 @@ mod tests {
         assert_eq!(duration, None); // Open ended video.
         assert_eq!(samples_statistics, re_video::SamplesStatistics::NO_BFRAMES);
         assert!(mp4_tracks.is_empty());
+        assert_eq!(sample_layout, re_video::SampleLayout::Contiguous);
 
         let VideoEncodingDetails {
             codec_string,
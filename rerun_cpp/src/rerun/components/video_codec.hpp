@@ -40,6 +40,30 @@ namespace rerun::components {
         ///
         /// Enum value is the fourcc for 'avc1' (the WebCodec string assigned to this codec) in big endian.
         H264 = 0x61766331,
+
+        /// High Efficiency Video Coding (HEVC/H.265)
+        ///
+        /// See <https://en.wikipedia.org/wiki/High_Efficiency_Video_Coding>
+        ///
+        /// `components::VideoSample`s using this codec should be formatted according to Annex B specification.
+        /// (Note that this is different from AVCC format found in MP4 files.
+        /// To learn more about Annex B, check for instance <https://membrane.stream/learn/h264/3>)
+        /// Key frames (IRAP) require inclusion of a SPS (Sequence Parameter Set), as well as the
+        /// VPS (Video Parameter Set) and PPS (Picture Parameter Set).
+        ///
+        /// Enum value is the fourcc for 'hev1' (the WebCodec string assigned to this codec) in big endian.
+        H265 = 0x68657631,
+
+        /// AOMedia Video 1 (AV1)
+        ///
+        /// See <https://en.wikipedia.org/wiki/AV1>
+        ///
+        /// `components::VideoSample`s using this codec should be formatted as a sequence of OBUs
+        /// (Open Bitstream Units) in the low overhead bitstream format, i.e. every OBU has a size field.
+        /// Key frames require inclusion of a sequence header OBU.
+        ///
+        /// Enum value is the fourcc for 'av01' (the WebCodec string assigned to this codec) in big endian.
+        AV1 = 0x61763031,
     };
 } // namespace rerun::components
 
@@ -795,6 +795,7 @@ def spawn(
     port: int = 9876,
     memory_limit: str = ...,
     server_memory_limit: str = ...,
+    time_to_live: Optional[str] = None,
     hide_welcome_screen: bool = False,
     detach_process: bool = True,
     executable_name: str = ...,
@@ -25,7 +25,7 @@ class AssetVideo(AssetVideoExt, Archetype):
     """
     **Archetype**: A video binary.
 
-    Only MP4 containers are currently supported.
+    Supported containers are MP4, Matroska (including `WebM`) and MPEG transport streams.
 
     See <https://rerun.io/docs/reference/video> for codec support and more general information.
 
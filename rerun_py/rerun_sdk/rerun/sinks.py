@@ -545,6 +545,7 @@ def spawn(
     connect: bool = True,
     memory_limit: str = "75%",
     server_memory_limit: str = "0B",
+    time_to_live: str | None = None,
     hide_welcome_screen: bool = False,
     detach_process: bool = True,
     default_blueprint: BlueprintLike | None = None,
@@ -575,6 +576,12 @@ def spawn(
         Example: `16GB` or `50%` (of system total).
 
         Defaults to `0B`.
+    time_to_live:
+        Only keep a sliding window of data in the Rerun Viewer: anything that falls behind
+        the latest time on the given timeline by more than the given duration gets dropped.
+        Example: `log_time=30s` or `frame_nr=1000`.
+
+        Defaults to `None` (keep everything, up to `memory_limit`).
     hide_welcome_screen:
         Hide the normal Rerun welcome screen.
     detach_process:
@@ -599,6 +606,7 @@ def spawn(
         port=port,
         memory_limit=memory_limit,
         server_memory_limit=server_memory_limit,
+        time_to_live=time_to_live,
         hide_welcome_screen=hide_welcome_screen,
         detach_process=detach_process,
     )
@@ -15,7 +15,9 @@ use pyo3::{Bound, Py, PyAny, PyRef, PyResult, Python, pyclass, pymethods};
 use tracing::instrument;
 
 use re_chunk::ComponentIdentifier;
-use re_chunk_store::{QueryExpression, SparseFillStrategy, ViewContentsSelector};
+use re_chunk_store::{
+    InterpolationStrategy, QueryExpression, SparseFillStrategy, ViewContentsSelector,
+};
 use re_datafusion::DataframeQueryTableProvider;
 use re_log_types::{AbsoluteTimeRange, EntityPath, EntityPathFilter};
 use re_sdk::ComponentDescriptor;
@@ -76,6 +78,7 @@ impl PyDataframeQueryView {
                 filtered_index_range: None,
                 filtered_index_values: None,
                 using_index_values: None,
+                interpolation_strategy: InterpolationStrategy::None,
                 filtered_is_not_null: None,
                 sparse_fill_strategy: SparseFillStrategy::None,
                 selection: None,
@@ -328,7 +331,7 @@ impl PyDataframeQueryView {
         let column = column.into_selector()?;
 
         Ok(self.clone_with_new_query(py, |query_expression| {
-            query_expression.filtered_is_not_null = Some(column);
+            query_expression.filtered_is_not_null = Some(column.into());
         }))
     }
 
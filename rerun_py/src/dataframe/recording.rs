@@ -6,8 +6,8 @@ use pyo3::types::PyDict;
 use pyo3::{Bound, Py, PyAny, PyResult, pyclass, pymethods};
 use re_chunk::ComponentIdentifier;
 use re_chunk_store::{
-    ChunkStoreHandle, QueryExpression, SparseFillStrategy, StaticColumnSelection,
-    ViewContentsSelector,
+    ChunkStoreHandle, InterpolationStrategy, QueryExpression, SparseFillStrategy,
+    StaticColumnSelection, ViewContentsSelector,
 };
 use re_dataframe::{QueryEngine, StorageEngine};
 use re_log_types::EntityPathFilter;
@@ -230,6 +230,7 @@ impl PyRecording {
             filtered_index_range: None,
             filtered_index_values: None,
             using_index_values: None,
+            interpolation_strategy: InterpolationStrategy::None,
             filtered_is_not_null: None,
             sparse_fill_strategy: SparseFillStrategy::None,
             selection: None,
@@ -484,7 +484,7 @@ impl PyRecordingView {
         let column = column.into_selector();
 
         let mut query_expression = self.query_expression.clone();
-        query_expression.filtered_is_not_null = Some(column?);
+        query_expression.filtered_is_not_null = Some(column?.into());
 
         Ok(Self {
             recording: self.recording.clone(),
@@ -831,6 +831,7 @@ fn send_mem_sink_as_default_blueprint(
     port = 9876,
     memory_limit = "75%".to_owned(),
     server_memory_limit = "0B".to_owned(),
+    time_to_live = None,
     hide_welcome_screen = false,
     detach_process = true,
     executable_name = "rerun".to_owned(),
@@ -842,6 +843,7 @@ fn spawn(
     port: u16,
     memory_limit: String,
     server_memory_limit: String,
+    time_to_live: Option<String>,
     hide_welcome_screen: bool,
     detach_process: bool,
     executable_name: String,
@@ -854,6 +856,7 @@ fn spawn(
         wait_for_bind: true,
         memory_limit,
         server_memory_limit,
+        time_to_live,
         hide_welcome_screen,
         detach_process,
         executable_name,